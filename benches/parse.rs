@@ -0,0 +1,107 @@
+//! Benchmarks of the grammars against the hand-written scanner.
+//!
+//! ```shell
+//! cargo bench --bench parse
+//! ```
+
+use criterion::{
+    black_box, criterion_group, criterion_main, Criterion, Throughput,
+};
+use subtp::srt::{SubRip, SubRipRef};
+use subtp::vtt::{WebVtt, WebVttRef};
+
+/// The number of cues in the generated files.
+const CUES: usize = 5000;
+
+fn srt_text() -> String {
+    let mut text = String::new();
+    for i in 0..CUES {
+        let start = i * 3000;
+        let end = start + 2500;
+        text += &format!(
+            "{}\n{} --> {}\n- Never drink liquid nitrogen, number {}.\n- It will perforate your stomach.\n\n",
+            i + 1,
+            srt_timestamp(start),
+            srt_timestamp(end),
+            i,
+        );
+    }
+
+    text
+}
+
+fn vtt_text() -> String {
+    let mut text =
+        "WEBVTT - Benchmark\n\nSTYLE\n::cue { color: lime }\n\n".to_string();
+    for i in 0..CUES {
+        let start = i * 3000;
+        let end = start + 2500;
+        if i % 100 == 0 {
+            text += &format!("NOTE Chapter {}\n\n", i / 100);
+        }
+        text += &format!(
+            "cue-{}\n{} --> {} align:center line:90%\n<v Roger>Never drink liquid nitrogen, number {}.</v>\n<i>It will perforate your stomach.</i>\n\n",
+            i,
+            vtt_timestamp(start),
+            vtt_timestamp(end),
+            i,
+        );
+    }
+
+    text
+}
+
+fn srt_timestamp(milliseconds: usize) -> String {
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        milliseconds / 3_600_000,
+        milliseconds / 60_000 % 60,
+        milliseconds / 1000 % 60,
+        milliseconds % 1000,
+    )
+}
+
+fn vtt_timestamp(milliseconds: usize) -> String {
+    srt_timestamp(milliseconds).replace(',', ".")
+}
+
+fn srt(c: &mut Criterion) {
+    let text = srt_text();
+    let mut group = c.benchmark_group("srt");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("parse", |b| {
+        b.iter(|| SubRip::parse(black_box(&text)).unwrap())
+    });
+    group.bench_function("parse_fast", |b| {
+        b.iter(|| SubRip::parse_fast(black_box(&text)).unwrap())
+    });
+    group.bench_function("parse_ref", |b| {
+        b.iter(|| SubRipRef::parse(black_box(&text)).unwrap())
+    });
+    group.bench_function("parse_fast_ref", |b| {
+        b.iter(|| SubRipRef::parse_fast(black_box(&text)).unwrap())
+    });
+    group.finish();
+}
+
+fn vtt(c: &mut Criterion) {
+    let text = vtt_text();
+    let mut group = c.benchmark_group("vtt");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("parse", |b| {
+        b.iter(|| WebVtt::parse(black_box(&text)).unwrap())
+    });
+    group.bench_function("parse_fast", |b| {
+        b.iter(|| WebVtt::parse_fast(black_box(&text)).unwrap())
+    });
+    group.bench_function("parse_ref", |b| {
+        b.iter(|| WebVttRef::parse(black_box(&text)).unwrap())
+    });
+    group.bench_function("parse_fast_ref", |b| {
+        b.iter(|| WebVttRef::parse_fast(black_box(&text)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, srt, vtt);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+//! An example of converting a SubRip Subtitle (.srt) format text into a WebVTT (.vtt) format text.
+//!
+//! ```shell
+//! $ cargo run --example convert_srt_to_vtt
+//! ```
+
+use subtp::srt::SubRip;
+use subtp::subtitles::Subtitles;
+
+fn main() -> anyhow::Result<()> {
+    // Prepare the SubRip Subtitle (.srt) format text.
+    let text = r#"1
+00:00:01,000 --> 00:00:02,000 X1:64 X2:576 Y1:48 Y2:96
+This is the first subtitle on the top.
+
+2
+00:00:03,000 --> 00:00:04,000
+<i>This is the second subtitle.</i>
+<font color="red">Font tags are not supported.</font>
+"#;
+
+    // Convert the SubRip Subtitle (.srt) format into the `Subtitles` model.
+    let srt = SubRip::parse(text)?;
+    let conversion = Subtitles::from_sub_rip(&srt);
+    for loss in &conversion.losses {
+        println!("Lost from srt: {}", loss);
+    }
+
+    // Set the resolution of the video to map the line positions.
+    let mut subtitles = conversion.value;
+    subtitles.metadata.resolution = Some((640, 480));
+
+    // Convert the `Subtitles` model into the WebVTT (.vtt) format.
+    let conversion = subtitles.to_web_vtt();
+    for loss in &conversion.losses {
+        println!("Lost to vtt: {}", loss);
+    }
+    println!("Converted vtt:\n{}", conversion.value.render());
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! An example of parsing an Advanced SubStation Alpha (.ass) format text.
+//!
+//! ```shell
+//! $ cargo run --example parse_ass
+//! ```
+
+use subtp::ass::SubStationAlpha;
+
+fn main() -> anyhow::Result<()> {
+    // Prepare the Advanced SubStation Alpha (.ass) format text.
+    let text = r#"[Script Info]
+; Script generated by hand
+Title: Example
+ScriptType: v4.00+
+PlayResX: 1920
+PlayResY: 1080
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,48,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+Style: Sign,Arial,36,&H0000FFFF,&H000000FF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,2,0,8,10,10,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:02.00,Default,,0,0,0,,This is the first subtitle.
+Dialogue: 0,0:00:02.00,0:00:04.00,Default,,0,0,0,,This is the second subtitle.\NSubtitle text can span multiple lines.
+Dialogue: 1,0:00:00.00,0:00:04.00,Sign,,0,0,0,,{\fad(200,200)}A sign on the top.
+Comment: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,A comment is not displayed.
+"#;
+
+    // Parse the Advanced SubStation Alpha (.ass) format text to the `SubStationAlpha` struct.
+    let ass = SubStationAlpha::parse(text)?;
+    println!("Parsed ass:\n{:?}", ass);
+
+    // Render the `SubStationAlpha` struct to the Advanced SubStation Alpha (.ass) format text.
+    let rendered = ass.render();
+    println!("Rendered ass:\n{}", rendered);
+
+    // Get each event.
+    println!("Iterate events:");
+    for event in ass.events.events {
+        println!("Event:\n{:?}", event);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! An example of parsing a Timed Text Markup Language (.ttml) format text.
+//!
+//! ```shell
+//! $ cargo run --example parse_ttml
+//! ```
+
+use subtp::srt::SubRip;
+use subtp::ttml::Ttml;
+use subtp::vtt::WebVtt;
+
+fn main() -> anyhow::Result<()> {
+    // Prepare the Timed Text Markup Language (.ttml) format text.
+    let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttp="http://www.w3.org/ns/ttml#parameter" xmlns:tts="http://www.w3.org/ns/ttml#styling" ttp:profile="http://www.w3.org/ns/ttml/profile/imsc1/text" ttp:tickRate="10000000" xml:lang="en">
+  <head>
+    <styling>
+      <style xml:id="default" tts:color="white" tts:fontFamily="proportionalSansSerif"/>
+      <style xml:id="emphasis" tts:fontStyle="italic"/>
+    </styling>
+    <layout>
+      <region xml:id="bottom" tts:origin="10% 80%" tts:extent="80% 20%" tts:displayAlign="after"/>
+    </layout>
+  </head>
+  <body style="default" region="bottom">
+    <div>
+      <p xml:id="c1" begin="00:00:00.000" end="00:00:02.000">This is the first subtitle.</p>
+      <p xml:id="c2" begin="20000000t" end="40000000t">
+        This is the second subtitle.<br/>
+        Subtitle text can <span style="emphasis">span</span> multiple lines.
+      </p>
+    </div>
+  </body>
+</tt>
+"#;
+
+    // Parse the Timed Text Markup Language (.ttml) format text to the `Ttml` struct.
+    let ttml = Ttml::parse(text)?;
+    println!("Parsed ttml:\n{:?}", ttml);
+
+    // Render the `Ttml` struct to the Timed Text Markup Language (.ttml) format text.
+    let rendered = ttml.render();
+    println!("Rendered ttml:\n{}", rendered);
+
+    // Get each cue with the resolved timing.
+    println!("Iterate cues:");
+    for cue in ttml.cues() {
+        println!("Cue:\n{:?}", cue);
+    }
+
+    // Convert to the SubRip Subtitle (.srt) and the WebVTT (.vtt) formats.
+    println!("Converted srt:\n{}", SubRip::from(&ttml));
+    println!("Converted vtt:\n{}", WebVtt::from(&ttml));
+
+    Ok(())
+}
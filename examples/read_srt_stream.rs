@@ -0,0 +1,31 @@
+//! An example of reading a SubRip Subtitle (.srt) format from the standard input one subtitle at a time.
+//!
+//! ```shell
+//! $ cat subtitles.srt | cargo run --example read_srt_stream
+//! ```
+
+use std::io::stdin;
+
+use subtp::srt::SrtReader;
+
+fn main() -> anyhow::Result<()> {
+    // Read the subtitles one at a time without loading the whole input.
+    for subtitle in SrtReader::new(stdin().lock()) {
+        match subtitle {
+            | Ok(subtitle) => {
+                println!(
+                    "{} --> {}: {}",
+                    subtitle.start,
+                    subtitle.end,
+                    subtitle.text.join(" ")
+                );
+            },
+            | Err(subtp::ReadError::Parse(err)) => {
+                eprintln!("Skipped: {}", err);
+            },
+            | Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,2032 @@
+//! A parser for the Advanced SubStation Alpha (`.ass`) and the SubStation Alpha (`.ssa`) formats provided by [`subtp::ass::SubStationAlpha`](SubStationAlpha).
+//!
+//! ## Example
+//! ```
+//! use subtp::ass::SubStationAlpha;
+//! use subtp::ass::AssEventKind;
+//! use subtp::ass::AssTimestamp;
+//!
+//! let text = r#"[Script Info]
+//! Title: Example
+//! ScriptType: v4.00+
+//!
+//! [V4+ Styles]
+//! Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+//! Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+//!
+//! [Events]
+//! Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+//! Dialogue: 0,0:00:01.00,0:00:04.00,Default,,0,0,0,,Hello, world!
+//! Comment: 0,0:00:05.00,0:00:06.00,Default,,0,0,0,,This is a comment.
+//! "#;
+//!
+//! let ass = SubStationAlpha::parse(text).unwrap();
+//!
+//! assert_eq!(ass.script_info.get("Title"), Some("Example"));
+//! assert_eq!(ass.styles.styles[0].name, "Default");
+//! assert_eq!(ass.events.events[0].kind, AssEventKind::Dialogue);
+//! assert_eq!(
+//!     ass.events.events[0].start,
+//!     AssTimestamp {
+//!         seconds: 1,
+//!         ..Default::default()
+//!     }
+//! );
+//! assert_eq!(ass.events.events[0].text, "Hello, world!");
+//!
+//! let rendered = ass.render();
+//! assert_eq!(rendered, text);
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::ass_parser;
+use crate::{ParseError, ParseResult};
+
+/// The Advanced SubStation Alpha (`.ass`) or SubStation Alpha (`.ssa`) format.
+///
+/// Parses from text by [`SubStationAlpha::parse`](SubStationAlpha::parse)
+/// and renders to text by [`SubStationAlpha::render`](SubStationAlpha::render).
+///
+/// ## Example
+/// ```
+/// use subtp::ass::SubStationAlpha;
+/// use subtp::ass::AssScriptInfo;
+/// use subtp::ass::AssInfoEntry;
+/// use subtp::ass::AssStyles;
+/// use subtp::ass::AssStyle;
+/// use subtp::ass::AssEvents;
+/// use subtp::ass::AssEvent;
+/// use subtp::ass::AssTimestamp;
+///
+/// let ass = SubStationAlpha {
+///     script_info: AssScriptInfo {
+///         entries: vec![AssInfoEntry::Property(
+///             "ScriptType".to_string(),
+///             "v4.00+".to_string(),
+///         )],
+///     },
+///     styles: AssStyles {
+///         styles: vec![AssStyle::default()],
+///         ..Default::default()
+///     },
+///     events: AssEvents {
+///         events: vec![AssEvent {
+///             start: AssTimestamp {
+///                 seconds: 1,
+///                 ..Default::default()
+///             },
+///             end: AssTimestamp {
+///                 seconds: 2,
+///                 ..Default::default()
+///             },
+///             text: "Hello, world!".to_string(),
+///             ..Default::default()
+///         }],
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     ass.render(),
+///     r#"[Script Info]
+/// ScriptType: v4.00+
+///
+/// [V4+ Styles]
+/// Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+/// Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+///
+/// [Events]
+/// Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+/// Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello, world!
+/// "#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SubStationAlpha {
+    /// The `[Script Info]` section.
+    pub script_info: AssScriptInfo,
+    /// The `[V4+ Styles]` or `[V4 Styles]` section.
+    pub styles: AssStyles,
+    /// The `[Events]` section.
+    pub events: AssEvents,
+    /// The other sections such as `[Fonts]` and `[Graphics]`.
+    pub sections: Vec<AssSection>,
+    /// The order of sections as written, or empty for the default order.
+    pub order: Vec<AssSectionKind>,
+}
+
+impl SubStationAlpha {
+    /// Parses the Advanced SubStation Alpha format from the given text.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::ass::SubStationAlpha;
+    ///
+    /// let text = r#"[Script Info]
+    /// ScriptType: v4.00+
+    ///
+    /// [Events]
+    /// Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+    /// Dialogue: 0,0:00:01.00,0:00:04.00,Default,,0,0,0,,Hello, world!
+    /// "#;
+    ///
+    /// let ass = SubStationAlpha::parse(text).unwrap();
+    /// ```
+    pub fn parse(text: &str) -> ParseResult<Self> {
+        ass_parser::ass(text).map_err(|err| {
+            let err = ParseError::from(err);
+            // The section being parsed is the last header before the error.
+            let sections = text[..err.offset.min(text.len())]
+                .lines()
+                .filter_map(|line| {
+                    line.trim()
+                        .strip_prefix('[')?
+                        .strip_suffix(']')
+                })
+                .collect::<Vec<_>>();
+            match sections.last() {
+                | Some(section) => {
+                    err.with_context(*section, Some(sections.len() - 1))
+                },
+                | None => err,
+            }
+        })
+    }
+
+    /// Renders the text from the Advanced SubStation Alpha format.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::ass::SubStationAlpha;
+    /// use subtp::ass::AssEvents;
+    /// use subtp::ass::AssEvent;
+    ///
+    /// let ass = SubStationAlpha {
+    ///     events: AssEvents {
+    ///         events: vec![AssEvent {
+    ///             text: "Hello, world!".to_string(),
+    ///             ..Default::default()
+    ///         }],
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let rendered = ass.render();
+    /// ```
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    /// Resolves the order of sections to be rendered.
+    ///
+    /// Without [`order`](SubStationAlpha::order), all sections are rendered in the default order.
+    /// Otherwise, the sections not in the order are rendered after the ordered ones
+    /// if they have any content.
+    fn section_order(&self) -> Vec<AssSectionKind> {
+        if self.order.is_empty() {
+            let mut order = vec![
+                AssSectionKind::ScriptInfo,
+                AssSectionKind::Styles,
+                AssSectionKind::Events,
+            ];
+            order.extend((0..self.sections.len()).map(AssSectionKind::Other));
+            return order;
+        }
+
+        let mut order = self.order.clone();
+        if !order.contains(&AssSectionKind::ScriptInfo) {
+            order.insert(0, AssSectionKind::ScriptInfo);
+        }
+        if !order.contains(&AssSectionKind::Styles)
+            && !self.styles.styles.is_empty()
+        {
+            order.push(AssSectionKind::Styles);
+        }
+        if !order.contains(&AssSectionKind::Events)
+            && !self.events.events.is_empty()
+        {
+            order.push(AssSectionKind::Events);
+        }
+        for index in 0..self.sections.len() {
+            if !order.contains(&AssSectionKind::Other(index)) {
+                order.push(AssSectionKind::Other(index));
+            }
+        }
+
+        order
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for SubStationAlpha {
+    fn default() -> Self {
+        Self {
+            script_info: AssScriptInfo::default(),
+            styles: AssStyles::default(),
+            events: AssEvents::default(),
+            sections: vec![],
+            order: vec![],
+        }
+    }
+}
+
+impl Display for SubStationAlpha {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        for (i, kind) in self
+            .section_order()
+            .into_iter()
+            .enumerate()
+        {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            match kind {
+                | AssSectionKind::ScriptInfo => {
+                    write!(f, "{}", self.script_info)?
+                },
+                | AssSectionKind::Styles => write!(f, "{}", self.styles)?,
+                | AssSectionKind::Events => write!(f, "{}", self.events)?,
+                | AssSectionKind::Other(index) => {
+                    if let Some(section) = self.sections.get(index) {
+                        write!(f, "{}", section)?;
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The kind of section to keep the order of sections.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::SubStationAlpha;
+/// use subtp::ass::AssSectionKind;
+///
+/// let text = r#"[Script Info]
+/// ScriptType: v4.00+
+///
+/// [Aegisub Project Garbage]
+/// Video File: video.mkv
+///
+/// [Events]
+/// Format: Start, End, Text
+/// Dialogue: 0:00:01.00,0:00:02.00,Hello, world!
+/// "#;
+///
+/// let ass = SubStationAlpha::parse(text).unwrap();
+///
+/// assert_eq!(
+///     ass.order,
+///     vec![
+///         AssSectionKind::ScriptInfo,
+///         AssSectionKind::Other(0),
+///         AssSectionKind::Events,
+///     ]
+/// );
+/// assert_eq!(ass.render(), text);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AssSectionKind {
+    /// The `[Script Info]` section.
+    ScriptInfo,
+    /// The `[V4+ Styles]` or `[V4 Styles]` section.
+    Styles,
+    /// The `[Events]` section.
+    Events,
+    /// The other section at the index of [`SubStationAlpha::sections`](SubStationAlpha::sections).
+    Other(usize),
+}
+
+/// The `[Script Info]` section.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssScriptInfo;
+/// use subtp::ass::AssInfoEntry;
+///
+/// let mut script_info = AssScriptInfo {
+///     entries: vec![
+///         AssInfoEntry::Comment("; Script generated by hand".to_string()),
+///         AssInfoEntry::Property("Title".to_string(), "Example".to_string()),
+///     ],
+/// };
+///
+/// script_info.set("PlayResX", "1920");
+///
+/// assert_eq!(script_info.get("Title"), Some("Example"));
+/// assert_eq!(
+///     script_info.to_string(),
+///     "[Script Info]\n; Script generated by hand\nTitle: Example\nPlayResX: 1920\n".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssScriptInfo {
+    /// The entries in order of appearance.
+    pub entries: Vec<AssInfoEntry>,
+}
+
+impl AssScriptInfo {
+    /// Gets the value of the property by the key.
+    pub fn get(
+        &self,
+        key: &str,
+    ) -> Option<&str> {
+        self.entries
+            .iter()
+            .find_map(|entry| {
+                match entry {
+                    | AssInfoEntry::Property(k, v) if k == key => {
+                        Some(v.as_str())
+                    },
+                    | _ => None,
+                }
+            })
+    }
+
+    /// Sets the value of the property by the key,
+    /// appending a new property if the key does not exist.
+    pub fn set(
+        &mut self,
+        key: &str,
+        value: &str,
+    ) {
+        for entry in self.entries.iter_mut() {
+            if let AssInfoEntry::Property(k, v) = entry {
+                if k == key {
+                    *v = value.to_string();
+                    return;
+                }
+            }
+        }
+
+        self.entries
+            .push(AssInfoEntry::Property(
+                key.to_string(),
+                value.to_string(),
+            ));
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for AssScriptInfo {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+        }
+    }
+}
+
+impl Display for AssScriptInfo {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(f, "[Script Info]")?;
+
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The entry of the `[Script Info]` section.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssInfoEntry;
+///
+/// let entry = AssInfoEntry::Property("Title".to_string(), "Example".to_string());
+/// assert_eq!(entry.to_string(), "Title: Example".to_string());
+///
+/// let entry = AssInfoEntry::Comment("; This is a comment.".to_string());
+/// assert_eq!(entry.to_string(), "; This is a comment.".to_string());
+///
+/// assert_eq!(AssInfoEntry::Blank.to_string(), "".to_string());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AssInfoEntry {
+    /// The comment line as written including the leading `;` or `!:`.
+    Comment(String),
+    /// The property line of `Key: Value`.
+    Property(String, String),
+    /// The blank line.
+    Blank,
+}
+
+impl Display for AssInfoEntry {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Comment(comment) => {
+                write!(f, "{}", comment)
+            },
+            | Self::Property(key, value) => {
+                write!(f, "{}: {}", key, value)
+            },
+            | Self::Blank => Ok(()),
+        }
+    }
+}
+
+/// The version of the styles section.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssVersion;
+///
+/// assert_eq!(AssVersion::V4Plus.to_string(), "[V4+ Styles]".to_string());
+/// assert_eq!(AssVersion::V4.to_string(), "[V4 Styles]".to_string());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AssVersion {
+    /// The SubStation Alpha v4 styles, `[V4 Styles]`.
+    V4,
+    /// The Advanced SubStation Alpha styles, `[V4+ Styles]`.
+    V4Plus,
+}
+
+impl Default for AssVersion {
+    fn default() -> Self {
+        Self::V4Plus
+    }
+}
+
+impl Display for AssVersion {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::V4 => {
+                write!(f, "[V4 Styles]")
+            },
+            | Self::V4Plus => {
+                write!(f, "[V4+ Styles]")
+            },
+        }
+    }
+}
+
+/// The styles section.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssStyles;
+/// use subtp::ass::AssStyle;
+/// use subtp::ass::AssStyleField;
+///
+/// let styles = AssStyles {
+///     format: vec![
+///         AssStyleField::Name,
+///         AssStyleField::Fontname,
+///         AssStyleField::Fontsize,
+///     ],
+///     styles: vec![AssStyle {
+///         name: "Title".to_string(),
+///         fontname: "Helvetica".to_string(),
+///         fontsize: 32.5,
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     styles.to_string(),
+///     "[V4+ Styles]\nFormat: Name, Fontname, Fontsize\nStyle: Title,Helvetica,32.5\n".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssStyles {
+    /// The version of styles.
+    pub version: AssVersion,
+    /// The order of fields in each style line.
+    pub format: Vec<AssStyleField>,
+    /// The styles.
+    pub styles: Vec<AssStyle>,
+    /// The comments, blank lines and unknown lines kept as written.
+    pub raw_lines: Vec<AssRawLine>,
+}
+
+impl Default for AssStyles {
+    fn default() -> Self {
+        Self {
+            version: AssVersion::default(),
+            format: AssStyleField::V4_PLUS.to_vec(),
+            styles: vec![],
+            raw_lines: vec![],
+        }
+    }
+}
+
+impl Display for AssStyles {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(f, "{}", self.version)?;
+
+        let mut raw_lines = RawLines::new(&self.raw_lines);
+        raw_lines.write_until(f, 0)?;
+        writeln!(
+            f,
+            "Format: {}",
+            join(&self.format, ", ")
+        )?;
+
+        for (i, style) in self.styles.iter().enumerate() {
+            raw_lines.write_until(f, i + 1)?;
+            writeln!(
+                f,
+                "Style: {}",
+                style
+                    .field_values(&self.format)
+                    .join(",")
+            )?;
+        }
+
+        raw_lines.write_rest(f)
+    }
+}
+
+/// The field name of the style format.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssStyleField;
+///
+/// assert_eq!(
+///     AssStyleField::PrimaryColour.to_string(),
+///     "PrimaryColour".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AssStyleField {
+    /// `Name`.
+    Name,
+    /// `Fontname`.
+    Fontname,
+    /// `Fontsize`.
+    Fontsize,
+    /// `PrimaryColour`.
+    PrimaryColour,
+    /// `SecondaryColour`.
+    SecondaryColour,
+    /// `OutlineColour`.
+    OutlineColour,
+    /// `TertiaryColour`, the SubStation Alpha name of `OutlineColour`.
+    TertiaryColour,
+    /// `BackColour`.
+    BackColour,
+    /// `Bold`.
+    Bold,
+    /// `Italic`.
+    Italic,
+    /// `Underline`.
+    Underline,
+    /// `StrikeOut`.
+    StrikeOut,
+    /// `ScaleX`.
+    ScaleX,
+    /// `ScaleY`.
+    ScaleY,
+    /// `Spacing`.
+    Spacing,
+    /// `Angle`.
+    Angle,
+    /// `BorderStyle`.
+    BorderStyle,
+    /// `Outline`.
+    Outline,
+    /// `Shadow`.
+    Shadow,
+    /// `Alignment`.
+    Alignment,
+    /// `MarginL`.
+    MarginL,
+    /// `MarginR`.
+    MarginR,
+    /// `MarginV`.
+    MarginV,
+    /// `AlphaLevel`, only in SubStation Alpha.
+    AlphaLevel,
+    /// `Encoding`.
+    Encoding,
+    /// The unknown field kept by its name.
+    Other(String),
+}
+
+impl AssStyleField {
+    /// The default format of `[V4 Styles]`.
+    pub const V4: [AssStyleField; 18] = [
+        Self::Name,
+        Self::Fontname,
+        Self::Fontsize,
+        Self::PrimaryColour,
+        Self::SecondaryColour,
+        Self::TertiaryColour,
+        Self::BackColour,
+        Self::Bold,
+        Self::Italic,
+        Self::BorderStyle,
+        Self::Outline,
+        Self::Shadow,
+        Self::Alignment,
+        Self::MarginL,
+        Self::MarginR,
+        Self::MarginV,
+        Self::AlphaLevel,
+        Self::Encoding,
+    ];
+    /// The default format of `[V4+ Styles]`.
+    pub const V4_PLUS: [AssStyleField; 23] = [
+        Self::Name,
+        Self::Fontname,
+        Self::Fontsize,
+        Self::PrimaryColour,
+        Self::SecondaryColour,
+        Self::OutlineColour,
+        Self::BackColour,
+        Self::Bold,
+        Self::Italic,
+        Self::Underline,
+        Self::StrikeOut,
+        Self::ScaleX,
+        Self::ScaleY,
+        Self::Spacing,
+        Self::Angle,
+        Self::BorderStyle,
+        Self::Outline,
+        Self::Shadow,
+        Self::Alignment,
+        Self::MarginL,
+        Self::MarginR,
+        Self::MarginV,
+        Self::Encoding,
+    ];
+}
+
+impl Display for AssStyleField {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        let name = match self {
+            | Self::Name => "Name",
+            | Self::Fontname => "Fontname",
+            | Self::Fontsize => "Fontsize",
+            | Self::PrimaryColour => "PrimaryColour",
+            | Self::SecondaryColour => "SecondaryColour",
+            | Self::OutlineColour => "OutlineColour",
+            | Self::TertiaryColour => "TertiaryColour",
+            | Self::BackColour => "BackColour",
+            | Self::Bold => "Bold",
+            | Self::Italic => "Italic",
+            | Self::Underline => "Underline",
+            | Self::StrikeOut => "StrikeOut",
+            | Self::ScaleX => "ScaleX",
+            | Self::ScaleY => "ScaleY",
+            | Self::Spacing => "Spacing",
+            | Self::Angle => "Angle",
+            | Self::BorderStyle => "BorderStyle",
+            | Self::Outline => "Outline",
+            | Self::Shadow => "Shadow",
+            | Self::Alignment => "Alignment",
+            | Self::MarginL => "MarginL",
+            | Self::MarginR => "MarginR",
+            | Self::MarginV => "MarginV",
+            | Self::AlphaLevel => "AlphaLevel",
+            | Self::Encoding => "Encoding",
+            | Self::Other(name) => name,
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The style.
+///
+/// Which fields are rendered depends on the format of [`AssStyles`](AssStyles).
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssStyle;
+/// use subtp::ass::AssColor;
+///
+/// let style = AssStyle {
+///     name: "Sign".to_string(),
+///     primary_color: AssColor {
+///         red: 255,
+///         green: 255,
+///         blue: 0,
+///         alpha: 0,
+///     },
+///     bold: true,
+///     alignment: 8,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssStyle {
+    /// The name of style.
+    pub name: String,
+    /// The font name.
+    pub fontname: String,
+    /// The font size.
+    pub fontsize: f32,
+    /// The primary fill color.
+    pub primary_color: AssColor,
+    /// The secondary fill color used for karaoke.
+    pub secondary_color: AssColor,
+    /// The outline color, called tertiary color in SubStation Alpha.
+    pub outline_color: AssColor,
+    /// The shadow color.
+    pub back_color: AssColor,
+    /// The bold flag.
+    pub bold: bool,
+    /// The italic flag.
+    pub italic: bool,
+    /// The underline flag.
+    pub underline: bool,
+    /// The strike-out flag.
+    pub strike_out: bool,
+    /// The horizontal scale in percent.
+    pub scale_x: f32,
+    /// The vertical scale in percent.
+    pub scale_y: f32,
+    /// The extra space between characters in pixels.
+    pub spacing: f32,
+    /// The rotation in degrees.
+    pub angle: f32,
+    /// The border style, `1` for outline and drop shadow and `3` for opaque box.
+    pub border_style: u32,
+    /// The width of outline in pixels.
+    pub outline: f32,
+    /// The depth of shadow in pixels.
+    pub shadow: f32,
+    /// The alignment in numpad layout, or legacy layout in SubStation Alpha.
+    pub alignment: u32,
+    /// The left margin in pixels.
+    pub margin_l: AssMargin,
+    /// The right margin in pixels.
+    pub margin_r: AssMargin,
+    /// The vertical margin in pixels.
+    pub margin_v: AssMargin,
+    /// The transparency of text, only in SubStation Alpha.
+    pub alpha_level: u32,
+    /// The font character set.
+    pub encoding: u32,
+    /// The values of unknown fields in order of the format.
+    pub others: Vec<String>,
+}
+
+impl AssStyle {
+    /// Renders the values of fields in the given format.
+    pub(crate) fn field_values(
+        &self,
+        format: &[AssStyleField],
+    ) -> Vec<String> {
+        let mut others = self.others.iter();
+        format
+            .iter()
+            .map(|field| {
+                match field {
+                    | AssStyleField::Other(_) => {
+                        others.next().cloned().unwrap_or_default()
+                    },
+                    | field => self.field_value(field),
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the value of the given field.
+    fn field_value(
+        &self,
+        field: &AssStyleField,
+    ) -> String {
+        match field {
+            | AssStyleField::Name => self.name.clone(),
+            | AssStyleField::Fontname => self.fontname.clone(),
+            | AssStyleField::Fontsize => float(self.fontsize),
+            | AssStyleField::PrimaryColour => self.primary_color.to_string(),
+            | AssStyleField::SecondaryColour => {
+                self.secondary_color
+                    .to_string()
+            },
+            | AssStyleField::OutlineColour | AssStyleField::TertiaryColour => {
+                self.outline_color.to_string()
+            },
+            | AssStyleField::BackColour => self.back_color.to_string(),
+            | AssStyleField::Bold => boolean(self.bold),
+            | AssStyleField::Italic => boolean(self.italic),
+            | AssStyleField::Underline => boolean(self.underline),
+            | AssStyleField::StrikeOut => boolean(self.strike_out),
+            | AssStyleField::ScaleX => float(self.scale_x),
+            | AssStyleField::ScaleY => float(self.scale_y),
+            | AssStyleField::Spacing => float(self.spacing),
+            | AssStyleField::Angle => float(self.angle),
+            | AssStyleField::BorderStyle => self.border_style.to_string(),
+            | AssStyleField::Outline => float(self.outline),
+            | AssStyleField::Shadow => float(self.shadow),
+            | AssStyleField::Alignment => self.alignment.to_string(),
+            | AssStyleField::MarginL => self.margin_l.to_string(),
+            | AssStyleField::MarginR => self.margin_r.to_string(),
+            | AssStyleField::MarginV => self.margin_v.to_string(),
+            | AssStyleField::AlphaLevel => self.alpha_level.to_string(),
+            | AssStyleField::Encoding => self.encoding.to_string(),
+            | AssStyleField::Other(_) => String::new(),
+        }
+    }
+}
+
+impl Default for AssStyle {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            fontname: "Arial".to_string(),
+            fontsize: 20.0,
+            primary_color: AssColor {
+                red: 255,
+                green: 255,
+                blue: 255,
+                alpha: 0,
+            },
+            secondary_color: AssColor {
+                red: 255,
+                green: 0,
+                blue: 0,
+                alpha: 0,
+            },
+            outline_color: AssColor::default(),
+            back_color: AssColor::default(),
+            bold: false,
+            italic: false,
+            underline: false,
+            strike_out: false,
+            scale_x: 100.0,
+            scale_y: 100.0,
+            spacing: 0.0,
+            angle: 0.0,
+            border_style: 1,
+            outline: 2.0,
+            shadow: 2.0,
+            alignment: 2,
+            margin_l: AssMargin::from(10),
+            margin_r: AssMargin::from(10),
+            margin_v: AssMargin::from(10),
+            alpha_level: 0,
+            encoding: 1,
+            others: vec![],
+        }
+    }
+}
+
+/// The color in `&HAABBGGRR` notation.
+///
+/// The alpha is the transparency, `0` for opaque and `255` for transparent.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssColor;
+///
+/// let color = AssColor {
+///     red: 0x12,
+///     green: 0x34,
+///     blue: 0x56,
+///     alpha: 0x78,
+/// };
+///
+/// assert_eq!(color.to_string(), "&H78563412".to_string());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssColor {
+    /// The red component.
+    pub red: u8,
+    /// The green component.
+    pub green: u8,
+    /// The blue component.
+    pub blue: u8,
+    /// The alpha component as transparency.
+    pub alpha: u8,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for AssColor {
+    fn default() -> Self {
+        Self {
+            red: 0,
+            green: 0,
+            blue: 0,
+            alpha: 0,
+        }
+    }
+}
+
+impl From<u32> for AssColor {
+    fn from(value: u32) -> Self {
+        let [red, green, blue, alpha] = value.to_le_bytes();
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
+impl From<AssColor> for u32 {
+    fn from(color: AssColor) -> Self {
+        u32::from_le_bytes([
+            color.red,
+            color.green,
+            color.blue,
+            color.alpha,
+        ])
+    }
+}
+
+impl Display for AssColor {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "&H{:08X}", u32::from(*self))
+    }
+}
+
+/// The margin in pixels keeping the zero padding as written, such as `0010`.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssMargin;
+///
+/// assert_eq!(AssMargin::from(10).to_string(), "10".to_string());
+///
+/// let margin = AssMargin {
+///     pixels: 10,
+///     digits: 4,
+/// };
+/// assert_eq!(margin.to_string(), "0010".to_string());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssMargin {
+    /// The margin in pixels.
+    pub pixels: u32,
+    /// The number of digits padded with zeros, `0` for no padding.
+    pub digits: u8,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for AssMargin {
+    fn default() -> Self {
+        Self {
+            pixels: 0,
+            digits: 0,
+        }
+    }
+}
+
+impl From<u32> for AssMargin {
+    fn from(pixels: u32) -> Self {
+        Self {
+            pixels,
+            digits: 0,
+        }
+    }
+}
+
+impl From<AssMargin> for u32 {
+    fn from(margin: AssMargin) -> Self {
+        margin.pixels
+    }
+}
+
+impl Display for AssMargin {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "{:0width$}",
+            self.pixels,
+            width = self.digits as usize
+        )
+    }
+}
+
+/// The `[Events]` section.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssEvents;
+/// use subtp::ass::AssEvent;
+/// use subtp::ass::AssEventField;
+/// use subtp::ass::AssTimestamp;
+///
+/// let events = AssEvents {
+///     format: vec![
+///         AssEventField::Start,
+///         AssEventField::End,
+///         AssEventField::Text,
+///     ],
+///     events: vec![AssEvent {
+///         start: AssTimestamp {
+///             seconds: 1,
+///             ..Default::default()
+///         },
+///         end: AssTimestamp {
+///             seconds: 2,
+///             centiseconds: 50,
+///             ..Default::default()
+///         },
+///         text: "Hello, world!".to_string(),
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     events.to_string(),
+///     "[Events]\nFormat: Start, End, Text\nDialogue: 0:00:01.00,0:00:02.50,Hello, world!\n".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssEvents {
+    /// The order of fields in each event line.
+    pub format: Vec<AssEventField>,
+    /// The events.
+    pub events: Vec<AssEvent>,
+    /// The comments, blank lines and unknown lines kept as written.
+    pub raw_lines: Vec<AssRawLine>,
+}
+
+impl Default for AssEvents {
+    fn default() -> Self {
+        Self {
+            format: AssEventField::V4_PLUS.to_vec(),
+            events: vec![],
+            raw_lines: vec![],
+        }
+    }
+}
+
+impl Display for AssEvents {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(f, "[Events]")?;
+
+        let mut raw_lines = RawLines::new(&self.raw_lines);
+        raw_lines.write_until(f, 0)?;
+        writeln!(
+            f,
+            "Format: {}",
+            join(&self.format, ", ")
+        )?;
+
+        for (i, event) in self.events.iter().enumerate() {
+            raw_lines.write_until(f, i + 1)?;
+            writeln!(
+                f,
+                "{}: {}",
+                event.kind,
+                event
+                    .field_values(&self.format)
+                    .join(",")
+            )?;
+        }
+
+        raw_lines.write_rest(f)
+    }
+}
+
+/// The field name of the event format.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssEventField;
+///
+/// assert_eq!(AssEventField::MarginL.to_string(), "MarginL".to_string());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AssEventField {
+    /// `Layer`.
+    Layer,
+    /// `Marked`, only in SubStation Alpha.
+    Marked,
+    /// `Start`.
+    Start,
+    /// `End`.
+    End,
+    /// `Style`.
+    Style,
+    /// `Name`, also known as actor.
+    Name,
+    /// `MarginL`.
+    MarginL,
+    /// `MarginR`.
+    MarginR,
+    /// `MarginV`.
+    MarginV,
+    /// `Effect`.
+    Effect,
+    /// `Text`.
+    Text,
+    /// The unknown field kept by its name.
+    Other(String),
+}
+
+impl AssEventField {
+    /// The default format of events in SubStation Alpha.
+    pub const V4: [AssEventField; 10] = [
+        Self::Marked,
+        Self::Start,
+        Self::End,
+        Self::Style,
+        Self::Name,
+        Self::MarginL,
+        Self::MarginR,
+        Self::MarginV,
+        Self::Effect,
+        Self::Text,
+    ];
+    /// The default format of events in Advanced SubStation Alpha.
+    pub const V4_PLUS: [AssEventField; 10] = [
+        Self::Layer,
+        Self::Start,
+        Self::End,
+        Self::Style,
+        Self::Name,
+        Self::MarginL,
+        Self::MarginR,
+        Self::MarginV,
+        Self::Effect,
+        Self::Text,
+    ];
+}
+
+impl Display for AssEventField {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        let name = match self {
+            | Self::Layer => "Layer",
+            | Self::Marked => "Marked",
+            | Self::Start => "Start",
+            | Self::End => "End",
+            | Self::Style => "Style",
+            | Self::Name => "Name",
+            | Self::MarginL => "MarginL",
+            | Self::MarginR => "MarginR",
+            | Self::MarginV => "MarginV",
+            | Self::Effect => "Effect",
+            | Self::Text => "Text",
+            | Self::Other(name) => name,
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The event line.
+///
+/// Which fields are rendered depends on the format of [`AssEvents`](AssEvents).
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssEvent;
+/// use subtp::ass::AssEventKind;
+/// use subtp::ass::AssTimestamp;
+///
+/// let event = AssEvent {
+///     kind: AssEventKind::Dialogue,
+///     start: AssTimestamp {
+///         seconds: 1,
+///         ..Default::default()
+///     },
+///     end: AssTimestamp {
+///         seconds: 2,
+///         ..Default::default()
+///     },
+///     style: "Default".to_string(),
+///     text: "{\\i1}Hello{\\i0}, world!".to_string(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssEvent {
+    /// The kind of event.
+    pub kind: AssEventKind,
+    /// The layer, higher layers are drawn above lower ones.
+    pub layer: u32,
+    /// The marked flag, only in SubStation Alpha.
+    pub marked: bool,
+    /// The start timestamp.
+    pub start: AssTimestamp,
+    /// The end timestamp.
+    pub end: AssTimestamp,
+    /// The name of style.
+    pub style: String,
+    /// The name of speaker.
+    pub name: String,
+    /// The left margin in pixels, `0` to use the margin of style.
+    pub margin_l: AssMargin,
+    /// The right margin in pixels, `0` to use the margin of style.
+    pub margin_r: AssMargin,
+    /// The vertical margin in pixels, `0` to use the margin of style.
+    pub margin_v: AssMargin,
+    /// The transition effect.
+    pub effect: String,
+    /// The text with override tags such as `{\i1}`.
+    pub text: String,
+    /// The values of unknown fields in order of the format.
+    pub others: Vec<String>,
+}
+
+impl AssEvent {
+    /// Renders the values of fields in the given format.
+    pub(crate) fn field_values(
+        &self,
+        format: &[AssEventField],
+    ) -> Vec<String> {
+        let mut others = self.others.iter();
+        format
+            .iter()
+            .map(|field| {
+                match field {
+                    | AssEventField::Other(_) => {
+                        others.next().cloned().unwrap_or_default()
+                    },
+                    | field => self.field_value(field),
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the value of the given field.
+    fn field_value(
+        &self,
+        field: &AssEventField,
+    ) -> String {
+        match field {
+            | AssEventField::Layer => self.layer.to_string(),
+            | AssEventField::Marked => {
+                format!("Marked={}", self.marked as u8)
+            },
+            | AssEventField::Start => self.start.to_string(),
+            | AssEventField::End => self.end.to_string(),
+            | AssEventField::Style => self.style.clone(),
+            | AssEventField::Name => self.name.clone(),
+            | AssEventField::MarginL => self.margin_l.to_string(),
+            | AssEventField::MarginR => self.margin_r.to_string(),
+            | AssEventField::MarginV => self.margin_v.to_string(),
+            | AssEventField::Effect => self.effect.clone(),
+            | AssEventField::Text => self.text.clone(),
+            | AssEventField::Other(_) => String::new(),
+        }
+    }
+}
+
+impl Default for AssEvent {
+    fn default() -> Self {
+        Self {
+            kind: AssEventKind::default(),
+            layer: 0,
+            marked: false,
+            start: AssTimestamp::default(),
+            end: AssTimestamp::default(),
+            style: "Default".to_string(),
+            name: String::new(),
+            margin_l: AssMargin::default(),
+            margin_r: AssMargin::default(),
+            margin_v: AssMargin::default(),
+            effect: String::new(),
+            text: String::new(),
+            others: vec![],
+        }
+    }
+}
+
+/// The kind of event line.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssEventKind;
+///
+/// assert_eq!(AssEventKind::Dialogue.to_string(), "Dialogue".to_string());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AssEventKind {
+    /// The dialogue to be displayed.
+    Dialogue,
+    /// The comment not to be displayed.
+    Comment,
+    /// The picture to be displayed.
+    Picture,
+    /// The sound to be played.
+    Sound,
+    /// The movie to be played.
+    Movie,
+    /// The command to be executed.
+    Command,
+}
+
+impl Default for AssEventKind {
+    fn default() -> Self {
+        Self::Dialogue
+    }
+}
+
+impl Display for AssEventKind {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Dialogue => {
+                write!(f, "Dialogue")
+            },
+            | Self::Comment => {
+                write!(f, "Comment")
+            },
+            | Self::Picture => {
+                write!(f, "Picture")
+            },
+            | Self::Sound => {
+                write!(f, "Sound")
+            },
+            | Self::Movie => {
+                write!(f, "Movie")
+            },
+            | Self::Command => {
+                write!(f, "Command")
+            },
+        }
+    }
+}
+
+/// The timestamp in centiseconds precision.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssTimestamp;
+///
+/// let timestamp = AssTimestamp {
+///     hours: 1,
+///     minutes: 2,
+///     seconds: 3,
+///     centiseconds: 4,
+/// };
+///
+/// assert_eq!(
+///     timestamp.to_string(),
+///     "1:02:03.04".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AssTimestamp {
+    /// The hours.
+    pub hours: u32,
+    /// The minutes.
+    pub minutes: u8,
+    /// The seconds.
+    pub seconds: u8,
+    /// The centiseconds.
+    pub centiseconds: u8,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for AssTimestamp {
+    fn default() -> Self {
+        Self {
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+            centiseconds: 0,
+        }
+    }
+}
+
+impl Display for AssTimestamp {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{:02}:{:02}.{:02}",
+            self.hours, self.minutes, self.seconds, self.centiseconds
+        )
+    }
+}
+
+impl From<Duration> for AssTimestamp {
+    fn from(duration: Duration) -> Self {
+        let seconds = duration.as_secs();
+        let centiseconds = (duration.subsec_millis() / 10) as u8;
+
+        let hours = (seconds / 3600) as u32;
+        let minutes = ((seconds % 3600) / 60) as u8;
+        let seconds = (seconds % 60) as u8;
+
+        Self {
+            hours,
+            minutes,
+            seconds,
+            centiseconds,
+        }
+    }
+}
+
+impl From<AssTimestamp> for Duration {
+    fn from(timestamp: AssTimestamp) -> Self {
+        Duration::new(
+            timestamp.hours as u64 * 3600
+                + timestamp.minutes as u64 * 60
+                + timestamp.seconds as u64,
+            timestamp.centiseconds as u32 * 10_000_000,
+        )
+    }
+}
+
+/// Serializes the timestamp as a string such as `0:00:01.00`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for AssTimestamp {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes the timestamp from a string such as `0:00:01.00`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AssTimestamp {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        ass_parser::timestamp(&text).map_err(|err| {
+            serde::de::Error::custom(crate::ParseError::from(err))
+        })
+    }
+}
+
+/// The section other than script info, styles and events,
+/// such as `[Fonts]` and `[Graphics]`, kept as raw lines.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssSection;
+///
+/// let section = AssSection {
+///     name: "Fonts".to_string(),
+///     lines: vec!["fontname: font.ttf".to_string()],
+/// };
+///
+/// assert_eq!(
+///     section.to_string(),
+///     "[Fonts]\nfontname: font.ttf\n".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssSection {
+    /// The name of section without brackets.
+    pub name: String,
+    /// The raw lines of section.
+    pub lines: Vec<String>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for AssSection {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            lines: vec![],
+        }
+    }
+}
+
+impl Display for AssSection {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(f, "[{}]", self.name)?;
+
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The line other than the format and the style or event lines,
+/// such as a comment, a blank line or an unknown line, kept as written.
+///
+/// ## Example
+/// ```
+/// use subtp::ass::AssEvents;
+/// use subtp::ass::AssEvent;
+/// use subtp::ass::AssEventField;
+/// use subtp::ass::AssRawLine;
+///
+/// let events = AssEvents {
+///     format: vec![AssEventField::Text],
+///     events: vec![AssEvent {
+///         text: "Hello, world!".to_string(),
+///         ..Default::default()
+///     }],
+///     raw_lines: vec![AssRawLine {
+///         position: 1,
+///         line: ";Greeting".to_string(),
+///     }],
+/// };
+///
+/// assert_eq!(
+///     events.to_string(),
+///     "[Events]\nFormat: Text\n;Greeting\nDialogue: Hello, world!\n".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AssRawLine {
+    /// The number of the format line and the style or event lines before this line.
+    pub position: usize,
+    /// The line as written.
+    pub line: String,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for AssRawLine {
+    fn default() -> Self {
+        Self {
+            position: 0,
+            line: String::new(),
+        }
+    }
+}
+
+/// Writes the raw lines in order of their positions.
+struct RawLines<'a> {
+    lines: Vec<&'a AssRawLine>,
+    next: usize,
+}
+
+impl<'a> RawLines<'a> {
+    fn new(lines: &'a [AssRawLine]) -> Self {
+        let mut lines: Vec<_> = lines.iter().collect();
+        lines.sort_by_key(|line| line.position);
+        Self {
+            lines,
+            next: 0,
+        }
+    }
+
+    /// Writes the lines up to the given position.
+    fn write_until(
+        &mut self,
+        f: &mut Formatter<'_>,
+        position: usize,
+    ) -> std::fmt::Result {
+        while let Some(line) = self.lines.get(self.next) {
+            if line.position > position {
+                break;
+            }
+            writeln!(f, "{}", line.line)?;
+            self.next += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the rest of lines.
+    fn write_rest(
+        &mut self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        self.write_until(f, usize::MAX)
+    }
+}
+
+/// Joins the displayed items with the separator.
+fn join<T: Display>(
+    items: &[T],
+    separator: &str,
+) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Renders the boolean as `-1` for true and `0` for false.
+fn boolean(value: bool) -> String {
+    if value {
+        "-1".to_string()
+    } else {
+        "0".to_string()
+    }
+}
+
+/// Renders the floating number without fraction if possible.
+fn float(value: f32) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let text = r#"[Script Info]
+; Comment
+Title: Test
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, Bold
+Style: Default,Arial,20,&H00FFFFFF,-1
+
+[Events]
+Format: Layer, Start, End, Style, Text
+Dialogue: 0,0:00:01.00,0:00:02.50,Default,Hello, world!
+"#;
+
+        let expected = SubStationAlpha {
+            script_info: AssScriptInfo {
+                entries: vec![
+                    AssInfoEntry::Comment("; Comment".to_string()),
+                    AssInfoEntry::Property(
+                        "Title".to_string(),
+                        "Test".to_string(),
+                    ),
+                    AssInfoEntry::Property(
+                        "ScriptType".to_string(),
+                        "v4.00+".to_string(),
+                    ),
+                ],
+            },
+            styles: AssStyles {
+                version: AssVersion::V4Plus,
+                format: vec![
+                    AssStyleField::Name,
+                    AssStyleField::Fontname,
+                    AssStyleField::Fontsize,
+                    AssStyleField::PrimaryColour,
+                    AssStyleField::Bold,
+                ],
+                styles: vec![AssStyle {
+                    bold: true,
+                    ..Default::default()
+                }],
+                raw_lines: vec![],
+            },
+            events: AssEvents {
+                format: vec![
+                    AssEventField::Layer,
+                    AssEventField::Start,
+                    AssEventField::End,
+                    AssEventField::Style,
+                    AssEventField::Text,
+                ],
+                events: vec![AssEvent {
+                    start: AssTimestamp {
+                        seconds: 1,
+                        ..Default::default()
+                    },
+                    end: AssTimestamp {
+                        seconds: 2,
+                        centiseconds: 50,
+                        ..Default::default()
+                    },
+                    text: "Hello, world!".to_string(),
+                    ..Default::default()
+                }],
+                raw_lines: vec![],
+            },
+            sections: vec![],
+            order: vec![
+                AssSectionKind::ScriptInfo,
+                AssSectionKind::Styles,
+                AssSectionKind::Events,
+            ],
+        };
+
+        assert_eq!(
+            SubStationAlpha::parse(text).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn render() {
+        let ass = SubStationAlpha {
+            script_info: AssScriptInfo {
+                entries: vec![
+                    AssInfoEntry::Property(
+                        "Title".to_string(),
+                        "Test".to_string(),
+                    ),
+                ],
+            },
+            styles: AssStyles {
+                format: vec![
+                    AssStyleField::Name,
+                    AssStyleField::Bold,
+                ],
+                styles: vec![AssStyle {
+                    bold: true,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            events: AssEvents {
+                format: vec![
+                    AssEventField::Start,
+                    AssEventField::End,
+                    AssEventField::Text,
+                ],
+                events: vec![AssEvent {
+                    kind: AssEventKind::Comment,
+                    text: "Note".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            sections: vec![AssSection {
+                name: "Fonts".to_string(),
+                lines: vec![],
+            }],
+            ..Default::default()
+        };
+
+        let expected = r#"[Script Info]
+Title: Test
+
+[V4+ Styles]
+Format: Name, Bold
+Style: Default,-1
+
+[Events]
+Format: Start, End, Text
+Comment: 0:00:00.00,0:00:00.00,Note
+
+[Fonts]
+"#;
+        assert_eq!(ass.render(), expected);
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = r#"[Script Info]
+; Script generated by Aegisub 3.2.2
+; http://www.aegisub.org/
+;Edited by hand
+Title: Round trip
+ScriptType: v4.00+
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+YCbCr Matrix: TV.709
+PlayResX: 1920
+PlayResY: 1080
+
+[Aegisub Project Garbage]
+Last Style Storage: Default
+Video File: episode01.mkv
+Video AR Mode: 4
+Video AR Value: 1.777778
+Video Zoom Percent: 0.500000
+Active Line: 3
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+; Dialogue styles
+Style: Default,Arial,72,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,3.5,1.5,2,0100,0100,0060,1
+Style: Sign,Times New Roman,48,&H0000FFFF,&H000000FF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,2,0,8,10,10,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:00.00,Default,,0000,0000,0000,,Opening
+; Part A
+
+Dialogue: 0,0:00:01.00,0:00:04.00,Default,Alice,0000,0000,0000,,Hello, world!
+Dialogue: 1,0:00:05.50,0:00:07.25,Sign,,0010,0010,0040,,{\an8\i1}A sign{\i0}
+Dialogue: 0,0:01:02.03,0:01:05.00,Default,Bob,0,0,0,,Line one\NLine two
+
+[Fonts]
+fontname: custom.ttf
+M)\"
+
+[Graphics]
+filename: logo.png
+"#;
+
+        let ass = SubStationAlpha::parse(text).unwrap();
+        assert_eq!(
+            ass.events.events[2].margin_v,
+            AssMargin {
+                pixels: 40,
+                digits: 4,
+            }
+        );
+        assert_eq!(ass.render(), text);
+    }
+
+    #[test]
+    fn unknown_format_field() {
+        let text = r#"[Script Info]
+ScriptType: v4.00+
+
+[Events]
+Format: Layer, Start, End, Style, Text, Extra
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,Hello,world
+"#;
+
+        let ass = SubStationAlpha::parse(text).unwrap();
+        assert_eq!(
+            ass.events.format[5],
+            AssEventField::Other("Extra".to_string())
+        );
+        assert_eq!(ass.events.events[0].text, "Hello");
+        assert_eq!(ass.events.events[0].others, vec!["world".to_string()]);
+        assert_eq!(ass.render(), text);
+    }
+
+    #[test]
+    fn script_info() {
+        let mut script_info = AssScriptInfo::default();
+        assert_eq!(script_info.get("Title"), None);
+
+        script_info.set("Title", "A");
+        script_info.set("Title", "B");
+        assert_eq!(script_info.get("Title"), Some("B"));
+        assert_eq!(script_info.entries.len(), 1);
+    }
+
+    #[test]
+    fn color() {
+        let color = AssColor::from(0x00FF8040);
+        assert_eq!(
+            color,
+            AssColor {
+                red: 0x40,
+                green: 0x80,
+                blue: 0xFF,
+                alpha: 0,
+            }
+        );
+        assert_eq!(u32::from(color), 0x00FF8040);
+        assert_eq!(color.to_string(), "&H00FF8040");
+    }
+
+    #[test]
+    fn from_duration_to_timestamp() {
+        let duration = Duration::new(3661, 456_000_000);
+        let timestamp: AssTimestamp = duration.into();
+        assert_eq!(
+            timestamp,
+            AssTimestamp {
+                hours: 1,
+                minutes: 1,
+                seconds: 1,
+                centiseconds: 45,
+            }
+        );
+    }
+
+    #[test]
+    fn from_timestamp_to_duration() {
+        let timestamp = AssTimestamp {
+            hours: 1,
+            minutes: 1,
+            seconds: 1,
+            centiseconds: 45,
+        };
+        let duration: Duration = timestamp.into();
+        assert_eq!(
+            duration,
+            Duration::new(3661, 450_000_000)
+        );
+    }
+
+    #[test]
+    fn order_timestamp() {
+        let timestamp1 = AssTimestamp {
+            seconds: 59,
+            ..Default::default()
+        };
+        let timestamp2 = AssTimestamp {
+            minutes: 1,
+            ..Default::default()
+        };
+        assert!(timestamp1 < timestamp2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let timestamp = AssTimestamp {
+            hours: 1,
+            minutes: 2,
+            seconds: 3,
+            centiseconds: 4,
+        };
+
+        let json = serde_json::to_string(&timestamp).unwrap();
+        assert_eq!(json, r#""1:02:03.04""#);
+        assert_eq!(
+            serde_json::from_str::<AssTimestamp>(&json).unwrap(),
+            timestamp
+        );
+    }
+}
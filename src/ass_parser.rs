@@ -0,0 +1,852 @@
+//! A parser implementation for the Advanced SubStation Alpha format.
+
+pub(crate) use ass_parser::ass;
+#[cfg(feature = "serde")]
+pub(crate) use ass_parser::timestamp;
+
+use crate::ass::{
+    AssEvent, AssEventField, AssEventKind, AssInfoEntry, AssStyle,
+    AssStyleField,
+};
+
+peg::parser! {
+    /// The parser for Advanced SubStation Alpha format.
+    grammar ass_parser() for str {
+        use crate::ass::SubStationAlpha;
+        use crate::ass::AssScriptInfo;
+        use crate::ass::AssVersion;
+        use crate::ass::AssStyles;
+        use crate::ass::AssStyleField;
+        use crate::ass::AssColor;
+        use crate::ass::AssEvents;
+        use crate::ass::AssEventField;
+        use crate::ass::AssEventKind;
+        use crate::ass::AssTimestamp;
+        use crate::ass::AssSection;
+        use crate::ass::AssSectionKind;
+        use crate::ass::AssMargin;
+        use crate::ass::AssRawLine;
+        use super::Section;
+
+        /// Whitespace.
+        rule whitespace() = [' ' | '\t']
+
+        /// Newline.
+        rule newline() = "\r\n" / "\n" / "\r"
+
+        /// End of line or end of input.
+        rule end_of_line() = newline() / ![_]
+
+        /// Any-digit number.
+        rule number() -> u32
+            = n:$(['0'..='9']+) {?
+                n.parse().or(Err("number in u32"))
+            }
+
+        /// Two-digit number.
+        rule two_number() -> u8
+            = n:$(['0'..='9']['0'..='9']) {?
+                n.parse().or(Err("two-digit number"))
+            }
+
+        /// Unsigned integer.
+        pub(crate) rule integer() -> u32
+            = whitespace()* n:number() whitespace()* { n }
+
+        /// Signed floating number.
+        pub(crate) rule float() -> f32
+            = whitespace()* n:$("-"? ['0'..='9']+ ("." ['0'..='9']*)?) whitespace()* {?
+                n.parse().or(Err("float"))
+            }
+
+        /// Boolean of `-1` or `1` for true and `0` for false.
+        pub(crate) rule boolean() -> bool
+            = whitespace()* b:("-1" { true } / "1" { true } / "0" { false }) whitespace()* { b }
+
+        /// Color in `&HAABBGGRR` or decimal notation.
+        pub(crate) rule color() -> AssColor
+            = whitespace()* "&H" n:$(['0'..='9' | 'a'..='f' | 'A'..='F']*<1,8>) "&"? whitespace()* {?
+                u32::from_str_radix(n, 16)
+                    .map(AssColor::from)
+                    .or(Err("hexadecimal color"))
+            }
+            / whitespace()* n:$("-"? ['0'..='9']+) whitespace()* {?
+                n.parse::<i64>()
+                    .map(|n| AssColor::from(n as u32))
+                    .or(Err("decimal color"))
+            }
+
+        /// Marked flag of SubStation Alpha, e.g. `Marked=0`.
+        pub(crate) rule marked() -> bool
+            = whitespace()* "Marked=" n:number() whitespace()* { n != 0 }
+
+        /// Timestamp.
+        pub(crate) rule timestamp() -> AssTimestamp
+            = whitespace()* hours:number() ":" minutes:two_number() ":" seconds:two_number() "." centiseconds:two_number() whitespace()*
+            {
+                AssTimestamp {
+                    hours,
+                    minutes,
+                    seconds,
+                    centiseconds,
+                }
+            }
+
+        /// Blank line separating sections, not kept in the section before.
+        rule separator() = whitespace()* newline() "["
+
+        /// Line in a section kept as written.
+        rule line() -> &'input str
+            = !separator() !"[" l:$([^ '\r' | '\n']+) end_of_line() { l }
+                / !separator() newline() { "" }
+
+        /// Margin keeping the zero padding.
+        pub(crate) rule margin() -> AssMargin
+            = whitespace()* n:$(['0'..='9']+) whitespace()* {?
+                let pixels = n.parse::<u32>().or(Err("margin in u32"))?;
+                let digits = if n.len() > pixels.to_string().len() {
+                    u8::try_from(n.len()).or(Err("margin digits"))?
+                } else {
+                    0
+                };
+                Ok(AssMargin { pixels, digits })
+            }
+
+        /// The `[Script Info]` section.
+        pub(crate) rule script_info() -> AssScriptInfo
+            = "[Script Info]" whitespace()* end_of_line()
+                lines:line()*
+            {?
+                let entries = lines
+                    .into_iter()
+                    .map(super::script_info_entry)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(AssScriptInfo { entries })
+            }
+
+        rule styles_version() -> AssVersion
+            = "[V4+ Styles]" { AssVersion::V4Plus }
+                / "[V4 Styles]" { AssVersion::V4 }
+
+        /// The `[V4+ Styles]` or `[V4 Styles]` section.
+        pub(crate) rule styles() -> AssStyles
+            = version:styles_version() whitespace()* end_of_line()
+                lines:line()*
+            {?
+                let mut styles = AssStyles {
+                    version,
+                    format: vec![],
+                    styles: vec![],
+                    raw_lines: vec![],
+                };
+                let mut has_format = false;
+                for line in lines {
+                    match super::property(line) {
+                        | Some(("Format", names)) if !has_format => {
+                            styles.format = super::format(names, |name| {
+                                style_field(name).or(Err("style field name"))
+                            })?;
+                            has_format = true;
+                        },
+                        | Some(("Style", values)) if has_format => {
+                            styles.styles.push(super::style(&styles.format, values)?);
+                        },
+                        | Some(("Style", _)) => return Err("format line"),
+                        | _ => styles.raw_lines.push(AssRawLine {
+                            position: has_format as usize + styles.styles.len(),
+                            line: line.to_string(),
+                        }),
+                    }
+                }
+
+                if has_format {
+                    Ok(styles)
+                } else {
+                    Err("format line")
+                }
+            }
+
+        /// Field name of style.
+        pub(crate) rule style_field() -> AssStyleField
+            = n:$([_]+) {?
+                match n.to_ascii_lowercase().as_str() {
+                    "name" => Ok(AssStyleField::Name),
+                    "fontname" => Ok(AssStyleField::Fontname),
+                    "fontsize" => Ok(AssStyleField::Fontsize),
+                    "primarycolour" => Ok(AssStyleField::PrimaryColour),
+                    "secondarycolour" => Ok(AssStyleField::SecondaryColour),
+                    "outlinecolour" => Ok(AssStyleField::OutlineColour),
+                    "tertiarycolour" => Ok(AssStyleField::TertiaryColour),
+                    "backcolour" => Ok(AssStyleField::BackColour),
+                    "bold" => Ok(AssStyleField::Bold),
+                    "italic" => Ok(AssStyleField::Italic),
+                    "underline" => Ok(AssStyleField::Underline),
+                    "strikeout" => Ok(AssStyleField::StrikeOut),
+                    "scalex" => Ok(AssStyleField::ScaleX),
+                    "scaley" => Ok(AssStyleField::ScaleY),
+                    "spacing" => Ok(AssStyleField::Spacing),
+                    "angle" => Ok(AssStyleField::Angle),
+                    "borderstyle" => Ok(AssStyleField::BorderStyle),
+                    "outline" => Ok(AssStyleField::Outline),
+                    "shadow" => Ok(AssStyleField::Shadow),
+                    "alignment" => Ok(AssStyleField::Alignment),
+                    "marginl" => Ok(AssStyleField::MarginL),
+                    "marginr" => Ok(AssStyleField::MarginR),
+                    "marginv" => Ok(AssStyleField::MarginV),
+                    "alphalevel" => Ok(AssStyleField::AlphaLevel),
+                    "encoding" => Ok(AssStyleField::Encoding),
+                    _ => Ok(AssStyleField::Other(n.to_string())),
+                }
+            }
+
+        /// The `[Events]` section.
+        pub(crate) rule events() -> AssEvents
+            = "[Events]" whitespace()* end_of_line()
+                lines:line()*
+            {?
+                let mut events = AssEvents {
+                    format: vec![],
+                    events: vec![],
+                    raw_lines: vec![],
+                };
+                let mut has_format = false;
+                for line in lines {
+                    let property = super::property(line);
+                    let kind = property.and_then(|(key, _)| event_kind(key).ok());
+                    match (property, kind) {
+                        | (Some(("Format", names)), _) if !has_format => {
+                            events.format = super::format(names, |name| {
+                                event_field(name).or(Err("event field name"))
+                            })?;
+                            has_format = true;
+                        },
+                        | (Some((_, values)), Some(kind)) if has_format => {
+                            events.events.push(super::event(&events.format, kind, values)?);
+                        },
+                        | (_, Some(_)) => return Err("format line"),
+                        | _ => events.raw_lines.push(AssRawLine {
+                            position: has_format as usize + events.events.len(),
+                            line: line.to_string(),
+                        }),
+                    }
+                }
+
+                if has_format {
+                    Ok(events)
+                } else {
+                    Err("format line")
+                }
+            }
+
+        /// Field name of event.
+        pub(crate) rule event_field() -> AssEventField
+            = n:$([_]+) {?
+                match n.to_ascii_lowercase().as_str() {
+                    "layer" => Ok(AssEventField::Layer),
+                    "marked" => Ok(AssEventField::Marked),
+                    "start" => Ok(AssEventField::Start),
+                    "end" => Ok(AssEventField::End),
+                    "style" => Ok(AssEventField::Style),
+                    "name" | "actor" => Ok(AssEventField::Name),
+                    "marginl" => Ok(AssEventField::MarginL),
+                    "marginr" => Ok(AssEventField::MarginR),
+                    "marginv" => Ok(AssEventField::MarginV),
+                    "effect" => Ok(AssEventField::Effect),
+                    "text" => Ok(AssEventField::Text),
+                    _ => Ok(AssEventField::Other(n.to_string())),
+                }
+            }
+
+        /// Kind of event line.
+        pub(crate) rule event_kind() -> AssEventKind
+            = "Dialogue" { AssEventKind::Dialogue }
+                / "Comment" { AssEventKind::Comment }
+                / "Picture" { AssEventKind::Picture }
+                / "Sound" { AssEventKind::Sound }
+                / "Movie" { AssEventKind::Movie }
+                / "Command" { AssEventKind::Command }
+
+        /// Other section kept as raw lines.
+        pub(crate) rule section() -> AssSection
+            = "[" name:$([^ ']' | '\r' | '\n']+) "]" whitespace()* end_of_line()
+                lines:(line:line() { line.to_string() })*
+            {
+                AssSection {
+                    name: name.to_string(),
+                    lines,
+                }
+            }
+
+        rule any_section() -> Section
+            = styles:styles() { Section::Styles(styles) }
+                / events:events() { Section::Events(events) }
+                / section:section() { Section::Other(section) }
+
+        /// The entire Advanced SubStation Alpha.
+        pub(crate) rule ass() -> SubStationAlpha
+            = "\u{FEFF}"? (whitespace() / newline())*
+                script_info:script_info()
+                sections:((whitespace() / newline())* section:any_section() { section })*
+                (whitespace() / newline())*
+            {
+                let mut ass = SubStationAlpha {
+                    script_info,
+                    ..Default::default()
+                };
+                ass.order.push(AssSectionKind::ScriptInfo);
+                for section in sections {
+                    let kind = match section {
+                        | Section::Styles(styles) => {
+                            ass.styles = styles;
+                            AssSectionKind::Styles
+                        },
+                        | Section::Events(events) => {
+                            ass.events = events;
+                            AssSectionKind::Events
+                        },
+                        | Section::Other(section) => {
+                            ass.sections.push(section);
+                            AssSectionKind::Other(ass.sections.len() - 1)
+                        },
+                    };
+                    if !ass.order.contains(&kind) {
+                        ass.order.push(kind);
+                    }
+                }
+                ass
+            }
+    }
+}
+
+/// Any section of the Advanced SubStation Alpha.
+enum Section {
+    Styles(crate::ass::AssStyles),
+    Events(crate::ass::AssEvents),
+    Other(crate::ass::AssSection),
+}
+
+/// Splits the line into the key and the value of `Key: Value`.
+fn property(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+
+    Some((key, value.trim_start_matches([' ', '\t'])))
+}
+
+/// Builds the field names of the format line.
+fn format<T>(
+    names: &str,
+    field: impl Fn(&str) -> Result<T, &'static str>,
+) -> Result<Vec<T>, &'static str> {
+    names
+        .split(',')
+        .map(|name| field(name.trim()))
+        .collect()
+}
+
+/// Builds an entry of script info from the line.
+fn script_info_entry(line: &str) -> Result<AssInfoEntry, &'static str> {
+    if line.trim().is_empty() {
+        Ok(AssInfoEntry::Blank)
+    } else if line.starts_with(';') || line.starts_with("!:") {
+        Ok(AssInfoEntry::Comment(line.to_string()))
+    } else {
+        property(line)
+            .map(|(key, value)| {
+                AssInfoEntry::Property(
+                    key.to_string(),
+                    value.trim_end().to_string(),
+                )
+            })
+            .ok_or("script info property")
+    }
+}
+
+/// Builds a style from comma separated values in the given format.
+fn style(
+    format: &[AssStyleField],
+    values: &str,
+) -> Result<AssStyle, &'static str> {
+    let values: Vec<&str> = values.split(',').collect();
+    if values.len() != format.len() {
+        return Err("style values matching the format");
+    }
+
+    let mut style = AssStyle::default();
+    for (field, value) in format.iter().zip(values) {
+        let invalid = |_| "style value";
+        match field {
+            | AssStyleField::Name => style.name = value.trim().to_string(),
+            | AssStyleField::Fontname => {
+                style.fontname = value.trim().to_string()
+            },
+            | AssStyleField::Fontsize => {
+                style.fontsize = ass_parser::float(value).map_err(invalid)?
+            },
+            | AssStyleField::PrimaryColour => {
+                style.primary_color =
+                    ass_parser::color(value).map_err(invalid)?
+            },
+            | AssStyleField::SecondaryColour => {
+                style.secondary_color =
+                    ass_parser::color(value).map_err(invalid)?
+            },
+            | AssStyleField::OutlineColour | AssStyleField::TertiaryColour => {
+                style.outline_color =
+                    ass_parser::color(value).map_err(invalid)?
+            },
+            | AssStyleField::BackColour => {
+                style.back_color = ass_parser::color(value).map_err(invalid)?
+            },
+            | AssStyleField::Bold => {
+                style.bold = ass_parser::boolean(value).map_err(invalid)?
+            },
+            | AssStyleField::Italic => {
+                style.italic = ass_parser::boolean(value).map_err(invalid)?
+            },
+            | AssStyleField::Underline => {
+                style.underline = ass_parser::boolean(value).map_err(invalid)?
+            },
+            | AssStyleField::StrikeOut => {
+                style.strike_out =
+                    ass_parser::boolean(value).map_err(invalid)?
+            },
+            | AssStyleField::ScaleX => {
+                style.scale_x = ass_parser::float(value).map_err(invalid)?
+            },
+            | AssStyleField::ScaleY => {
+                style.scale_y = ass_parser::float(value).map_err(invalid)?
+            },
+            | AssStyleField::Spacing => {
+                style.spacing = ass_parser::float(value).map_err(invalid)?
+            },
+            | AssStyleField::Angle => {
+                style.angle = ass_parser::float(value).map_err(invalid)?
+            },
+            | AssStyleField::BorderStyle => {
+                style.border_style =
+                    ass_parser::integer(value).map_err(invalid)?
+            },
+            | AssStyleField::Outline => {
+                style.outline = ass_parser::float(value).map_err(invalid)?
+            },
+            | AssStyleField::Shadow => {
+                style.shadow = ass_parser::float(value).map_err(invalid)?
+            },
+            | AssStyleField::Alignment => {
+                style.alignment = ass_parser::integer(value).map_err(invalid)?
+            },
+            | AssStyleField::MarginL => {
+                style.margin_l = ass_parser::margin(value).map_err(invalid)?
+            },
+            | AssStyleField::MarginR => {
+                style.margin_r = ass_parser::margin(value).map_err(invalid)?
+            },
+            | AssStyleField::MarginV => {
+                style.margin_v = ass_parser::margin(value).map_err(invalid)?
+            },
+            | AssStyleField::AlphaLevel => {
+                style.alpha_level =
+                    ass_parser::integer(value).map_err(invalid)?
+            },
+            | AssStyleField::Encoding => {
+                style.encoding = ass_parser::integer(value).map_err(invalid)?
+            },
+            | AssStyleField::Other(_) => style.others.push(value.to_string()),
+        }
+    }
+
+    Ok(style)
+}
+
+/// Builds an event from comma separated values in the given format.
+///
+/// The last field takes the rest of values including commas.
+fn event(
+    format: &[AssEventField],
+    kind: AssEventKind,
+    values: &str,
+) -> Result<AssEvent, &'static str> {
+    let values: Vec<&str> = values
+        .splitn(format.len(), ',')
+        .collect();
+    if values.len() != format.len() {
+        return Err("event values matching the format");
+    }
+
+    let mut event = AssEvent {
+        kind,
+        style: String::new(),
+        ..Default::default()
+    };
+    for (field, value) in format.iter().zip(values) {
+        let invalid = |_| "event value";
+        match field {
+            | AssEventField::Layer => {
+                event.layer = ass_parser::integer(value).map_err(invalid)?
+            },
+            | AssEventField::Marked => {
+                event.marked = ass_parser::marked(value).map_err(invalid)?
+            },
+            | AssEventField::Start => {
+                event.start = ass_parser::timestamp(value).map_err(invalid)?
+            },
+            | AssEventField::End => {
+                event.end = ass_parser::timestamp(value).map_err(invalid)?
+            },
+            | AssEventField::Style => event.style = value.trim().to_string(),
+            | AssEventField::Name => event.name = value.trim().to_string(),
+            | AssEventField::MarginL => {
+                event.margin_l = ass_parser::margin(value).map_err(invalid)?
+            },
+            | AssEventField::MarginR => {
+                event.margin_r = ass_parser::margin(value).map_err(invalid)?
+            },
+            | AssEventField::MarginV => {
+                event.margin_v = ass_parser::margin(value).map_err(invalid)?
+            },
+            | AssEventField::Effect => event.effect = value.trim().to_string(),
+            | AssEventField::Text => event.text = value.to_string(),
+            | AssEventField::Other(_) => event.others.push(value.to_string()),
+        }
+    }
+
+    Ok(event)
+}
+
+#[cfg(test)]
+mod test {
+    use super::ass_parser;
+    use crate::ass::*;
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(
+            ass_parser::timestamp("0:00:00.00").unwrap(),
+            AssTimestamp::default()
+        );
+        assert_eq!(
+            ass_parser::timestamp("1:02:03.04").unwrap(),
+            AssTimestamp {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                centiseconds: 4,
+            }
+        );
+        assert_eq!(
+            ass_parser::timestamp("10:00:00.00").unwrap(),
+            AssTimestamp {
+                hours: 10,
+                ..Default::default()
+            }
+        );
+
+        // Invalid digits.
+        assert!(ass_parser::timestamp("0:0:00.00").is_err());
+        assert!(ass_parser::timestamp("0:00:00.000").is_err());
+        // Invalid separators.
+        assert!(ass_parser::timestamp("0:00:00,00").is_err());
+    }
+
+    #[test]
+    fn color() {
+        assert_eq!(
+            ass_parser::color("&H00FFFFFF").unwrap(),
+            AssColor {
+                red: 255,
+                green: 255,
+                blue: 255,
+                alpha: 0,
+            }
+        );
+        assert_eq!(
+            ass_parser::color("&HFF&").unwrap(),
+            AssColor {
+                red: 255,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            ass_parser::color("16777215").unwrap(),
+            AssColor {
+                red: 255,
+                green: 255,
+                blue: 255,
+                alpha: 0,
+            }
+        );
+
+        assert!(ass_parser::color("&H").is_err());
+        assert!(ass_parser::color("&HGG").is_err());
+        assert!(ass_parser::color("&H123456789").is_err());
+    }
+
+    #[test]
+    fn boolean() {
+        assert!(ass_parser::boolean("-1").unwrap());
+        assert!(ass_parser::boolean("1").unwrap());
+        assert!(!ass_parser::boolean("0").unwrap());
+        assert!(ass_parser::boolean("2").is_err());
+    }
+
+    #[test]
+    fn script_info() {
+        assert_eq!(
+            ass_parser::script_info(
+                "[Script Info]\n; Comment\n!: Old comment\nTitle: Test\n\nScriptType:v4.00+\n"
+            )
+            .unwrap(),
+            AssScriptInfo {
+                entries: vec![
+                    AssInfoEntry::Comment("; Comment".to_string()),
+                    AssInfoEntry::Comment("!: Old comment".to_string()),
+                    AssInfoEntry::Property(
+                        "Title".to_string(),
+                        "Test".to_string()
+                    ),
+                    AssInfoEntry::Blank,
+                    AssInfoEntry::Property(
+                        "ScriptType".to_string(),
+                        "v4.00+".to_string()
+                    ),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn styles() {
+        assert_eq!(
+            ass_parser::styles(
+                "[V4 Styles]\nFormat: Name, Fontname, TertiaryColour, AlphaLevel\nStyle: Sign,Times, 65535,128\n"
+            )
+            .unwrap(),
+            AssStyles {
+                version: AssVersion::V4,
+                format: vec![
+                    AssStyleField::Name,
+                    AssStyleField::Fontname,
+                    AssStyleField::TertiaryColour,
+                    AssStyleField::AlphaLevel,
+                ],
+                styles: vec![AssStyle {
+                    name: "Sign".to_string(),
+                    fontname: "Times".to_string(),
+                    outline_color: AssColor {
+                        red: 255,
+                        green: 255,
+                        blue: 0,
+                        alpha: 0,
+                    },
+                    alpha_level: 128,
+                    ..Default::default()
+                }],
+                raw_lines: vec![],
+            }
+        );
+
+        // Comments, blank lines and unknown fields.
+        assert_eq!(
+            ass_parser::styles(
+                "[V4+ Styles]\n;Before\nFormat: Name, Unknown\n\nStyle: Default,0\nFoo: Bar\n"
+            )
+            .unwrap(),
+            AssStyles {
+                version: AssVersion::V4Plus,
+                format: vec![
+                    AssStyleField::Name,
+                    AssStyleField::Other("Unknown".to_string()),
+                ],
+                styles: vec![AssStyle {
+                    others: vec!["0".to_string()],
+                    ..Default::default()
+                }],
+                raw_lines: vec![
+                    AssRawLine {
+                        position: 0,
+                        line: ";Before".to_string(),
+                    },
+                    AssRawLine {
+                        position: 1,
+                        line: "".to_string(),
+                    },
+                    AssRawLine {
+                        position: 2,
+                        line: "Foo: Bar".to_string(),
+                    },
+                ],
+            }
+        );
+        // Missing format.
+        assert!(ass_parser::styles("[V4+ Styles]\nStyle: Default\n").is_err());
+        // Mismatched number of values.
+        assert!(ass_parser::styles(
+            "[V4+ Styles]\nFormat: Name, Bold\nStyle: Default\n"
+        )
+        .is_err());
+        // Invalid value.
+        assert!(ass_parser::styles(
+            "[V4+ Styles]\nFormat: Name, Bold\nStyle: Default,yes\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn events() {
+        assert_eq!(
+            ass_parser::events(
+                "[Events]\nFormat: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: Marked=1,0:00:01.00,0:00:02.00,*Default,Bob,0010,0020,0030,,A, B, {\\i1}C\n"
+            )
+            .unwrap(),
+            AssEvents {
+                format: AssEventField::V4.to_vec(),
+                events: vec![AssEvent {
+                    kind: AssEventKind::Dialogue,
+                    marked: true,
+                    start: AssTimestamp {
+                        seconds: 1,
+                        ..Default::default()
+                    },
+                    end: AssTimestamp {
+                        seconds: 2,
+                        ..Default::default()
+                    },
+                    style: "*Default".to_string(),
+                    name: "Bob".to_string(),
+                    margin_l: AssMargin {
+                        pixels: 10,
+                        digits: 4,
+                    },
+                    margin_r: AssMargin {
+                        pixels: 20,
+                        digits: 4,
+                    },
+                    margin_v: AssMargin {
+                        pixels: 30,
+                        digits: 4,
+                    },
+                    text: "A, B, {\\i1}C".to_string(),
+                    ..Default::default()
+                }],
+                raw_lines: vec![],
+            }
+        );
+
+        // Comments and unknown fields.
+        assert_eq!(
+            ass_parser::events(
+                "[Events]\nFormat: Start, Extra, Text\n; Comment\nDialogue: 0:00:01.00,x,A\n"
+            )
+            .unwrap(),
+            AssEvents {
+                format: vec![
+                    AssEventField::Start,
+                    AssEventField::Other("Extra".to_string()),
+                    AssEventField::Text,
+                ],
+                events: vec![AssEvent {
+                    start: AssTimestamp {
+                        seconds: 1,
+                        ..Default::default()
+                    },
+                    style: String::new(),
+                    text: "A".to_string(),
+                    others: vec!["x".to_string()],
+                    ..Default::default()
+                }],
+                raw_lines: vec![AssRawLine {
+                    position: 1,
+                    line: "; Comment".to_string(),
+                }],
+            }
+        );
+
+        // Invalid timestamp.
+        assert!(ass_parser::events(
+            "[Events]\nFormat: Start, Text\nDialogue: 0:00:01,A\n"
+        )
+        .is_err());
+        // Missing values.
+        assert!(ass_parser::events(
+            "[Events]\nFormat: Start, End, Text\nDialogue: 0:00:01.00\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn section() {
+        assert_eq!(
+            ass_parser::section("[Fonts]\nfontname: a.ttf\n\nM)\"\n").unwrap(),
+            AssSection {
+                name: "Fonts".to_string(),
+                lines: vec![
+                    "fontname: a.ttf".to_string(),
+                    "".to_string(),
+                    "M)\"".to_string()
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn ass() {
+        let text = "\u{FEFF}[Script Info]\r\nScriptType: v4.00+\r\n\r\n[Aegisub Project Garbage]\r\nVideo File: a.mkv\r\n\r\n[Events]\r\nFormat: Layer, Start, End, Style, Text\r\nComment: 0,0:00:00.00,0:00:01.00,Default,A\r\n\r\n[V4+ Styles]\r\nFormat: Name\r\nStyle: Default\r\n";
+
+        assert_eq!(
+            ass_parser::ass(text).unwrap(),
+            SubStationAlpha {
+                script_info: AssScriptInfo {
+                    entries: vec![
+                        AssInfoEntry::Property(
+                            "ScriptType".to_string(),
+                            "v4.00+".to_string()
+                        )
+                    ],
+                },
+                styles: AssStyles {
+                    version: AssVersion::V4Plus,
+                    format: vec![AssStyleField::Name],
+                    styles: vec![AssStyle::default()],
+                    raw_lines: vec![],
+                },
+                events: AssEvents {
+                    format: vec![
+                        AssEventField::Layer,
+                        AssEventField::Start,
+                        AssEventField::End,
+                        AssEventField::Style,
+                        AssEventField::Text,
+                    ],
+                    events: vec![AssEvent {
+                        kind: AssEventKind::Comment,
+                        end: AssTimestamp {
+                            seconds: 1,
+                            ..Default::default()
+                        },
+                        text: "A".to_string(),
+                        ..Default::default()
+                    }],
+                    raw_lines: vec![],
+                },
+                sections: vec![AssSection {
+                    name: "Aegisub Project Garbage".to_string(),
+                    lines: vec!["Video File: a.mkv".to_string()],
+                }],
+                order: vec![
+                    AssSectionKind::ScriptInfo,
+                    AssSectionKind::Other(0),
+                    AssSectionKind::Events,
+                    AssSectionKind::Styles,
+                ],
+            }
+        );
+
+        // Missing script info.
+        assert!(ass_parser::ass("[Events]\nFormat: Text\n").is_err());
+    }
+}
@@ -0,0 +1,609 @@
+//! The command-line tool for subtitle files.
+//!
+//! ```shell
+//! $ cargo install subtp --features cli
+//! $ subtp convert input.srt output.vtt
+//! $ subtp validate *.srt *.vtt
+//! $ subtp shift +1.5s input.srt -o output.srt
+//! $ subtp renumber input.srt -o output.srt
+//! $ subtp stats input.vtt
+//! ```
+//!
+//! ## Exit codes
+//! - `0`: Succeeded.
+//! - `1`: The input is invalid, e.g. a parse error or an unsupported conversion.
+//! - `2`: The command line is invalid.
+//! - `3`: Failed to read or write a file.
+
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use subtp::ass::SubStationAlpha;
+use subtp::srt::SubRip;
+use subtp::subtitles::Subtitles;
+use subtp::timing::{TimeOffset, TimingError};
+use subtp::ttml::Ttml;
+use subtp::vtt::WebVtt;
+
+#[derive(Parser)]
+#[command(
+    name = "subtp",
+    version,
+    about = "Convert, validate and edit subtitle files."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Converts the subtitle file into another format.
+    Convert {
+        /// The input file, or `-` for the standard input.
+        input: PathBuf,
+        /// The output file, or `-` for the standard output.
+        output: PathBuf,
+        /// The format of the input, detected from the extension by default.
+        #[arg(long)]
+        from: Option<Format>,
+        /// The format of the output, detected from the extension by default.
+        #[arg(long)]
+        to: Option<Format>,
+    },
+    /// Validates the subtitle files, reporting the parse errors.
+    Validate {
+        /// The input files, or `-` for the standard input.
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// The format of the inputs, detected from the extensions by default.
+        #[arg(long)]
+        from: Option<Format>,
+    },
+    /// Shifts the timestamps by the offset, e.g. `+1.5s`, `-500ms` or `-00:00:01,000`.
+    Shift {
+        /// The offset to shift.
+        #[arg(allow_hyphen_values = true)]
+        offset: String,
+        /// The input file, or `-` for the standard input.
+        input: PathBuf,
+        /// The output file, the standard output by default.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The format of the input, detected from the extension by default.
+        #[arg(long)]
+        from: Option<Format>,
+    },
+    /// Renumbers the sequence numbers of the SubRip Subtitle from 1.
+    Renumber {
+        /// The input file, or `-` for the standard input.
+        input: PathBuf,
+        /// The output file, the standard output by default.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Prints the statistics of the subtitle file.
+    Stats {
+        /// The input file, or `-` for the standard input.
+        input: PathBuf,
+        /// The format of the input, detected from the extension by default.
+        #[arg(long)]
+        from: Option<Format>,
+    },
+}
+
+/// The subtitle format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// SubRip Subtitle (.srt).
+    Srt,
+    /// WebVTT (.vtt).
+    Vtt,
+    /// Advanced SubStation Alpha (.ass/.ssa).
+    Ass,
+    /// Timed Text Markup Language (.ttml/.dfxp).
+    Ttml,
+}
+
+impl Format {
+    fn detect(
+        path: &Path,
+        format: Option<Format>,
+    ) -> Result<Self, Failure> {
+        if let Some(format) = format {
+            return Ok(format);
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            | Some("srt") => Ok(Self::Srt),
+            | Some("vtt") => Ok(Self::Vtt),
+            | Some("ass") | Some("ssa") => Ok(Self::Ass),
+            | Some("ttml") | Some("dfxp") | Some("xml") => Ok(Self::Ttml),
+            | _ => {
+                Err(Failure::Usage(format!(
+                    "unknown format of {}, use --from or --to",
+                    path.display()
+                )))
+            },
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Srt => write!(f, "srt"),
+            | Self::Vtt => write!(f, "vtt"),
+            | Self::Ass => write!(f, "ass"),
+            | Self::Ttml => write!(f, "ttml"),
+        }
+    }
+}
+
+/// The parsed subtitle document.
+enum Document {
+    Srt(SubRip),
+    Vtt(WebVtt),
+    Ass(SubStationAlpha),
+    Ttml(Ttml),
+}
+
+impl Document {
+    fn parse(
+        path: &Path,
+        format: Format,
+    ) -> Result<Self, Failure> {
+        let text = read(path)?;
+        let result = match format {
+            | Format::Srt => SubRip::parse(&text).map(Self::Srt),
+            | Format::Vtt => WebVtt::parse(&text).map(Self::Vtt),
+            | Format::Ass => SubStationAlpha::parse(&text).map(Self::Ass),
+            | Format::Ttml => Ttml::parse(&text).map(Self::Ttml),
+        };
+
+        result.map_err(|err| {
+            Failure::Invalid(format!(
+                "{}: {}\n{}",
+                path.display(),
+                err,
+                err.snippet(&text)
+            ))
+        })
+    }
+
+    fn render(&self) -> String {
+        match self {
+            | Self::Srt(srt) => srt.render(),
+            | Self::Vtt(vtt) => vtt.render(),
+            | Self::Ass(ass) => ass.render(),
+            | Self::Ttml(ttml) => ttml.render(),
+        }
+    }
+
+    /// Converts into the format-agnostic model, printing the losses.
+    fn subtitles(&self) -> Result<Subtitles, Failure> {
+        let conversion = match self {
+            | Self::Srt(srt) => Subtitles::from_sub_rip(srt),
+            | Self::Vtt(vtt) => {
+                Subtitles::from_web_vtt(vtt)
+                    .map_err(|err| Failure::Invalid(err.to_string()))?
+            },
+            | Self::Ttml(ttml) => {
+                Subtitles::from_web_vtt(&WebVtt::from(ttml))
+                    .map_err(|err| Failure::Invalid(err.to_string()))?
+            },
+            | Self::Ass(_) => {
+                return Err(Failure::Invalid(
+                    "conversion from ass is not supported".to_string(),
+                ))
+            },
+        };
+        for loss in &conversion.losses {
+            eprintln!("warning: lost {}", loss);
+        }
+
+        Ok(conversion.value)
+    }
+}
+
+/// The failure of the command with the exit code.
+enum Failure {
+    Invalid(String),
+    Usage(String),
+    Io(PathBuf, std::io::Error),
+}
+
+impl Failure {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            | Self::Invalid(_) => ExitCode::from(1),
+            | Self::Usage(_) => ExitCode::from(2),
+            | Self::Io(..) => ExitCode::from(3),
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Invalid(message) => write!(f, "{}", message),
+            | Self::Usage(message) => write!(f, "{}", message),
+            | Self::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+fn read(path: &Path) -> Result<String, Failure> {
+    if is_stdio(path) {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|err| Failure::Io(path.to_path_buf(), err))?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|err| Failure::Io(path.to_path_buf(), err))
+    }
+}
+
+fn write(
+    path: Option<&Path>,
+    text: &str,
+) -> Result<(), Failure> {
+    match path {
+        | Some(path) if !is_stdio(path) => {
+            std::fs::write(path, text)
+                .map_err(|err| Failure::Io(path.to_path_buf(), err))
+        },
+        | _ => {
+            std::io::stdout()
+                .write_all(text.as_bytes())
+                .map_err(|err| Failure::Io(PathBuf::from("-"), err))
+        },
+    }
+}
+
+/// Parses the signed offset such as `+1.5s`, `-500ms`, `-00:00:01,000` or `01:02.5`.
+fn parse_offset(text: &str) -> Result<TimeOffset, Failure> {
+    let invalid = || Failure::Usage(format!("invalid offset: {}", text));
+
+    let (negative, value) = match text.strip_prefix('-') {
+        | Some(value) => (true, value),
+        | None => {
+            (
+                false,
+                text.strip_prefix('+')
+                    .unwrap_or(text),
+            )
+        },
+    };
+    let seconds = |value: &str| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+    };
+
+    let duration = if let Some(milliseconds) = value.strip_suffix("ms") {
+        seconds(milliseconds).map(|milliseconds| milliseconds / 1000.0)
+    } else if let Some(value) = value.strip_suffix('s') {
+        seconds(value)
+    } else {
+        let mut total = 0.0;
+        for (i, field) in value
+            .replace(',', ".")
+            .split(':')
+            .enumerate()
+        {
+            if i > 2 {
+                return Err(invalid());
+            }
+            total = total * 60.0 + seconds(field).ok_or_else(invalid)?;
+        }
+        Some(total)
+    }
+    .ok_or_else(invalid)?;
+
+    Ok(TimeOffset {
+        negative,
+        duration: Duration::try_from_secs_f64(duration)
+            .map_err(|_| invalid())?,
+    })
+}
+
+fn convert(
+    input: &Path,
+    output: &Path,
+    from: Option<Format>,
+    to: Option<Format>,
+) -> Result<(), Failure> {
+    let from = Format::detect(input, from)?;
+    let to = Format::detect(output, to)?;
+    let document = Document::parse(input, from)?;
+
+    let rendered = match (&document, to) {
+        | (..) if from == to => document.render(),
+        | (Document::Ttml(ttml), Format::Srt) => SubRip::from(ttml).render(),
+        | (Document::Ttml(ttml), Format::Vtt) => WebVtt::from(ttml).render(),
+        | (_, Format::Srt) => {
+            let conversion = document
+                .subtitles()?
+                .to_sub_rip();
+            for loss in &conversion.losses {
+                eprintln!("warning: lost {}", loss);
+            }
+            conversion.value.render()
+        },
+        | (_, Format::Vtt) => {
+            let conversion = document
+                .subtitles()?
+                .to_web_vtt();
+            for loss in &conversion.losses {
+                eprintln!("warning: lost {}", loss);
+            }
+            conversion.value.render()
+        },
+        | (_, to) => {
+            return Err(Failure::Invalid(format!(
+                "conversion to {} is not supported",
+                to
+            )))
+        },
+    };
+
+    write(Some(output), &rendered)
+}
+
+fn validate(
+    inputs: &[PathBuf],
+    from: Option<Format>,
+) -> Result<(), Failure> {
+    let mut invalid = 0;
+    for input in inputs {
+        let format = Format::detect(input, from)?;
+        match Document::parse(input, format) {
+            | Ok(_) => println!("{}: ok", input.display()),
+            | Err(Failure::Invalid(message)) => {
+                eprintln!("{}", message);
+                invalid += 1;
+            },
+            | Err(failure) => return Err(failure),
+        }
+    }
+
+    if invalid > 0 {
+        Err(Failure::Invalid(format!(
+            "{} of {} files are invalid",
+            invalid,
+            inputs.len()
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+fn shift(
+    offset: &str,
+    input: &Path,
+    output: Option<&Path>,
+    from: Option<Format>,
+) -> Result<(), Failure> {
+    let offset = parse_offset(offset)?;
+    let format = Format::detect(input, from)?;
+    let invalid = |err: TimingError| Failure::Invalid(err.to_string());
+
+    let rendered = match Document::parse(input, format)? {
+        | Document::Srt(mut srt) => {
+            srt.shift_saturating(offset)
+                .map_err(invalid)?;
+            srt.render()
+        },
+        | Document::Vtt(mut vtt) => {
+            vtt.shift_saturating(offset)
+                .map_err(invalid)?;
+            vtt.render()
+        },
+        | _ => {
+            return Err(Failure::Invalid(format!(
+                "shift of {} is not supported",
+                format
+            )))
+        },
+    };
+
+    write(output, &rendered)
+}
+
+fn renumber(
+    input: &Path,
+    output: Option<&Path>,
+) -> Result<(), Failure> {
+    let Document::Srt(mut srt) = Document::parse(input, Format::Srt)? else {
+        unreachable!()
+    };
+    for (i, subtitle) in srt
+        .subtitles
+        .iter_mut()
+        .enumerate()
+    {
+        subtitle.sequence = i as u32 + 1;
+    }
+
+    write(output, &srt.render())
+}
+
+fn stats(
+    input: &Path,
+    from: Option<Format>,
+) -> Result<(), Failure> {
+    let format = Format::detect(input, from)?;
+    let subtitles = Document::parse(input, format)?.subtitles()?;
+    let cues = &subtitles.cues;
+
+    let start = cues
+        .iter()
+        .map(|cue| cue.start)
+        .min()
+        .unwrap_or_default();
+    let end = cues
+        .iter()
+        .map(|cue| cue.end)
+        .max()
+        .unwrap_or_default();
+    let displayed = cues
+        .iter()
+        .map(|cue| {
+            cue.end
+                .saturating_sub(cue.start)
+        })
+        .sum::<Duration>();
+    let max_line = cues
+        .iter()
+        .flat_map(|cue| {
+            cue.plain_text()
+                .lines()
+                .map(|line| line.chars().count())
+                .collect::<Vec<_>>()
+        })
+        .max()
+        .unwrap_or(0);
+    let max_cps = cues
+        .iter()
+        .filter(|cue| cue.end > cue.start)
+        .map(|cue| {
+            let characters = cue
+                .plain_text()
+                .chars()
+                .filter(|c| *c != '\n')
+                .count();
+            characters as f64 / (cue.end - cue.start).as_secs_f64()
+        })
+        .fold(0.0, f64::max);
+
+    println!("format: {}", format);
+    println!("cues: {}", cues.len());
+    println!("start: {:.3}s", start.as_secs_f64());
+    println!("end: {:.3}s", end.as_secs_f64());
+    println!(
+        "displayed: {:.3}s",
+        displayed.as_secs_f64()
+    );
+    println!("max characters per line: {}", max_line);
+    println!(
+        "max characters per second: {:.1}",
+        max_cps
+    );
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match &cli.command {
+        | Command::Convert {
+            input,
+            output,
+            from,
+            to,
+        } => convert(input, output, *from, *to),
+        | Command::Validate {
+            inputs,
+            from,
+        } => validate(inputs, *from),
+        | Command::Shift {
+            offset,
+            input,
+            output,
+            from,
+        } => shift(offset, input, output.as_deref(), *from),
+        | Command::Renumber {
+            input,
+            output,
+        } => renumber(input, output.as_deref()),
+        | Command::Stats {
+            input,
+            from,
+        } => stats(input, *from),
+    };
+
+    match result {
+        | Ok(()) => ExitCode::SUCCESS,
+        | Err(failure) => {
+            eprintln!("error: {}", failure);
+            failure.exit_code()
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_offset() {
+        let offset = |text| {
+            super::parse_offset(text)
+                .ok()
+                .map(|offset| {
+                    (
+                        offset.negative,
+                        offset.duration.as_millis(),
+                    )
+                })
+        };
+
+        assert_eq!(offset("+1.5s"), Some((false, 1500)));
+        assert_eq!(offset("1.5s"), Some((false, 1500)));
+        assert_eq!(offset("-500ms"), Some((true, 500)));
+        assert_eq!(
+            offset("-00:00:01,250"),
+            Some((true, 1250))
+        );
+        assert_eq!(offset("01:02.5"), Some((false, 62500)));
+        assert_eq!(offset("1:00:00:00"), None);
+        assert_eq!(offset("abc"), None);
+        assert_eq!(offset("--1s"), None);
+        // Too large for a duration.
+        assert_eq!(offset("1e30s"), None);
+        assert!(matches!(
+            super::parse_offset("1e30s"),
+            Err(Failure::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(
+            Format::detect(Path::new("a.SRT"), None).ok(),
+            Some(Format::Srt)
+        );
+        assert_eq!(
+            Format::detect(Path::new("a.dfxp"), None).ok(),
+            Some(Format::Ttml)
+        );
+        assert_eq!(
+            Format::detect(Path::new("-"), Some(Format::Vtt)).ok(),
+            Some(Format::Vtt)
+        );
+        assert!(Format::detect(Path::new("a.txt"), None).is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! A reader of blank-line separated blocks over [`BufRead`] for the streaming parsers.
+
+use std::io::{BufRead, ErrorKind};
+
+use crate::ParseError;
+
+/// A blank-line separated block of the input.
+pub(crate) struct Block {
+    /// The lines of the block with the newlines.
+    pub(crate) text: String,
+    /// The length of the text up to the last line that is not whitespace-only.
+    content: usize,
+    /// The line number of the first line starting from 1.
+    pub(crate) line: usize,
+    /// The byte offset of the first line.
+    pub(crate) offset: usize,
+    /// The index of the block starting from 0.
+    pub(crate) index: usize,
+}
+
+impl Block {
+    /// Returns the first line of the block without the newline.
+    pub(crate) fn first_line(&self) -> &str {
+        self.text
+            .split(['\r', '\n'])
+            .next()
+            .unwrap_or("")
+    }
+
+    /// Returns the text without the whitespace-only lines at the end.
+    pub(crate) fn trim_end_whitespace_lines(&self) -> &str {
+        &self.text[..self.content]
+    }
+
+    /// Relocates the parse error in the block to the position in the whole input.
+    pub(crate) fn relocate(
+        &self,
+        mut err: ParseError,
+    ) -> ParseError {
+        err.line += self.line - 1;
+        err.offset += self.offset;
+        err
+    }
+}
+
+/// Reads blank-line separated blocks one at a time,
+/// keeping only the current block in memory.
+pub(crate) struct BlockReader<R> {
+    inner: R,
+    /// The buffer of the current line.
+    buffer: String,
+    /// The number of lines read.
+    line: usize,
+    /// The number of bytes read.
+    offset: usize,
+    /// The number of blocks read.
+    index: usize,
+}
+
+impl<R: BufRead> BlockReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: String::new(),
+            line: 0,
+            offset: 0,
+            index: 0,
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads the next block, or returns `None` at the end of the input.
+    pub(crate) fn next_block(&mut self) -> std::io::Result<Option<Block>> {
+        let mut block: Option<Block> = None;
+
+        loop {
+            let read = self.read_line()?;
+            if read == 0 {
+                break;
+            }
+
+            let mut start = self.offset;
+            let mut line = self.buffer.as_str();
+            if self.line == 0 {
+                if let Some(stripped) = line.strip_prefix('\u{FEFF}') {
+                    start += '\u{FEFF}'.len_utf8();
+                    line = stripped;
+                }
+            }
+            self.line += 1;
+            self.offset += read;
+
+            // Only an empty line separates blocks as in the grammars,
+            // and the whitespace-only lines before a block are skipped.
+            let text = line.trim_end_matches(['\r', '\n']);
+            if text.is_empty() {
+                if block.is_some() {
+                    break;
+                }
+                continue;
+            }
+            if block.is_none() && is_whitespace(text) {
+                continue;
+            }
+
+            let block = block.get_or_insert_with(|| {
+                Block {
+                    text: String::new(),
+                    content: 0,
+                    line: self.line,
+                    offset: start,
+                    index: self.index,
+                }
+            });
+            block.text.push_str(line);
+            if !line.ends_with(['\r', '\n']) {
+                block.text.push('\n');
+            }
+            if !is_whitespace(text) {
+                block.content = block.text.len();
+            }
+        }
+
+        if block.is_some() {
+            self.index += 1;
+        }
+
+        Ok(block)
+    }
+
+    /// Reads the next line ending with `\r\n`, `\n` or `\r` into the buffer,
+    /// returning the number of bytes read.
+    fn read_line(&mut self) -> std::io::Result<usize> {
+        let mut bytes = vec![];
+        loop {
+            let available = match self.inner.fill_buf() {
+                | Ok(available) => available,
+                | Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                | Err(err) => return Err(err),
+            };
+            if available.is_empty() {
+                break;
+            }
+
+            match memchr::memchr2(b'\n', b'\r', available) {
+                | Some(i) => {
+                    let newline = available[i];
+                    bytes.extend_from_slice(&available[..=i]);
+                    self.inner.consume(i + 1);
+                    if newline == b'\r' && self.next_byte()? == Some(b'\n') {
+                        bytes.push(b'\n');
+                        self.inner.consume(1);
+                    }
+                    break;
+                },
+                | None => {
+                    let length = available.len();
+                    bytes.extend_from_slice(available);
+                    self.inner.consume(length);
+                },
+            }
+        }
+
+        let read = bytes.len();
+        self.buffer = String::from_utf8(bytes).map_err(|err| {
+            std::io::Error::new(ErrorKind::InvalidData, err)
+        })?;
+
+        Ok(read)
+    }
+
+    /// Peeks the next byte without consuming it.
+    fn next_byte(&mut self) -> std::io::Result<Option<u8>> {
+        loop {
+            match self.inner.fill_buf() {
+                | Ok(available) => return Ok(available.first().copied()),
+                | Err(err) if err.kind() == ErrorKind::Interrupted => {},
+                | Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Whether the line has only spaces and tabs.
+fn is_whitespace(line: &str) -> bool {
+    line.chars()
+        .all(|c| c == ' ' || c == '\t')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn next_block() {
+        let input = "\u{FEFF}a\r\nb\n\n  \n\nc\nd";
+        let mut reader = BlockReader::new(input.as_bytes());
+
+        let block = reader
+            .next_block()
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.text, "a\r\nb\n");
+        assert_eq!(block.line, 1);
+        assert_eq!(block.offset, 3);
+        assert_eq!(block.index, 0);
+        assert_eq!(block.first_line(), "a");
+
+        let block = reader
+            .next_block()
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.text, "c\nd\n");
+        assert_eq!(block.line, 6);
+        assert_eq!(block.offset, 13);
+        assert_eq!(block.index, 1);
+
+        assert!(reader
+            .next_block()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn next_block_cr_only() {
+        let input = "a\rb\r\r\rc\r\nd\r";
+        // Split the input so that `\r\n` straddles the buffers.
+        let mut reader =
+            BlockReader::new(std::io::BufReader::with_capacity(
+                8,
+                input.as_bytes(),
+            ));
+
+        let block = reader
+            .next_block()
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.text, "a\rb\r");
+        assert_eq!(block.line, 1);
+        assert_eq!(block.offset, 0);
+        assert_eq!(block.first_line(), "a");
+
+        let block = reader
+            .next_block()
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.text, "c\r\nd\r");
+        assert_eq!(block.line, 5);
+        assert_eq!(block.offset, 6);
+
+        assert!(reader
+            .next_block()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn next_block_whitespace_lines() {
+        let input = " \n\na\n \n\u{A0}\n\u{3000}\r\nb\n\t\n\nc";
+        let mut reader = BlockReader::new(input.as_bytes());
+
+        let block = reader
+            .next_block()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            block.text,
+            "a\n \n\u{A0}\n\u{3000}\r\nb\n\t\n"
+        );
+        assert_eq!(block.line, 3);
+        assert_eq!(
+            block.trim_end_whitespace_lines(),
+            "a\n \n\u{A0}\n\u{3000}\r\nb\n"
+        );
+
+        let block = reader
+            .next_block()
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.text, "c\n");
+        assert_eq!(block.line, 10);
+    }
+}
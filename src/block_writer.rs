@@ -0,0 +1,91 @@
+//! Helpers to write rendered blocks to [`Write`] for the streaming renderers.
+
+use std::io::Write;
+
+/// The line ending of the streaming renderers.
+///
+/// ## Example
+/// ```
+/// use subtp::LineEnding;
+///
+/// assert_eq!(LineEnding::default(), LineEnding::Lf);
+/// assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum LineEnding {
+    /// `\n`, used by the [`Display`](std::fmt::Display) renderers.
+    Lf,
+    /// `\r\n`, used by many Windows tools.
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the characters of the line ending.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            | Self::Lf => "\n",
+            | Self::CrLf => "\r\n",
+        }
+    }
+}
+
+impl Default for LineEnding {
+    fn default() -> Self {
+        Self::Lf
+    }
+}
+
+/// Writes the rendered text replacing each `\n` with the line ending.
+pub(crate) fn write_text<W: Write>(
+    writer: &mut W,
+    text: &str,
+    line_ending: LineEnding,
+) -> std::io::Result<()> {
+    if line_ending == LineEnding::Lf {
+        return writer.write_all(text.as_bytes());
+    }
+
+    for line in text.split_inclusive('\n') {
+        match line.strip_suffix('\n') {
+            | Some(line) => {
+                let line = line
+                    .strip_suffix('\r')
+                    .unwrap_or(line);
+                writer.write_all(line.as_bytes())?;
+                writer.write_all(
+                    line_ending
+                        .as_str()
+                        .as_bytes(),
+                )?;
+            },
+            | None => writer.write_all(line.as_bytes())?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_text() {
+        let mut buffer = vec![];
+        super::write_text(
+            &mut buffer,
+            "a\nb\r\nc",
+            LineEnding::CrLf,
+        )
+        .unwrap();
+        assert_eq!(buffer, b"a\r\nb\r\nc");
+
+        let mut buffer = vec![];
+        super::write_text(&mut buffer, "a\nb\n", LineEnding::Lf).unwrap();
+        assert_eq!(buffer, b"a\nb\n");
+    }
+}
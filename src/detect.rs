@@ -0,0 +1,540 @@
+//! Detection of subtitle formats provided by [`subtp::detect::detect`](detect)
+//! and parsing in the detected format by [`subtp::detect::parse_any`](parse_any).
+//!
+//! ## Example
+//! ```
+//! use subtp::detect::{parse_any, Confidence, Format, SubtitleFile};
+//!
+//! let text = r#"1
+//! 00:00:01,000 --> 00:00:02,000
+//! Hello, world!
+//! "#;
+//!
+//! let detection = subtp::detect::detect(text).unwrap();
+//! assert_eq!(detection.format, Format::SubRip);
+//! assert_eq!(detection.confidence, Confidence::High);
+//!
+//! match parse_any(text).unwrap() {
+//!     | SubtitleFile::SubRip(srt) => assert_eq!(srt.subtitles.len(), 1),
+//!     | _ => unreachable!(),
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use crate::ass::SubStationAlpha;
+use crate::srt::{SrtTimestamp, SubRip};
+use crate::ttml::Ttml;
+use crate::vtt::{VttTimestamp, WebVtt};
+use crate::{ParseError, ParseResult};
+
+/// The number of lines at the head of the text to look for timings.
+const HEAD_LINES: usize = 64;
+
+/// The number of bytes at the head of the input to detect the format from.
+const HEAD_BYTES: usize = 4096;
+
+/// The subtitle format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Format {
+    /// The SubRip Subtitle (`.srt`) format.
+    SubRip,
+    /// The WebVTT (`.vtt`) format.
+    WebVtt,
+    /// The Advanced SubStation Alpha (`.ass`/`.ssa`) format.
+    SubStationAlpha,
+    /// The Timed Text Markup Language (`.ttml`/`.dfxp`) format.
+    Ttml,
+}
+
+impl Format {
+    /// Returns the conventional file extension without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            | Self::SubRip => "srt",
+            | Self::WebVtt => "vtt",
+            | Self::SubStationAlpha => "ass",
+            | Self::Ttml => "ttml",
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::SubRip => write!(f, "SubRip Subtitle"),
+            | Self::WebVtt => write!(f, "WebVTT"),
+            | Self::SubStationAlpha => write!(f, "Advanced SubStation Alpha"),
+            | Self::Ttml => write!(f, "Timed Text Markup Language"),
+        }
+    }
+}
+
+/// The confidence of the detection in ascending order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Confidence {
+    /// Only the timings look like the format, e.g. WebVTT cues without the `WEBVTT` header.
+    Low,
+    /// The body looks like the format, but the signature at the start is missing.
+    Medium,
+    /// The signature of the format is found at the start.
+    High,
+}
+
+/// The detected format with the confidence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Detection {
+    /// The detected format.
+    pub format: Format,
+    /// The confidence of the detection.
+    pub confidence: Confidence,
+}
+
+/// The subtitle file parsed in the detected format.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum SubtitleFile {
+    /// The SubRip Subtitle format.
+    SubRip(SubRip),
+    /// The WebVTT format.
+    WebVtt(WebVtt),
+    /// The Advanced SubStation Alpha format.
+    SubStationAlpha(SubStationAlpha),
+    /// The Timed Text Markup Language format.
+    Ttml(Ttml),
+}
+
+impl SubtitleFile {
+    /// Returns the format of the file.
+    pub fn format(&self) -> Format {
+        match self {
+            | Self::SubRip(_) => Format::SubRip,
+            | Self::WebVtt(_) => Format::WebVtt,
+            | Self::SubStationAlpha(_) => Format::SubStationAlpha,
+            | Self::Ttml(_) => Format::Ttml,
+        }
+    }
+
+    /// Renders the text in the format of the file.
+    pub fn render(&self) -> String {
+        match self {
+            | Self::SubRip(srt) => srt.render(),
+            | Self::WebVtt(vtt) => vtt.render(),
+            | Self::SubStationAlpha(ass) => ass.render(),
+            | Self::Ttml(ttml) => ttml.render(),
+        }
+    }
+}
+
+/// Detects the format of the text by the signature at the start or the timings at the head,
+/// returning `None` if no format is likely.
+///
+/// The signatures are `WEBVTT` for WebVTT, `[Script Info]` for Advanced SubStation Alpha,
+/// and the `<tt>` element in the TTML namespace for Timed Text Markup Language.
+/// SubRip Subtitle has no signature, so the sequence number followed by
+/// the timings with comma timestamps such as `00:00:01,000 --> 00:00:02,000` is taken for it.
+/// The timings with period timestamps are taken for WebVTT without the header,
+/// even after a number, which is a valid cue identifier of WebVTT.
+///
+/// ## Example
+/// ```
+/// use subtp::detect::{detect, Confidence, Detection, Format};
+///
+/// assert_eq!(
+///     detect("WEBVTT\n\n00:01.000 --> 00:02.000\nHello\n"),
+///     Some(Detection {
+///         format: Format::WebVtt,
+///         confidence: Confidence::High,
+///     })
+/// );
+/// assert_eq!(
+///     detect("00:00:01.000 --> 00:00:02.000\nHello\n"),
+///     Some(Detection {
+///         format: Format::WebVtt,
+///         confidence: Confidence::Low,
+///     })
+/// );
+/// assert_eq!(detect("Hello, world!"), None);
+/// ```
+pub fn detect(text: &str) -> Option<Detection> {
+    let detection = |format, confidence| {
+        Some(Detection {
+            format,
+            confidence,
+        })
+    };
+
+    let text = text.trim_start_matches('\u{FEFF}');
+    if let Some(rest) = text.strip_prefix("WEBVTT") {
+        if rest.is_empty()
+            || rest.starts_with([
+                ' ', '\t', '\r', '\n',
+            ])
+        {
+            return detection(Format::WebVtt, Confidence::High);
+        }
+    }
+
+    let text = text.trim_start();
+    if text.starts_with("[Script Info]") {
+        return detection(
+            Format::SubStationAlpha,
+            Confidence::High,
+        );
+    }
+    if text.starts_with('<') {
+        if let Some(tt) = find_tt(text) {
+            return if tt.contains("http://www.w3.org/ns/ttml") {
+                detection(Format::Ttml, Confidence::High)
+            } else {
+                detection(Format::Ttml, Confidence::Medium)
+            };
+        }
+    }
+
+    let lines: Vec<&str> = text
+        .lines()
+        .take(HEAD_LINES)
+        .collect();
+    if lines
+        .iter()
+        .any(|line| line.starts_with("[Events]"))
+        && lines
+            .iter()
+            .any(|line| line.starts_with("Dialogue:"))
+    {
+        return detection(
+            Format::SubStationAlpha,
+            Confidence::Medium,
+        );
+    }
+
+    let sequence = lines
+        .first()
+        .map_or(false, |line| {
+            let line = line.trim();
+            !line.is_empty()
+                && line
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+        });
+    match lines
+        .iter()
+        .position(|line| is_srt_timing(line))
+    {
+        | Some(1) if sequence => {
+            return detection(Format::SubRip, Confidence::High)
+        },
+        | Some(_) => return detection(Format::SubRip, Confidence::Medium),
+        | None => {},
+    }
+    if lines
+        .iter()
+        .any(|line| is_vtt_timing(line))
+    {
+        return detection(Format::WebVtt, Confidence::Low);
+    }
+
+    None
+}
+
+/// Detects the format of the bytes by [`detect`] at the head,
+/// decoding UTF-16 with the byte order mark and the other encodings as UTF-8 with replacements.
+///
+/// ## Example
+/// ```
+/// use subtp::detect::{detect_bytes, Format};
+///
+/// let bytes: Vec<u8> = "\u{FEFF}WEBVTT\n"
+///     .encode_utf16()
+///     .flat_map(|unit| unit.to_be_bytes())
+///     .collect();
+///
+/// assert_eq!(detect_bytes(&bytes).unwrap().format, Format::WebVtt);
+/// ```
+pub fn detect_bytes(bytes: &[u8]) -> Option<Detection> {
+    let head = &bytes[..bytes.len().min(HEAD_BYTES)];
+    let utf16 = |rest: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        char::decode_utf16(
+            rest.chunks_exact(2)
+                .map(|pair| from_bytes([pair[0], pair[1]])),
+        )
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect::<String>()
+    };
+
+    let text = match head {
+        | [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        | [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        | _ => String::from_utf8_lossy(head).into_owned(),
+    };
+
+    detect(&text)
+}
+
+/// Parses the text in the format detected by [`detect`],
+/// skipping the byte order mark.
+///
+/// The WebVTT cues without the header, detected with [`Confidence::Low`],
+/// are parsed as if the `WEBVTT` header preceded them,
+/// reporting errors at the positions in the given text.
+///
+/// ## Example
+/// ```
+/// use subtp::detect::{parse_any, Format};
+///
+/// let text = r#"WEBVTT
+///
+/// 00:00:01.000 --> 00:00:02.000
+/// Hello, world!
+/// "#;
+///
+/// let file = parse_any(text).unwrap();
+/// assert_eq!(file.format(), Format::WebVtt);
+/// assert_eq!(file.render(), text);
+///
+/// let err = parse_any("Hello, world!").unwrap_err();
+/// assert_eq!(err.rule.as_deref(), Some("format"));
+/// ```
+pub fn parse_any(text: &str) -> ParseResult<SubtitleFile> {
+    let detection = detect(text).ok_or_else(|| {
+        ParseError {
+            line: 1,
+            column: 1,
+            offset: 0,
+            expected: BTreeSet::from(["subtitle format".to_string()]),
+            rule: Some("format".to_string()),
+            block: None,
+        }
+    })?;
+
+    let text = text.trim_start_matches('\u{FEFF}');
+    match detection.format {
+        | Format::SubRip => SubRip::parse(text).map(SubtitleFile::SubRip),
+        | Format::WebVtt if detection.confidence == Confidence::Low => {
+            parse_without_header(text).map(SubtitleFile::WebVtt)
+        },
+        | Format::WebVtt => WebVtt::parse(text).map(SubtitleFile::WebVtt),
+        | Format::SubStationAlpha => {
+            SubStationAlpha::parse(text).map(SubtitleFile::SubStationAlpha)
+        },
+        | Format::Ttml => Ttml::parse(text).map(SubtitleFile::Ttml),
+    }
+}
+
+/// Parses the WebVTT cues without the header by prepending the header,
+/// relocating errors to the positions in the text.
+fn parse_without_header(text: &str) -> ParseResult<WebVtt> {
+    const HEADER: &str = "WEBVTT\n\n";
+
+    WebVtt::parse(&format!("{}{}", HEADER, text)).map_err(|mut err| {
+        let lines = HEADER.matches('\n').count();
+        if err.line <= lines {
+            err.column = 1;
+        }
+        err.line = err
+            .line
+            .saturating_sub(lines)
+            .max(1);
+        err.offset = err
+            .offset
+            .saturating_sub(HEADER.len());
+        err
+    })
+}
+
+/// Returns the start tag of the `<tt>` element if found.
+fn find_tt(text: &str) -> Option<&str> {
+    let mut rest = text;
+    while let Some(start) = rest.find("<tt") {
+        let tag = &rest[start..];
+        if tag[3..]
+            .starts_with(|c: char| c == '>' || c == ':' || c.is_whitespace())
+        {
+            return Some(
+                &tag[..tag
+                    .find('>')
+                    .unwrap_or(tag.len())],
+            );
+        }
+        rest = &tag[3..];
+    }
+
+    None
+}
+
+/// Returns whether the line is the timings with the timestamps of SubRip Subtitle.
+fn is_srt_timing(line: &str) -> bool {
+    timing_timestamps(line).map_or(false, |(start, end)| {
+        start
+            .parse::<SrtTimestamp>()
+            .is_ok()
+            && end
+                .parse::<SrtTimestamp>()
+                .is_ok()
+    })
+}
+
+/// Returns whether the line is the timings with the timestamps of WebVTT.
+fn is_vtt_timing(line: &str) -> bool {
+    timing_timestamps(line).map_or(false, |(start, end)| {
+        start
+            .parse::<VttTimestamp>()
+            .is_ok()
+            && end
+                .parse::<VttTimestamp>()
+                .is_ok()
+    })
+}
+
+/// Splits the timings line into the start and end timestamps, ignoring the settings after the end.
+fn timing_timestamps(line: &str) -> Option<(&str, &str)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest
+        .split_whitespace()
+        .next()?;
+
+    Some((start.trim(), end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(text: &str) -> Option<(Format, Confidence)> {
+        detect(text).map(|detection| (detection.format, detection.confidence))
+    }
+
+    #[test]
+    fn detect_signatures() {
+        assert_eq!(
+            detected("\u{FEFF}WEBVTT - Title\n"),
+            Some((Format::WebVtt, Confidence::High))
+        );
+        assert_eq!(detected("WEBVTTX\n"), None);
+        assert_eq!(
+            detected("\n[Script Info]\nScriptType: v4.00+\n"),
+            Some((
+                Format::SubStationAlpha,
+                Confidence::High
+            ))
+        );
+        assert_eq!(
+            detected(
+                "<?xml version=\"1.0\"?>\n<tt:tt xmlns:tt=\"http://www.w3.org/ns/ttml\">\n"
+            ),
+            Some((Format::Ttml, Confidence::High))
+        );
+        assert_eq!(
+            detected("<tt>\n<body/>\n</tt>\n"),
+            Some((Format::Ttml, Confidence::Medium))
+        );
+        assert_eq!(detected("<html><ttx>"), None);
+    }
+
+    #[test]
+    fn detect_bodies() {
+        assert_eq!(
+            detected("1\r\n00:00:01,000 --> 00:00:02,000 X1:10\r\nHello\r\n"),
+            Some((Format::SubRip, Confidence::High))
+        );
+        assert_eq!(
+            detected("\n00:00:01,000 --> 00:00:02,000\nHello\n"),
+            Some((Format::SubRip, Confidence::Medium))
+        );
+        assert_eq!(
+            detected("1\n00:00:01.000 --> 00:00:02.000\nHello\n"),
+            Some((Format::WebVtt, Confidence::Low))
+        );
+        assert_eq!(
+            detected("intro\n00:01.000 --> 00:02.000 line:0\nHello\n"),
+            Some((Format::WebVtt, Confidence::Low))
+        );
+        assert_eq!(
+            detected("[Events]\nDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello\n"),
+            Some((Format::SubStationAlpha, Confidence::Medium))
+        );
+        assert_eq!(detected(""), None);
+        assert_eq!(
+            detected("1\n00:00:01 --> 00:00:02\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_any() {
+        let file = super::parse_any(
+            "\u{FEFF}1\n00:00:01,000 --> 00:00:02,000\nHello\n",
+        )
+        .unwrap();
+        assert_eq!(file.format(), Format::SubRip);
+        assert_eq!(
+            file.render(),
+            "1\n00:00:01,000 --> 00:00:02,000\nHello\n"
+        );
+
+        let file = super::parse_any(
+            "\u{FEFF}WEBVTT\n\n00:01.000 --> 00:02.000\nHello\n",
+        )
+        .unwrap();
+        assert_eq!(file.format(), Format::WebVtt);
+
+        // The cues without the header are parsed as WebVTT.
+        let file = super::parse_any(
+            "1\n00:00:01.000 --> 00:00:02.000\nHello\n",
+        )
+        .unwrap();
+        assert_eq!(file.format(), Format::WebVtt);
+        assert_eq!(
+            file.render(),
+            "WEBVTT\n\n1\n00:00:01.000 --> 00:00:02.000\nHello\n"
+        );
+
+        let err = super::parse_any(
+            "00:01.000 --> 00:02.000\nHello\n\n00:03.000 --> 00:0x.000\nBye\n",
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.offset), (5, 55));
+    }
+
+    #[test]
+    fn detect_bytes() {
+        let bytes: Vec<u8> = "\u{FEFF}1\n00:00:01,000 --> 00:00:02,000\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        assert_eq!(
+            super::detect_bytes(&bytes),
+            Some(Detection {
+                format: Format::SubRip,
+                confidence: Confidence::High,
+            })
+        );
+        assert_eq!(
+            super::detect_bytes(b"\xEF\xBB\xBFWEBVTT\n\xFF"),
+            Some(Detection {
+                format: Format::WebVtt,
+                confidence: Confidence::High,
+            })
+        );
+    }
+}
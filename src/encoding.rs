@@ -0,0 +1,312 @@
+//! Detection and conversion of character encodings of subtitle files
+//! provided by [`subtp::encoding::TextEncoding`](TextEncoding) behind `encoding` feature.
+//!
+//! The encodings are detected in the order of
+//! 1. the byte order mark of UTF-8, UTF-16LE or UTF-16BE,
+//! 2. UTF-16 without the byte order mark by the null bytes of ASCII characters,
+//! 3. UTF-8 if the bytes are valid,
+//! 4. the most likely legacy encoding such as Windows-1252, Shift_JIS or GBK by
+//!    [`chardetng`](https://docs.rs/chardetng), where GBK is decoded as its superset GB18030.
+//!
+//! ## Example
+//! ```
+//! use subtp::encoding::{Encoding, TextEncoding};
+//! use subtp::srt::SubRip;
+//!
+//! // "Café" in Windows-1252.
+//! let bytes = b"1\r\n00:00:01,000 --> 00:00:02,000\r\nCaf\xE9 cr\xE8me br\xFBl\xE9e\r\n";
+//!
+//! let decoded = SubRip::parse_bytes(bytes).unwrap();
+//! assert_eq!(decoded.encoding.encoding.name(), "windows-1252");
+//! assert_eq!(decoded.value.subtitles[0].text, vec!["Café crème brûlée".to_string()]);
+//!
+//! let utf16 = TextEncoding {
+//!     encoding: Encoding::for_label(b"utf-16le").unwrap(),
+//!     bom: true,
+//! };
+//! let (rendered, replaced) = decoded.value.render_bytes(&utf16);
+//! assert_eq!(&rendered[..4], b"\xFF\xFE1\x00");
+//! assert!(!replaced);
+//! ```
+
+use chardetng::EncodingDetector;
+pub use encoding_rs::Encoding;
+use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8};
+
+/// The decoded value with the encoding of the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded<T> {
+    /// The decoded value.
+    pub value: T,
+    /// The encoding of the input.
+    pub encoding: TextEncoding,
+    /// Whether malformed byte sequences of the input are replaced with `U+FFFD`.
+    pub replaced: bool,
+}
+
+/// The character encoding with whether the byte order mark is present.
+///
+/// ## Example
+/// ```
+/// use subtp::encoding::TextEncoding;
+///
+/// let encoding = TextEncoding::detect(b"\xEF\xBB\xBFWEBVTT\n");
+/// assert_eq!(encoding.encoding.name(), "UTF-8");
+/// assert!(encoding.bom);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextEncoding {
+    /// The encoding, e.g. obtained by [`Encoding::for_label`].
+    pub encoding: &'static Encoding,
+    /// Whether the byte order mark is present.
+    pub bom: bool,
+}
+
+impl TextEncoding {
+    /// Detects the encoding of the bytes.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return Self {
+                encoding,
+                bom: true,
+            };
+        }
+
+        let encoding = detect_utf16(bytes)
+            .or_else(|| {
+                std::str::from_utf8(bytes)
+                    .is_ok()
+                    .then_some(UTF_8)
+            })
+            .unwrap_or_else(|| {
+                let mut detector = EncodingDetector::new();
+                detector.feed(bytes, true);
+                detector.guess(None, true)
+            });
+
+        Self {
+            encoding,
+            bom: false,
+        }
+    }
+
+    /// Decodes the bytes into the text, skipping the byte order mark if present,
+    /// and returns whether malformed byte sequences are replaced with `U+FFFD`.
+    pub fn decode(
+        &self,
+        bytes: &[u8],
+    ) -> (String, bool) {
+        let bytes = match Encoding::for_bom(bytes) {
+            | Some((encoding, length))
+                if self.bom && encoding == self.encoding =>
+            {
+                &bytes[length..]
+            },
+            | _ => bytes,
+        };
+        let (text, replaced) = self
+            .encoding
+            .decode_without_bom_handling(bytes);
+
+        (text.into_owned(), replaced)
+    }
+
+    /// Encodes the text with the byte order mark if `bom` is set,
+    /// and returns whether the characters that the encoding can not represent
+    /// are replaced with the numeric character references such as `&#128512;`.
+    pub fn encode(
+        &self,
+        text: &str,
+    ) -> (Vec<u8>, bool) {
+        let mut bytes = vec![];
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let little_endian = self.encoding == UTF_16LE;
+            let units = (self.bom)
+                .then_some('\u{FEFF}' as u16)
+                .into_iter()
+                .chain(text.encode_utf16());
+            for unit in units {
+                bytes.extend(
+                    if little_endian {
+                        unit.to_le_bytes()
+                    } else {
+                        unit.to_be_bytes()
+                    },
+                );
+            }
+            return (bytes, false);
+        }
+
+        // The encodings other than UTF-16 encode the byte order mark only in UTF-8.
+        if self.bom && self.encoding == UTF_8 {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        let (encoded, _, replaced) = self.encoding.encode(text);
+        bytes.extend_from_slice(&encoded);
+
+        (bytes, replaced)
+    }
+}
+
+impl Default for TextEncoding {
+    /// UTF-8 without the byte order mark.
+    fn default() -> Self {
+        Self {
+            encoding: UTF_8,
+            bom: false,
+        }
+    }
+}
+
+/// Detects the encoding of the bytes and decodes them.
+///
+/// ## Example
+/// ```
+/// use subtp::encoding::decode;
+///
+/// // "こんにちは" in Shift_JIS.
+/// let decoded = decode(b"\x82\xb1\x82\xf1\x82\xc9\x82\xbf\x82\xcd");
+///
+/// assert_eq!(decoded.value, "こんにちは");
+/// assert_eq!(decoded.encoding.encoding.name(), "Shift_JIS");
+/// assert!(!decoded.replaced);
+/// ```
+pub fn decode(bytes: &[u8]) -> Decoded<String> {
+    let encoding = TextEncoding::detect(bytes);
+    let (value, replaced) = encoding.decode(bytes);
+
+    Decoded {
+        value,
+        encoding,
+        replaced,
+    }
+}
+
+/// Detects UTF-16 without the byte order mark,
+/// where a half of the code units is null in mostly ASCII texts such as subtitles.
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+
+    let units = bytes.len() / 2;
+    let mut even = 0;
+    let mut odd = 0;
+    for pair in bytes.chunks_exact(2) {
+        even += (pair[0] == 0) as usize;
+        odd += (pair[1] == 0) as usize;
+    }
+
+    if odd * 4 >= units && even * 8 < odd {
+        Some(UTF_16LE)
+    } else if even * 4 >= units && odd * 8 < even {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{GBK, SHIFT_JIS, WINDOWS_1252};
+
+    use super::*;
+
+    const TEXT: &str = "1\n00:00:01,000 --> 00:00:02,000\n";
+
+    fn encoded(
+        encoding: &'static Encoding,
+        bom: bool,
+        text: &str,
+    ) -> Vec<u8> {
+        let (bytes, replaced) = TextEncoding {
+            encoding,
+            bom,
+        }
+        .encode(text);
+        assert!(!replaced);
+
+        bytes
+    }
+
+    #[test]
+    fn detect() {
+        for encoding in [
+            UTF_8, UTF_16LE, UTF_16BE,
+        ] {
+            for bom in [true, false] {
+                assert_eq!(
+                    TextEncoding::detect(&encoded(
+                        encoding,
+                        bom,
+                        &format!("{}Hello, world!\n", TEXT)
+                    )),
+                    TextEncoding {
+                        encoding,
+                        bom,
+                    }
+                );
+            }
+        }
+
+        for (encoding, text) in [
+            (
+                WINDOWS_1252,
+                "Ça va très bien, où êtes-vous ?",
+            ),
+            (SHIFT_JIS, "今日はいい天気ですね。"),
+            (GBK, "今天天气很好，我们去公园散步吧。"),
+        ] {
+            assert_eq!(
+                TextEncoding::detect(&encoded(
+                    encoding,
+                    false,
+                    &format!("{}{}\n", TEXT, text)
+                ))
+                .encoding,
+                encoding,
+            );
+        }
+    }
+
+    #[test]
+    fn decode() {
+        let text = format!("{}Hello, 世界!\n", TEXT);
+        for encoding in [
+            UTF_8, UTF_16LE, UTF_16BE,
+        ] {
+            for bom in [true, false] {
+                let decoded = super::decode(&encoded(encoding, bom, &text));
+                assert_eq!(decoded.value, text);
+                assert!(!decoded.replaced);
+            }
+        }
+
+        let decoded = TextEncoding::default().decode(b"Hello\xFF");
+        assert_eq!(
+            decoded,
+            ("Hello\u{FFFD}".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn encode() {
+        let encoding = TextEncoding {
+            encoding: WINDOWS_1252,
+            bom: true,
+        };
+        assert_eq!(
+            encoding.encode("Café"),
+            (b"Caf\xE9".to_vec(), false)
+        );
+        // The emoji is not representable in Windows-1252.
+        assert_eq!(
+            encoding.encode("Café 😀"),
+            (b"Caf\xE9 &#128512;".to_vec(), true)
+        );
+        assert_eq!(
+            encoded(UTF_16BE, true, "A"),
+            b"\xFE\xFF\x00A"
+        );
+    }
+}
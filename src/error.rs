@@ -1,20 +1,347 @@
-//! Error types for parsing.
+//! Error types for parsing and timestamps.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 /// The error of parsing.
-#[derive(Debug, thiserror::Error)]
-#[error("Parse error at {location}: expected {expected}")]
+///
+/// ## Example
+/// ```
+/// use subtp::srt::SubRip;
+///
+/// let text = "1\n00:00:01,000 --> 00:00:02,000\nHello, world!\n\n2\n00:00:03,000 -> 00:00:04,000\nBroken\n";
+///
+/// let err = SubRip::parse(text).unwrap_err();
+/// assert_eq!(err.line, 6);
+/// assert_eq!(err.column, 14);
+/// assert_eq!(err.offset, 62);
+/// assert!(err.expected.contains("\"-->\""));
+/// assert_eq!(err.rule.as_deref(), Some("subtitle"));
+/// assert_eq!(err.block, Some(1));
+/// assert_eq!(
+///     err.snippet(text),
+///     "6 | 00:00:03,000 -> 00:00:04,000\n  |              ^\n".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct ParseError {
-    /// The furthest position the parser reached in the input before failing.
-    pub location: String,
-    /// The set of literals that failed to match at that position.
-    pub expected: String,
+    /// The line number of the furthest position the parser reached, starting from 1.
+    pub line: usize,
+    /// The column number in characters of the furthest position the parser reached, starting from 1.
+    pub column: usize,
+    /// The byte offset of the furthest position the parser reached.
+    pub offset: usize,
+    /// The set of literals or names that failed to match at that position.
+    pub expected: BTreeSet<String>,
+    /// The name of the rule or block being parsed, if known, e.g. `subtitle`, `cue` or `header`.
+    pub rule: Option<String>,
+    /// The index of the block being parsed in the document, if known.
+    pub block: Option<usize>,
+}
+
+impl ParseError {
+    /// Returns the location as `line:column`.
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.line, self.column)
+    }
+
+    /// Renders the offending line of the input with a caret under the error position.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nHello\n\nintro\n00:00:03 --> 00:00:04.000\nBroken\n";
+    ///
+    /// let err = WebVtt::parse(text).unwrap_err();
+    /// assert_eq!(err.rule.as_deref(), Some("cue"));
+    /// assert_eq!(
+    ///     err.snippet(text),
+    ///     "7 | 00:00:03 --> 00:00:04.000\n  |         ^\n".to_string()
+    /// );
+    /// ```
+    pub fn snippet(
+        &self,
+        input: &str,
+    ) -> String {
+        let source = input
+            .lines()
+            .nth(self.line.saturating_sub(1))
+            .unwrap_or("");
+        let number = self.line.to_string();
+        let padding = " ".repeat(number.len());
+        // Keeps tabs to align the caret with the source.
+        let indent = source
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| {
+                if c == '\t' {
+                    '\t'
+                } else {
+                    ' '
+                }
+            })
+            .collect::<String>();
+
+        format!(
+            "{} | {}\n{} | {}^\n",
+            number, source, padding, indent
+        )
+    }
+
+    /// Attaches the rule name and the block index to the error.
+    pub(crate) fn with_context(
+        mut self,
+        rule: impl Into<String>,
+        block: Option<usize>,
+    ) -> Self {
+        self.rule = Some(rule.into());
+        self.block = block;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "Parse error at {}", self.location())?;
+        if let Some(rule) = &self.rule {
+            write!(f, " in {}", rule)?;
+            if let Some(block) = self.block {
+                write!(f, " (block {})", block)?;
+            }
+        }
+        write!(f, ": expected ")?;
+
+        let mut expected = self.expected.iter();
+        match (expected.next(), self.expected.len()) {
+            | (None, _) => write!(f, "<unreported>"),
+            | (Some(first), 1) => write!(f, "{}", first),
+            | (Some(first), _) => {
+                write!(f, "one of {}", first)?;
+                for token in expected {
+                    write!(f, ", {}", token)?;
+                }
+                Ok(())
+            },
+        }
+    }
 }
 
 impl From<peg::error::ParseError<peg::str::LineCol>> for ParseError {
     fn from(err: peg::error::ParseError<peg::str::LineCol>) -> Self {
         ParseError {
-            location: format!("{}", err.location),
-            expected: format!("{}", err.expected),
+            line: err.location.line,
+            column: err.location.column,
+            offset: err.location.offset,
+            expected: err
+                .expected
+                .tokens()
+                .map(String::from)
+                .collect(),
+            rule: None,
+            block: None,
         }
     }
 }
+
+/// The error of reading from a stream.
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError {
+    /// The error of the underlying reader.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The error of parsing a block.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+}
+
+/// The error of converting a duration that exceeds the range of a timestamp type.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use subtp::srt::SrtTimestamp;
+/// use subtp::TimestampRangeError;
+///
+/// let duration = Duration::from_secs(256 * 3600);
+///
+/// assert_eq!(
+///     SrtTimestamp::try_from(duration),
+///     Err(TimestampRangeError {
+///         duration
+///     })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("duration {duration:?} exceeds the range of the timestamp")]
+pub struct TimestampRangeError {
+    /// The duration out of the range.
+    pub duration: Duration,
+}
+
+/// The error of constructing a timestamp with a field out of range.
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::VttTimestamp;
+/// use subtp::TimestampFieldError;
+///
+/// assert_eq!(
+///     VttTimestamp::new(0, 75, 0, 0),
+///     Err(TimestampFieldError {
+///         field: "minutes",
+///         value: 75,
+///         limit: 60,
+///     })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("{field} must be less than {limit}, but got {value}")]
+pub struct TimestampFieldError {
+    /// The name of the field, i.e. `minutes`, `seconds` or `milliseconds`.
+    pub field: &'static str,
+    /// The value of the field.
+    pub value: u16,
+    /// The exclusive upper limit of the field.
+    pub limit: u16,
+}
+
+impl TimestampFieldError {
+    /// Validates the minutes, the seconds and the milliseconds of a timestamp.
+    pub(crate) fn validate(
+        minutes: u8,
+        seconds: u8,
+        milliseconds: u16,
+    ) -> Result<(), Self> {
+        for (field, value, limit) in [
+            ("minutes", minutes as u16, 60),
+            ("seconds", seconds as u16, 60),
+            ("milliseconds", milliseconds, 1000),
+        ] {
+            if value >= limit {
+                return Err(Self {
+                    field,
+                    value,
+                    limit,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the index and the first line of the blank-line separated block that contains the offset,
+/// where the blank lines belong to the preceding block.
+pub(crate) fn block_at(
+    input: &str,
+    offset: usize,
+) -> (usize, &str) {
+    let mut blocks = 0usize;
+    let mut first = "";
+    let mut in_block = false;
+    let mut start = 0;
+    for line in input.split_inclusive('\n') {
+        if start > offset {
+            break;
+        }
+        if line.trim().is_empty() {
+            in_block = false;
+        } else {
+            if !in_block {
+                blocks += 1;
+                first = line.trim_end();
+            }
+            in_block = true;
+        }
+        start += line.len();
+    }
+
+    (blocks.saturating_sub(1), first)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ass::SubStationAlpha;
+
+    #[test]
+    fn block_at() {
+        let text = "a\nb\n\n\nc\n\nd\n";
+        assert_eq!(super::block_at(text, 0), (0, "a"));
+        assert_eq!(super::block_at(text, 2), (0, "a"));
+        assert_eq!(super::block_at(text, 5), (0, "a"));
+        assert_eq!(super::block_at(text, 6), (1, "c"));
+        assert_eq!(super::block_at(text, 9), (2, "d"));
+        assert_eq!(super::block_at("", 0), (0, ""));
+    }
+
+    #[test]
+    fn display() {
+        let mut err = ParseError {
+            line: 2,
+            column: 5,
+            offset: 10,
+            expected: ["\"-->\"".to_string()].into(),
+            rule: None,
+            block: None,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Parse error at 2:5: expected \"-->\"".to_string()
+        );
+
+        err.expected
+            .insert("[' ' | '\\t']".to_string());
+        err = err.with_context("cue", Some(3));
+        assert_eq!(
+            err.to_string(),
+            "Parse error at 2:5 in cue (block 3): expected one of \"-->\", [' ' | '\\t']"
+                .to_string()
+        );
+
+        err.expected.clear();
+        assert_eq!(
+            err.to_string(),
+            "Parse error at 2:5 in cue (block 3): expected <unreported>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn snippet() {
+        let err = ParseError {
+            line: 2,
+            column: 3,
+            offset: 0,
+            expected: BTreeSet::new(),
+            rule: None,
+            block: None,
+        };
+        assert_eq!(
+            err.snippet("first\n\tsecond\n"),
+            "2 | \tsecond\n  | \t ^\n"
+        );
+        assert_eq!(err.snippet(""), "2 | \n  | ^\n");
+    }
+
+    #[test]
+    fn ass_context() {
+        let text = "[Script Info]\nScriptType: v4.00+\n\n[Events]\nDialogue: 0\n\n[Broken\n";
+        let err = SubStationAlpha::parse(text).unwrap_err();
+        assert_eq!(err.rule.as_deref(), Some("Events"));
+        assert_eq!(err.block, Some(1));
+
+        let err = SubStationAlpha::parse("Garbage\n").unwrap_err();
+        assert_eq!(err.rule, None);
+        assert_eq!(err.block, None);
+    }
+}
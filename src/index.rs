@@ -0,0 +1,337 @@
+//! Time-indexed lookup of cues provided by [`subtp::index::CueIndex`](CueIndex).
+//!
+//! ## Example
+//! ```
+//! use std::time::Duration;
+//! use subtp::index::CueIndex;
+//! use subtp::srt::SubRip;
+//!
+//! let text = r#"1
+//! 00:00:01,000 --> 00:00:04,000
+//! Hello, world!
+//!
+//! 2
+//! 00:00:03,000 --> 00:00:05,000
+//! This is a sample.
+//! "#;
+//!
+//! let srt = SubRip::parse(text).unwrap();
+//! let index = CueIndex::from(&srt);
+//!
+//! let active = index.at(Duration::from_millis(3500));
+//! assert_eq!(active.len(), 2);
+//! assert_eq!(active[1].sequence, 2);
+//!
+//! let overlapping = index.overlapping(Duration::from_secs(4), Duration::from_secs(10));
+//! assert_eq!(overlapping.len(), 1);
+//! assert_eq!(overlapping[0].sequence, 2);
+//! ```
+
+use std::time::Duration;
+
+use crate::srt::{SrtSubtitle, SubRip};
+use crate::vtt::{VttBlock, VttCue, WebVtt};
+
+/// The index of cues by time for point and range queries.
+///
+/// The cues are sorted by the start time
+/// and the latest end time of each subtree is kept in a segment tree,
+/// so that a query takes `O(log n + k)` time for `k` results.
+/// The times of cues are half-open intervals `[start, end)`.
+///
+/// The index borrows the cues, so it is rebuilt after edits.
+/// Building takes `O(n)` time for cues already sorted by the start time,
+/// as most subtitle files are, and `O(n log n)` time otherwise.
+#[derive(Debug)]
+pub struct CueIndex<'a, T> {
+    /// The start, the end and the cue sorted by the start.
+    entries: Vec<(Duration, Duration, &'a T)>,
+    /// The segment tree of the latest end time, where the node `i` has children `2i` and `2i + 1`.
+    max_ends: Vec<Duration>,
+}
+
+impl<'a, T> CueIndex<'a, T> {
+    /// Builds the index from the start, the end and the cue.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::index::CueIndex;
+    ///
+    /// let cues = ["first", "second"];
+    /// let index = CueIndex::new(vec![
+    ///     (Duration::from_secs(3), Duration::from_secs(4), &cues[1]),
+    ///     (Duration::from_secs(1), Duration::from_secs(2), &cues[0]),
+    /// ]);
+    ///
+    /// assert_eq!(index.at(Duration::from_secs(1)), vec![&"first"]);
+    /// assert!(index.at(Duration::from_secs(2)).is_empty());
+    /// ```
+    pub fn new(
+        cues: impl IntoIterator<Item = (Duration, Duration, &'a T)>
+    ) -> Self {
+        let mut entries: Vec<_> = cues.into_iter().collect();
+        // The stable sort keeps the document order of cues starting at the same time.
+        entries.sort_by_key(|(start, ..)| *start);
+
+        let size = entries
+            .len()
+            .next_power_of_two();
+        let mut max_ends = vec![Duration::ZERO; size * 2];
+        for (i, (_, end, _)) in entries.iter().enumerate() {
+            max_ends[size + i] = *end;
+        }
+        for node in (1..size).rev() {
+            max_ends[node] = max_ends[node * 2].max(max_ends[node * 2 + 1]);
+        }
+
+        Self {
+            entries,
+            max_ends,
+        }
+    }
+
+    /// Returns the number of cues.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the index has no cues.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the cues showing at the time, i.e. `start <= time < end`, ordered by the start.
+    pub fn at(
+        &self,
+        time: Duration,
+    ) -> Vec<&'a T> {
+        let limit = self
+            .entries
+            .partition_point(|(start, ..)| *start <= time);
+
+        self.query(limit, |end| end > time)
+    }
+
+    /// Returns the cues intersecting the range `[start, end)`, ordered by the start.
+    pub fn overlapping(
+        &self,
+        start: Duration,
+        end: Duration,
+    ) -> Vec<&'a T> {
+        let limit = self
+            .entries
+            .partition_point(|(cue_start, ..)| *cue_start < end);
+
+        self.query(limit, |cue_end| cue_end > start)
+    }
+
+    /// Returns the cues in `entries[..limit]` whose end satisfies the predicate,
+    /// pruning the subtrees whose latest end does not.
+    fn query(
+        &self,
+        limit: usize,
+        predicate: impl Fn(Duration) -> bool,
+    ) -> Vec<&'a T> {
+        let mut cues = vec![];
+        if limit == 0 {
+            return cues;
+        }
+
+        let size = self.max_ends.len() / 2;
+        // The nodes with the range of leaves they cover, visited from left to right.
+        let mut stack = vec![(1, 0, size)];
+        while let Some((node, low, high)) = stack.pop() {
+            if low >= limit || !predicate(self.max_ends[node]) {
+                continue;
+            }
+            if high - low == 1 {
+                cues.push(self.entries[low].2);
+                continue;
+            }
+
+            let middle = (low + high) / 2;
+            stack.push((node * 2 + 1, middle, high));
+            stack.push((node * 2, low, middle));
+        }
+
+        cues
+    }
+}
+
+impl<'a> From<&'a SubRip> for CueIndex<'a, SrtSubtitle> {
+    fn from(srt: &'a SubRip) -> Self {
+        Self::new(
+            srt.subtitles
+                .iter()
+                .map(|subtitle| {
+                    (
+                        subtitle.start.into(),
+                        subtitle.end.into(),
+                        subtitle,
+                    )
+                }),
+        )
+    }
+}
+
+impl<'a> From<&'a WebVtt> for CueIndex<'a, VttCue> {
+    /// Builds the index of the cue blocks, skipping the other blocks.
+    fn from(vtt: &'a WebVtt) -> Self {
+        Self::new(
+            vtt.blocks
+                .iter()
+                .filter_map(|block| {
+                    match block {
+                        | VttBlock::Que(cue) => {
+                            Some((
+                                cue.timings.start.into(),
+                                cue.timings.end.into(),
+                                cue,
+                            ))
+                        },
+                        | _ => None,
+                    }
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(milliseconds: u64) -> Duration {
+        Duration::from_millis(milliseconds)
+    }
+
+    #[test]
+    fn empty() {
+        let index = CueIndex::<()>::new(vec![]);
+        assert!(index.is_empty());
+        assert!(index.at(millis(0)).is_empty());
+        assert!(index
+            .overlapping(millis(0), millis(1000))
+            .is_empty());
+    }
+
+    #[test]
+    fn web_vtt() {
+        let vtt = WebVtt::parse(
+            r#"WEBVTT
+
+NOTE A comment
+
+intro
+00:00:01.000 --> 00:00:10.000
+Hello
+
+00:00:02.000 --> 00:00:03.000
+World
+
+00:00:02.000 --> 00:00:02.000
+Empty
+"#,
+        )
+        .unwrap();
+        let index = CueIndex::from(&vtt);
+        assert_eq!(index.len(), 3);
+
+        let payloads = |cues: Vec<&VttCue>| {
+            cues.iter()
+                .map(|cue| cue.payload.join("\n"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            payloads(index.at(millis(500))),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            payloads(index.at(millis(1000))),
+            vec!["Hello"]
+        );
+        assert_eq!(
+            payloads(index.at(millis(2000))),
+            vec!["Hello", "World"]
+        );
+        assert_eq!(
+            payloads(index.at(millis(10000))),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            payloads(index.overlapping(millis(0), millis(2000))),
+            vec!["Hello"]
+        );
+        assert_eq!(
+            payloads(index.overlapping(millis(3000), millis(20000))),
+            vec!["Hello"]
+        );
+    }
+
+    #[test]
+    fn brute_force() {
+        // A deterministic pseudo-random sequence of cues.
+        let mut seed = 12345u64;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        let cues: Vec<(Duration, Duration)> = (0..500)
+            .map(|_| {
+                let start = next(100_000);
+                (
+                    millis(start),
+                    millis(start + next(10_000)),
+                )
+            })
+            .collect();
+        let index = CueIndex::new(
+            cues.iter()
+                .map(|cue| (cue.0, cue.1, cue)),
+        );
+
+        let sorted = |mut cues: Vec<(Duration, Duration)>| {
+            cues.sort();
+            cues
+        };
+        for _ in 0..200 {
+            let time = millis(next(120_000));
+            assert_eq!(
+                sorted(
+                    index
+                        .at(time)
+                        .into_iter()
+                        .copied()
+                        .collect()
+                ),
+                sorted(
+                    cues.iter()
+                        .filter(|(start, end)| *start <= time && time < *end)
+                        .copied()
+                        .collect()
+                )
+            );
+
+            let end = time + millis(next(5_000));
+            assert_eq!(
+                sorted(
+                    index
+                        .overlapping(time, end)
+                        .into_iter()
+                        .copied()
+                        .collect()
+                ),
+                sorted(
+                    cues.iter()
+                        .filter(|(start, cue_end)| {
+                            *start < end && time < *cue_end
+                        })
+                        .copied()
+                        .collect()
+                )
+            );
+        }
+    }
+}
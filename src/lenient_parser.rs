@@ -0,0 +1,1088 @@
+//! A lenient parser implementation that skips or repairs malformed blocks
+//! of the SubRip Subtitle and the WebVTT formats.
+
+use crate::srt::{SrtSubtitle, SrtTimestamp, SubRip};
+use crate::vtt::{
+    CueSettings, VttCue, VttHeader, VttTimestamp, VttTimings, WebVtt,
+};
+use crate::{str_parser, vtt_parser, Lenient, ParseWarning, WarningKind};
+
+/// A line of the input with its position.
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    /// The text without the newline.
+    text: &'a str,
+    /// The text with the newline if any.
+    raw: &'a str,
+    /// The byte offset of the line.
+    start: usize,
+    /// The line number starting from 1.
+    number: usize,
+}
+
+impl Line<'_> {
+    fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Only an empty line separates blocks as in the strict parsers.
+    fn is_blank(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Whether the line has only spaces and tabs.
+    fn is_whitespace(&self) -> bool {
+        self.text
+            .chars()
+            .all(|c| c == ' ' || c == '\t')
+    }
+
+    /// Whether the line has the start and the end timestamps around `-->`,
+    /// not only a text containing `-->`.
+    fn is_timing(&self) -> bool {
+        if !self.text.contains("-->") {
+            return false;
+        }
+
+        let parts = timing_line(self.text);
+        relaxed_timestamp(parts.start).is_some()
+            && relaxed_timestamp(parts.end).is_some()
+    }
+
+    /// Creates a warning at the sub-slice of the line text.
+    fn warning(
+        &self,
+        kind: WarningKind,
+        part: &str,
+    ) -> ParseWarning {
+        let offset = part.as_ptr() as usize - self.text.as_ptr() as usize;
+
+        ParseWarning {
+            kind,
+            line: self.number,
+            column: self.text[..offset]
+                .chars()
+                .count()
+                + 1,
+            span: self.start + offset..self.start + offset + part.len(),
+        }
+    }
+}
+
+/// Splits the input into lines with `\r\n`, `\n` or `\r`, skipping the byte order mark.
+fn lines(text: &str) -> Vec<Line> {
+    let mut lines = vec![];
+    let mut start = if text.starts_with('\u{FEFF}') {
+        '\u{FEFF}'.len_utf8()
+    } else {
+        0
+    };
+    let bytes = text.as_bytes();
+
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            | b'\n' | b'\r' => {
+                let end = i;
+                if bytes[i] == b'\r' && bytes.get(i + 1) == Some(&b'\n') {
+                    i += 1;
+                }
+                lines.push(Line {
+                    text: &text[start..end],
+                    raw: &text[start..i + 1],
+                    start,
+                    number: lines.len() + 1,
+                });
+                start = i + 1;
+            },
+            | _ => {},
+        }
+        i += 1;
+    }
+    if start < bytes.len() {
+        lines.push(Line {
+            text: &text[start..],
+            raw: &text[start..],
+            start,
+            number: lines.len() + 1,
+        });
+    }
+
+    lines
+}
+
+/// Groups the lines into blocks separated by blank lines,
+/// skipping the whitespace-only lines at the start of each block.
+fn blocks<'a>(lines: &[Line<'a>]) -> Vec<Vec<Line<'a>>> {
+    lines
+        .split(|line| line.is_blank())
+        .map(|block| {
+            block
+                .iter()
+                .skip_while(|line| line.is_whitespace())
+                .copied()
+                .collect::<Vec<_>>()
+        })
+        .filter(|block| !block.is_empty())
+        .collect()
+}
+
+/// Creates a warning spanning the whole lines.
+fn block_warning(
+    kind: WarningKind,
+    block: &[Line],
+) -> ParseWarning {
+    let first = block.first().unwrap();
+    let last = block.last().unwrap();
+
+    ParseWarning {
+        kind,
+        line: first.number,
+        column: 1,
+        span: first.start..last.end(),
+    }
+}
+
+/// Joins the lines of the block with the original newlines for the strict parser,
+/// ending the last line with `\n` if it has none.
+fn block_text(block: &[Line]) -> String {
+    let mut text = String::new();
+    for line in block {
+        text.push_str(line.raw);
+    }
+    if block
+        .last()
+        .is_some_and(|line| line.raw.len() == line.text.len())
+    {
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Drops the whitespace-only lines that the SubRip separator absorbs
+/// before the text, and splits the block at the ones after the text.
+fn split_whitespace<'a>(block: Vec<Line<'a>>) -> Vec<Vec<Line<'a>>> {
+    let mut blocks = vec![];
+    let mut current: Vec<Line<'a>> = vec![];
+    for line in block {
+        if !line.is_whitespace() {
+            current.push(line);
+            continue;
+        }
+
+        let has_text = current
+            .iter()
+            .position(|line| line.is_timing())
+            .is_some_and(|timing| timing + 1 < current.len());
+        if has_text {
+            blocks.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+
+    blocks
+}
+
+/// Splits the block before each extra timing line,
+/// including the preceding line if `with_sequence` returns true for it.
+fn split_block<'a>(
+    block: Vec<Line<'a>>,
+    with_sequence: impl Fn(&Line) -> bool,
+    warnings: &mut Vec<ParseWarning>,
+) -> Vec<Vec<Line<'a>>> {
+    let Some(first) = block
+        .iter()
+        .position(|line| line.is_timing())
+    else {
+        return vec![block];
+    };
+
+    let mut splits = vec![];
+    let mut previous = first;
+    for i in first + 1..block.len() {
+        if !block[i].is_timing() {
+            continue;
+        }
+
+        let split = if i - 1 > previous && with_sequence(&block[i - 1]) {
+            i - 1
+        } else {
+            i
+        };
+        warnings.push(block_warning(
+            WarningKind::MissingBlankLine,
+            &block[split..split + 1],
+        ));
+        splits.push(split);
+        previous = i;
+    }
+
+    let mut blocks = vec![];
+    let mut start = 0;
+    for split in splits {
+        blocks.push(block[start..split].to_vec());
+        start = split;
+    }
+    blocks.push(block[start..].to_vec());
+
+    blocks
+}
+
+/// Parses the timestamp with relaxed digits and separators,
+/// e.g. `1:2:3.4`, `00:01:02.003` or `01:02,003`,
+/// into hours, minutes, seconds and milliseconds.
+fn relaxed_timestamp(text: &str) -> Option<(u32, u8, u8, u16)> {
+    let text = text.trim();
+    let (clock, fraction) = match text.rfind(['.', ',']) {
+        | Some(i) => (&text[..i], Some(&text[i + 1..])),
+        | None => (text, None),
+    };
+
+    let mut fields = clock
+        .split(':')
+        .map(|field| {
+            if field.is_empty()
+                || !field
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+            {
+                None
+            } else {
+                field.parse::<u32>().ok()
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // The milliseconds separated by `:`.
+    let fraction = match (fraction, fields.len()) {
+        | (Some(fraction), _) => fraction,
+        | (None, 4) => {
+            fields.pop();
+            clock.rsplit(':').next()?
+        },
+        | (None, _) => "",
+    };
+    if fraction.len() > 3
+        || !fraction
+            .chars()
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let milliseconds = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<3}", fraction)
+            .parse()
+            .ok()?
+    };
+
+    let (hours, minutes, seconds) = match fields.as_slice() {
+        | [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+        | [minutes, seconds] => (0, *minutes, *seconds),
+        | _ => return None,
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    Some((
+        hours,
+        minutes as u8,
+        seconds as u8,
+        milliseconds,
+    ))
+}
+
+/// The timing line split into the start, the end and the rest.
+struct TimingLine<'a> {
+    start: &'a str,
+    end: &'a str,
+    rest: &'a str,
+}
+
+fn timing_line(text: &str) -> TimingLine {
+    let (start, right) = text
+        .split_once("-->")
+        .unwrap_or((text, ""));
+    let right = right.trim_start();
+    let (end, rest) = right
+        .split_once([' ', '\t'])
+        .unwrap_or((right, ""));
+
+    TimingLine {
+        start: start.trim(),
+        end,
+        rest: rest.trim(),
+    }
+}
+
+/// Parses the SubRip Subtitle leniently.
+pub(crate) fn srt(text: &str) -> Lenient<SubRip> {
+    // The valid input is the same as the strict parser without warnings.
+    if let Ok(srt) = str_parser::srt(text) {
+        return Lenient {
+            value: srt.to_sub_rip(),
+            warnings: vec![],
+        };
+    }
+
+    let mut warnings = vec![];
+    let mut subtitles: Vec<SrtSubtitle> = vec![];
+
+    for block in blocks(&lines(text)) {
+        let is_sequence = |line: &Line| {
+            line.text
+                .trim()
+                .parse::<u32>()
+                .is_ok()
+        };
+        for block in split_block(block, is_sequence, &mut warnings)
+            .into_iter()
+            .flat_map(split_whitespace)
+        {
+            let previous = subtitles
+                .last()
+                .map(|subtitle| subtitle.sequence)
+                .unwrap_or(0);
+            if let Ok(subtitle) = str_parser::subtitle(&block_text(&block)) {
+                subtitles.push(subtitle);
+            } else if let Some(subtitle) =
+                srt_subtitle(&block, previous, &mut warnings)
+            {
+                subtitles.push(subtitle);
+            }
+        }
+    }
+
+    warnings.sort_by_key(|warning| warning.span.start);
+
+    Lenient {
+        value: SubRip {
+            subtitles,
+        },
+        warnings,
+    }
+}
+
+fn srt_subtitle(
+    block: &[Line],
+    previous: u32,
+    warnings: &mut Vec<ParseWarning>,
+) -> Option<SrtSubtitle> {
+    let Some(timing) = block
+        .iter()
+        .position(|line| line.is_timing())
+    else {
+        warnings.push(block_warning(
+            WarningKind::InvalidBlock,
+            block,
+        ));
+        return None;
+    };
+
+    let sequence = match &block[..timing] {
+        | [] => None,
+        | [skipped @ .., sequence] => {
+            if !skipped.is_empty() {
+                warnings.push(block_warning(
+                    WarningKind::InvalidBlock,
+                    skipped,
+                ));
+            }
+            sequence
+                .text
+                .trim()
+                .parse()
+                .ok()
+        },
+    };
+    let sequence = sequence.unwrap_or_else(|| {
+        warnings.push(block_warning(
+            WarningKind::MissingSequence,
+            &block[..timing.max(1)],
+        ));
+        previous + 1
+    });
+
+    let line = &block[timing];
+    let parts = timing_line(line.text);
+    let mut timestamp = |part: &str| {
+        str_parser::timestamp(part)
+            .ok()
+            .or_else(|| {
+                let (hours, minutes, seconds, milliseconds) =
+                    relaxed_timestamp(part)?;
+                let timestamp = SrtTimestamp {
+                    hours: hours.try_into().ok()?,
+                    minutes,
+                    seconds,
+                    milliseconds,
+                };
+                warnings
+                    .push(line.warning(WarningKind::RepairedTimestamp, part));
+                Some(timestamp)
+            })
+    };
+    let (Some(start), Some(end)) = (
+        timestamp(parts.start),
+        timestamp(parts.end),
+    ) else {
+        warnings.push(block_warning(
+            WarningKind::InvalidBlock,
+            block,
+        ));
+        return None;
+    };
+
+    let line_position = if parts.rest.is_empty() {
+        None
+    } else {
+        match str_parser::line_position(parts.rest) {
+            | Ok(line_position) => Some(line_position),
+            | Err(_) => {
+                warnings.push(
+                    line.warning(WarningKind::InvalidSettings, parts.rest),
+                );
+                None
+            },
+        }
+    };
+
+    Some(SrtSubtitle {
+        sequence,
+        start,
+        end,
+        text: block[timing + 1..]
+            .iter()
+            .map(|line| line.text.trim().to_string())
+            .collect(),
+        line_position,
+    })
+}
+
+/// Parses the WebVTT leniently.
+pub(crate) fn vtt(text: &str) -> Lenient<WebVtt> {
+    // The valid input is the same as the strict parser without warnings.
+    if let Ok(vtt) = vtt_parser::vtt(text) {
+        return Lenient {
+            value: vtt.to_web_vtt(),
+            warnings: vec![],
+        };
+    }
+
+    let mut warnings = vec![];
+    let mut blocks = blocks(&lines(text)).into_iter();
+
+    let mut header = VttHeader::default();
+    let mut first = None;
+    match blocks.next() {
+        | Some(block)
+            if block[0]
+                .text
+                .starts_with("WEBVTT") =>
+        {
+            match vtt_parser::header(&block_text(&block)) {
+                | Ok(parsed) => header = parsed,
+                | Err(_) => {
+                    warnings.push(block_warning(
+                        WarningKind::MissingHeader,
+                        &block[..1],
+                    ))
+                },
+            }
+        },
+        | Some(block) => {
+            warnings.push(block_warning(
+                WarningKind::MissingHeader,
+                &block[..1],
+            ));
+            first = Some(block);
+        },
+        | None => {
+            warnings.push(ParseWarning {
+                kind: WarningKind::MissingHeader,
+                line: 1,
+                column: 1,
+                span: 0..0,
+            });
+        },
+    }
+
+    let mut parsed = vec![];
+    for block in first
+        .into_iter()
+        .chain(blocks)
+    {
+        let is_cue = ![
+            "NOTE", "STYLE", "REGION",
+        ]
+        .iter()
+        .any(|keyword| {
+            block[0]
+                .text
+                .starts_with(keyword)
+        });
+        let split = if is_cue {
+            split_block(block, |_| false, &mut warnings)
+        } else {
+            vec![block]
+        };
+
+        for block in split {
+            if let Ok(block) = vtt_parser::block(&block_text(&block)) {
+                parsed.push(block);
+            } else if let Some(cue) = vtt_cue(&block, &mut warnings) {
+                parsed.push(cue.into());
+            }
+        }
+    }
+
+    warnings.sort_by_key(|warning| warning.span.start);
+
+    Lenient {
+        value: WebVtt {
+            header,
+            blocks: parsed,
+        },
+        warnings,
+    }
+}
+
+fn vtt_cue(
+    block: &[Line],
+    warnings: &mut Vec<ParseWarning>,
+) -> Option<VttCue> {
+    let Some(timing) = block
+        .iter()
+        .position(|line| line.is_timing())
+    else {
+        warnings.push(block_warning(
+            WarningKind::InvalidBlock,
+            block,
+        ));
+        return None;
+    };
+
+    let identifier = match &block[..timing] {
+        | [] => None,
+        | [skipped @ .., identifier] => {
+            if !skipped.is_empty() {
+                warnings.push(block_warning(
+                    WarningKind::InvalidBlock,
+                    skipped,
+                ));
+            }
+            Some(
+                identifier
+                    .text
+                    .trim()
+                    .to_string(),
+            )
+        },
+    };
+
+    let line = &block[timing];
+    let parts = timing_line(line.text);
+    let mut timestamp = |part: &str| {
+        vtt_parser::timestamp(part)
+            .ok()
+            .or_else(|| {
+                let (hours, minutes, seconds, milliseconds) =
+                    relaxed_timestamp(part)?;
+                warnings
+                    .push(line.warning(WarningKind::RepairedTimestamp, part));
+                Some(VttTimestamp {
+                    hours,
+                    minutes,
+                    seconds,
+                    milliseconds,
+                })
+            })
+    };
+    let (Some(start), Some(end)) = (
+        timestamp(parts.start),
+        timestamp(parts.end),
+    ) else {
+        warnings.push(block_warning(
+            WarningKind::InvalidBlock,
+            block,
+        ));
+        return None;
+    };
+
+    let settings = if parts.rest.is_empty() {
+        None
+    } else {
+        let mut settings = CueSettings::default();
+        for option in parts.rest.split_whitespace() {
+            match vtt_parser::cue_settings(option) {
+                | Ok(option) => merge_settings(&mut settings, option),
+                | Err(_) => {
+                    warnings.push(
+                        line.warning(WarningKind::InvalidSettings, option),
+                    )
+                },
+            }
+        }
+        Some(settings)
+    };
+
+    Some(VttCue {
+        identifier,
+        timings: VttTimings {
+            start,
+            end,
+        },
+        settings,
+        payload: block[timing + 1..]
+            .iter()
+            .map(|line| line.text.trim().to_string())
+            .collect(),
+    })
+}
+
+fn merge_settings(
+    settings: &mut CueSettings,
+    option: CueSettings,
+) {
+    settings.vertical = option
+        .vertical
+        .or(settings.vertical.take());
+    settings.line = option
+        .line
+        .or(settings.line.take());
+    settings.position = option
+        .position
+        .or(settings.position.take());
+    settings.size = option
+        .size
+        .or(settings.size.take());
+    settings.align = option
+        .align
+        .or(settings.align.take());
+    settings.region = option
+        .region
+        .or(settings.region.take());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::srt::LinePosition;
+    use crate::vtt::{Line as VttLine, VttBlock, VttComment};
+
+    #[test]
+    fn split_lines() {
+        let lines = lines("\u{FEFF}a\r\nb\rc\n\nd");
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| (line.text, line.start, line.number))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a", 3, 1),
+                ("b", 6, 2),
+                ("c", 8, 3),
+                ("", 10, 4),
+                ("d", 11, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn relaxed_timestamp() {
+        assert_eq!(
+            super::relaxed_timestamp("00:00:01,000"),
+            Some((0, 0, 1, 0))
+        );
+        assert_eq!(
+            super::relaxed_timestamp("1:2:3.4"),
+            Some((1, 2, 3, 400))
+        );
+        assert_eq!(
+            super::relaxed_timestamp("01:02,03"),
+            Some((0, 1, 2, 30))
+        );
+        assert_eq!(
+            super::relaxed_timestamp("00:01:02:003"),
+            Some((0, 1, 2, 3))
+        );
+        assert_eq!(
+            super::relaxed_timestamp("100:00:00"),
+            Some((100, 0, 0, 0))
+        );
+        assert_eq!(
+            super::relaxed_timestamp("00:60:00,000"),
+            None
+        );
+        assert_eq!(
+            super::relaxed_timestamp("00:00:01,0000"),
+            None
+        );
+        assert_eq!(
+            super::relaxed_timestamp("a:00:01,000"),
+            None
+        );
+        assert_eq!(super::relaxed_timestamp("1"), None);
+        assert_eq!(super::relaxed_timestamp(""), None);
+    }
+
+    #[test]
+    fn srt_valid() {
+        let text = r#"1
+00:00:01,000 --> 00:00:02,000 X1:1 X2:2 Y1:3 Y2:4
+Hello, world!
+
+2
+00:00:03,000 --> 00:00:04,000
+This is a test.
+Thank you.
+"#;
+
+        let lenient = srt(text);
+        assert_eq!(
+            lenient.value,
+            SubRip::parse(text).unwrap()
+        );
+        assert_eq!(
+            lenient.value.subtitles[0].line_position,
+            Some(LinePosition {
+                x1: 1,
+                x2: 2,
+                y1: 3,
+                y2: 4,
+            })
+        );
+        assert!(lenient.warnings.is_empty());
+    }
+
+    #[test]
+    fn srt_repaired() {
+        let text = "1\n00:00:01.5 --> 00:00:02,000\nRepaired\n\n\
+            00:00:03,000 --> 00:00:04,000 X1:a\nNo sequence\n\
+            3\n00:00:05,000 --> 00:00:06,000\nNo blank line\n\n\
+            4\nBroken --> 00:00:08,000\nSkipped\n\n\
+            Garbage\n";
+
+        let lenient = srt(text);
+
+        assert_eq!(
+            lenient
+                .value
+                .subtitles
+                .iter()
+                .map(|subtitle| (subtitle.sequence, subtitle.text.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, vec!["Repaired".to_string()]),
+                (2, vec!["No sequence".to_string()]),
+                (3, vec!["No blank line".to_string()]),
+            ]
+        );
+        assert_eq!(
+            lenient.value.subtitles[0].start,
+            SrtTimestamp {
+                seconds: 1,
+                milliseconds: 500,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            lenient.warnings,
+            vec![
+                ParseWarning {
+                    kind: WarningKind::RepairedTimestamp,
+                    line: 2,
+                    column: 1,
+                    span: 2..12,
+                },
+                ParseWarning {
+                    kind: WarningKind::MissingSequence,
+                    line: 5,
+                    column: 1,
+                    span: 40..74,
+                },
+                ParseWarning {
+                    kind: WarningKind::InvalidSettings,
+                    line: 5,
+                    column: 31,
+                    span: 70..74,
+                },
+                ParseWarning {
+                    kind: WarningKind::MissingBlankLine,
+                    line: 7,
+                    column: 1,
+                    span: 87..88,
+                },
+                ParseWarning {
+                    kind: WarningKind::InvalidBlock,
+                    line: 11,
+                    column: 1,
+                    span: 134..167,
+                },
+                ParseWarning {
+                    kind: WarningKind::InvalidBlock,
+                    line: 15,
+                    column: 1,
+                    span: 169..176,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn vtt_valid() {
+        let text = r#"WEBVTT
+
+REGION
+id:bottom
+
+NOTE A comment
+
+intro
+00:00:01.000 --> 00:00:02.000 line:0 region:bottom
+Hello, world!
+
+00:00:03.000 --> 00:00:04.000
+This is a test.
+"#;
+
+        let lenient = vtt(text);
+        assert_eq!(
+            lenient.value,
+            WebVtt::parse(text).unwrap()
+        );
+        assert!(lenient.warnings.is_empty());
+    }
+
+    #[test]
+    fn vtt_repaired() {
+        let text = "NOTE No header\n\n\
+            00:01.5 --> 00:00:02.000 line:0 bogus\nRepaired\n\
+            00:00:03.000 --> 00:00:04.000\nNo blank line\n\n\
+            Broken --> 00:00:06.000\nSkipped\n";
+
+        let lenient = vtt(text);
+
+        assert_eq!(
+            lenient.value.header,
+            VttHeader::default()
+        );
+        assert_eq!(
+            lenient.value.blocks,
+            vec![
+                VttBlock::from(VttComment::Side(
+                    "No header".to_string()
+                )),
+                VttCue {
+                    identifier: None,
+                    timings: VttTimings {
+                        start: VttTimestamp {
+                            seconds: 1,
+                            milliseconds: 500,
+                            ..Default::default()
+                        },
+                        end: VttTimestamp {
+                            seconds: 2,
+                            ..Default::default()
+                        },
+                    },
+                    settings: Some(CueSettings {
+                        line: Some(VttLine::LineNumber(0.0, None)),
+                        ..Default::default()
+                    }),
+                    payload: vec!["Repaired".to_string()],
+                }
+                .into(),
+                VttCue {
+                    identifier: None,
+                    timings: VttTimings {
+                        start: VttTimestamp {
+                            seconds: 3,
+                            ..Default::default()
+                        },
+                        end: VttTimestamp {
+                            seconds: 4,
+                            ..Default::default()
+                        },
+                    },
+                    settings: None,
+                    payload: vec!["No blank line".to_string()],
+                }
+                .into(),
+            ]
+        );
+        assert_eq!(
+            lenient
+                .warnings
+                .iter()
+                .map(|warning| {
+                    (
+                        warning.kind,
+                        warning.line,
+                        warning.column,
+                    )
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                (WarningKind::MissingHeader, 1, 1),
+                (WarningKind::RepairedTimestamp, 3, 1),
+                (WarningKind::InvalidSettings, 3, 33),
+                (WarningKind::MissingBlankLine, 5, 1),
+                (WarningKind::InvalidBlock, 8, 1),
+            ]
+        );
+    }
+
+    /// A deterministic linear congruential generator for the inputs.
+    struct Random(u64);
+
+    impl Random {
+        fn pick<'a>(
+            &mut self,
+            choices: &[&'a str],
+        ) -> &'a str {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            choices[(self.0 >> 33) as usize % choices.len()]
+        }
+    }
+
+    /// Generates the blocks from the fragments with mixed newlines,
+    /// whitespace-only lines and texts containing `-->`.
+    fn generate(
+        random: &mut Random,
+        head: &[&str],
+        blocks: &[&[&str]],
+    ) -> String {
+        let mut text = random.pick(head).to_string();
+        for _ in 0..random.pick(&["1", "2", "3"]).parse().unwrap() {
+            for fragments in blocks {
+                text.push_str(random.pick(fragments));
+            }
+        }
+
+        text
+    }
+
+    #[test]
+    fn srt_strict_equivalence() {
+        let mut random = Random(1);
+        let mut valid = 0;
+        for _ in 0..2000 {
+            let text = generate(
+                &mut random,
+                &["", "\n", " \r\n"],
+                &[
+                    &["1\n", "2\r\n", "3 \n", "x\n", ""],
+                    &[
+                        "00:00:01,000 --> 00:00:02,000\n",
+                        "00:00:01,000 --> 00:00:02,000 X1:1 X2:2 Y1:3 Y2:4\r\n",
+                        "00:00:01.000 --> 00:00:02,000\n",
+                    ],
+                    &["", " \n", "\t\r\n"],
+                    &["Hello\n", "a --> b\r\n", "\u{A0}\n", "\u{3000}\r\n"],
+                    &["", "World\n", " \n", "\u{A0}\n"],
+                    &["\n", "\r\n", "\n\n", " \n\n", ""],
+                ],
+            );
+
+            if let Ok(strict) = SubRip::parse(&text) {
+                valid += 1;
+                let lenient = srt(&text);
+                assert_eq!(lenient.value, strict, "{:?}", text);
+                assert!(lenient.warnings.is_empty(), "{:?}", text);
+            }
+        }
+        assert!(valid > 100);
+    }
+
+    #[test]
+    fn vtt_strict_equivalence() {
+        let mut random = Random(1);
+        let mut valid = 0;
+        for _ in 0..2000 {
+            let text = generate(
+                &mut random,
+                &[
+                    "WEBVTT\n\n",
+                    "WEBVTT Side\r\n\r\n",
+                    "WEBVTT\r\nBelow\r\n\r\n",
+                    "WEBVTT\n \n\n",
+                ],
+                &[
+                    &[
+                        "",
+                        "NOTE A comment\n\n",
+                        "STYLE\r\n::cue { color: red }\r\n\r\n",
+                        "REGION\nid:r\n\n",
+                    ],
+                    &["", "intro\n", "a --> b\r\n"],
+                    &[
+                        "00:00:01.000 --> 00:00:02.000\n",
+                        "00:01.000 --> 00:02.000 line:0\r\n",
+                        "00:00:01,000 --> 00:00:02.000\n",
+                    ],
+                    &["Hello\n", "a --> b\r\n", " \n", "\u{3000}\n"],
+                    &["", "World\n", " \r\n", "\u{A0}\n"],
+                    &["\n", "\r\n", "\n\n", " \n", ""],
+                ],
+            );
+
+            if let Ok(strict) = WebVtt::parse(&text) {
+                valid += 1;
+                let lenient = vtt(&text);
+                assert_eq!(lenient.value, strict, "{:?}", text);
+                assert!(lenient.warnings.is_empty(), "{:?}", text);
+            }
+        }
+        assert!(valid > 100);
+    }
+
+    #[test]
+    fn srt_valid_blocks_in_invalid() {
+        let valid = "1\r\n00:00:01,000 --> 00:00:02,000\r\n \r\nHello\r\n\r\n\
+            2\n00:00:03,000 --> 00:00:04,000\na --> b\nWorld\n";
+
+        let lenient = srt(&format!("{}\nGarbage\n", valid));
+        assert_eq!(
+            lenient.value,
+            SubRip::parse(valid).unwrap()
+        );
+        assert_eq!(
+            lenient
+                .warnings
+                .iter()
+                .map(|warning| (warning.kind, warning.line))
+                .collect::<Vec<_>>(),
+            vec![(WarningKind::InvalidBlock, 11)]
+        );
+    }
+
+    #[test]
+    fn vtt_valid_blocks_in_invalid() {
+        let valid = "WEBVTT Side\r\n\r\n\
+            STYLE\r\n::cue { color: red }\r\n\r\n\
+            00:00:01.000 --> 00:00:02.000\nHello\n \na --> b\n";
+
+        let lenient = vtt(&format!("{}\nGarbage\n", valid));
+        assert_eq!(
+            lenient.value,
+            WebVtt::parse(valid).unwrap()
+        );
+        assert_eq!(
+            lenient
+                .warnings
+                .iter()
+                .map(|warning| (warning.kind, warning.line))
+                .collect::<Vec<_>>(),
+            vec![(WarningKind::InvalidBlock, 11)]
+        );
+    }
+}
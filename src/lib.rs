@@ -1,19 +1,49 @@
 //! # subtp
-//! A parser for subtitle files such as the SubRip Subtitle (.srt) and the WebVTT (.vtt).
+//! A parser for subtitle files such as the SubRip Subtitle (.srt), the WebVTT (.vtt) and the TTML (.ttml).
 //!
 //! - [SubRip Subtitle (.srt)](`crate::srt::SubRip`)
 //! - [WebVTT (.vtt)](`crate::vtt::WebVtt`)
+//! - [Advanced SubStation Alpha (.ass/.ssa)](`crate::ass::SubStationAlpha`)
+//! - [Timed Text Markup Language (.ttml/.dfxp)](`crate::ttml::Ttml`)
+//!
+//! Each format can be converted through the format-agnostic [`Subtitles`](`crate::subtitles::Subtitles`) model.
 
 // Re-exports.
-pub use error::ParseError;
-pub use result::ParseResult;
+pub use error::{
+    ParseError, ReadError, TimestampFieldError, TimestampRangeError,
+};
+pub use block_writer::LineEnding;
+pub use result::{ParseResult, ReadResult};
+pub use warning::{Lenient, ParseWarning, WarningKind};
 
 // Public modules.
+pub mod ass;
+pub mod detect;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+pub mod index;
+pub mod lint;
+pub mod reflow;
+pub mod repair;
+pub mod split;
 pub mod srt;
+pub mod subtitles;
+pub mod timecode;
+pub mod timing;
+pub mod ttml;
 pub mod vtt;
 
 // Internal modules.
+mod ass_parser;
+mod block_reader;
+mod block_writer;
 mod error;
+mod lenient_parser;
 mod result;
+mod scanner;
 mod str_parser;
+mod timecode_parser;
+mod ttml_parser;
 mod vtt_parser;
+mod vtt_spec_parser;
+mod warning;
@@ -0,0 +1,749 @@
+//! Quality checks of subtitle timings and texts provided by [`subtp::lint::LintConfig`](LintConfig).
+//!
+//! ## Example
+//! ```
+//! use subtp::lint::{LintConfig, LintKind, Severity};
+//! use subtp::srt::SubRip;
+//!
+//! let text = r#"1
+//! 00:00:01,000 --> 00:00:03,000
+//! Hello, world!
+//!
+//! 2
+//! 00:00:02,500 --> 00:00:04,000
+//! This is a sample.
+//! "#;
+//!
+//! let srt = SubRip::parse(text).unwrap();
+//! let findings = srt.lint(&LintConfig::default());
+//!
+//! assert_eq!(findings.len(), 1);
+//! assert_eq!(findings[0].cue, 0);
+//! assert_eq!(
+//!     findings[0].kind,
+//!     LintKind::Overlap {
+//!         next: 1
+//!     }
+//! );
+//! assert_eq!(findings[0].severity, Severity::Error);
+//! assert_eq!(
+//!     findings[0].to_string(),
+//!     "cue 0: error: overlaps cue 1"
+//! );
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::timing::FrameRate;
+
+/// The configuration of the quality checks.
+///
+/// Each check is disabled by `false` or `None`.
+///
+/// ## Example
+/// ```
+/// use subtp::lint::LintConfig;
+///
+/// let config = LintConfig {
+///     max_line_length: Some(37),
+///     max_reading_speed: None,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct LintConfig {
+    /// Checks that a cue does not overlap any later cue.
+    pub overlaps: bool,
+    /// Checks that a cue ends after it starts.
+    pub durations: bool,
+    /// Checks that a cue does not start before the previous cue.
+    pub order: bool,
+    /// Checks that the sequence numbers of SubRip Subtitle are increasing.
+    pub sequence: bool,
+    /// The minimum duration of a cue.
+    pub min_duration: Option<Duration>,
+    /// The minimum number of frames between a cue and the next cue,
+    /// unless the next cue starts exactly at the end.
+    pub min_gap_frames: Option<u32>,
+    /// The frame rate of the frames of `min_gap_frames`.
+    pub frame_rate: FrameRate,
+    /// The maximum number of lines of a cue.
+    pub max_lines: Option<usize>,
+    /// The maximum number of characters of a line without markups.
+    pub max_line_length: Option<usize>,
+    /// The maximum number of characters per second without markups and line breaks.
+    pub max_reading_speed: Option<f64>,
+}
+
+impl Default for LintConfig {
+    /// Enables all checks with common delivery limits:
+    /// 5/6 seconds of duration, 2 frames of gap at 24 fps, 2 lines of 42 characters,
+    /// and 20 characters per second.
+    fn default() -> Self {
+        Self {
+            overlaps: true,
+            durations: true,
+            order: true,
+            sequence: true,
+            min_duration: Some(Duration::from_millis(833)),
+            min_gap_frames: Some(2),
+            frame_rate: FrameRate::default(),
+            max_lines: Some(2),
+            max_line_length: Some(42),
+            max_reading_speed: Some(20.0),
+        }
+    }
+}
+
+/// The severity of a finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Severity {
+    /// The cue is displayable but violates a guideline.
+    Warning,
+    /// The cue is broken or displayed incorrectly.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Warning => write!(f, "warning"),
+            | Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A finding of the quality checks.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct LintFinding {
+    /// The index of the cue.
+    pub cue: usize,
+    /// The kind of the finding.
+    pub kind: LintKind,
+    /// The severity of the finding.
+    pub severity: Severity,
+}
+
+impl Display for LintFinding {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "cue {}: {}: {}",
+            self.cue, self.severity, self.kind
+        )
+    }
+}
+
+/// The kind of a finding.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum LintKind {
+    /// The cue and a later cue in the document order are displayed at the same time.
+    Overlap {
+        /// The index of the later cue.
+        next: usize,
+    },
+    /// The cue starts before the previous cue.
+    StartOrder {
+        /// The index of the previous cue.
+        previous: usize,
+    },
+    /// The cue ends when it starts.
+    ZeroDuration,
+    /// The cue ends before it starts.
+    NegativeDuration,
+    /// The cue is shorter than the minimum duration.
+    ShortDuration {
+        /// The duration of the cue.
+        duration: Duration,
+    },
+    /// The gap to the next cue is shorter than the minimum number of frames.
+    ShortGap {
+        /// The index of the next cue.
+        next: usize,
+        /// The gap between the cues.
+        gap: Duration,
+    },
+    /// The cue has more lines than the maximum.
+    TooManyLines {
+        /// The number of lines.
+        lines: usize,
+    },
+    /// A line of the cue is longer than the maximum.
+    LineTooLong {
+        /// The index of the line in the cue.
+        line: usize,
+        /// The number of characters of the line.
+        length: usize,
+    },
+    /// The cue is faster to read than the maximum.
+    ReadingSpeed {
+        /// The characters per second.
+        characters_per_second: f64,
+    },
+    /// The sequence number is not greater than the previous one.
+    SequenceOrder {
+        /// The previous sequence number.
+        previous: u32,
+        /// The sequence number of the cue.
+        sequence: u32,
+    },
+}
+
+impl LintKind {
+    /// Returns the severity of the finding.
+    pub fn severity(&self) -> Severity {
+        match self {
+            | Self::Overlap {
+                ..
+            }
+            | Self::ZeroDuration
+            | Self::NegativeDuration
+            | Self::SequenceOrder {
+                ..
+            } => Severity::Error,
+            | Self::StartOrder {
+                ..
+            }
+            | Self::ShortDuration {
+                ..
+            }
+            | Self::ShortGap {
+                ..
+            }
+            | Self::TooManyLines {
+                ..
+            }
+            | Self::LineTooLong {
+                ..
+            }
+            | Self::ReadingSpeed {
+                ..
+            } => Severity::Warning,
+        }
+    }
+}
+
+impl Display for LintKind {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Overlap {
+                next,
+            } => write!(f, "overlaps cue {}", next),
+            | Self::StartOrder {
+                previous,
+            } => write!(f, "starts before cue {}", previous),
+            | Self::ZeroDuration => write!(f, "duration is zero"),
+            | Self::NegativeDuration => write!(f, "ends before it starts"),
+            | Self::ShortDuration {
+                duration,
+            } => {
+                write!(
+                    f,
+                    "duration {} ms is too short",
+                    duration.as_millis()
+                )
+            },
+            | Self::ShortGap {
+                next,
+                gap,
+            } => {
+                write!(
+                    f,
+                    "gap {} ms to cue {} is too short",
+                    gap.as_millis(),
+                    next
+                )
+            },
+            | Self::TooManyLines {
+                lines,
+            } => write!(f, "{} lines are too many", lines),
+            | Self::LineTooLong {
+                line,
+                length,
+            } => {
+                write!(
+                    f,
+                    "line {} of {} characters is too long",
+                    line + 1,
+                    length
+                )
+            },
+            | Self::ReadingSpeed {
+                characters_per_second,
+            } => {
+                write!(
+                    f,
+                    "reading speed {:.1} characters per second is too fast",
+                    characters_per_second
+                )
+            },
+            | Self::SequenceOrder {
+                previous,
+                sequence,
+            } => {
+                write!(
+                    f,
+                    "sequence number {} follows {}",
+                    sequence, previous
+                )
+            },
+        }
+    }
+}
+
+/// A cue to check.
+pub(crate) struct LintCue {
+    /// The start time.
+    pub(crate) start: Duration,
+    /// The end time.
+    pub(crate) end: Duration,
+    /// The lines without markups.
+    pub(crate) lines: Vec<String>,
+    /// The sequence number of SubRip Subtitle.
+    pub(crate) sequence: Option<u32>,
+}
+
+/// Runs the checks over the cues in the document order.
+pub(crate) fn lint(
+    cues: &[LintCue],
+    config: &LintConfig,
+) -> Vec<LintFinding> {
+    let mut findings = vec![];
+    let overlaps = if config.overlaps {
+        overlaps(cues)
+    } else {
+        vec![vec![]; cues.len()]
+    };
+    let min_gap = config
+        .min_gap_frames
+        .and_then(|frames| {
+            config
+                .frame_rate
+                .frames_to_duration(frames as u64)
+        });
+
+    for (index, cue) in cues.iter().enumerate() {
+        let mut find = |kind: LintKind| {
+            findings.push(LintFinding {
+                cue: index,
+                severity: kind.severity(),
+                kind,
+            })
+        };
+
+        if config.sequence {
+            if let (Some(previous), Some(sequence)) = (
+                index
+                    .checked_sub(1)
+                    .and_then(|previous| cues[previous].sequence),
+                cue.sequence,
+            ) {
+                if sequence <= previous {
+                    find(LintKind::SequenceOrder {
+                        previous,
+                        sequence,
+                    });
+                }
+            }
+        }
+
+        if let Some(previous) = index.checked_sub(1) {
+            if config.order && cue.start < cues[previous].start {
+                find(LintKind::StartOrder {
+                    previous,
+                });
+            }
+        }
+
+        if config.durations && cue.end <= cue.start {
+            find(
+                if cue.end == cue.start {
+                    LintKind::ZeroDuration
+                } else {
+                    LintKind::NegativeDuration
+                },
+            );
+        }
+        let duration = cue
+            .end
+            .saturating_sub(cue.start);
+        if let Some(min_duration) = config.min_duration {
+            if cue.end > cue.start && duration < min_duration {
+                find(LintKind::ShortDuration {
+                    duration,
+                });
+            }
+        }
+
+        for &next in &overlaps[index] {
+            find(LintKind::Overlap {
+                next,
+            });
+        }
+        if let Some(next) = cues.get(index + 1) {
+            if let Some(min_gap) = min_gap {
+                let gap = next
+                    .start
+                    .saturating_sub(cue.end);
+                if next.start > cue.end && gap < min_gap {
+                    find(LintKind::ShortGap {
+                        next: index + 1,
+                        gap,
+                    });
+                }
+            }
+        }
+
+        if let Some(max_lines) = config.max_lines {
+            if cue.lines.len() > max_lines {
+                find(LintKind::TooManyLines {
+                    lines: cue.lines.len(),
+                });
+            }
+        }
+        if let Some(max_line_length) = config.max_line_length {
+            for (line, text) in cue.lines.iter().enumerate() {
+                let length = text.chars().count();
+                if length > max_line_length {
+                    find(LintKind::LineTooLong {
+                        line,
+                        length,
+                    });
+                }
+            }
+        }
+        if let Some(max_reading_speed) = config.max_reading_speed {
+            let characters: usize = cue
+                .lines
+                .iter()
+                .map(|line| line.chars().count())
+                .sum();
+            if !duration.is_zero() {
+                let characters_per_second =
+                    characters as f64 / duration.as_secs_f64();
+                if characters_per_second > max_reading_speed {
+                    find(LintKind::ReadingSpeed {
+                        characters_per_second,
+                    });
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+/// Finds the later cues that each cue overlaps,
+/// sweeping the cues by the start so that an unsorted or long cue overlapping
+/// several others reports every pair.
+fn overlaps(cues: &[LintCue]) -> Vec<Vec<usize>> {
+    let mut order = (0..cues.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&index| cues[index].start);
+
+    let mut overlaps = vec![vec![]; cues.len()];
+    for (position, &index) in order.iter().enumerate() {
+        let cue = &cues[index];
+        for &other in &order[position + 1..] {
+            if cues[other].start >= cue.end {
+                break;
+            }
+            if cue.start < cues[other].end {
+                overlaps[index.min(other)].push(index.max(other));
+            }
+        }
+    }
+    for overlaps in &mut overlaps {
+        overlaps.sort_unstable();
+    }
+
+    overlaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(
+        start: u64,
+        end: u64,
+        lines: &[&str],
+        sequence: Option<u32>,
+    ) -> LintCue {
+        LintCue {
+            start: Duration::from_millis(start),
+            end: Duration::from_millis(end),
+            lines: lines
+                .iter()
+                .map(|line| line.to_string())
+                .collect(),
+            sequence,
+        }
+    }
+
+    fn kinds(findings: Vec<LintFinding>) -> Vec<(usize, LintKind)> {
+        findings
+            .into_iter()
+            .map(|finding| (finding.cue, finding.kind))
+            .collect()
+    }
+
+    #[test]
+    fn timings() {
+        let cues = vec![
+            cue(1000, 3000, &["A"], None),
+            cue(3000, 2000, &["B"], None),
+            cue(5000, 5000, &["C"], None),
+            cue(5050, 5500, &["D"], None),
+            cue(7000, 9000, &["E"], None),
+        ];
+
+        assert_eq!(
+            kinds(lint(&cues, &LintConfig::default())),
+            vec![
+                (1, LintKind::NegativeDuration),
+                (2, LintKind::ZeroDuration),
+                (
+                    2,
+                    LintKind::ShortGap {
+                        next: 3,
+                        gap: Duration::from_millis(50),
+                    }
+                ),
+                (
+                    3,
+                    LintKind::ShortDuration {
+                        duration: Duration::from_millis(450),
+                    }
+                ),
+            ]
+        );
+
+        let cues = vec![
+            cue(1000, 3000, &["A"], None),
+            cue(2000, 4000, &["B"], None),
+        ];
+        assert_eq!(
+            kinds(lint(&cues, &LintConfig::default())),
+            vec![(
+                0,
+                LintKind::Overlap {
+                    next: 1
+                }
+            )]
+        );
+        assert!(lint(
+            &cues,
+            &LintConfig {
+                overlaps: false,
+                ..Default::default()
+            }
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn unsorted() {
+        let cues = vec![
+            cue(5000, 6000, &["A"], None),
+            cue(1000, 2000, &["B"], None),
+            cue(1500, 3000, &["C"], None),
+        ];
+
+        assert_eq!(
+            kinds(lint(&cues, &LintConfig::default())),
+            vec![
+                (
+                    1,
+                    LintKind::StartOrder {
+                        previous: 0
+                    }
+                ),
+                (
+                    1,
+                    LintKind::Overlap {
+                        next: 2
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            lint(&cues, &LintConfig::default())[0].to_string(),
+            "cue 1: warning: starts before cue 0"
+        );
+        assert_eq!(
+            kinds(lint(
+                &cues,
+                &LintConfig {
+                    order: false,
+                    ..Default::default()
+                }
+            )),
+            vec![(
+                1,
+                LintKind::Overlap {
+                    next: 2
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn overlaps_spanning() {
+        let cues = vec![
+            cue(1000, 9000, &["A"], None),
+            cue(2000, 3000, &["B"], None),
+            cue(4000, 5000, &["C"], None),
+            cue(6000, 7000, &["D"], None),
+            cue(9000, 10000, &["E"], None),
+        ];
+        let config = LintConfig {
+            min_gap_frames: None,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            kinds(lint(&cues, &config)),
+            vec![
+                (
+                    0,
+                    LintKind::Overlap {
+                        next: 1
+                    }
+                ),
+                (
+                    0,
+                    LintKind::Overlap {
+                        next: 2
+                    }
+                ),
+                (
+                    0,
+                    LintKind::Overlap {
+                        next: 3
+                    }
+                ),
+            ]
+        );
+
+        // The spanning cue at the end of the document order.
+        let mut cues = cues;
+        let spanning = cues.remove(0);
+        cues.push(spanning);
+        assert_eq!(
+            kinds(lint(
+                &cues,
+                &LintConfig {
+                    order: false,
+                    ..config
+                }
+            )),
+            vec![
+                (
+                    0,
+                    LintKind::Overlap {
+                        next: 4
+                    }
+                ),
+                (
+                    1,
+                    LintKind::Overlap {
+                        next: 4
+                    }
+                ),
+                (
+                    2,
+                    LintKind::Overlap {
+                        next: 4
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn texts() {
+        let cues = vec![cue(
+            0,
+            1000,
+            &[
+                "A line longer than ten",
+                "B",
+                "C",
+            ],
+            None,
+        )];
+        let config = LintConfig {
+            max_line_length: Some(10),
+            ..Default::default()
+        };
+
+        let findings = lint(&cues, &config);
+        assert_eq!(
+            findings
+                .iter()
+                .map(|finding| finding.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "cue 0: warning: 3 lines are too many",
+                "cue 0: warning: line 1 of 22 characters is too long",
+                "cue 0: warning: reading speed 24.0 characters per second is too fast",
+            ]
+        );
+    }
+
+    #[test]
+    fn sequence() {
+        let cues = vec![
+            cue(0, 1000, &["A"], Some(1)),
+            cue(2000, 3000, &["B"], Some(3)),
+            cue(4000, 5000, &["C"], Some(2)),
+            cue(6000, 7000, &["D"], Some(2)),
+        ];
+
+        assert_eq!(
+            kinds(lint(&cues, &LintConfig::default())),
+            vec![
+                (
+                    2,
+                    LintKind::SequenceOrder {
+                        previous: 3,
+                        sequence: 2,
+                    }
+                ),
+                (
+                    3,
+                    LintKind::SequenceOrder {
+                        previous: 2,
+                        sequence: 2,
+                    }
+                ),
+            ]
+        );
+    }
+}
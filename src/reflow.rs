@@ -0,0 +1,639 @@
+//! Line wrapping of cue texts provided by [`subtp::reflow::reflow`](reflow).
+//!
+//! The widths are measured in columns of the display,
+//! where full-width characters such as CJK count as 2 and markups such as `<i>` count as 0.
+//!
+//! ## Example
+//! ```
+//! use subtp::reflow::{reflow, ReflowConfig};
+//!
+//! let lines = vec!["Hello, I'm <i>the translator</i> who writes very long lines.".to_string()];
+//!
+//! assert_eq!(
+//!     reflow(&lines, &ReflowConfig::default()),
+//!     vec![
+//!         "Hello, I'm <i>the translator</i>".to_string(),
+//!         "who writes very long lines.".to_string(),
+//!     ]
+//! );
+//! ```
+
+use unicode_width::UnicodeWidthChar;
+
+/// The configuration of line wrapping.
+///
+/// ## Example
+/// ```
+/// use subtp::reflow::ReflowConfig;
+///
+/// let config = ReflowConfig {
+///     max_width: 37,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct ReflowConfig {
+    /// The maximum width of a line in columns.
+    pub max_width: usize,
+    /// The maximum number of lines, exceeded only by a word wider than the width.
+    pub max_lines: usize,
+    /// Balances the widths of lines instead of filling the earlier lines.
+    pub balanced: bool,
+}
+
+impl Default for ReflowConfig {
+    /// Two balanced lines of 42 columns.
+    fn default() -> Self {
+        Self {
+            max_width: 42,
+            max_lines: 2,
+            balanced: true,
+        }
+    }
+}
+
+/// Returns the width of the text in columns of the display, ignoring markups.
+///
+/// ## Example
+/// ```
+/// use subtp::reflow::display_width;
+///
+/// assert_eq!(display_width("<i>Hello</i>"), 5);
+/// assert_eq!(display_width("こんにちは"), 10);
+/// assert_eq!(display_width("Tom &amp; Jerry"), 11);
+/// ```
+pub fn display_width(text: &str) -> usize {
+    tokenize(text)
+        .iter()
+        .enumerate()
+        .map(|(i, token)| token.width + (i > 0 && token.space_before) as usize)
+        .sum()
+}
+
+/// Rewraps the lines into at most `max_lines` lines of `max_width` columns,
+/// breaking at spaces or around full-width characters but never inside markups,
+/// and preferably after punctuations.
+///
+/// If the text can not fit, the lines exceed the width rather than the number of lines.
+/// Blank lines are returned as they are.
+pub fn reflow(
+    lines: &[String],
+    config: &ReflowConfig,
+) -> Vec<String> {
+    let tokens = tokenize(&join_lines(lines));
+    if tokens.is_empty() {
+        return lines.to_vec();
+    }
+
+    breaks(&tokens, config)
+        .windows(2)
+        .map(|range| {
+            let mut line = String::new();
+            for (i, token) in tokens[range[0]..range[1]]
+                .iter()
+                .enumerate()
+            {
+                if i > 0 && token.space_before {
+                    line.push(' ');
+                }
+                line.push_str(&token.text);
+            }
+            line
+        })
+        .collect()
+}
+
+/// Joins the lines with a space,
+/// or without one where the character on either side of the break is full-width,
+/// since CJK texts have no spaces between words.
+///
+/// Hangul is full-width but has spaces between words, so it is joined with a space.
+pub(crate) fn join_lines(lines: &[String]) -> String {
+    let mut joined = String::new();
+    let mut last = None;
+    for (i, line) in lines.iter().enumerate() {
+        let stripped = strip_markups(line);
+        let first = stripped.trim_start().chars().next();
+        let wide = [first, last]
+            .into_iter()
+            .any(|c| c.is_some_and(is_wide_without_spaces));
+        if i > 0 && !wide {
+            joined.push(' ');
+        }
+        joined.push_str(line);
+        last = stripped.trim_end().chars().next_back();
+    }
+
+    joined
+}
+
+/// Returns whether the character is full-width in a script without spaces between words.
+fn is_wide_without_spaces(c: char) -> bool {
+    let hangul = matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{AC00}'..='\u{D7AF}'
+    );
+    c.width() == Some(2) && !hangul
+}
+
+/// An unbreakable run of the text.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Token {
+    /// The text including markups.
+    pub(crate) text: String,
+    /// The width without markups.
+    pub(crate) width: usize,
+    /// Whether a space precedes the token.
+    pub(crate) space_before: bool,
+}
+
+/// Returns whether the character must not start a line, e.g. `。` or `）`.
+fn is_closing(c: char) -> bool {
+    matches!(
+        c,
+        '、' | '。'
+            | '，'
+            | '．'
+            | '！'
+            | '？'
+            | '：'
+            | '；'
+            | '）'
+            | '」'
+            | '』'
+            | '】'
+            | '〉'
+            | '》'
+            | '…'
+            | 'ー'
+            | 'ゃ'
+            | 'ゅ'
+            | 'ょ'
+            | 'っ'
+            | 'ャ'
+            | 'ュ'
+            | 'ョ'
+            | 'ッ'
+    )
+}
+
+/// Returns whether a line break after the token is preferred.
+fn ends_with_punctuation(token: &Token) -> bool {
+    let text = strip_markups(&token.text);
+    text.ends_with([
+        '.', ',', ';', ':', '!', '?', '、', '。', '，', '！', '？', '…',
+    ])
+}
+
+/// Removes the markups from the text.
+pub(crate) fn strip_markups(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match markup_len(rest) {
+            | Some(len) => rest = &rest[len..],
+            | None => {
+                stripped.push(c);
+                rest = &rest[c.len_utf8()..];
+            },
+        }
+    }
+    stripped
+}
+
+/// Returns the byte length of a tag `<...>` or an override `{\...}` at the start of the text.
+///
+/// A tag starts with `<` followed by a letter, `/` or a digit of a timestamp,
+/// so that a less-than sign in the text is not taken as a tag.
+pub(crate) fn markup_len(text: &str) -> Option<usize> {
+    let close = if let Some(tag) = text.strip_prefix('<') {
+        if !tag.starts_with(|c: char| {
+            c.is_ascii_alphanumeric() || c == '/'
+        }) {
+            return None;
+        }
+        '>'
+    } else if text.starts_with("{\\") {
+        '}'
+    } else {
+        return None;
+    };
+
+    text.find(close)
+        .map(|end| end + 1)
+}
+
+/// Returns the byte length of a character reference such as `&amp;` at the start of the text.
+fn reference_len(text: &str) -> Option<usize> {
+    let body = text.strip_prefix('&')?;
+    let end = body.find(';')?;
+    if end > 0
+        && end <= 32
+        && body[..end]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '#')
+    {
+        Some(end + 2)
+    } else {
+        None
+    }
+}
+
+/// Splits the text into tokens at whitespaces outside markups and around full-width characters.
+pub(crate) fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut current = Token {
+        text: String::new(),
+        width: 0,
+        space_before: false,
+    };
+    let mut space = false;
+    // Whether a break is allowed before the next character, after a full-width character.
+    let mut after_wide = false;
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some(len) = markup_len(rest) {
+            if space && !current.text.is_empty() {
+                tokens.push(std::mem::replace(
+                    &mut current,
+                    Token {
+                        text: String::new(),
+                        width: 0,
+                        space_before: true,
+                    },
+                ));
+                space = false;
+            }
+            current
+                .text
+                .push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+
+        let (len, width) = match reference_len(rest) {
+            | Some(len) => (len, 1),
+            | None => (c.len_utf8(), c.width().unwrap_or(0)),
+        };
+        let chunk = &rest[..len];
+        rest = &rest[len..];
+
+        if c.is_whitespace() {
+            space = true;
+            continue;
+        }
+
+        let closing = is_closing(c);
+        if space || (!closing && (width == 2 || after_wide)) {
+            if current.text.is_empty() {
+                current.space_before |= space;
+            } else {
+                tokens.push(std::mem::replace(
+                    &mut current,
+                    Token {
+                        text: String::new(),
+                        width: 0,
+                        space_before: space,
+                    },
+                ));
+            }
+        }
+        current.text.push_str(chunk);
+        current.width += width;
+        space = false;
+        after_wide = width == 2;
+    }
+    if !current.text.is_empty() {
+        tokens.push(current);
+    }
+
+    // Markups surrounded by spaces are attached to the next token, or the previous one at the end.
+    let mut merged: Vec<Token> = vec![];
+    let mut pending: Option<Token> = None;
+    for mut token in tokens {
+        if let Some(markup) = pending.take() {
+            let separator = if token.space_before {
+                " "
+            } else {
+                ""
+            };
+            token.width += separator.len();
+            token.text = format!(
+                "{}{}{}",
+                markup.text, separator, token.text
+            );
+            token.space_before = markup.space_before;
+        }
+        if token.width == 0 {
+            pending = Some(token);
+        } else {
+            merged.push(token);
+        }
+    }
+    if let Some(markup) = pending {
+        match merged.last_mut() {
+            | Some(last) => {
+                if markup.space_before {
+                    last.text.push(' ');
+                }
+                last.text
+                    .push_str(&markup.text);
+            },
+            | None => merged.push(markup),
+        }
+    }
+
+    merged
+}
+
+/// Finds the token indices of line starts with the end, minimizing the cost of lines.
+fn breaks(
+    tokens: &[Token],
+    config: &ReflowConfig,
+) -> Vec<usize> {
+    let count = tokens.len();
+    let max_width = config.max_width as u64;
+    let max_lines = config.max_lines.max(1);
+    // Each line costs the square of the width so that fewer lines are preferred.
+    let line_penalty = max_width.saturating_mul(max_width);
+    // A break after a word without punctuation is worth an eighth of the square of the width.
+    let punctuation_penalty = line_penalty / 8;
+
+    // The widths of the first `i` tokens with the spaces before them.
+    let mut prefix = vec![0u64; count + 1];
+    for (i, token) in tokens.iter().enumerate() {
+        prefix[i + 1] = prefix[i]
+            + (token.width + token.space_before as usize) as u64;
+    }
+    let punctuations: Vec<bool> = tokens
+        .iter()
+        .map(ends_with_punctuation)
+        .collect();
+
+    let width = |from: usize, to: usize| -> u64 {
+        prefix[to] - prefix[from] - tokens[from].space_before as u64
+    };
+    let cost = |from: usize, to: usize| -> u64 {
+        let width = width(from, to);
+        let mut cost = line_penalty;
+        if width > max_width {
+            // A line over the width is worse than any line within the width.
+            cost = cost.saturating_add(
+                (width - max_width).saturating_mul(1_000_000),
+            );
+        } else if config.balanced || to < count {
+            cost = cost.saturating_add(
+                (max_width - width).saturating_pow(2),
+            );
+        }
+        if to < count && !punctuations[to - 1] {
+            cost = cost.saturating_add(punctuation_penalty);
+        }
+        cost
+    };
+
+    // The minimum cost and the previous break to lay out the first `j` tokens in `k + 1` lines.
+    let mut table = vec![vec![None::<(u64, usize)>; count + 1]; max_lines];
+    for to in 1..=count {
+        table[0][to] = Some((cost(0, to), 0));
+    }
+    for lines in 1..max_lines {
+        for to in (lines + 1)..=count {
+            table[lines][to] = (lines..to)
+                .filter_map(|from| {
+                    table[lines - 1][from]
+                        .map(|(previous, _)| {
+                            (previous.saturating_add(cost(from, to)), from)
+                        })
+                })
+                .min();
+        }
+    }
+
+    let (mut lines, _) = (0..max_lines)
+        .filter_map(|lines| table[lines][count].map(|(cost, _)| (lines, cost)))
+        .min_by_key(|(_, cost)| *cost)
+        .expect("one line always fits the tokens");
+    let mut breaks = vec![count];
+    let mut to = count;
+    loop {
+        let (_, from) = table[lines][to].expect("previous break exists");
+        breaks.push(from);
+        if lines == 0 {
+            break;
+        }
+        to = from;
+        lines -= 1;
+    }
+    breaks.reverse();
+
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    fn texts(tokens: &[Token]) -> Vec<&str> {
+        tokens
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn tokenize() {
+        assert_eq!(
+            texts(&super::tokenize(
+                "Hello,  <font color=\"red\">big</font> world"
+            )),
+            vec![
+                "Hello,",
+                "<font color=\"red\">big</font>",
+                "world"
+            ]
+        );
+        assert_eq!(
+            texts(&super::tokenize("<v Roger> Hi </v>")),
+            vec!["<v Roger> Hi </v>"]
+        );
+        assert_eq!(
+            texts(&super::tokenize(
+                "{\\an8}日本語です。OK"
+            )),
+            vec![
+                "{\\an8}日",
+                "本",
+                "語",
+                "で",
+                "す。",
+                "OK"
+            ]
+        );
+        assert_eq!(
+            texts(&super::tokenize("x < 5 <00:00:01.000>and y > 3")),
+            vec!["x", "<", "5", "<00:00:01.000>and", "y", ">", "3"]
+        );
+        assert_eq!(
+            super::tokenize("Tom &amp; Jerry")[1],
+            Token {
+                text: "&amp;".to_string(),
+                width: 1,
+                space_before: true,
+            }
+        );
+    }
+
+    #[test]
+    fn join_lines() {
+        assert_eq!(
+            super::join_lines(&lines(&["Hello,", "world!"])),
+            "Hello, world!"
+        );
+        assert_eq!(
+            super::join_lines(&lines(&["こんにちは", "世界"])),
+            "こんにちは世界"
+        );
+        assert_eq!(
+            super::join_lines(&lines(&["<i>こんにちは</i>", "OK"])),
+            "<i>こんにちは</i>OK"
+        );
+        assert_eq!(
+            super::join_lines(&lines(&["Hello", "世界"])),
+            "Hello世界"
+        );
+        assert_eq!(
+            super::join_lines(&lines(&["안녕하세요", "세계"])),
+            "안녕하세요 세계"
+        );
+    }
+
+    #[test]
+    fn reflow() {
+        let config = ReflowConfig::default();
+
+        // Short text is joined into one line.
+        assert_eq!(
+            super::reflow(&lines(&["Hello,", "world!"]), &config),
+            lines(&["Hello, world!"])
+        );
+
+        // Balanced lines.
+        assert_eq!(
+            super::reflow(
+                &lines(&[
+                    "The quick brown fox jumps over the lazy dog and runs away"
+                ]),
+                &config
+            ),
+            lines(&[
+                "The quick brown fox jumps over",
+                "the lazy dog and runs away"
+            ])
+        );
+
+        // Preferring a break after the punctuation.
+        assert_eq!(
+            super::reflow(
+                &lines(&["I think the quick brown fox, as usual, jumps over the lazy dog"]),
+                &config
+            ),
+            lines(&["I think the quick brown fox,", "as usual, jumps over the lazy dog"])
+        );
+
+        // Filling the earlier lines.
+        assert_eq!(
+            super::reflow(
+                &lines(&["The quick brown fox jumps over the lazy dog"]),
+                &ReflowConfig {
+                    max_width: 30,
+                    balanced: false,
+                    ..config
+                }
+            ),
+            lines(&[
+                "The quick brown fox jumps over",
+                "the lazy dog"
+            ])
+        );
+
+        // CJK lines are joined without a space.
+        assert_eq!(
+            super::reflow(&lines(&["こんにちは", "世界"]), &config),
+            lines(&["こんにちは世界"])
+        );
+
+        // Full-width characters count double.
+        assert_eq!(
+            super::reflow(
+                &lines(&["吾輩は猫である。名前はまだ無い。"]),
+                &ReflowConfig {
+                    max_width: 20,
+                    ..config
+                }
+            ),
+            lines(&[
+                "吾輩は猫である。",
+                "名前はまだ無い。"
+            ])
+        );
+
+        // Overflowing the width rather than the number of lines.
+        assert_eq!(
+            super::reflow(
+                &lines(&["a b c"]),
+                &ReflowConfig {
+                    max_width: 1,
+                    max_lines: 2,
+                    ..config
+                }
+            )
+            .len(),
+            2
+        );
+
+        // A less-than sign is not a tag.
+        assert_eq!(
+            super::reflow(
+                &lines(&[
+                    "If x < 5 then the answer is clearly wrong and y > 3 is fine too ok"
+                ]),
+                &config
+            ),
+            lines(&[
+                "If x < 5 then the answer is clearly",
+                "wrong and y > 3 is fine too ok"
+            ])
+        );
+
+        // The costs do not overflow with the largest width.
+        assert_eq!(
+            super::reflow(
+                &lines(&["Hello,", "world!"]),
+                &ReflowConfig {
+                    max_width: usize::MAX,
+                    ..config
+                }
+            ),
+            lines(&["Hello, world!"])
+        );
+
+        assert_eq!(
+            super::reflow(&lines(&[""]), &config),
+            lines(&[""])
+        );
+    }
+}
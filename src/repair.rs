@@ -0,0 +1,583 @@
+//! Automatic repairs of common timing defects provided by [`subtp::repair::RepairConfig`](RepairConfig).
+//!
+//! ## Example
+//! ```
+//! use subtp::repair::RepairConfig;
+//! use subtp::srt::SubRip;
+//!
+//! let text = r#"5
+//! 00:00:03,000 --> 00:00:05,000
+//! This is a sample.
+//!
+//! 2
+//! 00:00:01,000 --> 00:00:03,500
+//! Hello, world!
+//! "#;
+//!
+//! let mut srt = SubRip::parse(text).unwrap();
+//! let changes = srt.repair(&RepairConfig::default());
+//!
+//! assert_eq!(
+//!     srt.render(),
+//!     "1\n00:00:01,000 --> 00:00:03,000\nHello, world!\n\n2\n00:00:03,000 --> 00:00:05,000\nThis is a sample.\n"
+//! );
+//! assert_eq!(
+//!     changes
+//!         .iter()
+//!         .map(|change| change.to_string())
+//!         .collect::<Vec<_>>(),
+//!     vec![
+//!         "cue 0: moved from cue 1",
+//!         "cue 1: moved from cue 0",
+//!         "cue 0: end trimmed from 3500 ms to 3000 ms to resolve overlap",
+//!         "cue 0: renumbered from 2 to 1",
+//!         "cue 1: renumbered from 5 to 2",
+//!     ]
+//! );
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::timing::FrameRate;
+
+/// The configuration of the repairs, applied in the order of the fields.
+///
+/// Each repair is disabled by `false` or `None`.
+///
+/// ## Example
+/// ```
+/// use subtp::repair::{OverlapFix, RepairConfig};
+///
+/// let config = RepairConfig {
+///     overlaps: Some(OverlapFix::PushLater),
+///     min_duration: None,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct RepairConfig {
+    /// Sorts the cues by the start time, keeping the order of cues starting at the same time.
+    pub sort: bool,
+    /// Resolves a cue ending after the next cue starts.
+    pub overlaps: Option<OverlapFix>,
+    /// Extends the end of a cue shorter than the minimum duration,
+    /// without overlapping the next cue.
+    pub min_duration: Option<Duration>,
+    /// Closes a gap shorter than the number of frames by extending the end to the next start.
+    pub min_gap_frames: Option<u32>,
+    /// The frame rate of the frames of `min_gap_frames`.
+    pub frame_rate: FrameRate,
+    /// Renumbers the sequence numbers of SubRip Subtitle densely from 1.
+    pub renumber: bool,
+}
+
+impl Default for RepairConfig {
+    /// Enables all repairs with the defaults of [`LintConfig`](crate::lint::LintConfig),
+    /// trimming the earlier cue of an overlap.
+    fn default() -> Self {
+        Self {
+            sort: true,
+            overlaps: Some(OverlapFix::TrimEarlier),
+            min_duration: Some(Duration::from_millis(833)),
+            min_gap_frames: Some(2),
+            frame_rate: FrameRate::default(),
+            renumber: true,
+        }
+    }
+}
+
+/// The way to resolve overlapping cues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum OverlapFix {
+    /// Trims the end of the earlier cue to the start of the later cue.
+    TrimEarlier,
+    /// Delays the later cue to the end of the earlier cue, keeping its duration.
+    PushLater,
+}
+
+impl Default for OverlapFix {
+    fn default() -> Self {
+        Self::TrimEarlier
+    }
+}
+
+/// A change made by the repairs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct RepairChange {
+    /// The index of the cue after sorting.
+    pub cue: usize,
+    /// The kind of the change.
+    pub kind: RepairKind,
+}
+
+impl Display for RepairChange {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "cue {}: {}", self.cue, self.kind)
+    }
+}
+
+/// The kind of a change made by the repairs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum RepairKind {
+    /// The cue is moved by sorting.
+    Moved {
+        /// The index of the cue before sorting.
+        from: usize,
+    },
+    /// The end is trimmed to the start of the next cue to resolve an overlap.
+    OverlapTrimmed {
+        /// The end before the change.
+        from: Duration,
+        /// The end after the change.
+        to: Duration,
+    },
+    /// The cue is delayed to the end of the previous cue to resolve an overlap.
+    OverlapPushed {
+        /// The start before the change.
+        from: Duration,
+        /// The start after the change.
+        to: Duration,
+    },
+    /// The end is extended to the minimum duration.
+    DurationExtended {
+        /// The end before the change.
+        from: Duration,
+        /// The end after the change.
+        to: Duration,
+    },
+    /// The end is extended to the start of the next cue to close a short gap.
+    GapClosed {
+        /// The end before the change.
+        from: Duration,
+        /// The end after the change.
+        to: Duration,
+    },
+    /// The sequence number is renumbered.
+    Renumbered {
+        /// The sequence number before the change.
+        from: u32,
+        /// The sequence number after the change.
+        to: u32,
+    },
+}
+
+impl Display for RepairKind {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Moved {
+                from,
+            } => write!(f, "moved from cue {}", from),
+            | Self::OverlapTrimmed {
+                from,
+                to,
+            } => {
+                write!(
+                    f,
+                    "end trimmed from {} ms to {} ms to resolve overlap",
+                    from.as_millis(),
+                    to.as_millis()
+                )
+            },
+            | Self::OverlapPushed {
+                from,
+                to,
+            } => {
+                write!(
+                    f,
+                    "start delayed from {} ms to {} ms to resolve overlap",
+                    from.as_millis(),
+                    to.as_millis()
+                )
+            },
+            | Self::DurationExtended {
+                from,
+                to,
+            } => {
+                write!(
+                    f,
+                    "end extended from {} ms to {} ms for minimum duration",
+                    from.as_millis(),
+                    to.as_millis()
+                )
+            },
+            | Self::GapClosed {
+                from,
+                to,
+            } => {
+                write!(
+                    f,
+                    "end extended from {} ms to {} ms to close gap",
+                    from.as_millis(),
+                    to.as_millis()
+                )
+            },
+            | Self::Renumbered {
+                from,
+                to,
+            } => write!(f, "renumbered from {} to {}", from, to),
+        }
+    }
+}
+
+/// The repaired order and times of the cues.
+pub(crate) struct Repaired {
+    /// The indices of the cues before sorting in the repaired order.
+    pub(crate) order: Vec<usize>,
+    /// The start and end times in the repaired order.
+    pub(crate) times: Vec<(Duration, Duration)>,
+    /// The changes made.
+    pub(crate) changes: Vec<RepairChange>,
+}
+
+/// Repairs the start and end times of the cues in the document order,
+/// except for renumbering that depends on the format.
+pub(crate) fn repair(
+    times: Vec<(Duration, Duration)>,
+    config: &RepairConfig,
+) -> Repaired {
+    let mut changes = vec![];
+    let mut change = |cue, kind| {
+        changes.push(RepairChange {
+            cue,
+            kind,
+        })
+    };
+
+    let mut order: Vec<usize> = (0..times.len()).collect();
+    if config.sort {
+        // The stable sort keeps the document order of cues starting at the same time.
+        order.sort_by_key(|&index| times[index].0);
+        for (cue, &from) in order.iter().enumerate() {
+            if cue != from {
+                change(
+                    cue,
+                    RepairKind::Moved {
+                        from,
+                    },
+                );
+            }
+        }
+    }
+    let mut times: Vec<_> = order
+        .iter()
+        .map(|&index| times[index])
+        .collect();
+
+    if let Some(fix) = config.overlaps {
+        for cue in 1..times.len() {
+            let (_, previous_end) = times[cue - 1];
+            let (start, end) = times[cue];
+            if start >= previous_end {
+                continue;
+            }
+
+            match fix {
+                | OverlapFix::TrimEarlier => {
+                    // The end is kept after the start for unsorted cues.
+                    let to = start.max(times[cue - 1].0);
+                    times[cue - 1].1 = to;
+                    change(
+                        cue - 1,
+                        RepairKind::OverlapTrimmed {
+                            from: previous_end,
+                            to,
+                        },
+                    );
+                },
+                | OverlapFix::PushLater => {
+                    times[cue] = (
+                        previous_end,
+                        end.max(start) + (previous_end - start),
+                    );
+                    change(
+                        cue,
+                        RepairKind::OverlapPushed {
+                            from: start,
+                            to: previous_end,
+                        },
+                    );
+                },
+            }
+        }
+    }
+
+    if let Some(min_duration) = config.min_duration {
+        for cue in 0..times.len() {
+            let (start, end) = times[cue];
+            let mut to = start + min_duration;
+            if let Some(&(next_start, _)) = times.get(cue + 1) {
+                to = to.min(next_start);
+            }
+            if to > end {
+                times[cue].1 = to;
+                change(
+                    cue,
+                    RepairKind::DurationExtended {
+                        from: end,
+                        to,
+                    },
+                );
+            }
+        }
+    }
+
+    let min_gap = config
+        .min_gap_frames
+        .and_then(|frames| {
+            config
+                .frame_rate
+                .frames_to_duration(frames as u64)
+        });
+    if let Some(min_gap) = min_gap {
+        for cue in 1..times.len() {
+            let (_, previous_end) = times[cue - 1];
+            let (start, _) = times[cue];
+            if start > previous_end && start - previous_end < min_gap {
+                times[cue - 1].1 = start;
+                change(
+                    cue - 1,
+                    RepairKind::GapClosed {
+                        from: previous_end,
+                        to: start,
+                    },
+                );
+            }
+        }
+    }
+
+    Repaired {
+        order,
+        times,
+        changes,
+    }
+}
+
+/// Reorders the items by the indices of the items before reordering.
+pub(crate) fn reorder<T>(
+    items: Vec<T>,
+    order: &[usize],
+) -> Vec<T> {
+    let mut items: Vec<_> = items
+        .into_iter()
+        .map(Some)
+        .collect();
+
+    order
+        .iter()
+        .filter_map(|&index| items[index].take())
+        .collect()
+}
+
+/// Renumbers the sequence numbers densely from 1, returning the changes.
+pub(crate) fn renumber<'a>(
+    sequences: impl Iterator<Item = &'a mut u32>
+) -> Vec<RepairChange> {
+    let mut changes = vec![];
+    for (cue, sequence) in sequences.enumerate() {
+        let to = cue as u32 + 1;
+        if *sequence != to {
+            changes.push(RepairChange {
+                cue,
+                kind: RepairKind::Renumbered {
+                    from: *sequence,
+                    to,
+                },
+            });
+            *sequence = to;
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(milliseconds: u64) -> Duration {
+        Duration::from_millis(milliseconds)
+    }
+
+    fn times(times: &[(u64, u64)]) -> Vec<(Duration, Duration)> {
+        times
+            .iter()
+            .map(|&(start, end)| (millis(start), millis(end)))
+            .collect()
+    }
+
+    #[test]
+    fn sort() {
+        let repaired = repair(
+            times(&[
+                (3000, 4000),
+                (1000, 2000),
+                (1000, 1500),
+            ]),
+            &RepairConfig {
+                overlaps: None,
+                min_duration: None,
+                min_gap_frames: None,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(repaired.order, vec![1, 2, 0]);
+        assert_eq!(
+            repaired.times,
+            times(&[
+                (1000, 2000),
+                (1000, 1500),
+                (3000, 4000)
+            ])
+        );
+        assert_eq!(
+            repaired.changes,
+            vec![
+                RepairChange {
+                    cue: 0,
+                    kind: RepairKind::Moved {
+                        from: 1
+                    },
+                },
+                RepairChange {
+                    cue: 1,
+                    kind: RepairKind::Moved {
+                        from: 2
+                    },
+                },
+                RepairChange {
+                    cue: 2,
+                    kind: RepairKind::Moved {
+                        from: 0
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn overlaps() {
+        let config = RepairConfig {
+            min_duration: None,
+            min_gap_frames: None,
+            ..Default::default()
+        };
+
+        let repaired = repair(
+            times(&[
+                (1000, 3000),
+                (2000, 4000),
+                (3500, 5000),
+            ]),
+            &config,
+        );
+        assert_eq!(
+            repaired.times,
+            times(&[
+                (1000, 2000),
+                (2000, 3500),
+                (3500, 5000)
+            ])
+        );
+
+        let repaired = repair(
+            times(&[
+                (1000, 3000),
+                (2000, 4000),
+                (3500, 5000),
+            ]),
+            &RepairConfig {
+                overlaps: Some(OverlapFix::PushLater),
+                ..config
+            },
+        );
+        assert_eq!(
+            repaired.times,
+            times(&[
+                (1000, 3000),
+                (3000, 5000),
+                (5000, 6500)
+            ])
+        );
+        assert_eq!(
+            repaired.changes[1].to_string(),
+            "cue 2: start delayed from 3500 ms to 5000 ms to resolve overlap"
+        );
+    }
+
+    #[test]
+    fn durations_and_gaps() {
+        let repaired = repair(
+            times(&[
+                (1000, 1200),
+                (1500, 1600),
+                (1650, 3000),
+                (3050, 4000),
+            ]),
+            &RepairConfig::default(),
+        );
+
+        assert_eq!(
+            repaired.times,
+            times(&[
+                (1000, 1500),
+                (1500, 1650),
+                (1650, 3050),
+                (3050, 4000)
+            ])
+        );
+        assert_eq!(
+            repaired
+                .changes
+                .iter()
+                .map(|change| change.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "cue 0: end extended from 1200 ms to 1500 ms for minimum duration",
+                "cue 1: end extended from 1600 ms to 1650 ms for minimum duration",
+                "cue 2: end extended from 3000 ms to 3050 ms to close gap",
+            ]
+        );
+    }
+
+    #[test]
+    fn renumber() {
+        let mut sequences = [3, 2, 3];
+        let changes = super::renumber(sequences.iter_mut());
+
+        assert_eq!(sequences, [1, 2, 3]);
+        assert_eq!(
+            changes,
+            vec![RepairChange {
+                cue: 0,
+                kind: RepairKind::Renumbered {
+                    from: 3,
+                    to: 1
+                },
+            }]
+        );
+    }
+}
@@ -1,6 +1,9 @@
 //! Result types for parsing.
 
-use crate::ParseError;
+use crate::{ParseError, ReadError};
 
 /// The result of parsing.
 pub type ParseResult<T> = Result<T, ParseError>;
+
+/// The result of reading from a stream.
+pub type ReadResult<T> = Result<T, ReadError>;
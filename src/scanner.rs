@@ -0,0 +1,951 @@
+//! A hand-written scanner for the SubRip Subtitle and the WebVTT formats
+//! that produces the same values as the grammars of [`str_parser`](crate::str_parser)
+//! and [`vtt_parser`](crate::vtt_parser) in a single pass without backtracking.
+//!
+//! The lines are split by [`memchr`] and each block is decided by its first two lines.
+//! The scanner accepts a subset of the inputs accepted by the grammars
+//! and returns `None` for the others, e.g. malformed inputs or irregular layouts
+//! such as a sequence number and timings on the same line,
+//! so that the caller falls back to the grammar for the same value or error.
+
+use memchr::memchr2;
+
+use crate::srt::{LinePosition, SrtSubtitleRef, SrtTimestamp, SubRipRef};
+use crate::vtt::{
+    Alignment, CueSettings, Line as CueLine, LineAlignment, Percentage,
+    Position, PositionAlignment, Vertical, VttBlockRef, VttComment, VttCueRef,
+    VttDescription, VttHeader, VttStyle, VttTimestamp, VttTimings, WebVttRef,
+};
+use crate::{str_parser, vtt_parser};
+
+/// A line of the input without the line terminator.
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    /// The text of the line.
+    text: &'a str,
+    /// Whether the line ends with a line terminator.
+    terminated: bool,
+    /// The byte position after the line terminator.
+    next: usize,
+}
+
+/// Returns the line starting at the position, or `None` at the end of the input.
+fn line_at(
+    input: &str,
+    position: usize,
+) -> Option<Line<'_>> {
+    if position >= input.len() {
+        return None;
+    }
+
+    let bytes = input.as_bytes();
+    Some(
+        match memchr2(b'\n', b'\r', &bytes[position..]) {
+            | Some(offset) => {
+                let end = position + offset;
+                let next = if bytes[end] == b'\r'
+                    && bytes.get(end + 1) == Some(&b'\n')
+                {
+                    end + 2
+                } else {
+                    end + 1
+                };
+                Line {
+                    text: &input[position..end],
+                    terminated: true,
+                    next,
+                }
+            },
+            | None => {
+                Line {
+                    text: &input[position..],
+                    terminated: false,
+                    next: input.len(),
+                }
+            },
+        },
+    )
+}
+
+/// Returns whether the byte is a whitespace or a newline of the grammars.
+fn is_blank(byte: &u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+/// Returns whether the text consists of spaces and tabs.
+fn is_whitespaces(text: &str) -> bool {
+    text.bytes()
+        .all(|byte| byte == b' ' || byte == b'\t')
+}
+
+/// Removes the leading spaces and tabs.
+fn trim_whitespaces_start(text: &str) -> &str {
+    text.trim_start_matches([' ', '\t'])
+}
+
+/// Skips the whitespaces and newlines from the position.
+fn skip_blanks(
+    input: &str,
+    position: usize,
+) -> usize {
+    position
+        + input.as_bytes()[position..]
+            .iter()
+            .take_while(|byte| is_blank(byte))
+            .count()
+}
+
+/// Skips the empty lines from the position,
+/// returning the position after them and whether any line is skipped.
+fn skip_empty_lines(
+    input: &str,
+    mut position: usize,
+) -> (usize, bool) {
+    let start = position;
+    while let Some(line) = line_at(input, position) {
+        if !line.text.is_empty() || !line.terminated {
+            break;
+        }
+        position = line.next;
+    }
+
+    (position, position > start)
+}
+
+/// Returns the number of the ASCII digits at the start of the text.
+fn count_digits(text: &str) -> usize {
+    text.bytes()
+        .take_while(u8::is_ascii_digit)
+        .count()
+}
+
+/// Parses the exact number of the digits at the start of the text.
+fn fixed_digits(
+    text: &mut &str,
+    count: usize,
+) -> Option<u32> {
+    let digits = text.get(..count)?;
+    if count_digits(digits) != count {
+        return None;
+    }
+    *text = &text[count..];
+
+    digits.parse().ok()
+}
+
+/// Parses the two digits of minutes or seconds less than 60.
+fn sexagesimal(text: &mut &str) -> Option<u8> {
+    fixed_digits(text, 2)
+        .filter(|value| *value < 60)
+        .map(|value| value as u8)
+}
+
+/// Consumes the prefix of the text.
+fn prefix(
+    text: &mut &str,
+    prefix: &str,
+) -> Option<()> {
+    *text = text.strip_prefix(prefix)?;
+    Some(())
+}
+
+/// Parses the SubRip Subtitle format as `str_parser::srt`.
+pub(crate) fn srt(input: &str) -> Option<SubRipRef<'_>> {
+    let mut subtitles = vec![];
+    let mut position = skip_blanks(input, 0);
+    loop {
+        if input.as_bytes()[position..]
+            .iter()
+            .all(is_blank)
+        {
+            break;
+        }
+        if !subtitles.is_empty() {
+            let (next, skipped) = skip_empty_lines(input, position);
+            if !skipped {
+                return None;
+            }
+            position = next;
+        }
+
+        let (subtitle, next) = subtitle(input, position)?;
+        subtitles.push(subtitle);
+        position = next;
+    }
+
+    Some(SubRipRef {
+        subtitles,
+    })
+}
+
+/// Parses a subtitle starting with the sequence number at the position.
+fn subtitle(
+    input: &str,
+    position: usize,
+) -> Option<(SrtSubtitleRef<'_>, usize)> {
+    let line = line_at(input, position)?;
+    let digits = count_digits(line.text);
+    if !line.terminated || digits == 0 || !is_whitespaces(&line.text[digits..])
+    {
+        return None;
+    }
+    let sequence = line.text[..digits]
+        .parse()
+        .ok()?;
+
+    let line = line_at(input, line.next)?;
+    let mut text = trim_whitespaces_start(line.text);
+    if !line.terminated || text.is_empty() {
+        return None;
+    }
+    let start = srt_timestamp(&mut text)?;
+    text = trim_whitespaces_start(text);
+    prefix(&mut text, "-->")?;
+    text = trim_whitespaces_start(text);
+    let end = srt_timestamp(&mut text)?;
+    let line_position = if is_whitespaces(text) {
+        None
+    } else if text.starts_with([' ', '\t']) {
+        Some(line_position(trim_whitespaces_start(
+            text,
+        ))?)
+    } else {
+        return None;
+    };
+
+    // The first line of the text is required and the text ends at a blank line.
+    let mut lines = vec![];
+    let mut next = line.next;
+    while let Some(line) = line_at(input, next) {
+        if !line.terminated || is_whitespaces(line.text) {
+            break;
+        }
+        lines.push(line.text.trim());
+        next = line.next;
+    }
+    if lines.is_empty() {
+        return None;
+    }
+
+    Some((
+        SrtSubtitleRef {
+            sequence,
+            start,
+            end,
+            text: lines,
+            line_position,
+        },
+        next,
+    ))
+}
+
+/// Parses a timestamp of `00:00:00,000` as `str_parser::timestamp`.
+fn srt_timestamp(text: &mut &str) -> Option<SrtTimestamp> {
+    let hours = fixed_digits(text, 2)? as u8;
+    prefix(text, ":")?;
+    let minutes = sexagesimal(text)?;
+    prefix(text, ":")?;
+    let seconds = sexagesimal(text)?;
+    prefix(text, ",")?;
+    let milliseconds = fixed_digits(text, 3)? as u16;
+
+    Some(SrtTimestamp {
+        hours,
+        minutes,
+        seconds,
+        milliseconds,
+    })
+}
+
+/// Parses the whole text as `str_parser::line_position`
+/// separated only by spaces and tabs, allowing trailing ones.
+fn line_position(text: &str) -> Option<LinePosition> {
+    let text = text.trim_end_matches([' ', '\t']);
+    if text
+        .bytes()
+        .any(|byte| byte == b'\r' || byte == b'\n')
+    {
+        return None;
+    }
+
+    str_parser::line_position(text).ok()
+}
+
+/// Parses the WebVTT format as `vtt_parser::vtt`.
+pub(crate) fn vtt(input: &str) -> Option<WebVttRef<'_>> {
+    let rest = input.strip_prefix("WEBVTT")?;
+    let mut position = input.len() - trim_whitespaces_start(rest).len();
+
+    // The description below or on the side of the signature.
+    let line = line_at(input, position)?;
+    let description = if line.text.is_empty() {
+        let (block, next) = text_block(input, line.next);
+        position = next;
+        block.map(|block| VttDescription::Below(block.to_string()))
+    } else {
+        let (block, next) = text_block(input, position);
+        position = next;
+        Some(VttDescription::Side(block?.to_string()))
+    };
+
+    // A blank line is required after the header.
+    let line = line_at(input, position)?;
+    if !line.text.is_empty() || !line.terminated {
+        return None;
+    }
+    position = skip_blanks(input, line.next);
+
+    let mut blocks = vec![];
+    loop {
+        if input.as_bytes()[position..]
+            .iter()
+            .all(is_blank)
+        {
+            break;
+        }
+        if !blocks.is_empty() {
+            let (next, skipped) = skip_empty_lines(input, position);
+            if !skipped {
+                return None;
+            }
+            position = next;
+        }
+
+        let (block, next) = block(input, position)?;
+        blocks.push(block);
+        position = next;
+    }
+
+    Some(WebVttRef {
+        header: VttHeader {
+            description,
+        },
+        blocks,
+    })
+}
+
+/// Collects the lines of at least one character until an empty line as `text_block`,
+/// returning the text with the line terminators and the position after it.
+fn text_block(
+    input: &str,
+    position: usize,
+) -> (Option<&str>, usize) {
+    let mut next = position;
+    while let Some(line) = line_at(input, next) {
+        if line.text.is_empty() || !line.terminated {
+            break;
+        }
+        next = line.next;
+    }
+
+    (
+        (next > position).then(|| &input[position..next]),
+        next,
+    )
+}
+
+/// Collects the trimmed lines of at least one character until an empty line as `multiline`.
+fn multiline(
+    input: &str,
+    mut position: usize,
+) -> (Vec<&str>, usize) {
+    let mut lines = vec![];
+    while let Some(line) = line_at(input, position) {
+        if line.text.is_empty() || !line.terminated {
+            break;
+        }
+        lines.push(line.text.trim());
+        position = line.next;
+    }
+
+    (lines, position)
+}
+
+/// Parses a block at the position, trying a cue, a comment, a style and a region in order.
+fn block(
+    input: &str,
+    position: usize,
+) -> Option<(VttBlockRef<'_>, usize)> {
+    let first = line_at(input, position)?;
+    if !first.terminated {
+        return None;
+    }
+
+    // A cue with an identifier takes precedence over a cue without it.
+    let second = line_at(input, first.next).filter(|line| line.terminated);
+    if let Some(second) = second {
+        if second.text.contains("-->") {
+            let (timings, settings) = timing_line(second.text)?;
+            let identifier = first.text.trim();
+            if trim_whitespaces_start(first.text).is_empty() {
+                return None;
+            }
+            let (payload, next) = multiline(input, second.next);
+            return Some((
+                VttBlockRef::Que(VttCueRef {
+                    identifier: Some(identifier),
+                    timings,
+                    settings,
+                    payload,
+                }),
+                next,
+            ));
+        }
+    }
+    if first.text.contains("-->") {
+        let (timings, settings) = timing_line(first.text)?;
+        let (payload, next) = multiline(input, first.next);
+        return Some((
+            VttBlockRef::Que(VttCueRef {
+                identifier: None,
+                timings,
+                settings,
+                payload,
+            }),
+            next,
+        ));
+    }
+
+    if let Some(rest) = first
+        .text
+        .strip_prefix("NOTE")
+    {
+        return if is_whitespaces(rest) {
+            let (lines, next) = multiline(input, first.next);
+            Some((
+                VttBlockRef::Comment(VttComment::Below(lines.join("\n"))),
+                next,
+            ))
+        } else if rest.starts_with([' ', '\t']) {
+            // The comment starts after the whitespaces on the line of `NOTE`.
+            let start = position + first.text.len()
+                - trim_whitespaces_start(rest).len();
+            let (lines, next) = multiline(input, start);
+            Some((
+                VttBlockRef::Comment(VttComment::Side(lines.join("\n"))),
+                next,
+            ))
+        } else {
+            None
+        };
+    }
+
+    if first
+        .text
+        .strip_prefix("STYLE")
+        .map_or(false, is_whitespaces)
+    {
+        let (style, next) = text_block(input, first.next);
+        return Some((
+            VttBlockRef::Style(VttStyle {
+                style: style?.to_string(),
+            }),
+            next,
+        ));
+    }
+
+    if first
+        .text
+        .strip_prefix("REGION")
+        .map_or(false, is_whitespaces)
+    {
+        // The options are lines without whitespaces, one or more.
+        let mut next = first.next;
+        while let Some(line) = line_at(input, next) {
+            if line.text.is_empty() || !line.terminated {
+                break;
+            }
+            if line
+                .text
+                .bytes()
+                .any(|byte| byte == b' ' || byte == b'\t')
+            {
+                return None;
+            }
+            next = line.next;
+        }
+        if next == first.next {
+            return None;
+        }
+        let region = vtt_parser::region(&input[position..next]).ok()?;
+        return Some((VttBlockRef::Region(region), next));
+    }
+
+    None
+}
+
+/// Parses a line of the cue timings and settings as the cue rules,
+/// where the settings are present if any whitespace follows the timings.
+fn timing_line(line: &str) -> Option<(VttTimings, Option<CueSettings>)> {
+    let mut text = trim_whitespaces_start(line);
+    let start = vtt_timestamp(&mut text)?;
+    text = trim_whitespaces_start(text);
+    prefix(&mut text, "-->")?;
+    text = trim_whitespaces_start(text);
+    let end = vtt_timestamp(&mut text)?;
+
+    let settings = if text.is_empty() {
+        None
+    } else if text.starts_with([' ', '\t']) {
+        Some(cue_settings(text)?)
+    } else {
+        return None;
+    };
+
+    Some((
+        VttTimings {
+            start,
+            end,
+        },
+        settings,
+    ))
+}
+
+/// Parses the cue settings separated by whitespaces as `vtt_parser::cue_settings`,
+/// where a later setting overrides an earlier one and an invalid setting fails.
+fn cue_settings(text: &str) -> Option<CueSettings> {
+    let mut settings = CueSettings::default();
+    for option in text
+        .split([' ', '\t'])
+        .filter(|option| !option.is_empty())
+    {
+        if let Some(region) = option.strip_prefix("region:") {
+            if region.is_empty() {
+                return None;
+            }
+            settings.region = Some(region.to_string());
+        } else if let Some(vertical) = option.strip_prefix("vertical:") {
+            settings.vertical = Some(match vertical {
+                | "rl" => Vertical::Rl,
+                | "lr" => Vertical::Lr,
+                | _ => return None,
+            });
+        } else if let Some(line) = option.strip_prefix("line:") {
+            settings.line = Some(cue_line(line)?);
+        } else if let Some(position) = option.strip_prefix("position:") {
+            settings.position = Some(cue_position(position)?);
+        } else if let Some(size) = option.strip_prefix("size:") {
+            let mut rest = size;
+            let size = percentage(&mut rest)?;
+            if !rest.is_empty() {
+                return None;
+            }
+            settings.size = Some(size);
+        } else if let Some(align) = option.strip_prefix("align:") {
+            settings.align = Some(match align {
+                | "start" => Alignment::Start,
+                | "center" => Alignment::Center,
+                | "end" => Alignment::End,
+                | "left" => Alignment::Left,
+                | "right" => Alignment::Right,
+                | _ => return None,
+            });
+        } else {
+            return None;
+        }
+    }
+
+    Some(settings)
+}
+
+/// Parses the value of the line setting as a percentage or a signed integer
+/// with an optional alignment after `,`.
+fn cue_line(value: &str) -> Option<CueLine> {
+    let alignment = |rest: &str| {
+        match rest {
+            | "" => Some(None),
+            | ",start" => Some(Some(LineAlignment::Start)),
+            | ",center" => Some(Some(LineAlignment::Center)),
+            | ",end" => Some(Some(LineAlignment::End)),
+            | _ => None,
+        }
+    };
+
+    let mut rest = value;
+    if let Some(percentage) = percentage(&mut rest) {
+        return Some(CueLine::Percentage(
+            percentage,
+            alignment(rest)?,
+        ));
+    }
+
+    let sign = usize::from(value.starts_with(['+', '-']));
+    let digits = count_digits(&value[sign..]);
+    if digits == 0 {
+        return None;
+    }
+    let (number, rest) = value.split_at(sign + digits);
+
+    Some(CueLine::LineNumber(
+        number.parse::<i32>().ok()? as f32,
+        alignment(rest)?,
+    ))
+}
+
+/// Parses the value of the position setting as a percentage
+/// with an optional alignment after `,`.
+fn cue_position(value: &str) -> Option<Position> {
+    let mut rest = value;
+    let percentage = percentage(&mut rest)?;
+    let alignment = match rest {
+        | "" => None,
+        | ",line-left" => Some(PositionAlignment::LineLeft),
+        | ",center" => Some(PositionAlignment::Center),
+        | ",line-right" => Some(PositionAlignment::LineRight),
+        | _ => return None,
+    };
+
+    Some(Position {
+        value: percentage,
+        alignment,
+    })
+}
+
+/// Parses a percentage of an integer or a decimal up to 100 followed by `%`.
+fn percentage(text: &mut &str) -> Option<Percentage> {
+    let integer = count_digits(text);
+    if integer == 0 {
+        return None;
+    }
+
+    let rest = &text[integer..];
+    let (length, value) = if rest.starts_with('%') {
+        let value: u32 = text[..integer].parse().ok()?;
+        (
+            integer,
+            (value <= 100).then_some(value as f32)?,
+        )
+    } else if let Some(fraction) = rest.strip_prefix('.') {
+        let fraction = count_digits(fraction);
+        if fraction == 0 || !rest[1 + fraction..].starts_with('%') {
+            return None;
+        }
+        let length = integer + 1 + fraction;
+        let value: f32 = text[..length].parse().ok()?;
+        (
+            length,
+            (0.0..=100.0)
+                .contains(&value)
+                .then_some(value)?,
+        )
+    } else {
+        return None;
+    };
+    *text = &text[length + 1..];
+
+    Some(Percentage {
+        value,
+    })
+}
+
+/// Parses a timestamp of `00:00:00.000` or `00:00.000` as `vtt_parser::timestamp`.
+fn vtt_timestamp(text: &mut &str) -> Option<VttTimestamp> {
+    vtt_timestamp_with_hours(text).or_else(|| {
+        let mut rest = *text;
+        let minutes = sexagesimal(&mut rest)?;
+        prefix(&mut rest, ":")?;
+        let seconds = sexagesimal(&mut rest)?;
+        prefix(&mut rest, ".")?;
+        let milliseconds = fixed_digits(&mut rest, 3)? as u16;
+        *text = rest;
+
+        Some(VttTimestamp {
+            hours: 0,
+            minutes,
+            seconds,
+            milliseconds,
+        })
+    })
+}
+
+/// Parses a timestamp with two or more digits of hours.
+fn vtt_timestamp_with_hours(text: &mut &str) -> Option<VttTimestamp> {
+    let mut rest = *text;
+    let digits = count_digits(rest);
+    if digits < 2 {
+        return None;
+    }
+    let hours = rest[..digits].parse().ok()?;
+    rest = &rest[digits..];
+    prefix(&mut rest, ":")?;
+    let minutes = sexagesimal(&mut rest)?;
+    prefix(&mut rest, ":")?;
+    let seconds = sexagesimal(&mut rest)?;
+    prefix(&mut rest, ".")?;
+    let milliseconds = fixed_digits(&mut rest, 3)? as u16;
+    *text = rest;
+
+    Some(VttTimestamp {
+        hours,
+        minutes,
+        seconds,
+        milliseconds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pseudo-random generator of xorshift for reproducible inputs.
+    struct Random(u64);
+
+    impl Random {
+        fn pick<'a>(
+            &mut self,
+            items: &[&'a str],
+        ) -> &'a str {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            items[(self.0 % items.len() as u64) as usize]
+        }
+
+        fn count(
+            &mut self,
+            max: usize,
+        ) -> usize {
+            self.pick(&[
+                "0", "1", "2", "3", "4",
+            ])
+            .parse::<usize>()
+            .unwrap()
+                % (max + 1)
+        }
+    }
+
+    const NEWLINES: &[&str] = &[
+        "\n", "\n", "\r\n", "\r",
+    ];
+
+    fn srt_input(random: &mut Random) -> String {
+        let mut input = random
+            .pick(&[
+                "", "", "\n", " \n", "\r\n\r\n",
+            ])
+            .to_string();
+        for i in 0..random.count(3) {
+            if i > 0 {
+                input += random.pick(&[
+                    "\n", "\n", "\n\n", " \n", "\r\n", "",
+                ]);
+            }
+            input += random.pick(&[
+                "1", "2", "10", " 3", "4 ", "x",
+            ]);
+            input += random.pick(&[
+                "\n", "\n", "\r\n", "\r", " \n", "\n\n", " ",
+            ]);
+            input += random.pick(&[
+                "00:00:01,000",
+                "00:00:01,000",
+                "00:00:01.000",
+                "00:61:00,000",
+            ]);
+            input += random.pick(&[
+                " --> ", " --> ", "-->", "  -->\t", " ->", "\n-->\n",
+            ]);
+            input += random.pick(&[
+                "00:00:02,000",
+                "99:59:59,999",
+                "0:00:02,000",
+            ]);
+            input += random.pick(&[
+                "",
+                "",
+                " ",
+                " X1:1 X2:2 Y1:3 Y2:4",
+                " X1:1 X2:2 Y1:3 Y2:4 ",
+                " X1:1 X2:2",
+                " junk",
+            ]);
+            input += random.pick(NEWLINES);
+            for _ in 0..random.count(3) {
+                input += random.pick(&[
+                    "Hello",
+                    "Hello",
+                    " padded ",
+                    " ",
+                    "\t",
+                    "a --> b",
+                    "１２３",
+                    "\u{3000}x",
+                    "",
+                ]);
+                input += random.pick(NEWLINES);
+            }
+        }
+        input += random.pick(&[
+            "", "", "\n", " ", "\n \n", "x",
+        ]);
+
+        input
+    }
+
+    fn vtt_input(random: &mut Random) -> String {
+        let mut input = random
+            .pick(&[
+                "WEBVTT",
+                "WEBVTT",
+                "WEBVTT ",
+                "WEBVTT - desc",
+                "WEBVTT\ndesc",
+                "WEBVTTx",
+                "\u{FEFF}WEBVTT",
+            ])
+            .to_string();
+        input += random.pick(&[
+            "\n\n", "\n\n", "\r\n\r\n", "\n", "\n \n", "\n\n\n",
+        ]);
+        for i in 0..random.count(4) {
+            if i > 0 {
+                input += random.pick(&[
+                    "\n", "\n", "\n\n", " \n", "\r\n", "",
+                ]);
+            }
+            match random.pick(&[
+                "cue", "cue", "cue", "note", "style", "region",
+            ]) {
+                | "cue" => {
+                    input += random.pick(&[
+                        "",
+                        "",
+                        "id\n",
+                        " id \n",
+                        "NOTE x\n",
+                        "00:01.000 --> 00:02.000\n",
+                        "  \n",
+                    ]);
+                    input += random.pick(&[
+                        "00:01.000 --> 00:02.000",
+                        "00:00:01.000 --> 00:00:02.000",
+                        " 00:01.000-->100:00:02.000",
+                        "00:01.000 --> 00:60.000",
+                        "00:01.0000 --> 00:02.000",
+                        "1:00.000 --> 00:02.000",
+                    ]);
+                    input += random.pick(&[
+                        "",
+                        "",
+                        " ",
+                        " align:end",
+                        " align:bogus",
+                        " line:10%,start  position:50% ",
+                        " region:r vertical:lr size:50%",
+                        " line:-3,end position:100.5% size:101%",
+                        " line:+2 line:10.5%,center position:0%,line-right",
+                        " line:10.5 size:50",
+                        " region: vertical:rl",
+                        "\tsize:100.0%\t",
+                        " line:100%,start position:100%",
+                        "x",
+                    ]);
+                    input += random.pick(NEWLINES);
+                },
+                | "note" => {
+                    input += random.pick(&[
+                        "NOTE",
+                        "NOTE text",
+                        "NOTE  ",
+                        "NOTES",
+                    ]);
+                    input += random.pick(NEWLINES);
+                },
+                | "style" => {
+                    input += random.pick(&["STYLE", "STYLE "]);
+                    input += random.pick(NEWLINES);
+                },
+                | _ => {
+                    input += "REGION";
+                    input += random.pick(NEWLINES);
+                    for _ in 0..random.count(3) {
+                        input += random.pick(&[
+                            "id:r",
+                            "width:40%",
+                            "lines:3",
+                            "regionanchor:0%,100%",
+                            "bad:1",
+                            "id:a b",
+                        ]);
+                        input += random.pick(NEWLINES);
+                    }
+                    continue;
+                },
+            }
+            for _ in 0..random.count(3) {
+                input += random.pick(&[
+                    "Hello",
+                    "Hello",
+                    " padded ",
+                    " ",
+                    "a --> b",
+                    "00:03.000 --> 00:04.000",
+                    "<i>１２３</i>",
+                    "",
+                ]);
+                input += random.pick(NEWLINES);
+            }
+        }
+        input += random.pick(&[
+            "", "", "\n", " ", "\n \n", "x",
+        ]);
+
+        input
+    }
+
+    #[test]
+    fn srt_same_as_grammar() {
+        let mut random = Random(0x2545_F491_4F6C_DD1D);
+        let mut scanned = 0;
+        for _ in 0..20000 {
+            let input = srt_input(&mut random);
+            if let Some(srt) = srt(&input) {
+                // `SrtSubtitle` is compared only by the sequence number.
+                assert_eq!(
+                    format!("{:?}", Ok::<_, ()>(srt)),
+                    format!(
+                        "{:?}",
+                        str_parser::srt(&input).map_err(|_| ())
+                    ),
+                    "{:?}",
+                    input
+                );
+                scanned += 1;
+            }
+        }
+        assert!(scanned > 1000, "{}", scanned);
+    }
+
+    #[test]
+    fn vtt_same_as_grammar() {
+        let mut random = Random(0x9E37_79B9_7F4A_7C15);
+        let mut scanned = 0;
+        for _ in 0..20000 {
+            let input = vtt_input(&mut random);
+            if let Some(vtt) = vtt(&input) {
+                assert_eq!(
+                    Ok(vtt),
+                    vtt_parser::vtt(&input).map_err(|_| ()),
+                    "{:?}",
+                    input
+                );
+                scanned += 1;
+            }
+        }
+        assert!(scanned > 1000, "{}", scanned);
+    }
+
+    #[test]
+    fn canonical() {
+        let text = "1\n00:00:01,000 --> 00:00:02,000\nHello, world!\n\n2\r\n00:00:03,000 --> 00:00:04,000 X1:1 X2:2 Y1:3 Y2:4\r\nThis is a sample.\r\n Thank you. \r\n";
+        assert_eq!(
+            srt(text),
+            Some(str_parser::srt(text).unwrap())
+        );
+
+        let text = "WEBVTT - description\n\nSTYLE\n::cue { color: lime }\n\nREGION\nid:r\nwidth:40%\n\nNOTE\ncomment\n\nid\n00:01.000 --> 00:00:02.000 region:r align:end\n- Hello\n\n00:03.000 --> 00:04.000\n- world\n";
+        let vtt = vtt(text).unwrap();
+        assert_eq!(vtt.blocks.len(), 5);
+        assert_eq!(vtt, vtt_parser::vtt(text).unwrap());
+    }
+}
@@ -0,0 +1,753 @@
+//! Splitting of long cues and merging of short cues provided by
+//! [`subtp::split::SplitConfig`](SplitConfig) and [`subtp::split::MergeConfig`](MergeConfig).
+//!
+//! The lengths of texts are measured by [`display_width`](crate::reflow::display_width).
+//!
+//! ## Example
+//! ```
+//! use std::time::Duration;
+//! use subtp::split::SplitConfig;
+//! use subtp::srt::SubRip;
+//!
+//! let text = r#"1
+//! 00:00:01,000 --> 00:00:09,000
+//! Hello, world! This is a sample.
+//! "#;
+//!
+//! let mut srt = SubRip::parse(text).unwrap();
+//! srt.split(&SplitConfig {
+//!     max_duration: Some(Duration::from_secs(5)),
+//!     ..Default::default()
+//! });
+//!
+//! assert_eq!(
+//!     srt.render(),
+//!     "1\n00:00:01,000 --> 00:00:04,466\nHello, world!\n\n2\n00:00:04,466 --> 00:00:09,000\nThis is a sample.\n"
+//! );
+//! ```
+
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::reflow::{self, Token};
+
+/// The configuration of splitting long cues.
+///
+/// A cue exceeding either limit is split into consecutive cues
+/// with the times distributed in proportion to the lengths of the texts.
+/// Each limit is disabled by `None`.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use subtp::split::{SplitBoundary, SplitConfig};
+///
+/// let config = SplitConfig {
+///     max_duration: Some(Duration::from_secs(6)),
+///     boundary: SplitBoundary::Words,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SplitConfig {
+    /// The maximum duration of a cue.
+    pub max_duration: Option<Duration>,
+    /// The maximum width of the text of a cue in columns.
+    pub max_width: Option<usize>,
+    /// The boundaries to split the text at.
+    pub boundary: SplitBoundary,
+}
+
+impl Default for SplitConfig {
+    /// Splits cues longer than 7 seconds or 2 lines of 42 columns at sentence boundaries.
+    fn default() -> Self {
+        Self {
+            max_duration: Some(Duration::from_secs(7)),
+            max_width: Some(84),
+            boundary: SplitBoundary::Sentences,
+        }
+    }
+}
+
+/// The boundaries to split the text of a cue at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum SplitBoundary {
+    /// Splits at spaces or around full-width characters into the fewest parts of balanced lengths.
+    Words,
+    /// Splits after sentences, packing as many sentences as fit the limits in each part,
+    /// and splits a sentence exceeding the limits at words.
+    Sentences,
+}
+
+impl Default for SplitBoundary {
+    fn default() -> Self {
+        Self::Sentences
+    }
+}
+
+/// The configuration of merging short cues.
+///
+/// A cue shorter than the minimum duration is merged with the consecutive cues
+/// as long as the merged cue fits the limits.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use subtp::split::MergeConfig;
+///
+/// let config = MergeConfig {
+///     min_duration: Duration::from_secs(1),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MergeConfig {
+    /// The duration under which a cue is merged with the neighbors.
+    pub min_duration: Duration,
+    /// The maximum gap between the cues to merge.
+    pub max_gap: Duration,
+    /// The maximum duration of a merged cue.
+    pub max_duration: Duration,
+    /// The maximum width of the text of a merged cue in columns.
+    pub max_width: usize,
+}
+
+impl Default for MergeConfig {
+    /// Merges cues shorter than the minimum duration of [`LintConfig`](crate::lint::LintConfig)
+    /// within the limits of [`SplitConfig`].
+    fn default() -> Self {
+        Self {
+            min_duration: Duration::from_millis(833),
+            max_gap: Duration::from_millis(500),
+            max_duration: Duration::from_secs(7),
+            max_width: 84,
+        }
+    }
+}
+
+/// A part of a split cue.
+pub(crate) struct Part {
+    pub(crate) start: Duration,
+    pub(crate) end: Duration,
+    pub(crate) lines: Vec<String>,
+}
+
+/// A token of the text with whether it starts a line.
+struct Word {
+    token: Token,
+    line_break: bool,
+}
+
+impl Word {
+    /// Returns the width with the separator from the previous word.
+    fn width(&self) -> usize {
+        self.token.width + (self.line_break || self.token.space_before) as usize
+    }
+}
+
+/// Splits the cue into parts by the configuration,
+/// returning `None` if the cue fits the limits or can not be split.
+pub(crate) fn split(
+    start: Duration,
+    end: Duration,
+    lines: &[String],
+    config: &SplitConfig,
+) -> Option<Vec<Part>> {
+    let mut words = vec![];
+    for line in lines {
+        for (i, token) in reflow::tokenize(line)
+            .into_iter()
+            .enumerate()
+        {
+            words.push(Word {
+                line_break: i == 0 && !words.is_empty(),
+                token,
+            });
+        }
+    }
+    if words.len() < 2 {
+        return None;
+    }
+
+    let total = width(&words);
+    let duration = end.saturating_sub(start);
+    let mut budget = config
+        .max_width
+        .unwrap_or(usize::MAX);
+    if let Some(max_duration) = config.max_duration {
+        if !duration.is_zero() {
+            // The width of a part showing for the maximum duration.
+            let width =
+                total as u128 * max_duration.as_millis() / duration.as_millis();
+            budget = budget.min(width.min(usize::MAX as u128) as usize);
+        }
+    }
+    let budget = budget.max(1);
+    if total <= budget {
+        return None;
+    }
+
+    let ranges = match config.boundary {
+        | SplitBoundary::Words => split_words(&words, 0..words.len(), budget),
+        | SplitBoundary::Sentences => split_sentences(&words, budget),
+    };
+    if ranges.len() < 2 {
+        return None;
+    }
+
+    // Distributes the times in proportion to the widths of the parts.
+    let widths: Vec<usize> = ranges
+        .iter()
+        .map(|range| width(&words[range.clone()]))
+        .collect();
+    let sum: usize = widths.iter().sum();
+    let count = ranges.len();
+    let mut elapsed = 0;
+    let mut part_start = start;
+    let mut open = vec![];
+    let mut parts = vec![];
+    for (i, (range, width)) in ranges
+        .into_iter()
+        .zip(widths)
+        .enumerate()
+    {
+        elapsed += width;
+        let part_end = if elapsed == sum {
+            end.max(start)
+        } else {
+            start
+                + Duration::from_millis(
+                    (duration.as_millis() * elapsed as u128 / sum as u128)
+                        as u64,
+                )
+        };
+        parts.push(Part {
+            start: part_start,
+            end: part_end,
+            lines: render(&words[range], &mut open, i + 1 < count),
+        });
+        part_start = part_end;
+    }
+
+    Some(parts)
+}
+
+/// Returns the ranges of consecutive cues to merge by the configuration.
+///
+/// The cues are given by the start, the end and the width of the text,
+/// and `compatible(i)` tells whether the cue `i` may be merged into the cue `i - 1`.
+pub(crate) fn merge(
+    cues: &[(Duration, Duration, usize)],
+    compatible: impl Fn(usize) -> bool,
+    config: &MergeConfig,
+) -> Vec<Range<usize>> {
+    let mut groups = vec![];
+    let mut first = 0;
+    while first < cues.len() {
+        let (start, mut end, mut width) = cues[first];
+        let mut next = first + 1;
+        while let Some(&(cue_start, cue_end, cue_width)) = cues.get(next) {
+            let short = end.saturating_sub(start) < config.min_duration
+                || cue_end.saturating_sub(cue_start) < config.min_duration;
+            let merged_end = end.max(cue_end);
+            let merged_width = width + 1 + cue_width;
+            if !short
+                || !compatible(next)
+                || cue_start < start
+                || cue_start.saturating_sub(end) > config.max_gap
+                || merged_end - start > config.max_duration
+                || merged_width > config.max_width
+            {
+                break;
+            }
+
+            end = merged_end;
+            width = merged_width;
+            next += 1;
+        }
+
+        groups.push(first..next);
+        first = next;
+    }
+
+    groups
+}
+
+/// Returns the width of the text of the lines as [`display_width`](crate::reflow::display_width).
+pub(crate) fn text_width(lines: &[String]) -> usize {
+    reflow::display_width(&reflow::join_lines(lines))
+}
+
+/// Returns the width of the words without the separator before the first word.
+fn width(words: &[Word]) -> usize {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            if i == 0 {
+                word.token.width
+            } else {
+                word.width()
+            }
+        })
+        .sum()
+}
+
+/// Splits the words into the fewest parts within the budget of balanced widths.
+fn split_words(
+    words: &[Word],
+    range: Range<usize>,
+    budget: usize,
+) -> Vec<Range<usize>> {
+    let total = width(&words[range.clone()]);
+    let count = total
+        .div_ceil(budget)
+        .min(range.len())
+        .max(1);
+
+    let mut ranges = vec![];
+    let mut first = range.start;
+    let mut elapsed = 0;
+    for part in 1..count {
+        // Cuts at the word boundary nearest to the ideal position,
+        // leaving at least a word for each of the remaining parts.
+        let target = total * part / count;
+        let last = range.end - (count - part);
+        let mut cut = first + 1;
+        elapsed += words[first].token.width;
+        while cut < last {
+            let next = elapsed + words[cut].width();
+            if next.abs_diff(target) >= elapsed.abs_diff(target) {
+                break;
+            }
+            elapsed = next;
+            cut += 1;
+        }
+
+        ranges.push(first..cut);
+        first = cut;
+        if first < range.end {
+            // The separator before the first word of the next part is not counted.
+            elapsed += words[first].width() - words[first].token.width;
+        }
+    }
+    ranges.push(first..range.end);
+
+    ranges
+}
+
+/// Splits the words after sentences into parts within the budget,
+/// falling back to [`split_words`] for a sentence exceeding the budget.
+fn split_sentences(
+    words: &[Word],
+    budget: usize,
+) -> Vec<Range<usize>> {
+    let mut sentences = vec![];
+    let mut first = 0;
+    for (i, word) in words.iter().enumerate() {
+        if i + 1 == words.len() || ends_sentence(&word.token) {
+            sentences.push(first..i + 1);
+            first = i + 1;
+        }
+    }
+
+    let mut ranges: Vec<Range<usize>> = vec![];
+    let mut current: Option<Range<usize>> = None;
+    for sentence in sentences {
+        if let Some(range) = current.take() {
+            let packed = range.start..sentence.end;
+            if width(&words[packed.clone()]) <= budget {
+                current = Some(packed);
+                continue;
+            }
+            ranges.push(range);
+        }
+
+        if width(&words[sentence.clone()]) <= budget {
+            current = Some(sentence);
+        } else {
+            ranges.extend(split_words(words, sentence, budget));
+        }
+    }
+    ranges.extend(current);
+
+    ranges
+}
+
+/// Returns whether the token ends a sentence, e.g. `world!` or `"Yes."`.
+fn ends_sentence(token: &Token) -> bool {
+    reflow::strip_markups(&token.text)
+        .trim_end_matches([
+            '"', '\'', ')', ']', '”', '’', '）', '」', '』',
+        ])
+        .ends_with([
+            '.', '!', '?', '…', '。', '！', '？',
+        ])
+}
+
+/// Renders the words into lines, keeping the line breaks of the original text.
+///
+/// The tags in `open` left open by the previous part are reopened at the start,
+/// and the tags still open at the end are closed if the text is `cut` there,
+/// so that each part has balanced markups, e.g. `<i>` or `<v Bob>`.
+fn render(
+    words: &[Word],
+    open: &mut Vec<String>,
+    cut: bool,
+) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for (i, word) in words.iter().enumerate() {
+        let text = if i == 0 {
+            format!("{}{}", open.concat(), word.token.text)
+        } else {
+            word.token.text.clone()
+        };
+        update_tags(open, &word.token.text);
+
+        match lines.last_mut() {
+            | Some(line) if !word.line_break => {
+                if word.token.space_before {
+                    line.push(' ');
+                }
+                line.push_str(&text);
+            },
+            | _ => lines.push(text),
+        }
+    }
+
+    if cut {
+        if let Some(line) = lines.last_mut() {
+            for tag in open.iter().rev() {
+                if let Some(name) = tag_name(tag) {
+                    line.push_str(&format!("</{}>", name));
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+/// Pushes the opening tags in the text to `open` and removes the closed ones.
+fn update_tags(
+    open: &mut Vec<String>,
+    text: &str,
+) {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let Some(len) = reflow::markup_len(rest) else {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        let markup = &rest[..len];
+        rest = &rest[len..];
+
+        if let Some(name) = markup
+            .strip_prefix("</")
+            .and_then(|tag| tag.strip_suffix('>'))
+        {
+            let name = name.trim();
+            if let Some(position) = open
+                .iter()
+                .rposition(|tag| tag_name(tag) == Some(name))
+            {
+                open.remove(position);
+            }
+        } else if tag_name(markup).is_some() && !markup.ends_with("/>") {
+            open.push(markup.to_string());
+        }
+    }
+}
+
+/// Returns the name of the opening tag, e.g. `v` of `<v Bob>` or `c` of `<c.yellow>`,
+/// or `None` for a closing tag, a timestamp or an override.
+fn tag_name(markup: &str) -> Option<&str> {
+    let tag = markup.strip_prefix('<')?;
+    if !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    tag.split(|c: char| c.is_whitespace() || c == '.' || c == '>' || c == '/')
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(milliseconds: u64) -> Duration {
+        Duration::from_millis(milliseconds)
+    }
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    fn parts(
+        start: u64,
+        end: u64,
+        text: &[&str],
+        config: &SplitConfig,
+    ) -> Option<Vec<(u64, u64, Vec<String>)>> {
+        split(
+            millis(start),
+            millis(end),
+            &lines(text),
+            config,
+        )
+        .map(|parts| {
+            parts
+                .into_iter()
+                .map(|part| {
+                    (
+                        part.start.as_millis() as u64,
+                        part.end.as_millis() as u64,
+                        part.lines,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn split_within_limits() {
+        let config = SplitConfig::default();
+        assert_eq!(
+            parts(0, 7000, &["Hello, world!"], &config),
+            None
+        );
+        assert_eq!(
+            parts(0, 20000, &["Hello"], &config),
+            None
+        );
+        assert_eq!(parts(0, 20000, &[], &config), None);
+        assert_eq!(
+            parts(
+                0,
+                20000,
+                &["Hello, world!"],
+                &SplitConfig {
+                    max_duration: None,
+                    ..config
+                }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn split_words() {
+        let config = SplitConfig {
+            max_duration: None,
+            max_width: Some(25),
+            boundary: SplitBoundary::Words,
+        };
+        assert_eq!(
+            parts(
+                1000,
+                5000,
+                &[
+                    "The quick brown fox",
+                    "jumps over the lazy dog.",
+                ],
+                &config
+            ),
+            Some(vec![
+                (
+                    1000,
+                    2767,
+                    lines(&["The quick brown fox"])
+                ),
+                (
+                    2767,
+                    5000,
+                    lines(&["jumps over the lazy dog."])
+                ),
+            ])
+        );
+        assert_eq!(
+            parts(
+                0,
+                3000,
+                &["<i>一二三四五六七八九十</i>"],
+                &SplitConfig {
+                    max_width: Some(8),
+                    ..config
+                }
+            ),
+            Some(vec![
+                (0, 900, lines(&["<i>一二三</i>"])),
+                (900, 1800, lines(&["<i>四五六</i>"])),
+                (1800, 3000, lines(&["<i>七八九十</i>"])),
+            ])
+        );
+    }
+
+    #[test]
+    fn split_sentences() {
+        let config = SplitConfig {
+            max_duration: Some(millis(4500)),
+            max_width: None,
+            boundary: SplitBoundary::Sentences,
+        };
+        assert_eq!(
+            parts(
+                0,
+                6000,
+                &["Yes. No. Maybe, if you say \"Please.\""],
+                &config
+            ),
+            Some(vec![
+                (0, 1371, lines(&["Yes. No."])),
+                (
+                    1371,
+                    6000,
+                    lines(&["Maybe, if you say \"Please.\""])
+                ),
+            ])
+        );
+        assert_eq!(
+            parts(
+                0,
+                8000,
+                &[
+                    "A sentence too long to fit",
+                    "in a part. Short one.",
+                ],
+                &SplitConfig {
+                    max_duration: Some(millis(4000)),
+                    ..config
+                }
+            ),
+            Some(vec![
+                (0, 3304, lines(&["A sentence too long"])),
+                (
+                    3304,
+                    6260,
+                    lines(&[
+                        "to fit",
+                        "in a part."
+                    ])
+                ),
+                (6260, 8000, lines(&["Short one."])),
+            ])
+        );
+    }
+
+    #[test]
+    fn split_markups() {
+        let config = SplitConfig {
+            max_duration: Some(millis(5000)),
+            max_width: None,
+            boundary: SplitBoundary::Sentences,
+        };
+
+        // SubRip Subtitle italics across the cut.
+        assert_eq!(
+            parts(
+                1000,
+                9000,
+                &["<i>Hello, world! This is a sample.</i>"],
+                &config
+            ),
+            Some(vec![
+                (
+                    1000,
+                    4466,
+                    lines(&["<i>Hello, world!</i>"])
+                ),
+                (
+                    4466,
+                    9000,
+                    lines(&["<i>This is a sample.</i>"])
+                ),
+            ])
+        );
+
+        // WebVTT voice span without the end tag and a nested class span.
+        assert_eq!(
+            parts(
+                0,
+                9000,
+                &[
+                    "<v Bob>Hello, <c.loud>world!",
+                    "This</c> is a sample. Bye now.",
+                ],
+                &config
+            ),
+            Some(vec![
+                (
+                    0,
+                    3078,
+                    lines(&["<v Bob>Hello, <c.loud>world!</c></v>"])
+                ),
+                (
+                    3078,
+                    7105,
+                    lines(&["<v Bob><c.loud>This</c> is a sample.</v>"])
+                ),
+                (7105, 9000, lines(&["<v Bob>Bye now."])),
+            ])
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let config = MergeConfig {
+            min_duration: millis(1000),
+            max_gap: millis(500),
+            max_duration: millis(5000),
+            max_width: 30,
+        };
+        let cues = [
+            (millis(0), millis(500), 5),
+            (millis(500), millis(1500), 5),
+            (millis(1500), millis(3000), 5),
+            // The gap is too long.
+            (millis(4000), millis(4200), 5),
+            (millis(4200), millis(4300), 20),
+            // The width is too long.
+            (millis(4300), millis(4400), 5),
+            // The duration is too long.
+            (millis(4400), millis(9400), 5),
+            (millis(9400), millis(9500), 5),
+        ];
+        assert_eq!(
+            super::merge(&cues, |_| true, &config),
+            vec![
+                0..2,
+                2..3,
+                3..5,
+                5..6,
+                6..7,
+                7..8
+            ]
+        );
+        assert_eq!(
+            super::merge(&cues, |cue| cue != 1, &config),
+            vec![
+                0..1,
+                1..2,
+                2..3,
+                3..5,
+                5..6,
+                6..7,
+                7..8
+            ]
+        );
+    }
+}
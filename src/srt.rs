@@ -65,10 +65,32 @@
 
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, Write};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
 use std::time::Duration;
 
+use crate::block_reader::BlockReader;
+use crate::block_writer;
+#[cfg(feature = "encoding")]
+use crate::encoding::{self, Decoded, TextEncoding};
+use crate::error;
+use crate::lenient_parser;
+use crate::lint::{self, LintConfig, LintCue, LintFinding};
+use crate::reflow::{self, ReflowConfig};
+use crate::repair::{self, RepairChange, RepairConfig};
+use crate::scanner;
+use crate::split::{self, MergeConfig, SplitConfig};
 use crate::str_parser;
-use crate::ParseResult;
+use crate::subtitles::Subtitles;
+use crate::timing::{
+    self, FrameRate, FrameSnapping, OutOfRange, TimeOffset, TimingError,
+};
+use crate::{
+    Lenient, LineEnding, ParseError, ParseResult, ReadResult,
+    TimestampFieldError, TimestampRangeError,
+};
 
 /// The SubRip Subtitle (`.srt`) format.
 ///
@@ -109,6 +131,10 @@ use crate::ParseResult;
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct SubRip {
     /// The collection of subtitles.
     pub subtitles: Vec<SrtSubtitle>,
@@ -134,7 +160,93 @@ impl SubRip {
     /// let srt = SubRip::parse(text).unwrap();
     /// ```
     pub fn parse(text: &str) -> ParseResult<Self> {
-        str_parser::srt(text).map_err(|err| err.into())
+        SubRipRef::parse(text).map(|srt| srt.to_sub_rip())
+    }
+
+    /// Parses the SubRip Subtitle format from the given text by the hand-written scanner
+    /// in a single pass without backtracking, producing the same value as [`SubRip::parse`].
+    ///
+    /// The input that the scanner does not accept, e.g. malformed one,
+    /// falls back to [`SubRip::parse`] for the same error.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::srt::SubRip;
+    ///
+    /// let text = r#"1
+    /// 00:00:01,000 --> 00:00:02,000
+    /// Hello, world!
+    /// "#;
+    ///
+    /// assert_eq!(SubRip::parse_fast(text), SubRip::parse(text));
+    /// ```
+    pub fn parse_fast(text: &str) -> ParseResult<Self> {
+        SubRipRef::parse_fast(text).map(|srt| srt.to_sub_rip())
+    }
+
+    /// Parses the SubRip Subtitle format from the given text leniently,
+    /// skipping or repairing malformed blocks instead of failing.
+    /// The valid input gives the same value as [`SubRip::parse`] without warnings.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::srt::SubRip;
+    /// use subtp::WarningKind;
+    ///
+    /// let text = r#"1
+    /// 00:00:01.000 --> 00:00:02,000
+    /// Hello, world!
+    ///
+    /// 00:00:03,000 --> 00:00:04,000
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let lenient = SubRip::parse_lenient(text);
+    /// assert_eq!(lenient.value.subtitles.len(), 2);
+    /// assert_eq!(lenient.value.subtitles[1].sequence, 2);
+    /// assert_eq!(
+    ///     lenient
+    ///         .warnings
+    ///         .iter()
+    ///         .map(|warning| warning.kind)
+    ///         .collect::<Vec<_>>(),
+    ///     vec![
+    ///         WarningKind::RepairedTimestamp,
+    ///         WarningKind::MissingSequence,
+    ///     ]
+    /// );
+    /// ```
+    pub fn parse_lenient(text: &str) -> Lenient<Self> {
+        lenient_parser::srt(text)
+    }
+
+    /// Parses the bytes of the SubRip Subtitle format with the character encoding detected by
+    /// [`TextEncoding::detect`](crate::encoding::TextEncoding::detect) behind `encoding` feature.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::srt::SubRip;
+    ///
+    /// // UTF-16LE with the byte order mark.
+    /// let bytes: Vec<u8> = "\u{FEFF}1\n00:00:01,000 --> 00:00:02,000\nHello, world!\n"
+    ///     .encode_utf16()
+    ///     .flat_map(|unit| unit.to_le_bytes())
+    ///     .collect();
+    ///
+    /// let decoded = SubRip::parse_bytes(&bytes).unwrap();
+    /// assert_eq!(decoded.encoding.encoding.name(), "UTF-16LE");
+    /// assert!(decoded.encoding.bom);
+    /// assert_eq!(decoded.value.render(), "1\n00:00:01,000 --> 00:00:02,000\nHello, world!\n");
+    /// ```
+    #[cfg(feature = "encoding")]
+    pub fn parse_bytes(bytes: &[u8]) -> ParseResult<Decoded<Self>> {
+        let decoded = encoding::decode(bytes);
+
+        Ok(Decoded {
+            value: Self::parse(&decoded.value)?,
+            encoding: decoded.encoding,
+            replaced: decoded.replaced,
+        })
     }
 
     /// Renders the text from the SubRip Subtitle format.
@@ -172,8 +284,458 @@ impl SubRip {
     pub fn render(&self) -> String {
         self.to_string()
     }
+
+    /// Renders the bytes of the SubRip Subtitle format in the character encoding behind `encoding` feature,
+    /// e.g. the encoding of the input detected by [`parse_bytes`](SubRip::parse_bytes),
+    /// and returns whether unrepresentable characters are replaced as by
+    /// [`TextEncoding::encode`](crate::encoding::TextEncoding::encode).
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::encoding::TextEncoding;
+    /// use subtp::srt::SubRip;
+    ///
+    /// let decoded = SubRip::parse_bytes(b"1\n00:00:01,000 --> 00:00:02,000\nHello, world!\n").unwrap();
+    /// let (bytes, replaced) = decoded.value.render_bytes(&TextEncoding {
+    ///     bom: true,
+    ///     ..decoded.encoding
+    /// });
+    ///
+    /// assert!(!replaced);
+    /// assert_eq!(&bytes[..5], b"\xEF\xBB\xBF1\n");
+    /// ```
+    #[cfg(feature = "encoding")]
+    pub fn render_bytes(
+        &self,
+        encoding: &TextEncoding,
+    ) -> (Vec<u8>, bool) {
+        encoding.encode(&self.render())
+    }
+
+    /// Shifts all subtitles by the offset,
+    /// failing without changes if a time would be negative.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::timing::TimeOffset;
+    /// use subtp::timing::TimingError;
+    /// use subtp::srt::SubRip;
+    ///
+    /// let text = r#"1
+    /// 00:00:01,000 --> 00:00:02,000
+    /// Hello, world!
+    /// "#;
+    ///
+    /// let mut srt = SubRip::parse(text).unwrap();
+    ///
+    /// srt.shift(TimeOffset::backward(Duration::from_millis(500)))
+    ///     .unwrap();
+    /// assert_eq!(srt.subtitles[0].start.to_string(), "00:00:00,500");
+    ///
+    /// assert_eq!(
+    ///     srt.shift(TimeOffset::backward(Duration::from_secs(1))),
+    ///     Err(TimingError::Underflow { cue: 0 })
+    /// );
+    /// assert_eq!(srt.subtitles[0].start.to_string(), "00:00:00,500");
+    /// ```
+    pub fn shift(
+        &mut self,
+        offset: TimeOffset,
+    ) -> Result<(), TimingError> {
+        self.map_times(timing::shift(offset))
+    }
+
+    /// Shifts all subtitles by the offset, clamping negative times at zero.
+    pub fn shift_saturating(
+        &mut self,
+        offset: TimeOffset,
+    ) -> Result<(), TimingError> {
+        self.map_times(|time| Ok(offset.saturating_apply(time)))
+    }
+
+    /// Scales all times of subtitles by the factor, rounding to milliseconds,
+    /// e.g. `25.0 / 23.976` to convert from 25 fps to 23.976 fps.
+    pub fn scale(
+        &mut self,
+        factor: f64,
+    ) -> Result<(), TimingError> {
+        self.map_times(timing::scale(factor)?)
+    }
+
+    /// Resyncs all subtitles linearly so that the start of the cue at index `a` is at `a_time`
+    /// and the start of the cue at index `b` is at `b_time`,
+    /// clamping negative times at zero.
+    pub fn resync(
+        &mut self,
+        a: usize,
+        a_time: Duration,
+        b: usize,
+        b_time: Duration,
+    ) -> Result<(), TimingError> {
+        let times = self.times();
+        let start = |cue: usize| {
+            times
+                .get(cue)
+                .map(|(start, _)| *start)
+                .ok_or(TimingError::CueNotFound {
+                    cue,
+                })
+        };
+
+        self.map_times(timing::resync(
+            (start(a)?, a_time),
+            (start(b)?, b_time),
+        )?)
+    }
+
+    /// Converts the times of all subtitles from the source frame rate to the target frame rate,
+    /// keeping the frame numbers, e.g. for the PAL speedup from 23.976 fps to 25 fps.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::timing::{FrameRate, FrameSnapping};
+    /// use subtp::srt::SubRip;
+    ///
+    /// let text = r#"1
+    /// 01:00:00,000 --> 01:00:02,000
+    /// Hello, world!
+    /// "#;
+    ///
+    /// let mut srt = SubRip::parse(text).unwrap();
+    /// srt.convert_frame_rate(
+    ///     FrameRate::FPS_23_976,
+    ///     FrameRate::FPS_25,
+    ///     FrameSnapping::Off,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(srt.subtitles[0].start.to_string(), "00:57:32,547");
+    /// assert_eq!(srt.subtitles[0].end.to_string(), "00:57:34,466");
+    /// ```
+    pub fn convert_frame_rate(
+        &mut self,
+        source: FrameRate,
+        target: FrameRate,
+        snapping: FrameSnapping,
+    ) -> Result<(), TimingError> {
+        self.map_times(timing::frame_rate(
+            source, target, snapping,
+        )?)
+    }
+
+    /// Runs the quality checks over the subtitles,
+    /// measuring the lengths of the lines without markups.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::lint::LintConfig;
+    /// use subtp::srt::SubRip;
+    ///
+    /// let text = r#"1
+    /// 00:00:01,000 --> 00:00:02,000
+    /// <i>Hello, world!</i>
+    ///
+    /// 1
+    /// 00:00:03,000 --> 00:00:04,000
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let srt = SubRip::parse(text).unwrap();
+    /// let findings = srt.lint(&LintConfig {
+    ///     max_line_length: Some(13),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(
+    ///     findings
+    ///         .iter()
+    ///         .map(|finding| finding.to_string())
+    ///         .collect::<Vec<_>>(),
+    ///     vec![
+    ///         "cue 1: error: sequence number 1 follows 1",
+    ///         "cue 1: warning: line 1 of 17 characters is too long",
+    ///     ]
+    /// );
+    /// ```
+    pub fn lint(
+        &self,
+        config: &LintConfig,
+    ) -> Vec<LintFinding> {
+        let subtitles = Subtitles::from(self);
+        let cues: Vec<_> = self
+            .subtitles
+            .iter()
+            .zip(&subtitles.cues)
+            .map(|(subtitle, cue)| {
+                LintCue {
+                    start: subtitle.start.into(),
+                    end: subtitle.end.into(),
+                    lines: cue
+                        .plain_text()
+                        .split('\n')
+                        .map(|line| line.to_string())
+                        .collect(),
+                    sequence: Some(subtitle.sequence),
+                }
+            })
+            .collect();
+
+        lint::lint(&cues, config)
+    }
+
+    /// Repairs the timing defects of the subtitles and renumbers them,
+    /// returning the changes made.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::repair::{RepairConfig, RepairKind};
+    /// use subtp::srt::SubRip;
+    ///
+    /// let text = r#"1
+    /// 00:00:01,000 --> 00:00:01,500
+    /// Hello, world!
+    ///
+    /// 2
+    /// 00:00:03,000 --> 00:00:04,000
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let mut srt = SubRip::parse(text).unwrap();
+    /// let changes = srt.repair(&RepairConfig::default());
+    ///
+    /// assert_eq!(srt.subtitles[0].end.to_string(), "00:00:01,833");
+    /// assert_eq!(changes.len(), 1);
+    /// assert!(matches!(
+    ///     changes[0].kind,
+    ///     RepairKind::DurationExtended { .. }
+    /// ));
+    /// ```
+    pub fn repair(
+        &mut self,
+        config: &RepairConfig,
+    ) -> Vec<RepairChange> {
+        let repaired = repair::repair(self.times(), config);
+        self.subtitles = repair::reorder(
+            std::mem::take(&mut self.subtitles),
+            &repaired.order,
+        );
+        for (subtitle, (start, end)) in self
+            .subtitles
+            .iter_mut()
+            .zip(repaired.times)
+        {
+            subtitle.start = SrtTimestamp::saturating_from(start);
+            subtitle.end = SrtTimestamp::saturating_from(end);
+        }
+
+        let mut changes = repaired.changes;
+        if config.renumber {
+            changes.extend(repair::renumber(
+                self.subtitles
+                    .iter_mut()
+                    .map(|subtitle| &mut subtitle.sequence),
+            ));
+        }
+
+        changes
+    }
+
+    /// Rewraps the texts of all subtitles by [`reflow`](crate::reflow::reflow).
+    pub fn reflow(
+        &mut self,
+        config: &ReflowConfig,
+    ) {
+        for subtitle in &mut self.subtitles {
+            subtitle.reflow(config);
+        }
+    }
+
+    /// Splits the subtitles exceeding the limits into consecutive subtitles
+    /// with the same line position, and renumbers them if split.
+    ///
+    /// The tags open at a cut, e.g. `<i>`, are closed there and reopened in the next part.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::split::{SplitBoundary, SplitConfig};
+    /// use subtp::srt::SubRip;
+    ///
+    /// let text = r#"1
+    /// 00:00:01,000 --> 00:00:03,000
+    /// Hello, world!
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let mut srt = SubRip::parse(text).unwrap();
+    /// srt.split(&SplitConfig {
+    ///     max_duration: None,
+    ///     max_width: Some(20),
+    ///     boundary: SplitBoundary::Words,
+    /// });
+    ///
+    /// assert_eq!(srt.subtitles.len(), 2);
+    /// assert_eq!(srt.subtitles[0].end.to_string(), "00:00:01,866");
+    /// assert_eq!(srt.subtitles[1].sequence, 2);
+    /// assert_eq!(srt.subtitles[1].text, vec!["This is a sample.".to_string()]);
+    /// ```
+    pub fn split(
+        &mut self,
+        config: &SplitConfig,
+    ) {
+        let mut subtitles = Vec::with_capacity(self.subtitles.len());
+        let mut changed = false;
+        for subtitle in std::mem::take(&mut self.subtitles) {
+            let parts = split::split(
+                subtitle.start.into(),
+                subtitle.end.into(),
+                &subtitle.text,
+                config,
+            );
+            match parts {
+                | Some(parts) => {
+                    changed = true;
+                    subtitles.extend(parts.into_iter().map(|part| {
+                        SrtSubtitle {
+                            sequence: subtitle.sequence,
+                            start: SrtTimestamp::saturating_from(part.start),
+                            end: SrtTimestamp::saturating_from(part.end),
+                            text: part.lines,
+                            line_position: subtitle.line_position,
+                        }
+                    }));
+                },
+                | None => subtitles.push(subtitle),
+            }
+        }
+        self.subtitles = subtitles;
+
+        if changed {
+            repair::renumber(
+                self.subtitles
+                    .iter_mut()
+                    .map(|subtitle| &mut subtitle.sequence),
+            );
+        }
+    }
+
+    /// Merges the short subtitles with the consecutive subtitles of the same line position,
+    /// and renumbers them if merged.
+    ///
+    /// The merged subtitle has the lines of all subtitles.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::split::MergeConfig;
+    /// use subtp::srt::SubRip;
+    ///
+    /// let text = r#"1
+    /// 00:00:01,000 --> 00:00:01,500
+    /// Hello,
+    ///
+    /// 2
+    /// 00:00:01,500 --> 00:00:03,000
+    /// world!
+    ///
+    /// 3
+    /// 00:00:05,000 --> 00:00:07,000
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let mut srt = SubRip::parse(text).unwrap();
+    /// srt.merge(&MergeConfig::default());
+    ///
+    /// assert_eq!(
+    ///     srt.render(),
+    ///     "1\n00:00:01,000 --> 00:00:03,000\nHello,\nworld!\n\n2\n00:00:05,000 --> 00:00:07,000\nThis is a sample.\n"
+    /// );
+    /// ```
+    pub fn merge(
+        &mut self,
+        config: &MergeConfig,
+    ) {
+        let cues: Vec<_> = self
+            .subtitles
+            .iter()
+            .map(|subtitle| {
+                (
+                    subtitle.start.into(),
+                    subtitle.end.into(),
+                    split::text_width(&subtitle.text),
+                )
+            })
+            .collect();
+        let groups = split::merge(
+            &cues,
+            |cue| {
+                self.subtitles[cue].line_position
+                    == self.subtitles[cue - 1].line_position
+            },
+            config,
+        );
+        if groups.len() == self.subtitles.len() {
+            return;
+        }
+
+        let mut subtitles = std::mem::take(&mut self.subtitles).into_iter();
+        for group in groups {
+            let Some(mut merged) = subtitles.next() else {
+                break;
+            };
+            for subtitle in subtitles
+                .by_ref()
+                .take(group.len() - 1)
+            {
+                merged.end = merged.end.max(subtitle.end);
+                merged
+                    .text
+                    .extend(subtitle.text);
+            }
+            self.subtitles.push(merged);
+        }
+
+        repair::renumber(
+            self.subtitles
+                .iter_mut()
+                .map(|subtitle| &mut subtitle.sequence),
+        );
+    }
+
+    /// Returns the start and end times of the subtitles.
+    fn times(&self) -> Vec<(Duration, Duration)> {
+        self.subtitles
+            .iter()
+            .map(|subtitle| {
+                (
+                    subtitle.start.into(),
+                    subtitle.end.into(),
+                )
+            })
+            .collect()
+    }
+
+    /// Maps the times of the subtitles, failing without changes.
+    fn map_times(
+        &mut self,
+        map: impl Fn(Duration) -> Result<Duration, OutOfRange>,
+    ) -> Result<(), TimingError> {
+        let max = Duration::from(SrtTimestamp::MAX);
+        let times = timing::map_times(self.times(), max, map)?;
+        for (subtitle, (start, end)) in self
+            .subtitles
+            .iter_mut()
+            .zip(times)
+        {
+            // The times are bounded by the maximum timestamp.
+            subtitle.start = SrtTimestamp::saturating_from(start);
+            subtitle.end = SrtTimestamp::saturating_from(end);
+        }
+
+        Ok(())
+    }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for SubRip {
     fn default() -> Self {
         Self {
@@ -194,7 +756,7 @@ impl Display for SubRip {
             .enumerate()
         {
             if i + 1 < length {
-                write!(f, "{}\n", subtitle)?;
+                writeln!(f, "{}", subtitle)?;
             } else {
                 write!(f, "{}", subtitle)?;
             }
@@ -216,6 +778,239 @@ impl Iterator for SubRip {
     }
 }
 
+/// The SubRip Subtitle (`.srt`) format borrowing the texts from the input,
+/// parsed by the same grammar as [`SubRip::parse`] without allocating each line.
+///
+/// ## Example
+/// ```
+/// use subtp::srt::SubRipRef;
+///
+/// let text = r#"1
+/// 00:00:01,000 --> 00:00:02,000
+/// Hello, world!
+/// "#;
+///
+/// let srt = SubRipRef::parse(text).unwrap();
+/// assert_eq!(srt.subtitles[0].text, vec!["Hello, world!"]);
+///
+/// let owned = srt.to_sub_rip();
+/// assert_eq!(owned.render(), text);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize)
+)]
+pub struct SubRipRef<'a> {
+    /// The collection of subtitles.
+    pub subtitles: Vec<SrtSubtitleRef<'a>>,
+}
+
+impl<'a> SubRipRef<'a> {
+    /// Parses the SubRip Subtitle format from the given text, borrowing the texts.
+    pub fn parse(text: &'a str) -> ParseResult<Self> {
+        str_parser::srt(text).map_err(|err| {
+            let err = ParseError::from(err);
+            let (block, _) = error::block_at(text, err.offset);
+            err.with_context("subtitle", Some(block))
+        })
+    }
+
+    /// Parses the SubRip Subtitle format from the given text by the hand-written scanner,
+    /// borrowing the texts as [`SubRip::parse_fast`].
+    pub fn parse_fast(text: &'a str) -> ParseResult<Self> {
+        match scanner::srt(text) {
+            | Some(srt) => Ok(srt),
+            | None => Self::parse(text),
+        }
+    }
+
+    /// Converts into [`SubRip`] by allocating the texts.
+    pub fn to_sub_rip(&self) -> SubRip {
+        SubRip {
+            subtitles: self
+                .subtitles
+                .iter()
+                .map(SrtSubtitleRef::to_srt_subtitle)
+                .collect(),
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for SubRipRef<'_> {
+    fn default() -> Self {
+        Self {
+            subtitles: vec![],
+        }
+    }
+}
+
+/// The streaming reader of the SubRip Subtitle format
+/// that yields subtitles one at a time from [`BufRead`].
+///
+/// Only the current subtitle is kept in memory,
+/// so that long or incrementally piped inputs can be processed.
+/// A malformed subtitle is reported as an error and the reader continues with the next one.
+///
+/// ## Example
+/// ```
+/// use subtp::srt::SrtReader;
+///
+/// let text = r#"1
+/// 00:00:01,000 --> 00:00:02,000
+/// Hello, world!
+///
+/// 2
+/// 00:00:03,000 --> 00:00:04,000
+/// This is a sample.
+/// "#;
+///
+/// let reader = SrtReader::new(text.as_bytes());
+/// let subtitles = reader
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(subtitles.len(), 2);
+/// assert_eq!(subtitles[1].text, vec!["This is a sample.".to_string()]);
+/// ```
+pub struct SrtReader<R> {
+    blocks: BlockReader<R>,
+    /// Whether the underlying reader has failed.
+    failed: bool,
+}
+
+impl<R: BufRead> SrtReader<R> {
+    /// Creates a reader from the buffered reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            blocks: BlockReader::new(reader),
+            failed: false,
+        }
+    }
+
+    /// Unwraps the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.blocks.into_inner()
+    }
+}
+
+impl<R: BufRead> Iterator for SrtReader<R> {
+    type Item = ReadResult<SrtSubtitle>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let block = match self.blocks.next_block() {
+            | Ok(block) => block?,
+            | Err(err) => {
+                self.failed = true;
+                return Some(Err(err.into()));
+            },
+        };
+
+        Some(
+            // The text ends before a whitespace-only line,
+            // which only the trailing whitespaces of the input may follow.
+            str_parser::subtitle(block.trim_end_whitespace_lines())
+                .map_err(|err| {
+                    block
+                        .relocate(err.into())
+                        .with_context("subtitle", Some(block.index))
+                        .into()
+                }),
+        )
+    }
+}
+
+/// The streaming renderer of the SubRip Subtitle format
+/// that writes subtitles one at a time to [`Write`].
+///
+/// ## Example
+/// ```
+/// use subtp::srt::SrtSubtitle;
+/// use subtp::srt::SrtTimestamp;
+/// use subtp::srt::SrtWriter;
+/// use subtp::LineEnding;
+///
+/// let mut writer = SrtWriter::with_line_ending(vec![], LineEnding::CrLf);
+/// writer
+///     .write_subtitle(&SrtSubtitle {
+///         sequence: 1,
+///         start: SrtTimestamp {
+///             seconds: 1,
+///             ..Default::default()
+///         },
+///         end: SrtTimestamp {
+///             seconds: 2,
+///             ..Default::default()
+///         },
+///         text: vec!["Hello, world!".to_string()],
+///         ..Default::default()
+///     })
+///     .unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(writer.into_inner()).unwrap(),
+///     "1\r\n00:00:01,000 --> 00:00:02,000\r\nHello, world!\r\n".to_string()
+/// );
+/// ```
+pub struct SrtWriter<W> {
+    inner: W,
+    line_ending: LineEnding,
+    /// The number of subtitles written.
+    written: usize,
+}
+
+impl<W: Write> SrtWriter<W> {
+    /// Creates a writer with `\n` line endings.
+    pub fn new(writer: W) -> Self {
+        Self::with_line_ending(writer, LineEnding::default())
+    }
+
+    /// Creates a writer with the line ending.
+    pub fn with_line_ending(
+        writer: W,
+        line_ending: LineEnding,
+    ) -> Self {
+        Self {
+            inner: writer,
+            line_ending,
+            written: 0,
+        }
+    }
+
+    /// Writes the subtitle, preceded by a blank line unless it is the first one.
+    pub fn write_subtitle(
+        &mut self,
+        subtitle: &SrtSubtitle,
+    ) -> std::io::Result<()> {
+        if self.written > 0 {
+            block_writer::write_text(&mut self.inner, "\n", self.line_ending)?;
+        }
+        block_writer::write_text(
+            &mut self.inner,
+            &subtitle.to_string(),
+            self.line_ending,
+        )?;
+        self.written += 1;
+
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
 /// The subtitle entry.
 ///
 /// ## Example
@@ -267,7 +1062,11 @@ impl Iterator for SubRip {
 ///     ..Default::default()
 /// };
 /// ```
-#[derive(Debug, Clone, Eq, Hash)]
+#[derive(Debug, Clone, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct SrtSubtitle {
     /// The sequence number.
     pub sequence: u32,
@@ -290,6 +1089,15 @@ impl PartialEq<Self> for SrtSubtitle {
     }
 }
 
+impl Hash for SrtSubtitle {
+    fn hash<H: Hasher>(
+        &self,
+        state: &mut H,
+    ) {
+        self.sequence.hash(state);
+    }
+}
+
 impl PartialOrd<Self> for SrtSubtitle {
     fn partial_cmp(
         &self,
@@ -309,6 +1117,37 @@ impl Ord for SrtSubtitle {
     }
 }
 
+impl SrtSubtitle {
+    /// Rewraps the text by [`reflow`](crate::reflow::reflow).
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::reflow::ReflowConfig;
+    /// use subtp::srt::SrtSubtitle;
+    ///
+    /// let mut subtitle = SrtSubtitle {
+    ///     text: vec!["The quick brown fox jumps over the lazy dog and runs away".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// subtitle.reflow(&ReflowConfig::default());
+    ///
+    /// assert_eq!(
+    ///     subtitle.text,
+    ///     vec![
+    ///         "The quick brown fox jumps over".to_string(),
+    ///         "the lazy dog and runs away".to_string(),
+    ///     ]
+    /// );
+    /// ```
+    pub fn reflow(
+        &mut self,
+        config: &ReflowConfig,
+    ) {
+        self.text = reflow::reflow(&self.text, config);
+    }
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for SrtSubtitle {
     fn default() -> Self {
         Self {
@@ -326,14 +1165,66 @@ impl Display for SrtSubtitle {
         &self,
         f: &mut Formatter<'_>,
     ) -> std::fmt::Result {
-        write!(
+        writeln!(
             f,
-            "{}\n{} --> {}\n{}\n",
-            self.sequence,
-            self.start,
-            self.end,
-            self.text.join("\n"),
-        )
+            "{}\n{} --> {}",
+            self.sequence, self.start, self.end,
+        )?;
+        // No text line is written for no text, which would end the subtitle.
+        if !self.text.is_empty() {
+            writeln!(f, "{}", self.text.join("\n"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The subtitle entry of [`SubRipRef`] borrowing the text lines from the input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize)
+)]
+pub struct SrtSubtitleRef<'a> {
+    /// The sequence number.
+    pub sequence: u32,
+    /// The start timestamp.
+    pub start: SrtTimestamp,
+    /// The end timestamp.
+    pub end: SrtTimestamp,
+    /// The subtitle text.
+    pub text: Vec<&'a str>,
+    /// The unofficial line position.
+    pub line_position: Option<LinePosition>,
+}
+
+impl SrtSubtitleRef<'_> {
+    /// Converts into [`SrtSubtitle`] by allocating the text lines.
+    pub fn to_srt_subtitle(&self) -> SrtSubtitle {
+        SrtSubtitle {
+            sequence: self.sequence,
+            start: self.start,
+            end: self.end,
+            text: self
+                .text
+                .iter()
+                .map(|line| line.to_string())
+                .collect(),
+            line_position: self.line_position,
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for SrtSubtitleRef<'_> {
+    fn default() -> Self {
+        Self {
+            sequence: 0,
+            start: SrtTimestamp::default(),
+            end: SrtTimestamp::default(),
+            text: vec![],
+            line_position: None,
+        }
     }
 }
 
@@ -383,6 +1274,127 @@ pub struct SrtTimestamp {
     pub milliseconds: u16,
 }
 
+impl SrtTimestamp {
+    /// The maximum timestamp `255:59:59,999`.
+    pub const MAX: Self = Self {
+        hours: u8::MAX,
+        minutes: 59,
+        seconds: 59,
+        milliseconds: 999,
+    };
+
+    /// Creates the timestamp, validating that the minutes and the seconds are less than 60
+    /// and the milliseconds are less than 1000.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::srt::SrtTimestamp;
+    ///
+    /// let timestamp = SrtTimestamp::new(1, 2, 3, 4).unwrap();
+    /// assert_eq!(timestamp.to_string(), "01:02:03,004");
+    ///
+    /// assert!(SrtTimestamp::new(0, 0, 60, 0).is_err());
+    /// ```
+    pub fn new(
+        hours: u8,
+        minutes: u8,
+        seconds: u8,
+        milliseconds: u16,
+    ) -> Result<Self, TimestampFieldError> {
+        TimestampFieldError::validate(minutes, seconds, milliseconds)?;
+
+        Ok(Self {
+            hours,
+            minutes,
+            seconds,
+            milliseconds,
+        })
+    }
+
+    /// Converts the duration truncated to milliseconds, clamping at [`SrtTimestamp::MAX`].
+    pub fn saturating_from(duration: Duration) -> Self {
+        Self::try_from(duration).unwrap_or(Self::MAX)
+    }
+
+    /// Adds the duration, returning `None` if the result exceeds [`SrtTimestamp::MAX`].
+    ///
+    /// The result is truncated to milliseconds.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::srt::SrtTimestamp;
+    ///
+    /// let timestamp = SrtTimestamp {
+    ///     seconds: 1,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     timestamp
+    ///         .checked_add(Duration::from_millis(1500))
+    ///         .map(|timestamp| timestamp.to_string()),
+    ///     Some("00:00:02,500".to_string())
+    /// );
+    /// assert_eq!(SrtTimestamp::MAX.checked_add(Duration::from_millis(1)), None);
+    /// ```
+    pub fn checked_add(
+        self,
+        duration: Duration,
+    ) -> Option<Self> {
+        Duration::from(self)
+            .checked_add(duration)
+            .and_then(|duration| Self::try_from(duration).ok())
+    }
+
+    /// Subtracts the duration, returning `None` if the result is negative.
+    ///
+    /// The result is truncated to milliseconds.
+    pub fn checked_sub(
+        self,
+        duration: Duration,
+    ) -> Option<Self> {
+        Duration::from(self)
+            .checked_sub(duration)
+            .and_then(|duration| Self::try_from(duration).ok())
+    }
+
+    /// Adds the duration, clamping at [`SrtTimestamp::MAX`].
+    pub fn saturating_add(
+        self,
+        duration: Duration,
+    ) -> Self {
+        self.checked_add(duration)
+            .unwrap_or(Self::MAX)
+    }
+
+    /// Subtracts the duration, clamping at zero.
+    pub fn saturating_sub(
+        self,
+        duration: Duration,
+    ) -> Self {
+        self.checked_sub(duration)
+            .unwrap_or_default()
+    }
+
+    /// Returns the duration from the earlier timestamp, or `None` if it is later than this one.
+    pub fn checked_duration_since(
+        self,
+        earlier: Self,
+    ) -> Option<Duration> {
+        Duration::from(self).checked_sub(earlier.into())
+    }
+
+    /// Returns the duration from the earlier timestamp, or zero if it is later than this one.
+    pub fn saturating_duration_since(
+        self,
+        earlier: Self,
+    ) -> Duration {
+        Duration::from(self).saturating_sub(earlier.into())
+    }
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for SrtTimestamp {
     fn default() -> Self {
         Self {
@@ -407,37 +1419,157 @@ impl Display for SrtTimestamp {
     }
 }
 
-impl From<Duration> for SrtTimestamp {
-    fn from(duration: Duration) -> Self {
+impl FromStr for SrtTimestamp {
+    type Err = ParseError;
+
+    /// Parses the timestamp such as `00:01:02,345`.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::srt::SrtTimestamp;
+    ///
+    /// let timestamp: SrtTimestamp = "00:01:02,345".parse().unwrap();
+    /// assert_eq!(timestamp.minutes, 1);
+    ///
+    /// assert!("00:61:02,345".parse::<SrtTimestamp>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        str_parser::timestamp(s).map_err(ParseError::from)
+    }
+}
+
+impl TryFrom<Duration> for SrtTimestamp {
+    type Error = TimestampRangeError;
+
+    /// Converts the duration truncated to milliseconds,
+    /// failing if it exceeds [`SrtTimestamp::MAX`].
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
         let seconds = duration.as_secs();
         let milliseconds = duration.subsec_millis() as u16;
 
-        let hours = (seconds / 3600) as u8;
+        let hours = u8::try_from(seconds / 3600).map_err(|_| {
+            TimestampRangeError {
+                duration,
+            }
+        })?;
         let minutes = ((seconds % 3600) / 60) as u8;
         let seconds = (seconds % 60) as u8;
 
-        Self {
+        Ok(Self {
             hours,
             minutes,
             seconds,
             milliseconds,
-        }
+        })
+    }
+}
+
+impl Add<Duration> for SrtTimestamp {
+    type Output = Self;
+
+    /// Adds the duration.
+    ///
+    /// ## Panics
+    /// Panics if the result exceeds [`SrtTimestamp::MAX`], see [`SrtTimestamp::checked_add`].
+    fn add(
+        self,
+        duration: Duration,
+    ) -> Self::Output {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to timestamp")
+    }
+}
+
+impl AddAssign<Duration> for SrtTimestamp {
+    fn add_assign(
+        &mut self,
+        duration: Duration,
+    ) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for SrtTimestamp {
+    type Output = Self;
+
+    /// Subtracts the duration.
+    ///
+    /// ## Panics
+    /// Panics if the result is negative, see [`SrtTimestamp::checked_sub`].
+    fn sub(
+        self,
+        duration: Duration,
+    ) -> Self::Output {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from timestamp")
+    }
+}
+
+impl SubAssign<Duration> for SrtTimestamp {
+    fn sub_assign(
+        &mut self,
+        duration: Duration,
+    ) {
+        *self = *self - duration;
     }
 }
 
-impl Into<Duration> for SrtTimestamp {
-    fn into(self) -> Duration {
+impl Sub for SrtTimestamp {
+    type Output = Duration;
+
+    /// Returns the duration between the timestamps.
+    ///
+    /// ## Panics
+    /// Panics if `rhs` is later than `self`, see [`SrtTimestamp::checked_duration_since`].
+    fn sub(
+        self,
+        rhs: Self,
+    ) -> Self::Output {
+        self.checked_duration_since(rhs)
+            .expect("overflow when subtracting timestamps")
+    }
+}
+
+impl From<SrtTimestamp> for Duration {
+    fn from(timestamp: SrtTimestamp) -> Self {
         Duration::new(
-            self.hours as u64 * 3600
-                + self.minutes as u64 * 60
-                + self.seconds as u64,
-            self.milliseconds as u32 * 1_000_000,
+            timestamp.hours as u64 * 3600
+                + timestamp.minutes as u64 * 60
+                + timestamp.seconds as u64,
+            timestamp.milliseconds as u32 * 1_000_000,
         )
     }
 }
 
+/// Serializes the timestamp as a string such as `00:00:01,000`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SrtTimestamp {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes the timestamp from a string such as `00:00:01,000`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SrtTimestamp {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 /// Unofficial line position settings.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct LinePosition {
     /// X1 of the line position.
     pub x1: u32,
@@ -449,6 +1581,7 @@ pub struct LinePosition {
     pub y2: u32,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for LinePosition {
     fn default() -> Self {
         Self {
@@ -477,6 +1610,32 @@ impl Display for LinePosition {
 mod tests {
     use super::*;
 
+    /// The sequence, the timings, the text and the line position.
+    type Fields = (
+        u32,
+        SrtTimestamp,
+        SrtTimestamp,
+        Vec<String>,
+        Option<LinePosition>,
+    );
+
+    /// The fields of subtitles,
+    /// since the equality of [`SrtSubtitle`] compares only the sequence.
+    fn fields(subtitles: &[SrtSubtitle]) -> Vec<Fields> {
+        subtitles
+            .iter()
+            .map(|subtitle| {
+                (
+                    subtitle.sequence,
+                    subtitle.start,
+                    subtitle.end,
+                    subtitle.text.clone(),
+                    subtitle.line_position,
+                )
+            })
+            .collect()
+    }
+
     #[test]
     fn parse() {
         let srt_text = r#"
@@ -802,7 +1961,7 @@ This is a test.
     #[test]
     fn from_duration_to_timestamp() {
         let duration = Duration::new(1, 0);
-        let timestamp: SrtTimestamp = duration.into();
+        let timestamp: SrtTimestamp = duration.try_into().unwrap();
         assert_eq!(
             timestamp,
             SrtTimestamp {
@@ -814,7 +1973,7 @@ This is a test.
         );
 
         let duration = Duration::new(3661, 0);
-        let timestamp: SrtTimestamp = duration.into();
+        let timestamp: SrtTimestamp = duration.try_into().unwrap();
         assert_eq!(
             timestamp,
             SrtTimestamp {
@@ -826,7 +1985,7 @@ This is a test.
         );
 
         let duration = Duration::new(3661, 500 * 1_000_000);
-        let timestamp: SrtTimestamp = duration.into();
+        let timestamp: SrtTimestamp = duration.try_into().unwrap();
         assert_eq!(
             timestamp,
             SrtTimestamp {
@@ -836,6 +1995,18 @@ This is a test.
                 milliseconds: 500,
             }
         );
+
+        let duration = Duration::new(256 * 3600, 0);
+        assert_eq!(
+            SrtTimestamp::try_from(duration),
+            Err(TimestampRangeError {
+                duration
+            })
+        );
+        assert_eq!(
+            SrtTimestamp::saturating_from(duration),
+            SrtTimestamp::MAX
+        );
     }
 
     #[test]
@@ -880,7 +2051,7 @@ This is a test.
         let duration: Duration = end - start;
         assert_eq!(duration, Duration::new(4, 0));
 
-        let duration: SrtTimestamp = duration.into();
+        let duration: SrtTimestamp = duration.try_into().unwrap();
         assert_eq!(
             duration,
             SrtTimestamp {
@@ -895,7 +2066,7 @@ This is a test.
         let duration: Duration = end - start;
         assert_eq!(duration, Duration::new(5, 0));
 
-        let duration: SrtTimestamp = duration.into();
+        let duration: SrtTimestamp = duration.try_into().unwrap();
         assert_eq!(
             duration,
             SrtTimestamp {
@@ -907,6 +2078,91 @@ This is a test.
         );
     }
 
+    #[test]
+    fn parse_and_validate_timestamp() {
+        assert_eq!(
+            "01:02:03,004".parse::<SrtTimestamp>(),
+            Ok(SrtTimestamp::new(1, 2, 3, 4).unwrap())
+        );
+        assert!("1:02:03,004"
+            .parse::<SrtTimestamp>()
+            .is_err());
+        assert!("00:00:00,000 "
+            .parse::<SrtTimestamp>()
+            .is_err());
+
+        assert_eq!(
+            SrtTimestamp::new(0, 0, 0, 1000),
+            Err(TimestampFieldError {
+                field: "milliseconds",
+                value: 1000,
+                limit: 1000,
+            })
+        );
+        assert_eq!(
+            SrtTimestamp::new(0, 60, 0, 0)
+                .unwrap_err()
+                .to_string(),
+            "minutes must be less than 60, but got 60"
+        );
+    }
+
+    #[test]
+    fn timestamp_arithmetic() {
+        let timestamp = SrtTimestamp {
+            seconds: 1,
+            ..Default::default()
+        };
+
+        let mut later = timestamp + Duration::from_millis(1500);
+        assert_eq!(later.to_string(), "00:00:02,500");
+        later -= Duration::from_millis(500);
+        assert_eq!(later.to_string(), "00:00:02,000");
+        later += Duration::from_secs(3600);
+        assert_eq!(later.to_string(), "01:00:02,000");
+        assert_eq!(
+            later - timestamp,
+            Duration::from_secs(3601)
+        );
+
+        assert_eq!(
+            timestamp.checked_sub(Duration::from_secs(2)),
+            None
+        );
+        assert_eq!(
+            timestamp.saturating_sub(Duration::from_secs(2)),
+            SrtTimestamp::default()
+        );
+        assert_eq!(
+            SrtTimestamp::MAX.checked_add(Duration::from_millis(1)),
+            None
+        );
+        assert_eq!(
+            timestamp.saturating_add(Duration::from_secs(256 * 3600)),
+            SrtTimestamp::MAX
+        );
+        assert_eq!(
+            timestamp.checked_duration_since(later),
+            None
+        );
+        assert_eq!(
+            timestamp.saturating_duration_since(later),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when adding duration to timestamp")]
+    fn timestamp_add_overflow() {
+        let _ = SrtTimestamp::MAX + Duration::from_millis(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when subtracting timestamps")]
+    fn timestamp_sub_overflow() {
+        let _ = SrtTimestamp::default() - SrtTimestamp::MAX;
+    }
+
     #[test]
     fn order_timestamp() {
         let timestamp1 = SrtTimestamp {
@@ -923,4 +2179,303 @@ This is a test.
         };
         assert!(timestamp1 < timestamp2);
     }
+
+    #[test]
+    fn reader() {
+        let text = "\u{FEFF}1\r\n00:00:01,000 --> 00:00:02,000\r\nHello, world!\r\n\r\n\
+            2\n00:00:03,000 -> 00:00:04,000\nBroken\n\n\n\
+            3\n00:00:05,000 --> 00:00:06,000 X1:1 X2:2 Y1:3 Y2:4\nThis is a test.\nThank you.";
+
+        let mut reader = SrtReader::new(text.as_bytes());
+
+        let subtitle = reader
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(subtitle.sequence, 1);
+        assert_eq!(
+            subtitle.text,
+            vec!["Hello, world!".to_string()]
+        );
+
+        let err = match reader.next().unwrap() {
+            | Err(crate::ReadError::Parse(err)) => err,
+            | other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(err.line, 6);
+        assert_eq!(err.column, 14);
+        assert_eq!(err.offset, 69);
+        assert_eq!(err.rule.as_deref(), Some("subtitle"));
+        assert_eq!(err.block, Some(1));
+
+        let subtitle = reader
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(subtitle.sequence, 3);
+        assert_eq!(
+            subtitle.text,
+            vec![
+                "This is a test.".to_string(),
+                "Thank you.".to_string()
+            ]
+        );
+        assert!(subtitle
+            .line_position
+            .is_some());
+
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn reader_matches_parse() {
+        let text = r#"1
+00:00:01,000 --> 00:00:02,000
+Hello, world!
+
+2
+00:00:03,000 --> 00:00:04,000 X1:1 X2:2 Y1:3 Y2:4
+This is a test.
+Thank you.
+"#;
+
+        let subtitles = SrtReader::new(text.as_bytes())
+            .collect::<ReadResult<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            fields(&subtitles),
+            fields(
+                &SubRip::parse(text)
+                    .unwrap()
+                    .subtitles
+            )
+        );
+    }
+
+    #[test]
+    fn reader_whitespace_lines() {
+        for text in [
+            "1\n00:00:01,000 --> 00:00:02,000\n \nHello\n",
+            "1\n00:00:01,000 --> 00:00:02,000\n\
+                Hello\n\u{A0}\n\u{3000}\n",
+            " \n\n1\n00:00:01,000 --> 00:00:02,000\nHello\n \n\t\n",
+        ] {
+            let subtitles = SrtReader::new(text.as_bytes())
+                .collect::<ReadResult<Vec<_>>>()
+                .unwrap();
+
+            assert_eq!(
+                fields(&subtitles),
+                fields(
+                    &SubRip::parse(text)
+                        .unwrap()
+                        .subtitles
+                ),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn reader_cr_only() {
+        let text = "1\r00:00:01,000 --> 00:00:02,000\rHello,\rworld!\r\r\
+            2\r00:00:03,000 --> 00:00:04,000\rThis is a test.\r";
+
+        let subtitles = SrtReader::new(text.as_bytes())
+            .collect::<ReadResult<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(subtitles.len(), 2);
+        assert_eq!(
+            subtitles[0].text,
+            vec![
+                "Hello,".to_string(),
+                "world!".to_string()
+            ]
+        );
+        assert_eq!(
+            fields(&subtitles),
+            fields(
+                &SubRip::parse(text)
+                    .unwrap()
+                    .subtitles
+            )
+        );
+    }
+
+    #[test]
+    fn writer_matches_render() {
+        let text = r#"1
+00:00:01,000 --> 00:00:02,000
+Hello, world!
+
+2
+00:00:03,000 --> 00:00:04,000 X1:1 X2:2 Y1:3 Y2:4
+This is a test.
+Thank you.
+"#;
+        let srt = SubRip::parse(text).unwrap();
+
+        let mut writer = SrtWriter::new(vec![]);
+        for subtitle in &srt.subtitles {
+            writer
+                .write_subtitle(subtitle)
+                .unwrap();
+        }
+        writer.flush().unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            srt.render()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let subtitle = SrtSubtitle {
+            sequence: 1,
+            start: SrtTimestamp {
+                seconds: 1,
+                ..Default::default()
+            },
+            end: SrtTimestamp {
+                seconds: 2,
+                milliseconds: 500,
+                ..Default::default()
+            },
+            text: vec!["Hello, world!".to_string()],
+            line_position: Some(LinePosition {
+                x1: 1,
+                x2: 2,
+                y1: 3,
+                y2: 4,
+            }),
+        };
+
+        let json = serde_json::to_string(&subtitle).unwrap();
+        assert_eq!(
+            json,
+            r#"{"sequence":1,"start":"00:00:01,000","end":"00:00:02,500","text":["Hello, world!"],"line_position":{"x1":1,"x2":2,"y1":3,"y2":4}}"#
+        );
+        let deserialized =
+            serde_json::from_str::<SrtSubtitle>(&json).unwrap();
+        assert_eq!(
+            fields(&[deserialized.clone()]),
+            fields(&[subtitle])
+        );
+        assert_eq!(
+            serde_json::to_string(&deserialized).unwrap(),
+            json
+        );
+
+        assert!(
+            serde_json::from_str::<SrtTimestamp>(r#""00:00:01.000""#).is_err()
+        );
+    }
+
+    #[test]
+    fn timing() {
+        let text = r#"1
+00:00:01,000 --> 00:00:02,000
+Hello, world!
+
+2
+00:00:03,000 --> 00:00:04,000
+This is a test.
+"#;
+        let mut srt = SubRip::parse(text).unwrap();
+
+        srt.shift_saturating(TimeOffset::backward(
+            Duration::from_millis(1500),
+        ))
+        .unwrap();
+        assert_eq!(
+            srt.subtitles[0].start,
+            SrtTimestamp::default()
+        );
+        assert_eq!(
+            srt.subtitles[1].start,
+            SrtTimestamp {
+                seconds: 1,
+                milliseconds: 500,
+                ..Default::default()
+            }
+        );
+
+        srt.scale(2.0).unwrap();
+        assert_eq!(
+            srt.subtitles[1].end,
+            SrtTimestamp {
+                seconds: 5,
+                ..Default::default()
+            }
+        );
+
+        let before = srt.clone();
+        assert_eq!(
+            srt.shift(TimeOffset::forward(
+                Duration::from_secs(256 * 3600)
+            )),
+            Err(TimingError::Overflow {
+                cue: 0
+            })
+        );
+        assert_eq!(
+            srt.shift(TimeOffset::forward(Duration::MAX)),
+            Err(TimingError::Overflow {
+                cue: 0
+            })
+        );
+        assert_eq!(
+            srt.resync(0, Duration::ZERO, 2, Duration::ZERO),
+            Err(TimingError::CueNotFound {
+                cue: 2
+            })
+        );
+        assert_eq!(
+            fields(&srt.subtitles),
+            fields(&before.subtitles)
+        );
+    }
+
+    #[test]
+    fn parse_ref() {
+        let text = "1\n00:00:01,000 --> 00:00:02,000\n  Hello, world!  \nBye.\n\n2\n00:00:03,000 --> 00:00:04,000 X1:1 X2:2 Y1:3 Y2:4\nAgain.\n";
+        let srt = SubRipRef::parse(text).unwrap();
+
+        assert_eq!(srt.subtitles.len(), 2);
+        assert_eq!(
+            srt.subtitles[0].text,
+            vec![
+                "Hello, world!",
+                "Bye."
+            ]
+        );
+        // The lines are slices of the input.
+        let range = text.as_bytes().as_ptr_range();
+        assert!(range.contains(&srt.subtitles[0].text[0].as_ptr()));
+        assert_eq!(
+            srt.subtitles[1].line_position,
+            Some(LinePosition {
+                x1: 1,
+                x2: 2,
+                y1: 3,
+                y2: 4,
+            })
+        );
+
+        let owned = srt.to_sub_rip();
+        assert_eq!(owned, SubRip::parse(text).unwrap());
+        assert_eq!(
+            owned.subtitles[0].text,
+            vec![
+                "Hello, world!".to_string(),
+                "Bye.".to_string()
+            ]
+        );
+        assert!(SubRipRef::parse("1\n00:00:01,000\n").is_err());
+    }
 }
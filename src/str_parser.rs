@@ -1,13 +1,17 @@
 //! A parser implementation for the SubRip Subtitle format.
 
+pub(crate) use srt_parser::line_position;
 pub(crate) use srt_parser::srt;
+pub(crate) use srt_parser::subtitle;
+pub(crate) use srt_parser::timestamp;
 
 peg::parser! {
     /// The parser for SubRip Subtitle format.
     grammar srt_parser() for str {
         use crate::srt::SrtTimestamp;
-        use crate::srt::SubRip;
+        use crate::srt::SubRipRef;
         use crate::srt::SrtSubtitle;
+        use crate::srt::SrtSubtitleRef;
         use crate::srt::LinePosition;
 
         /// Whitespace.
@@ -31,6 +35,12 @@ peg::parser! {
                 n.parse().or(Err("two-digit number"))
             }
 
+        /// Two-digit number of minutes or seconds less than 60.
+        rule sexagesimal_number() -> u8
+            = n:two_number() {?
+                if n < 60 { Ok(n) } else { Err("two-digit number less than 60") }
+            }
+
         /// Three-digit number.
         rule three_number() -> u16
             = n:$(['0'..='9']['0'..='9']['0'..='9']) {?
@@ -38,18 +48,18 @@ peg::parser! {
             }
 
         /// Multiple lines block of text.
-        rule multiline() -> Vec<String>
+        rule multiline() -> Vec<&'input str>
             = !(whitespace() / newline()) lines:$(!(whitespace()+ newline()) (!newline() [_])+ newline()) ++ ()
             {
                 lines
                     .iter()
-                    .map(|l| l.to_string().trim().to_string())
+                    .map(|l| l.trim())
                     .collect()
             }
 
         /// Timestamp.
         pub(crate) rule timestamp() -> SrtTimestamp
-            = hours:two_number() ":" minutes:two_number() ":" seconds:two_number() "," milliseconds:three_number()
+            = hours:two_number() ":" minutes:sexagesimal_number() ":" seconds:sexagesimal_number() "," milliseconds:three_number()
             {
                 SrtTimestamp {
                     hours,
@@ -75,14 +85,18 @@ peg::parser! {
 
         /// Single subtitle entry.
         pub(crate) rule subtitle() -> SrtSubtitle
+            = subtitle:subtitle_ref() { subtitle.to_srt_subtitle() }
+
+        /// Single subtitle entry borrowing the text.
+        rule subtitle_ref() -> SrtSubtitleRef<'input>
             = subtitle_with_line_position() / subtitle_without_line_position()
 
-        rule subtitle_without_line_position() -> SrtSubtitle
+        rule subtitle_without_line_position() -> SrtSubtitleRef<'input>
             = sequence:number() separator()
                 start:timestamp() separator()* "-->" separator()* end:timestamp() separator()
                 text:multiline()
             {
-                SrtSubtitle {
+                SrtSubtitleRef {
                     sequence,
                     start,
                     end,
@@ -91,12 +105,12 @@ peg::parser! {
                 }
             }
 
-        rule subtitle_with_line_position() -> SrtSubtitle
+        rule subtitle_with_line_position() -> SrtSubtitleRef<'input>
             = sequence:number() separator()
                 start:timestamp() separator()* "-->" separator()* end:timestamp() separator()+ line_position:line_position() separator()
                 text:multiline()
             {
-                SrtSubtitle {
+                SrtSubtitleRef {
                     sequence,
                     start,
                     end,
@@ -106,12 +120,12 @@ peg::parser! {
             }
 
         /// The entire SRT.
-        pub(crate) rule srt() -> SubRip
+        pub(crate) rule srt() -> SubRipRef<'input>
             = (whitespace() / newline())*
-                subtitles:subtitle() ** (newline()+)
+                subtitles:subtitle_ref() ** (newline()+)
                 (whitespace() / newline())*
             {
-                SubRip { subtitles, }
+                SubRipRef { subtitles, }
             }
     }
 }
@@ -181,6 +195,9 @@ mod test {
         assert!(srt_parser::timestamp("00:00,000").is_err());
         // Invalid separators. (like WebVTT)
         assert!(srt_parser::timestamp("00:00:00.000").is_err());
+        // Out of range.
+        assert!(srt_parser::timestamp("00:61:00,000").is_err());
+        assert!(srt_parser::timestamp("00:00:60,000").is_err());
     }
 
     #[test]
@@ -337,7 +354,12 @@ mod test {
 00:00:00,000 --> 00:00:01,000
 Hello, world!
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = r#"1
 00:00:00,000 --> 00:00:01,000
@@ -349,14 +371,24 @@ Hello, world!"#;
 00:00:00,000 --> 00:00:01,000
 Hello, world!
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = r#"1
 00:00:00,000 --> 00:00:01,000
 Hello, world!
 
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = r#"
 
@@ -366,10 +398,20 @@ Hello, world!
 
 
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = "1 \n00:00:00,000 --> 00:00:01,000 \nHello, world!   \n   ";
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let srt = SubRip {
             subtitles: vec![
@@ -418,7 +460,12 @@ Hello, world!
 00:00:01,000 --> 00:00:02,000
 This is a test.
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = r#"
 1
@@ -429,7 +476,12 @@ Hello, world!
 00:00:01,000 --> 00:00:02,000
 This is a test.
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = r#"1
 00:00:00,000 --> 00:00:01,000
@@ -440,7 +492,12 @@ Hello, world!
 This is a test.
 
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = r#"
 1
@@ -452,7 +509,12 @@ Hello, world!
 This is a test.
 
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
 
         let text = r#"1
 00:00:00,000 --> 00:00:01,000
@@ -465,6 +527,11 @@ This is a test.
 
 
 "#;
-        assert_eq!(srt_parser::srt(text).unwrap(), srt);
+        assert_eq!(
+            srt_parser::srt(text)
+                .unwrap()
+                .to_sub_rip(),
+            srt
+        );
     }
 }
@@ -0,0 +1,1545 @@
+//! A format-agnostic subtitle document model provided by [`subtp::subtitles::Subtitles`](Subtitles).
+//!
+//! Converts from and to [`SubRip`](crate::srt::SubRip) and [`WebVtt`](crate::vtt::WebVtt)
+//! by `From`/`TryFrom`, or by the methods that also report what was lost in the conversion.
+//!
+//! ## Example
+//! ```
+//! use subtp::srt::SubRip;
+//! use subtp::subtitles::Subtitles;
+//! use subtp::subtitles::TextNode;
+//! use subtp::vtt::WebVtt;
+//!
+//! let text = r#"1
+//! 00:00:01,000 --> 00:00:02,000
+//! Hello, <i>world</i>!
+//! "#;
+//!
+//! let srt = SubRip::parse(text).unwrap();
+//! let subtitles = Subtitles::from(&srt);
+//!
+//! assert_eq!(
+//!     subtitles.cues[0].text,
+//!     vec![
+//!         TextNode::Text("Hello, ".to_string()),
+//!         TextNode::Italic(vec![TextNode::Text("world".to_string())]),
+//!         TextNode::Text("!".to_string()),
+//!     ]
+//! );
+//!
+//! let vtt = WebVtt::from(&subtitles);
+//!
+//! assert_eq!(
+//!     vtt.render(),
+//!     "WEBVTT\n\n1\n00:00:01.000 --> 00:00:02.000\nHello, <i>world</i>!\n"
+//! );
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::srt::{LinePosition, SrtSubtitle, SrtTimestamp, SubRip};
+use crate::vtt::cue_text::{CueAnnotatedSpan, CueNode, CueSpan, CueText};
+use crate::vtt::{
+    Alignment, CueSettings, Line, LineAlignment, Percentage, Position,
+    PositionAlignment, VttBlock, VttComment, VttCue, VttDescription, VttHeader,
+    VttRegion, VttStyle, VttTimestamp, VttTimings, WebVtt,
+};
+use crate::{ParseError, ParseResult};
+
+/// The format-agnostic subtitle document.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use subtp::srt::SubRip;
+/// use subtp::subtitles::Subtitles;
+/// use subtp::subtitles::SubtitleCue;
+/// use subtp::subtitles::TextNode;
+///
+/// let subtitles = Subtitles {
+///     cues: vec![SubtitleCue {
+///         start: Duration::from_secs(1),
+///         end: Duration::from_secs(2),
+///         text: vec![TextNode::Bold(vec![TextNode::Text(
+///             "Hello, world!".to_string(),
+///         )])],
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     SubRip::from(&subtitles).render(),
+///     "1\n00:00:01,000 --> 00:00:02,000\n<b>Hello, world!</b>\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Subtitles {
+    /// The metadata of the document.
+    pub metadata: SubtitleMetadata,
+    /// The cues.
+    pub cues: Vec<SubtitleCue>,
+}
+
+impl Subtitles {
+    /// Converts from the SubRip Subtitle format,
+    /// reporting the markups that are not supported by this model such as `<font>`.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::srt::SubRip;
+    /// use subtp::subtitles::LossKind;
+    /// use subtp::subtitles::Subtitles;
+    ///
+    /// let srt = SubRip::parse(
+    ///     "1\n00:00:01,000 --> 00:00:02,000\n<font color=\"red\">Hello</font>\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let conversion = Subtitles::from_sub_rip(&srt);
+    ///
+    /// assert_eq!(conversion.value.cues[0].plain_text(), "Hello");
+    /// assert_eq!(conversion.losses[0].kind, LossKind::Markup);
+    /// ```
+    pub fn from_sub_rip(srt: &SubRip) -> Conversion<Self> {
+        let mut losses = vec![];
+        let cues = srt
+            .subtitles
+            .iter()
+            .enumerate()
+            .map(|(index, subtitle)| {
+                let (text, lost) = parse_srt_text(&subtitle.text.join("\n"));
+                if lost {
+                    losses.push(ConversionLoss {
+                        cue: Some(index),
+                        kind: LossKind::Markup,
+                    });
+                }
+
+                SubtitleCue {
+                    id: Some(subtitle.sequence.to_string()),
+                    start: subtitle.start.into(),
+                    end: subtitle.end.into(),
+                    text,
+                    settings: None,
+                    line_position: subtitle.line_position,
+                }
+            })
+            .collect();
+
+        Conversion {
+            value: Self {
+                metadata: SubtitleMetadata::default(),
+                cues,
+            },
+            losses,
+        }
+    }
+
+    /// Converts from the WebVTT format by parsing the cue text markup,
+    /// reporting the markups that are not supported by this model such as ruby and timestamps.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::subtitles::Subtitles;
+    /// use subtp::subtitles::TextNode;
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let vtt = WebVtt::parse(
+    ///     "WEBVTT\n\n00:00:01.000 --> 00:00:02.000 line:90%\n<v Roger>Hello</v>\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let conversion = Subtitles::from_web_vtt(&vtt).unwrap();
+    /// let cue = &conversion.value.cues[0];
+    ///
+    /// assert!(cue.settings.is_some());
+    /// assert_eq!(
+    ///     cue.text,
+    ///     vec![TextNode::Voice {
+    ///         name: "Roger".to_string(),
+    ///         children: vec![TextNode::Text("Hello".to_string())],
+    ///     }]
+    /// );
+    /// assert!(conversion.losses.is_empty());
+    /// ```
+    pub fn from_web_vtt(vtt: &WebVtt) -> ParseResult<Conversion<Self>> {
+        let mut metadata = SubtitleMetadata {
+            description: vtt
+                .header
+                .description
+                .as_ref()
+                .map(|description| {
+                    match description {
+                        | VttDescription::Side(description)
+                        | VttDescription::Below(description) => {
+                            description
+                                .trim_end()
+                                .to_string()
+                        },
+                    }
+                }),
+            ..Default::default()
+        };
+        let mut cues = vec![];
+        let mut losses = vec![];
+
+        for block in &vtt.blocks {
+            match block {
+                | VttBlock::Que(cue) => {
+                    let mut lost = false;
+                    let text =
+                        from_cue_nodes(&cue.cue_text()?.nodes, &mut lost);
+                    if lost {
+                        losses.push(ConversionLoss {
+                            cue: Some(cues.len()),
+                            kind: LossKind::Markup,
+                        });
+                    }
+
+                    cues.push(SubtitleCue {
+                        id: cue.identifier.clone(),
+                        start: cue.timings.start.into(),
+                        end: cue.timings.end.into(),
+                        text,
+                        settings: cue.settings.clone(),
+                        line_position: None,
+                    });
+                },
+                | VttBlock::Comment(comment) => {
+                    metadata
+                        .comments
+                        .push(match comment {
+                            | VttComment::Side(comment) => comment.clone(),
+                            | VttComment::Below(comment) => comment.clone(),
+                        });
+                },
+                | VttBlock::Style(style) => {
+                    metadata.styles.push(
+                        style
+                            .style
+                            .trim_end()
+                            .to_string(),
+                    );
+                },
+                | VttBlock::Region(region) => {
+                    metadata
+                        .regions
+                        .push(region.clone());
+                },
+            }
+        }
+
+        Ok(Conversion {
+            value: Self {
+                metadata,
+                cues,
+            },
+            losses,
+        })
+    }
+
+    /// Converts into the SubRip Subtitle format,
+    /// reporting the metadata, identifiers, settings, markups and times that can not be represented.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::subtitles::LossKind;
+    /// use subtp::subtitles::Subtitles;
+    /// use subtp::subtitles::SubtitleCue;
+    /// use subtp::subtitles::TextNode;
+    ///
+    /// let subtitles = Subtitles {
+    ///     cues: vec![SubtitleCue {
+    ///         id: Some("intro".to_string()),
+    ///         end: Duration::from_secs(1),
+    ///         text: vec![TextNode::Text("Hello".to_string())],
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let conversion = subtitles.to_sub_rip();
+    ///
+    /// assert_eq!(conversion.value.subtitles[0].sequence, 1);
+    /// assert_eq!(conversion.losses[0].kind, LossKind::Identifier);
+    /// ```
+    pub fn to_sub_rip(&self) -> Conversion<SubRip> {
+        let mut losses = self.metadata.losses(true);
+        let mut sequences = HashSet::new();
+        let subtitles = self
+            .cues
+            .iter()
+            .enumerate()
+            .map(|(index, cue)| {
+                let mut loss = |kind| {
+                    losses.push(ConversionLoss {
+                        cue: Some(index),
+                        kind,
+                    })
+                };
+
+                // The sequence numbers must be positive and unique.
+                let sequence = match cue
+                    .id
+                    .as_ref()
+                    .map(|id| id.parse::<u32>())
+                {
+                    | Some(Ok(id)) if id > 0 && !sequences.contains(&id) => id,
+                    | id => {
+                        if id.is_some() {
+                            loss(LossKind::Identifier);
+                        }
+                        let fallback = index as u32 + 1;
+                        if sequences.contains(&fallback) {
+                            sequences
+                                .iter()
+                                .max()
+                                .map_or(fallback, |max| max + 1)
+                        } else {
+                            fallback
+                        }
+                    },
+                };
+                sequences.insert(sequence);
+                if cue.settings.is_some() {
+                    loss(LossKind::Settings);
+                }
+                let mut lost = false;
+                let text = render_srt_text(&cue.text, &mut lost);
+                if lost {
+                    loss(LossKind::Markup);
+                }
+                let (start, end) = match (
+                    SrtTimestamp::try_from(cue.start),
+                    SrtTimestamp::try_from(cue.end),
+                ) {
+                    | (Ok(start), Ok(end)) => (start, end),
+                    | _ => {
+                        loss(LossKind::Timing);
+                        (
+                            SrtTimestamp::saturating_from(cue.start),
+                            SrtTimestamp::saturating_from(cue.end),
+                        )
+                    },
+                };
+
+                SrtSubtitle {
+                    sequence,
+                    start,
+                    end,
+                    // An empty line would end the subtitle.
+                    text: if text.is_empty() {
+                        vec![]
+                    } else {
+                        text.split('\n')
+                            .map(|line| line.to_string())
+                            .collect()
+                    },
+                    line_position: cue.line_position,
+                }
+            })
+            .collect();
+
+        Conversion {
+            value: SubRip {
+                subtitles,
+            },
+            losses,
+        }
+    }
+
+    /// Converts into the WebVTT format,
+    /// mapping the line positions onto the cue settings if the resolution is known.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::srt::LinePosition;
+    /// use subtp::subtitles::SubtitleCue;
+    /// use subtp::subtitles::SubtitleMetadata;
+    /// use subtp::subtitles::Subtitles;
+    /// use subtp::subtitles::TextNode;
+    ///
+    /// let subtitles = Subtitles {
+    ///     metadata: SubtitleMetadata {
+    ///         resolution: Some((640, 480)),
+    ///         ..Default::default()
+    ///     },
+    ///     cues: vec![SubtitleCue {
+    ///         end: Duration::from_secs(1),
+    ///         text: vec![TextNode::Text("Hello".to_string())],
+    ///         line_position: Some(LinePosition {
+    ///             x1: 64,
+    ///             x2: 576,
+    ///             y1: 432,
+    ///             y2: 480,
+    ///         }),
+    ///         ..Default::default()
+    ///     }],
+    /// };
+    ///
+    /// let conversion = subtitles.to_web_vtt();
+    ///
+    /// assert_eq!(
+    ///     conversion.value.render(),
+    ///     "WEBVTT\n\n00:00:00.000 --> 00:00:01.000 line:90%,start position:10%,line-left size:80% align:left\nHello\n"
+    /// );
+    /// assert!(conversion.losses.is_empty());
+    /// ```
+    pub fn to_web_vtt(&self) -> Conversion<WebVtt> {
+        let mut losses = self.metadata.losses(false);
+
+        let mut blocks: Vec<VttBlock> = vec![];
+        blocks.extend(
+            self.metadata
+                .regions
+                .iter()
+                .cloned()
+                .map(VttBlock::from),
+        );
+        blocks.extend(
+            self.metadata
+                .styles
+                .iter()
+                .map(|style| {
+                    VttBlock::from(VttStyle {
+                        style: style.clone(),
+                    })
+                }),
+        );
+        blocks.extend(
+            self.metadata
+                .comments
+                .iter()
+                .map(|comment| VttBlock::from(vtt_comment(comment))),
+        );
+
+        for (index, cue) in self.cues.iter().enumerate() {
+            let settings = match (&cue.settings, cue.line_position) {
+                | (Some(settings), line_position) => {
+                    if line_position.is_some() {
+                        losses.push(ConversionLoss {
+                            cue: Some(index),
+                            kind: LossKind::LinePosition,
+                        });
+                    }
+                    Some(settings.clone())
+                },
+                | (None, Some(line_position)) => {
+                    match self.metadata.resolution {
+                        | Some(resolution) => {
+                            Some(line_position_to_settings(
+                                line_position,
+                                resolution,
+                            ))
+                        },
+                        | None => {
+                            losses.push(ConversionLoss {
+                                cue: Some(index),
+                                kind: LossKind::LinePosition,
+                            });
+                            None
+                        },
+                    }
+                },
+                | (None, None) => None,
+            };
+
+            let payload = CueText {
+                nodes: to_cue_nodes(&cue.text),
+            }
+            .render()
+            .split('\n')
+            .map(|line| line.to_string())
+            .collect();
+
+            let timings = match (
+                VttTimestamp::try_from(cue.start),
+                VttTimestamp::try_from(cue.end),
+            ) {
+                | (Ok(start), Ok(end)) => {
+                    VttTimings {
+                        start,
+                        end,
+                    }
+                },
+                | _ => {
+                    losses.push(ConversionLoss {
+                        cue: Some(index),
+                        kind: LossKind::Timing,
+                    });
+                    VttTimings {
+                        start: VttTimestamp::saturating_from(cue.start),
+                        end: VttTimestamp::saturating_from(cue.end),
+                    }
+                },
+            };
+
+            blocks.push(
+                VttCue {
+                    identifier: cue.id.clone(),
+                    timings,
+                    settings,
+                    payload,
+                }
+                .into(),
+            );
+        }
+
+        Conversion {
+            value: WebVtt {
+                header: VttHeader {
+                    description: self
+                        .metadata
+                        .description
+                        .as_ref()
+                        .map(|description| vtt_description(description)),
+                },
+                blocks,
+            },
+            losses,
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Subtitles {
+    fn default() -> Self {
+        Self {
+            metadata: SubtitleMetadata::default(),
+            cues: vec![],
+        }
+    }
+}
+
+impl From<&SubRip> for Subtitles {
+    fn from(srt: &SubRip) -> Self {
+        Self::from_sub_rip(srt).value
+    }
+}
+
+impl TryFrom<&WebVtt> for Subtitles {
+    type Error = ParseError;
+
+    fn try_from(vtt: &WebVtt) -> Result<Self, Self::Error> {
+        Self::from_web_vtt(vtt).map(|conversion| conversion.value)
+    }
+}
+
+impl From<&Subtitles> for SubRip {
+    fn from(subtitles: &Subtitles) -> Self {
+        subtitles.to_sub_rip().value
+    }
+}
+
+impl From<&Subtitles> for WebVtt {
+    fn from(subtitles: &Subtitles) -> Self {
+        subtitles.to_web_vtt().value
+    }
+}
+
+/// The metadata of the subtitle document.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SubtitleMetadata {
+    /// The description of the document such as the WebVTT header.
+    pub description: Option<String>,
+    /// The width and height of the video in pixels to map the absolute positions.
+    pub resolution: Option<(u32, u32)>,
+    /// The style sheets.
+    pub styles: Vec<String>,
+    /// The regions.
+    pub regions: Vec<VttRegion>,
+    /// The comments.
+    pub comments: Vec<String>,
+}
+
+impl SubtitleMetadata {
+    /// Reports the metadata that can not be represented in the target format.
+    fn losses(
+        &self,
+        sub_rip: bool,
+    ) -> Vec<ConversionLoss> {
+        if !sub_rip {
+            return vec![];
+        }
+
+        let mut losses = vec![];
+        if self.description.is_some() {
+            losses.push(ConversionLoss {
+                cue: None,
+                kind: LossKind::Description,
+            });
+        }
+        for (kind, count) in [
+            (LossKind::Style, self.styles.len()),
+            (LossKind::Region, self.regions.len()),
+            (LossKind::Comment, self.comments.len()),
+        ] {
+            for _ in 0..count {
+                losses.push(ConversionLoss {
+                    cue: None,
+                    kind,
+                });
+            }
+        }
+
+        losses
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for SubtitleMetadata {
+    fn default() -> Self {
+        Self {
+            description: None,
+            resolution: None,
+            styles: vec![],
+            regions: vec![],
+            comments: vec![],
+        }
+    }
+}
+
+/// The format-agnostic cue.
+///
+/// ## Example
+/// ```
+/// use subtp::subtitles::SubtitleCue;
+/// use subtp::subtitles::TextNode;
+///
+/// let cue = SubtitleCue {
+///     text: vec![
+///         TextNode::Text("Hello,".to_string()),
+///         TextNode::LineBreak,
+///         TextNode::Italic(vec![TextNode::Text("world!".to_string())]),
+///     ],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(cue.plain_text(), "Hello,\nworld!");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SubtitleCue {
+    /// The identifier, which is the sequence number in the SubRip Subtitle.
+    pub id: Option<String>,
+    /// The start time.
+    pub start: Duration,
+    /// The end time.
+    pub end: Duration,
+    /// The styled text.
+    pub text: Vec<TextNode>,
+    /// The cue settings of WebVTT.
+    pub settings: Option<CueSettings>,
+    /// The absolute line position in pixels of SubRip Subtitle.
+    pub line_position: Option<LinePosition>,
+}
+
+impl SubtitleCue {
+    /// Returns the text without styles, separating lines by `\n`.
+    pub fn plain_text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.text {
+            node.write_plain_text(&mut text);
+        }
+
+        text
+    }
+}
+
+impl Default for SubtitleCue {
+    fn default() -> Self {
+        Self {
+            id: None,
+            start: Duration::ZERO,
+            end: Duration::ZERO,
+            text: vec![],
+            settings: None,
+            line_position: None,
+        }
+    }
+}
+
+/// A node of the styled text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TextNode {
+    /// The plain text without line breaks.
+    Text(String),
+    /// The line break.
+    LineBreak,
+    /// The italic span.
+    Italic(Vec<TextNode>),
+    /// The bold span.
+    Bold(Vec<TextNode>),
+    /// The underline span.
+    Underline(Vec<TextNode>),
+    /// The span of a speaker.
+    Voice {
+        /// The name of the speaker.
+        name: String,
+        /// The children.
+        children: Vec<TextNode>,
+    },
+    /// The span with style classes.
+    Class {
+        /// The class names.
+        classes: Vec<String>,
+        /// The children.
+        children: Vec<TextNode>,
+    },
+    /// The span in a language.
+    Language {
+        /// The language tag.
+        language: String,
+        /// The children.
+        children: Vec<TextNode>,
+    },
+}
+
+impl TextNode {
+    fn write_plain_text(
+        &self,
+        text: &mut String,
+    ) {
+        match self {
+            | Self::Text(t) => text.push_str(t),
+            | Self::LineBreak => text.push('\n'),
+            | Self::Italic(children)
+            | Self::Bold(children)
+            | Self::Underline(children)
+            | Self::Voice {
+                children,
+                ..
+            }
+            | Self::Class {
+                children,
+                ..
+            }
+            | Self::Language {
+                children,
+                ..
+            } => {
+                for child in children {
+                    child.write_plain_text(text);
+                }
+            },
+        }
+    }
+}
+
+impl Default for TextNode {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+/// The result of a conversion with the losses.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Conversion<T> {
+    /// The converted value.
+    pub value: T,
+    /// What was lost in the conversion.
+    pub losses: Vec<ConversionLoss>,
+}
+
+/// A loss in a conversion.
+///
+/// ## Example
+/// ```
+/// use subtp::subtitles::ConversionLoss;
+/// use subtp::subtitles::LossKind;
+///
+/// let loss = ConversionLoss {
+///     cue: Some(2),
+///     kind: LossKind::Settings,
+/// };
+///
+/// assert_eq!(
+///     loss.to_string(),
+///     "cue 2: cue settings are dropped".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct ConversionLoss {
+    /// The index of the cue, or `None` for the document.
+    pub cue: Option<usize>,
+    /// The kind of the loss.
+    pub kind: LossKind,
+}
+
+impl Display for ConversionLoss {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self.cue {
+            | Some(cue) => write!(f, "cue {}: {}", cue, self.kind),
+            | None => write!(f, "document: {}", self.kind),
+        }
+    }
+}
+
+/// The kind of a loss in a conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum LossKind {
+    /// The description of the document is dropped.
+    Description,
+    /// A style sheet is dropped.
+    Style,
+    /// A region is dropped.
+    Region,
+    /// A comment is dropped.
+    Comment,
+    /// A non-numeric, zero or duplicate identifier is replaced by the sequence number.
+    Identifier,
+    /// The cue settings are dropped.
+    Settings,
+    /// The line position is dropped.
+    LinePosition,
+    /// Unsupported markups are dropped while keeping their text.
+    Markup,
+    /// A time beyond the maximum timestamp of the format is clamped.
+    Timing,
+}
+
+impl Display for LossKind {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Description => write!(f, "description is dropped"),
+            | Self::Style => write!(f, "style sheet is dropped"),
+            | Self::Region => write!(f, "region is dropped"),
+            | Self::Comment => write!(f, "comment is dropped"),
+            | Self::Identifier => {
+                write!(
+                    f,
+                    "identifier is replaced by the sequence number"
+                )
+            },
+            | Self::Settings => write!(f, "cue settings are dropped"),
+            | Self::LinePosition => write!(f, "line position is dropped"),
+            | Self::Markup => write!(f, "unsupported markups are dropped"),
+            | Self::Timing => {
+                write!(
+                    f,
+                    "time is clamped to the maximum timestamp"
+                )
+            },
+        }
+    }
+}
+
+/// Splits the text by `\n` into the text nodes and the line breaks.
+fn push_text(
+    nodes: &mut Vec<TextNode>,
+    text: &str,
+) {
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            nodes.push(TextNode::LineBreak);
+        }
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(TextNode::Text(last)) = nodes.last_mut() {
+            last.push_str(line);
+        } else {
+            nodes.push(TextNode::Text(line.to_string()));
+        }
+    }
+}
+
+/// Parses the SubRip Subtitle text with `<i>`, `<b>` and `<u>` tags,
+/// returning whether other tags such as `<font>` are dropped.
+fn parse_srt_text(text: &str) -> (Vec<TextNode>, bool) {
+    // The stack of the open tags with their children.
+    let mut stack: Vec<(char, Vec<TextNode>)> = vec![(' ', vec![])];
+    let mut lost = false;
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        let (before, after) = rest.split_at(open);
+        push_text(&mut stack.last_mut().unwrap().1, before);
+
+        let Some(close) = after.find('>') else {
+            rest = after;
+            break;
+        };
+        let tag = after[1..close]
+            .trim()
+            .to_ascii_lowercase();
+        rest = &after[close + 1..];
+
+        match tag.as_str() {
+            | "i" | "b" | "u" => {
+                stack.push((tag.chars().next().unwrap(), vec![]));
+            },
+            | "/i" | "/b" | "/u" => {
+                let name = tag.chars().nth(1).unwrap();
+                if stack.len() > 1 && stack.last().unwrap().0 == name {
+                    let (name, children) = stack.pop().unwrap();
+                    stack
+                        .last_mut()
+                        .unwrap()
+                        .1
+                        .push(styled(name, children));
+                } else {
+                    lost = true;
+                }
+            },
+            | _ if tag.starts_with("font") || tag == "/font" => lost = true,
+            | _ => {
+                push_text(
+                    &mut stack.last_mut().unwrap().1,
+                    &after[..close + 1],
+                );
+            },
+        }
+    }
+    push_text(&mut stack.last_mut().unwrap().1, rest);
+
+    while stack.len() > 1 {
+        let (name, children) = stack.pop().unwrap();
+        stack
+            .last_mut()
+            .unwrap()
+            .1
+            .push(styled(name, children));
+    }
+
+    (stack.pop().unwrap().1, lost)
+}
+
+fn styled(
+    name: char,
+    children: Vec<TextNode>,
+) -> TextNode {
+    match name {
+        | 'i' => TextNode::Italic(children),
+        | 'b' => TextNode::Bold(children),
+        | _ => TextNode::Underline(children),
+    }
+}
+
+/// Renders the text nodes with `<i>`, `<b>` and `<u>` tags,
+/// marking the loss if other spans are flattened.
+fn render_srt_text(
+    nodes: &[TextNode],
+    lost: &mut bool,
+) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            | TextNode::Text(t) => text.push_str(t),
+            | TextNode::LineBreak => text.push('\n'),
+            | TextNode::Italic(children) => {
+                text.push_str(&format!(
+                    "<i>{}</i>",
+                    render_srt_text(children, lost)
+                ));
+            },
+            | TextNode::Bold(children) => {
+                text.push_str(&format!(
+                    "<b>{}</b>",
+                    render_srt_text(children, lost)
+                ));
+            },
+            | TextNode::Underline(children) => {
+                text.push_str(&format!(
+                    "<u>{}</u>",
+                    render_srt_text(children, lost)
+                ));
+            },
+            | TextNode::Voice {
+                children,
+                ..
+            }
+            | TextNode::Class {
+                children,
+                ..
+            }
+            | TextNode::Language {
+                children,
+                ..
+            } => {
+                *lost = true;
+                text.push_str(&render_srt_text(children, lost));
+            },
+        }
+    }
+
+    text
+}
+
+/// Converts the WebVTT cue text nodes,
+/// marking the loss if ruby annotations or timestamps are dropped.
+fn from_cue_nodes(
+    nodes: &[CueNode],
+    lost: &mut bool,
+) -> Vec<TextNode> {
+    let mut converted = vec![];
+    for node in nodes {
+        match node {
+            | CueNode::Text(text) => push_text(&mut converted, text),
+            | CueNode::Timestamp(_) | CueNode::RubyText(_) => *lost = true,
+            | CueNode::Ruby(span) => {
+                *lost = true;
+                for node in from_cue_nodes(&span.children, lost) {
+                    match node {
+                        | TextNode::Text(text) => {
+                            push_text(&mut converted, &text)
+                        },
+                        | node => converted.push(node),
+                    }
+                }
+            },
+            | CueNode::Class(span) => {
+                converted.push(TextNode::Class {
+                    classes: span.classes.clone(),
+                    children: from_cue_nodes(&span.children, lost),
+                });
+            },
+            | CueNode::Italic(span) => {
+                converted.push(TextNode::Italic(with_classes(
+                    span, lost,
+                )));
+            },
+            | CueNode::Bold(span) => {
+                converted.push(TextNode::Bold(with_classes(span, lost)));
+            },
+            | CueNode::Underline(span) => {
+                converted.push(TextNode::Underline(with_classes(
+                    span, lost,
+                )));
+            },
+            | CueNode::Voice(span) => {
+                converted.push(TextNode::Voice {
+                    name: span.annotation.clone(),
+                    children: with_classes(
+                        &CueSpan {
+                            classes: span.classes.clone(),
+                            children: span.children.clone(),
+                        },
+                        lost,
+                    ),
+                });
+            },
+            | CueNode::Language(span) => {
+                converted.push(TextNode::Language {
+                    language: span.annotation.clone(),
+                    children: with_classes(
+                        &CueSpan {
+                            classes: span.classes.clone(),
+                            children: span.children.clone(),
+                        },
+                        lost,
+                    ),
+                });
+            },
+        }
+    }
+
+    converted
+}
+
+/// Converts the children of the span, wrapping them by a class span if the span has classes.
+fn with_classes(
+    span: &CueSpan,
+    lost: &mut bool,
+) -> Vec<TextNode> {
+    let children = from_cue_nodes(&span.children, lost);
+    if span.classes.is_empty() {
+        children
+    } else {
+        vec![TextNode::Class {
+            classes: span.classes.clone(),
+            children,
+        }]
+    }
+}
+
+/// Converts the text nodes into the WebVTT cue text nodes.
+fn to_cue_nodes(nodes: &[TextNode]) -> Vec<CueNode> {
+    let mut converted = vec![];
+    for node in nodes {
+        let node = match node {
+            | TextNode::Text(text) => CueNode::Text(text.clone()),
+            | TextNode::LineBreak => CueNode::Text("\n".to_string()),
+            | TextNode::Italic(children) => CueNode::Italic(span(children)),
+            | TextNode::Bold(children) => CueNode::Bold(span(children)),
+            | TextNode::Underline(children) => {
+                CueNode::Underline(span(children))
+            },
+            | TextNode::Class {
+                classes,
+                children,
+            } => {
+                CueNode::Class(CueSpan {
+                    classes: classes.clone(),
+                    children: to_cue_nodes(children),
+                })
+            },
+            | TextNode::Voice {
+                name,
+                children,
+            } => CueNode::Voice(annotated_span(name, children)),
+            | TextNode::Language {
+                language,
+                children,
+            } => CueNode::Language(annotated_span(language, children)),
+        };
+        converted.push(node);
+    }
+
+    converted
+}
+
+fn span(children: &[TextNode]) -> CueSpan {
+    CueSpan {
+        classes: vec![],
+        children: to_cue_nodes(children),
+    }
+}
+
+fn annotated_span(
+    annotation: &str,
+    children: &[TextNode],
+) -> CueAnnotatedSpan {
+    CueAnnotatedSpan {
+        annotation: annotation.to_string(),
+        classes: vec![],
+        children: to_cue_nodes(children),
+    }
+}
+
+/// Maps the absolute line position onto the cue settings in percentages of the resolution.
+fn line_position_to_settings(
+    line_position: LinePosition,
+    (width, height): (u32, u32),
+) -> CueSettings {
+    let percentage = |value: u32, total: u32| {
+        Percentage {
+            value: if total == 0 {
+                0.0
+            } else {
+                (value as f32 / total as f32 * 100.0).clamp(0.0, 100.0)
+            },
+        }
+    };
+
+    CueSettings {
+        line: Some(Line::Percentage(
+            percentage(line_position.y1, height),
+            Some(LineAlignment::Start),
+        )),
+        position: Some(Position {
+            value: percentage(line_position.x1, width),
+            alignment: Some(PositionAlignment::LineLeft),
+        }),
+        size: Some(percentage(
+            line_position
+                .x2
+                .saturating_sub(line_position.x1),
+            width,
+        )),
+        align: Some(Alignment::Left),
+        ..Default::default()
+    }
+}
+
+fn vtt_description(description: &str) -> VttDescription {
+    if description.contains('\n') {
+        VttDescription::Below(description.to_string())
+    } else {
+        VttDescription::Side(description.to_string())
+    }
+}
+
+fn vtt_comment(comment: &str) -> VttComment {
+    if comment.contains('\n') {
+        VttComment::Below(comment.to_string())
+    } else {
+        VttComment::Side(comment.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srt::SrtTimestamp;
+
+    #[test]
+    fn from_sub_rip() {
+        let srt = SubRip::parse(
+            r#"1
+00:00:01,000 --> 00:00:02,000 X1:10 X2:20 Y1:30 Y2:40
+<i>Hello,</i>
+<b>wor<u>ld</u></b>!
+
+2
+00:00:03,000 --> 00:00:04,000
+<font color="red">Red</font> <i>unclosed
+"#,
+        )
+        .unwrap();
+
+        let conversion = Subtitles::from_sub_rip(&srt);
+
+        assert_eq!(
+            conversion.value.cues,
+            vec![
+                SubtitleCue {
+                    id: Some("1".to_string()),
+                    start: Duration::from_secs(1),
+                    end: Duration::from_secs(2),
+                    text: vec![
+                        TextNode::Italic(vec![TextNode::Text(
+                            "Hello,".to_string()
+                        )]),
+                        TextNode::LineBreak,
+                        TextNode::Bold(vec![
+                            TextNode::Text("wor".to_string()),
+                            TextNode::Underline(vec![TextNode::Text(
+                                "ld".to_string()
+                            )]),
+                        ]),
+                        TextNode::Text("!".to_string()),
+                    ],
+                    settings: None,
+                    line_position: Some(LinePosition {
+                        x1: 10,
+                        x2: 20,
+                        y1: 30,
+                        y2: 40,
+                    }),
+                },
+                SubtitleCue {
+                    id: Some("2".to_string()),
+                    start: Duration::from_secs(3),
+                    end: Duration::from_secs(4),
+                    text: vec![
+                        TextNode::Text("Red ".to_string()),
+                        TextNode::Italic(vec![TextNode::Text(
+                            "unclosed".to_string()
+                        )]),
+                    ],
+                    settings: None,
+                    line_position: None,
+                },
+            ]
+        );
+        assert_eq!(
+            conversion.losses,
+            vec![ConversionLoss {
+                cue: Some(1),
+                kind: LossKind::Markup,
+            }]
+        );
+    }
+
+    #[test]
+    fn from_web_vtt() {
+        let vtt = WebVtt::parse(
+            r#"WEBVTT Example
+
+REGION
+id:bottom
+
+STYLE
+::cue { color: white; }
+
+NOTE A comment
+
+intro
+00:00:01.000 --> 00:00:02.000 region:bottom
+<v.loud Roger>Hello</v> <ruby>漢<rt>kan</rt></ruby>
+<lang en><i.foo>world</i></lang>
+"#,
+        )
+        .unwrap();
+
+        let conversion = Subtitles::from_web_vtt(&vtt).unwrap();
+        let subtitles = conversion.value;
+
+        assert_eq!(
+            subtitles.metadata.description,
+            Some("Example".to_string())
+        );
+        assert_eq!(
+            subtitles
+                .metadata
+                .regions
+                .len(),
+            1
+        );
+        assert_eq!(
+            subtitles.metadata.styles,
+            vec!["::cue { color: white; }".to_string()]
+        );
+        assert_eq!(
+            subtitles.metadata.comments,
+            vec!["A comment".to_string()]
+        );
+        assert_eq!(
+            subtitles.cues[0].id,
+            Some("intro".to_string())
+        );
+        assert_eq!(
+            subtitles.cues[0].text,
+            vec![
+                TextNode::Voice {
+                    name: "Roger".to_string(),
+                    children: vec![TextNode::Class {
+                        classes: vec!["loud".to_string()],
+                        children: vec![TextNode::Text(
+                            "Hello".to_string()
+                        )],
+                    }],
+                },
+                TextNode::Text(" 漢".to_string()),
+                TextNode::LineBreak,
+                TextNode::Language {
+                    language: "en".to_string(),
+                    children: vec![TextNode::Italic(
+                        vec![TextNode::Class {
+                            classes: vec!["foo".to_string()],
+                            children: vec![TextNode::Text(
+                                "world".to_string()
+                            )],
+                        }]
+                    )],
+                },
+            ]
+        );
+        assert_eq!(
+            conversion.losses,
+            vec![ConversionLoss {
+                cue: Some(0),
+                kind: LossKind::Markup,
+            }]
+        );
+    }
+
+    #[test]
+    fn to_sub_rip() {
+        let vtt = WebVtt::parse(
+            r#"WEBVTT Example
+
+NOTE A comment
+
+intro
+00:00:01.000 --> 00:00:02.000 line:0
+<v Roger><i>Hello</i></v>
+
+2
+00:00:03.000 --> 00:00:04.000
+World
+"#,
+        )
+        .unwrap();
+
+        let conversion = Subtitles::try_from(&vtt)
+            .unwrap()
+            .to_sub_rip();
+
+        assert_eq!(
+            conversion.value.subtitles,
+            vec![
+                SrtSubtitle {
+                    sequence: 1,
+                    start: SrtTimestamp {
+                        seconds: 1,
+                        ..Default::default()
+                    },
+                    end: SrtTimestamp {
+                        seconds: 2,
+                        ..Default::default()
+                    },
+                    text: vec!["<i>Hello</i>".to_string()],
+                    line_position: None,
+                },
+                SrtSubtitle {
+                    sequence: 2,
+                    start: SrtTimestamp {
+                        seconds: 3,
+                        ..Default::default()
+                    },
+                    end: SrtTimestamp {
+                        seconds: 4,
+                        ..Default::default()
+                    },
+                    text: vec!["World".to_string()],
+                    line_position: None,
+                },
+            ]
+        );
+        assert_eq!(
+            conversion
+                .losses
+                .iter()
+                .map(|loss| loss.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "document: description is dropped",
+                "document: comment is dropped",
+                "cue 0: identifier is replaced by the sequence number",
+                "cue 0: cue settings are dropped",
+                "cue 0: unsupported markups are dropped",
+            ]
+        );
+
+        let vtt =
+            WebVtt::parse("WEBVTT\n\n00:00:01.000 --> 300:00:00.000\nLong\n")
+                .unwrap();
+        let conversion = Subtitles::try_from(&vtt)
+            .unwrap()
+            .to_sub_rip();
+        assert_eq!(
+            conversion.value.subtitles[0].end,
+            SrtTimestamp::MAX
+        );
+        assert_eq!(
+            conversion.losses,
+            vec![ConversionLoss {
+                cue: Some(0),
+                kind: LossKind::Timing,
+            }]
+        );
+
+        let cue = |id: &str, text: &str| SubtitleCue {
+            id: Some(id.to_string()),
+            text: vec![TextNode::Text(text.to_string())],
+            ..Default::default()
+        };
+        let conversion = Subtitles {
+            cues: vec![
+                cue("0", "A"),
+                cue("2", "B"),
+                cue("2", ""),
+                cue("7", "D"),
+            ],
+            ..Default::default()
+        }
+        .to_sub_rip();
+        assert_eq!(
+            conversion
+                .value
+                .subtitles
+                .iter()
+                .map(|subtitle| (subtitle.sequence, subtitle.text.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, vec!["A".to_string()]),
+                (2, vec!["B".to_string()]),
+                (3, vec![]),
+                (7, vec!["D".to_string()]),
+            ]
+        );
+        assert_eq!(
+            conversion.losses,
+            vec![
+                ConversionLoss {
+                    cue: Some(0),
+                    kind: LossKind::Identifier,
+                },
+                ConversionLoss {
+                    cue: Some(2),
+                    kind: LossKind::Identifier,
+                },
+            ]
+        );
+        assert_eq!(
+            conversion.value.subtitles[2].to_string(),
+            "3\n00:00:00,000 --> 00:00:00,000\n"
+        );
+    }
+
+    #[test]
+    fn to_web_vtt() {
+        let srt = SubRip::parse(
+            r#"1
+00:00:01,000 --> 00:00:02,000 X1:0 X2:320 Y1:240 Y2:480
+Tom & <i>Jerry</i>
+"#,
+        )
+        .unwrap();
+        let mut subtitles = Subtitles::from(&srt);
+
+        let conversion = subtitles.to_web_vtt();
+        assert_eq!(
+            conversion.value.render(),
+            "WEBVTT\n\n1\n00:00:01.000 --> 00:00:02.000\nTom &amp; <i>Jerry</i>\n"
+        );
+        assert_eq!(
+            conversion.losses,
+            vec![ConversionLoss {
+                cue: Some(0),
+                kind: LossKind::LinePosition,
+            }]
+        );
+
+        subtitles.metadata.resolution = Some((640, 480));
+        let conversion = subtitles.to_web_vtt();
+        assert_eq!(
+            conversion.value.render(),
+            "WEBVTT\n\n1\n00:00:01.000 --> 00:00:02.000 line:50%,start position:0%,line-left size:50% align:left\nTom &amp; <i>Jerry</i>\n"
+        );
+        assert!(conversion.losses.is_empty());
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = r#"WEBVTT
+
+REGION
+id:bottom
+
+STYLE
+::cue { color: white; }
+
+NOTE A comment
+
+intro
+00:00:01.000 --> 00:00:02.000 region:bottom
+<v Roger>Hello,</v>
+<c.loud><b>world</b></c>
+"#;
+        let vtt = WebVtt::parse(text).unwrap();
+        let subtitles = Subtitles::try_from(&vtt).unwrap();
+
+        assert_eq!(WebVtt::from(&subtitles).render(), text);
+    }
+}
@@ -0,0 +1,596 @@
+//! SMPTE timecodes `HH:MM:SS:FF` and drop-frame `HH:MM:SS;FF` provided by [`subtp::timecode::Timecode`](Timecode).
+//!
+//! ## Example
+//! ```
+//! use std::time::Duration;
+//! use subtp::srt::SrtTimestamp;
+//! use subtp::timecode::Timecode;
+//! use subtp::timing::FrameRate;
+//!
+//! let timecode = Timecode::parse("00:01:00;02", FrameRate::FPS_29_97).unwrap();
+//! assert!(timecode.drop_frame);
+//! assert_eq!(timecode.to_frames(), 1800);
+//!
+//! let timestamp = SrtTimestamp::try_from(timecode).unwrap();
+//! assert_eq!(timestamp.to_string(), "00:01:00,060");
+//!
+//! let back = Timecode::from_duration(timestamp.into(), FrameRate::FPS_29_97, true).unwrap();
+//! assert_eq!(back, timecode);
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::srt::SrtTimestamp;
+use crate::timecode_parser;
+use crate::timing::FrameRate;
+use crate::vtt::VttTimestamp;
+use crate::{ParseError, TimestampRangeError};
+
+/// The SMPTE timecode at a frame rate.
+///
+/// The frames are counted with the nominal integer frame rate, e.g. 30 for 29.97 fps.
+/// The drop-frame timecode skips the frame numbers 0 and 1 (0 to 3 at 59.94 fps)
+/// at the start of each minute except every tenth minute to keep up with the real time.
+///
+/// Conversions to the timestamps round to the nearest millisecond,
+/// and conversions from the timestamps round to the nearest frame,
+/// so that a timecode survives the round trip through the timestamps.
+///
+/// ## Example
+/// ```
+/// use subtp::timecode::Timecode;
+/// use subtp::timing::FrameRate;
+///
+/// let timecode = Timecode::new(1, 2, 3, 4, FrameRate::FPS_25, false).unwrap();
+///
+/// assert_eq!(timecode.to_string(), "01:02:03:04");
+/// assert_eq!(
+///     timecode.checked_add_frames(21),
+///     Some(Timecode::new(1, 2, 4, 0, FrameRate::FPS_25, false).unwrap())
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timecode {
+    /// The hours.
+    pub hours: u32,
+    /// The minutes.
+    pub minutes: u8,
+    /// The seconds.
+    pub seconds: u8,
+    /// The frames in the second.
+    pub frames: u32,
+    /// The frame rate.
+    pub frame_rate: FrameRate,
+    /// Whether the timecode is drop-frame.
+    pub drop_frame: bool,
+}
+
+impl Timecode {
+    /// Creates the timecode, validating the fields against the frame rate.
+    pub fn new(
+        hours: u32,
+        minutes: u8,
+        seconds: u8,
+        frames: u32,
+        frame_rate: FrameRate,
+        drop_frame: bool,
+    ) -> Result<Self, TimecodeError> {
+        let timecode = Self {
+            hours,
+            minutes,
+            seconds,
+            frames,
+            frame_rate,
+            drop_frame,
+        };
+        timecode.validate()?;
+
+        Ok(timecode)
+    }
+
+    /// Parses the timecode `HH:MM:SS:FF`, or drop-frame `HH:MM:SS;FF`, at the frame rate.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::timecode::Timecode;
+    /// use subtp::timecode::TimecodeError;
+    /// use subtp::timing::FrameRate;
+    ///
+    /// let timecode = Timecode::parse("10:00:00:12", FrameRate::FPS_24).unwrap();
+    /// assert_eq!(timecode.hours, 10);
+    /// assert_eq!(timecode.frames, 12);
+    ///
+    /// assert!(matches!(
+    ///     Timecode::parse("00:01:00;00", FrameRate::FPS_29_97),
+    ///     Err(TimecodeError::DroppedFrame)
+    /// ));
+    /// ```
+    pub fn parse(
+        text: &str,
+        frame_rate: FrameRate,
+    ) -> Result<Self, TimecodeError> {
+        let raw = timecode_parser::timecode(text).map_err(ParseError::from)?;
+
+        Self::new(
+            raw.hours,
+            raw.minutes,
+            raw.seconds,
+            raw.frames,
+            frame_rate,
+            raw.drop_frame,
+        )
+    }
+
+    /// Creates the timecode from the number of frames since zero.
+    pub fn from_frames(
+        frames: u64,
+        frame_rate: FrameRate,
+        drop_frame: bool,
+    ) -> Result<Self, TimecodeError> {
+        let nominal = validate_frame_rate(frame_rate, drop_frame)? as u64;
+
+        let mut number = frames;
+        if drop_frame {
+            let dropped = drop_count(nominal);
+            let per_minute = nominal * 60 - dropped;
+            let per_ten_minutes = nominal * 600 - dropped * 9;
+
+            let tens = frames / per_ten_minutes;
+            let rest = frames % per_ten_minutes;
+            number += dropped * 9 * tens;
+            if rest >= dropped {
+                number += dropped * ((rest - dropped) / per_minute);
+            }
+        }
+
+        let seconds = number / nominal;
+        let hours = u32::try_from(seconds / 3600)
+            .map_err(|_| TimecodeError::Overflow)?;
+
+        Ok(Self {
+            hours,
+            minutes: ((seconds % 3600) / 60) as u8,
+            seconds: (seconds % 60) as u8,
+            frames: (number % nominal) as u32,
+            frame_rate,
+            drop_frame,
+        })
+    }
+
+    /// Returns the number of frames since zero.
+    pub fn to_frames(&self) -> u64 {
+        let nominal = self.frame_rate.nominal() as u64;
+        let minutes = self.hours as u64 * 60 + self.minutes as u64;
+        let frames =
+            (minutes * 60 + self.seconds as u64) * nominal + self.frames as u64;
+
+        if self.drop_frame {
+            frames - drop_count(nominal) * (minutes - minutes / 10)
+        } else {
+            frames
+        }
+    }
+
+    /// Creates the timecode at the nearest frame to the duration.
+    pub fn from_duration(
+        duration: Duration,
+        frame_rate: FrameRate,
+        drop_frame: bool,
+    ) -> Result<Self, TimecodeError> {
+        validate_frame_rate(frame_rate, drop_frame)?;
+
+        let nanoseconds = duration.as_nanos() * frame_rate.numerator as u128;
+        let divisor = frame_rate.denominator as u128 * 1_000_000_000;
+        let frames = (nanoseconds + divisor / 2) / divisor;
+
+        Self::from_frames(
+            u64::try_from(frames).map_err(|_| TimecodeError::Overflow)?,
+            frame_rate,
+            drop_frame,
+        )
+    }
+
+    /// Returns the exact duration of the frame.
+    pub fn to_duration(&self) -> Duration {
+        let nanoseconds = self.to_frames() as u128
+            * self.frame_rate.denominator as u128
+            * 1_000_000_000;
+        let numerator = self.frame_rate.numerator as u128;
+
+        Duration::from_nanos(((nanoseconds + numerator / 2) / numerator) as u64)
+    }
+
+    /// Adds the number of frames, returning `None` if the result is negative or overflows.
+    pub fn checked_add_frames(
+        &self,
+        frames: i64,
+    ) -> Option<Self> {
+        let frames = self
+            .to_frames()
+            .checked_add_signed(frames)?;
+
+        Self::from_frames(frames, self.frame_rate, self.drop_frame).ok()
+    }
+
+    /// Adds the timecode as a length of frames,
+    /// returning `None` if the frame rates or the drop-frame modes differ.
+    pub fn checked_add(
+        &self,
+        rhs: &Self,
+    ) -> Option<Self> {
+        if !self.is_compatible(rhs) {
+            return None;
+        }
+        let frames = self
+            .to_frames()
+            .checked_add(rhs.to_frames())?;
+
+        Self::from_frames(frames, self.frame_rate, self.drop_frame).ok()
+    }
+
+    /// Subtracts the timecode as a length of frames,
+    /// returning `None` if the result is negative or the frame rates or the drop-frame modes differ.
+    pub fn checked_sub(
+        &self,
+        rhs: &Self,
+    ) -> Option<Self> {
+        if !self.is_compatible(rhs) {
+            return None;
+        }
+        let frames = self
+            .to_frames()
+            .checked_sub(rhs.to_frames())?;
+
+        Self::from_frames(frames, self.frame_rate, self.drop_frame).ok()
+    }
+
+    /// Converts the timecode to the nearest timecode at the other frame rate and drop-frame mode.
+    pub fn convert(
+        &self,
+        frame_rate: FrameRate,
+        drop_frame: bool,
+    ) -> Result<Self, TimecodeError> {
+        Self::from_duration(
+            self.to_duration(),
+            frame_rate,
+            drop_frame,
+        )
+    }
+
+    fn is_compatible(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.frame_rate == other.frame_rate
+            && self.drop_frame == other.drop_frame
+    }
+
+    fn validate(&self) -> Result<(), TimecodeError> {
+        let nominal = validate_frame_rate(self.frame_rate, self.drop_frame)?;
+
+        if self.minutes >= 60 || self.seconds >= 60 || self.frames >= nominal {
+            return Err(TimecodeError::OutOfRange);
+        }
+        if self.drop_frame
+            && self.minutes % 10 != 0
+            && self.seconds == 0
+            && (self.frames as u64) < drop_count(nominal as u64)
+        {
+            return Err(TimecodeError::DroppedFrame);
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Timecode {
+    fn default() -> Self {
+        Self {
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+            frames: 0,
+            frame_rate: FrameRate::default(),
+            drop_frame: false,
+        }
+    }
+}
+
+impl Display for Timecode {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{}{:02}",
+            self.hours,
+            self.minutes,
+            self.seconds,
+            if self.drop_frame {
+                ';'
+            } else {
+                ':'
+            },
+            self.frames
+        )
+    }
+}
+
+impl From<Timecode> for Duration {
+    fn from(timecode: Timecode) -> Self {
+        timecode.to_duration()
+    }
+}
+
+impl TryFrom<Timecode> for SrtTimestamp {
+    type Error = TimestampRangeError;
+
+    fn try_from(timecode: Timecode) -> Result<Self, Self::Error> {
+        round_to_milliseconds(timecode.to_duration()).try_into()
+    }
+}
+
+impl TryFrom<Timecode> for VttTimestamp {
+    type Error = TimestampRangeError;
+
+    fn try_from(timecode: Timecode) -> Result<Self, Self::Error> {
+        round_to_milliseconds(timecode.to_duration()).try_into()
+    }
+}
+
+/// The error of timecodes.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TimecodeError {
+    /// The text is not a timecode.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// The frame rate has zero numerator or denominator.
+    #[error("invalid frame rate: {0}")]
+    InvalidFrameRate(FrameRate),
+    /// The drop-frame timecode is only defined for 29.97 fps and its multiples.
+    #[error("drop-frame is not supported at {0} fps")]
+    DropFrameUnsupported(FrameRate),
+    /// The minutes, the seconds or the frames are out of range.
+    #[error("minutes, seconds or frames out of range")]
+    OutOfRange,
+    /// The frame number is skipped in the drop-frame timecode.
+    #[error("frame number dropped in drop-frame timecode")]
+    DroppedFrame,
+    /// The hours overflow.
+    #[error("timecode overflow")]
+    Overflow,
+}
+
+/// Returns the number of frames dropped each minute, e.g. 2 at 29.97 fps.
+fn drop_count(nominal: u64) -> u64 {
+    nominal / 15
+}
+
+/// Validates the frame rate for the drop-frame mode and returns the nominal frame rate.
+fn validate_frame_rate(
+    frame_rate: FrameRate,
+    drop_frame: bool,
+) -> Result<u32, TimecodeError> {
+    if !frame_rate.is_valid() || frame_rate.nominal() == 0 {
+        return Err(TimecodeError::InvalidFrameRate(
+            frame_rate,
+        ));
+    }
+    if drop_frame
+        && (frame_rate.denominator != 1001 || frame_rate.nominal() % 30 != 0)
+    {
+        return Err(TimecodeError::DropFrameUnsupported(
+            frame_rate,
+        ));
+    }
+
+    Ok(frame_rate.nominal())
+}
+
+fn round_to_milliseconds(duration: Duration) -> Duration {
+    Duration::from_millis(((duration.as_nanos() + 500_000) / 1_000_000) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Timecode::parse("01:02:03:04", FrameRate::FPS_25),
+            Ok(Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                frames: 4,
+                frame_rate: FrameRate::FPS_25,
+                drop_frame: false,
+            })
+        );
+        assert!(matches!(
+            Timecode::parse("01:02:03.04", FrameRate::FPS_25),
+            Err(TimecodeError::Parse(_))
+        ));
+        assert_eq!(
+            Timecode::parse("01:02:03:25", FrameRate::FPS_25),
+            Err(TimecodeError::OutOfRange)
+        );
+        assert_eq!(
+            Timecode::parse("01:02:03;04", FrameRate::FPS_25),
+            Err(TimecodeError::DropFrameUnsupported(
+                FrameRate::FPS_25
+            ))
+        );
+        assert_eq!(
+            Timecode::parse("00:01:00;01", FrameRate::FPS_29_97),
+            Err(TimecodeError::DroppedFrame)
+        );
+        assert!(Timecode::parse("00:10:00;00", FrameRate::FPS_29_97).is_ok());
+        assert_eq!(
+            Timecode::parse("00:01:00;03", FrameRate::FPS_59_94),
+            Err(TimecodeError::DroppedFrame)
+        );
+    }
+
+    #[test]
+    fn display() {
+        let timecode =
+            Timecode::parse("00:09:59;29", FrameRate::FPS_29_97).unwrap();
+        assert_eq!(timecode.to_string(), "00:09:59;29");
+
+        let timecode =
+            Timecode::parse("123:00:00:00", FrameRate::FPS_24).unwrap();
+        assert_eq!(timecode.to_string(), "123:00:00:00");
+    }
+
+    #[test]
+    fn frames() {
+        let drop_frame = |text| {
+            Timecode::parse(text, FrameRate::FPS_29_97)
+                .unwrap()
+                .to_frames()
+        };
+        assert_eq!(drop_frame("00:00:59;29"), 1799);
+        assert_eq!(drop_frame("00:01:00;02"), 1800);
+        assert_eq!(drop_frame("00:09:59;29"), 17981);
+        assert_eq!(drop_frame("00:10:00;00"), 17982);
+        assert_eq!(drop_frame("01:00:00;00"), 107892);
+
+        // Round trip through the frame numbers.
+        for frames in (0..200_000).step_by(7) {
+            let timecode =
+                Timecode::from_frames(frames, FrameRate::FPS_29_97, true)
+                    .unwrap();
+            assert!(timecode.validate().is_ok());
+            assert_eq!(timecode.to_frames(), frames);
+
+            let timecode =
+                Timecode::from_frames(frames, FrameRate::FPS_59_94, true)
+                    .unwrap();
+            assert!(timecode.validate().is_ok());
+            assert_eq!(timecode.to_frames(), frames);
+        }
+
+        let timecode =
+            Timecode::parse("00:00:01:00", FrameRate::FPS_23_976).unwrap();
+        assert_eq!(timecode.to_frames(), 24);
+    }
+
+    #[test]
+    fn duration() {
+        // One hour of drop-frame timecode is almost exactly one hour.
+        let timecode =
+            Timecode::parse("01:00:00;00", FrameRate::FPS_29_97).unwrap();
+        assert_eq!(
+            SrtTimestamp::try_from(timecode)
+                .unwrap()
+                .to_string(),
+            "00:59:59,996"
+        );
+
+        // One hour of non-drop-frame timecode at 29.97 fps is 3.6 seconds late.
+        let timecode =
+            Timecode::parse("01:00:00:00", FrameRate::FPS_29_97).unwrap();
+        assert_eq!(
+            VttTimestamp::try_from(timecode)
+                .unwrap()
+                .to_string(),
+            "01:00:03.600"
+        );
+
+        let timecode =
+            Timecode::parse("00:00:01:12", FrameRate::FPS_25).unwrap();
+        assert_eq!(
+            timecode.to_duration(),
+            Duration::from_millis(1480)
+        );
+        assert_eq!(
+            Timecode::from_duration(
+                Duration::from_millis(1490),
+                FrameRate::FPS_25,
+                false
+            ),
+            Ok(timecode)
+        );
+
+        // Round trip through the timestamps.
+        for frames in (0..100_000).step_by(13) {
+            for (frame_rate, drop_frame) in [
+                (FrameRate::FPS_23_976, false),
+                (FrameRate::FPS_29_97, true),
+                (FrameRate::FPS_59_94, true),
+                (FrameRate::FPS_60, false),
+            ] {
+                let timecode =
+                    Timecode::from_frames(frames, frame_rate, drop_frame)
+                        .unwrap();
+                let timestamp = VttTimestamp::try_from(timecode).unwrap();
+                assert_eq!(
+                    Timecode::from_duration(
+                        timestamp.into(),
+                        frame_rate,
+                        drop_frame
+                    ),
+                    Ok(timecode)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let timecode =
+            Timecode::parse("00:00:59;29", FrameRate::FPS_29_97).unwrap();
+        assert_eq!(
+            timecode
+                .checked_add_frames(1)
+                .unwrap()
+                .to_string(),
+            "00:01:00;02"
+        );
+        assert_eq!(
+            timecode
+                .checked_add_frames(-1800)
+                .map(|timecode| timecode.to_string()),
+            None
+        );
+
+        let length =
+            Timecode::parse("00:00:01;00", FrameRate::FPS_29_97).unwrap();
+        assert_eq!(
+            timecode
+                .checked_add(&length)
+                .unwrap()
+                .to_string(),
+            "00:01:01;01"
+        );
+        assert_eq!(
+            timecode
+                .checked_sub(&length)
+                .unwrap()
+                .to_string(),
+            "00:00:58;29"
+        );
+        assert_eq!(length.checked_sub(&timecode), None);
+
+        let other = Timecode::parse("00:00:01:00", FrameRate::FPS_25).unwrap();
+        assert_eq!(timecode.checked_add(&other), None);
+    }
+
+    #[test]
+    fn convert() {
+        let timecode =
+            Timecode::parse("01:00:00:00", FrameRate::FPS_25).unwrap();
+        assert_eq!(
+            timecode
+                .convert(FrameRate::FPS_29_97, true)
+                .unwrap()
+                .to_string(),
+            "01:00:00;00"
+        );
+    }
+}
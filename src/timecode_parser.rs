@@ -0,0 +1,84 @@
+//! A parser implementation for the SMPTE timecode.
+
+pub(crate) use timecode_parser::timecode;
+
+/// The fields of the timecode before validation against the frame rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RawTimecode {
+    pub(crate) hours: u32,
+    pub(crate) minutes: u8,
+    pub(crate) seconds: u8,
+    pub(crate) frames: u32,
+    pub(crate) drop_frame: bool,
+}
+
+peg::parser! {
+    /// The parser for the SMPTE timecode.
+    grammar timecode_parser() for str {
+        use super::RawTimecode;
+
+        /// Two or more digits number.
+        rule two_or_more_number() -> u32
+            = n:$(['0'..='9']['0'..='9']+) {?
+                n.parse().or(Err("two or more digits number"))
+            }
+
+        /// Two-digit number.
+        rule two_number() -> u8
+            = n:$(['0'..='9']['0'..='9']) {?
+                n.parse().or(Err("two-digit number"))
+            }
+
+        /// Separator of the frames, where `;` or `,` means drop-frame.
+        rule frame_separator() -> bool
+            = ":" { false }
+                / ";" { true }
+                / "," { true }
+
+        /// Timecode in `HH:MM:SS:FF` or drop-frame `HH:MM:SS;FF`.
+        pub(crate) rule timecode() -> RawTimecode
+            = hours:two_or_more_number() ":" minutes:two_number() ":" seconds:two_number()
+                drop_frame:frame_separator() frames:two_or_more_number()
+            {
+                RawTimecode {
+                    hours,
+                    minutes,
+                    seconds,
+                    frames,
+                    drop_frame,
+                }
+            }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn timecode() {
+        assert_eq!(
+            timecode_parser::timecode("01:02:03:04").unwrap(),
+            RawTimecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                frames: 4,
+                drop_frame: false,
+            }
+        );
+        assert_eq!(
+            timecode_parser::timecode("100:00:00;02").unwrap(),
+            RawTimecode {
+                hours: 100,
+                minutes: 0,
+                seconds: 0,
+                frames: 2,
+                drop_frame: true,
+            }
+        );
+        assert!(timecode_parser::timecode("01:02:03.04").is_err());
+        assert!(timecode_parser::timecode("1:02:03:04").is_err());
+        assert!(timecode_parser::timecode("01:02:03:4").is_err());
+    }
+}
@@ -0,0 +1,644 @@
+//! Global timing operations shared by the subtitle formats.
+//!
+//! ## Example
+//! ```
+//! use std::time::Duration;
+//! use subtp::srt::SubRip;
+//! use subtp::timing::TimeOffset;
+//!
+//! let text = r#"1
+//! 00:00:01,000 --> 00:00:02,000
+//! Hello, world!
+//!
+//! 2
+//! 00:00:03,000 --> 00:00:04,000
+//! This is a sample.
+//! "#;
+//!
+//! let mut srt = SubRip::parse(text).unwrap();
+//!
+//! // Delays all subtitles by 1.5 seconds.
+//! srt.shift(TimeOffset::forward(Duration::from_millis(1500)))
+//!     .unwrap();
+//! assert_eq!(srt.subtitles[0].start.to_string(), "00:00:02,500");
+//!
+//! // Slows down all subtitles from 25 fps to 23.976 fps.
+//! srt.scale(25.0 / 23.976).unwrap();
+//! assert_eq!(srt.subtitles[0].start.to_string(), "00:00:02,607");
+//!
+//! // Moves the first subtitle to 1 second and the second subtitle to 5 seconds.
+//! srt.resync(
+//!     0,
+//!     Duration::from_secs(1),
+//!     1,
+//!     Duration::from_secs(5),
+//! )
+//! .unwrap();
+//! assert_eq!(srt.subtitles[0].start.to_string(), "00:00:01,000");
+//! assert_eq!(srt.subtitles[1].start.to_string(), "00:00:05,000");
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// The signed offset of time.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use subtp::timing::TimeOffset;
+///
+/// let offset = TimeOffset::backward(Duration::from_secs(2));
+///
+/// assert_eq!(
+///     offset.checked_apply(Duration::from_secs(3)),
+///     Some(Duration::from_secs(1))
+/// );
+/// assert_eq!(offset.checked_apply(Duration::from_secs(1)), None);
+/// assert_eq!(
+///     offset.saturating_apply(Duration::from_secs(1)),
+///     Duration::ZERO
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeOffset {
+    /// Whether the offset moves the time backward.
+    pub negative: bool,
+    /// The magnitude of the offset.
+    pub duration: Duration,
+}
+
+impl TimeOffset {
+    /// Creates an offset that delays the time.
+    pub fn forward(duration: Duration) -> Self {
+        Self {
+            negative: false,
+            duration,
+        }
+    }
+
+    /// Creates an offset that advances the time.
+    pub fn backward(duration: Duration) -> Self {
+        Self {
+            negative: true,
+            duration,
+        }
+    }
+
+    /// Applies the offset to the time, returning `None` if the result is negative.
+    pub fn checked_apply(
+        &self,
+        time: Duration,
+    ) -> Option<Duration> {
+        if self.negative {
+            time.checked_sub(self.duration)
+        } else {
+            time.checked_add(self.duration)
+        }
+    }
+
+    /// Applies the offset to the time, clamping a negative result at zero.
+    pub fn saturating_apply(
+        &self,
+        time: Duration,
+    ) -> Duration {
+        if self.negative {
+            time.saturating_sub(self.duration)
+        } else {
+            time.saturating_add(self.duration)
+        }
+    }
+}
+
+impl Default for TimeOffset {
+    fn default() -> Self {
+        Self::forward(Duration::ZERO)
+    }
+}
+
+impl From<Duration> for TimeOffset {
+    fn from(duration: Duration) -> Self {
+        Self::forward(duration)
+    }
+}
+
+/// The frame rate as a rational number of frames per second.
+///
+/// ## Example
+/// ```
+/// use subtp::timing::FrameRate;
+///
+/// assert_eq!(FrameRate::FPS_23_976, FrameRate::new(24000, 1001));
+/// assert_eq!(FrameRate::FPS_29_97.to_string(), "30000/1001");
+/// assert!((FrameRate::FPS_29_97.as_f64() - 29.97).abs() < 0.001);
+/// assert_eq!(FrameRate::FPS_29_97.nominal(), 30);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct FrameRate {
+    /// The numerator of the frames per second.
+    pub numerator: u32,
+    /// The denominator of the frames per second.
+    pub denominator: u32,
+}
+
+impl FrameRate {
+    /// 24000/1001 (23.976) fps of NTSC film.
+    pub const FPS_23_976: Self = Self::new(24000, 1001);
+    /// 24 fps of film.
+    pub const FPS_24: Self = Self::new(24, 1);
+    /// 25 fps of PAL.
+    pub const FPS_25: Self = Self::new(25, 1);
+    /// 30000/1001 (29.97) fps of NTSC.
+    pub const FPS_29_97: Self = Self::new(30000, 1001);
+    /// 30 fps.
+    pub const FPS_30: Self = Self::new(30, 1);
+    /// 50 fps.
+    pub const FPS_50: Self = Self::new(50, 1);
+    /// 60000/1001 (59.94) fps.
+    pub const FPS_59_94: Self = Self::new(60000, 1001);
+    /// 60 fps.
+    pub const FPS_60: Self = Self::new(60, 1);
+
+    /// Creates the frame rate of `numerator / denominator` frames per second.
+    pub const fn new(
+        numerator: u32,
+        denominator: u32,
+    ) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Returns the frames per second.
+    pub fn as_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Returns the nominal integer frames per second used to count the frames of timecodes,
+    /// e.g. 30 for 29.97 fps.
+    pub fn nominal(&self) -> u32 {
+        let (numerator, denominator) = (
+            self.numerator as u64,
+            self.denominator as u64,
+        );
+
+        (numerator + denominator / 2)
+            .checked_div(denominator)
+            .unwrap_or(0) as u32
+    }
+
+    /// Returns the duration of the number of frames rounded to the nearest nanosecond,
+    /// or `None` if the frame rate is invalid.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::timing::FrameRate;
+    ///
+    /// assert_eq!(
+    ///     FrameRate::FPS_25.frames_to_duration(2),
+    ///     Some(Duration::from_millis(80))
+    /// );
+    /// assert_eq!(FrameRate::new(0, 1).frames_to_duration(2), None);
+    /// ```
+    pub fn frames_to_duration(
+        &self,
+        frames: u64,
+    ) -> Option<Duration> {
+        if !self.is_valid() {
+            return None;
+        }
+        let nanoseconds = div_round(
+            frames as u128 * self.denominator as u128 * 1_000_000_000,
+            self.numerator as u128,
+        );
+
+        u64::try_from(nanoseconds)
+            .ok()
+            .map(Duration::from_nanos)
+    }
+
+    /// Returns whether both of the numerator and the denominator are positive.
+    pub fn is_valid(&self) -> bool {
+        self.numerator > 0 && self.denominator > 0
+    }
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self::FPS_24
+    }
+}
+
+impl Display for FrameRate {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(
+                f,
+                "{}/{}",
+                self.numerator, self.denominator
+            )
+        }
+    }
+}
+
+/// Whether to snap the converted times to the frames of the target frame rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameSnapping {
+    /// Keeps the converted times as they are.
+    Off,
+    /// Snaps the converted times to the nearest frames.
+    Nearest,
+}
+
+impl Default for FrameSnapping {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+/// The error of timing operations.
+///
+/// The cues are not modified when an error is returned.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TimingError {
+    /// A time of the cue would be negative.
+    #[error("time of cue {cue} would be negative")]
+    Underflow {
+        /// The index of the cue.
+        cue: usize,
+    },
+    /// A time of the cue would exceed the maximum timestamp of the format.
+    #[error("time of cue {cue} would exceed the maximum timestamp")]
+    Overflow {
+        /// The index of the cue.
+        cue: usize,
+    },
+    /// The scale factor is negative or not finite.
+    #[error("invalid scale factor: {0}")]
+    InvalidFactor(f64),
+    /// The reference cue does not exist.
+    #[error("cue {cue} does not exist")]
+    CueNotFound {
+        /// The index of the cue.
+        cue: usize,
+    },
+    /// The reference cues start at the same time, so the mapping is undefined.
+    #[error("reference cues start at the same time")]
+    IdenticalReferences,
+    /// The frame rate has zero numerator or denominator.
+    #[error("invalid frame rate: {0}")]
+    InvalidFrameRate(FrameRate),
+}
+
+/// The bound of time crossed by a mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutOfRange {
+    /// The time would be negative.
+    Underflow,
+    /// The time would not fit in a duration.
+    Overflow,
+}
+
+impl OutOfRange {
+    /// Converts into the timing error of the cue.
+    fn at(
+        self,
+        cue: usize,
+    ) -> TimingError {
+        match self {
+            | Self::Underflow => TimingError::Underflow {
+                cue,
+            },
+            | Self::Overflow => TimingError::Overflow {
+                cue,
+            },
+        }
+    }
+}
+
+/// Maps the start and end times of the cues, failing without partial results.
+pub(crate) fn map_times(
+    times: Vec<(Duration, Duration)>,
+    max: Duration,
+    map: impl Fn(Duration) -> Result<Duration, OutOfRange>,
+) -> Result<Vec<(Duration, Duration)>, TimingError> {
+    times
+        .into_iter()
+        .enumerate()
+        .map(|(cue, (start, end))| {
+            let start = map(start).map_err(|err| err.at(cue))?;
+            let end = map(end).map_err(|err| err.at(cue))?;
+            if start > max || end > max {
+                return Err(TimingError::Overflow {
+                    cue,
+                });
+            }
+
+            Ok((start, end))
+        })
+        .collect()
+}
+
+/// Creates the mapping that shifts the time by the offset.
+pub(crate) fn shift(
+    offset: TimeOffset
+) -> impl Fn(Duration) -> Result<Duration, OutOfRange> {
+    move |time: Duration| {
+        offset
+            .checked_apply(time)
+            .ok_or(if offset.negative {
+                OutOfRange::Underflow
+            } else {
+                OutOfRange::Overflow
+            })
+    }
+}
+
+/// Rounds the seconds to the nearest millisecond.
+fn from_seconds(seconds: f64) -> Result<Duration, OutOfRange> {
+    if seconds.is_nan() || seconds < 0.0 {
+        return Err(OutOfRange::Underflow);
+    }
+
+    let milliseconds = (seconds * 1000.0).round();
+    if milliseconds < u64::MAX as f64 {
+        Ok(Duration::from_millis(milliseconds as u64))
+    } else {
+        Err(OutOfRange::Overflow)
+    }
+}
+
+/// Creates the mapping that scales the time by the factor.
+pub(crate) fn scale(
+    factor: f64
+) -> Result<impl Fn(Duration) -> Result<Duration, OutOfRange>, TimingError> {
+    if !factor.is_finite() || factor < 0.0 {
+        return Err(TimingError::InvalidFactor(factor));
+    }
+
+    Ok(move |time: Duration| from_seconds(time.as_secs_f64() * factor))
+}
+
+/// Divides with rounding half up.
+fn div_round(
+    dividend: u128,
+    divisor: u128,
+) -> u128 {
+    (dividend + divisor / 2) / divisor
+}
+
+/// Creates the mapping that converts the time from the source frame rate to the target frame rate,
+/// keeping the frame numbers, i.e. the time is multiplied by `source / target`.
+pub(crate) fn frame_rate(
+    source: FrameRate,
+    target: FrameRate,
+    snapping: FrameSnapping,
+) -> Result<impl Fn(Duration) -> Result<Duration, OutOfRange>, TimingError> {
+    for rate in [source, target] {
+        if !rate.is_valid() {
+            return Err(TimingError::InvalidFrameRate(rate));
+        }
+    }
+
+    Ok(move |time: Duration| {
+        let nanoseconds = div_round(
+            time.as_nanos()
+                * source.numerator as u128
+                * target.denominator as u128,
+            source.denominator as u128 * target.numerator as u128,
+        );
+        let nanoseconds = match snapping {
+            | FrameSnapping::Off => nanoseconds,
+            | FrameSnapping::Nearest => {
+                let frame = div_round(
+                    nanoseconds * target.numerator as u128,
+                    target.denominator as u128 * 1_000_000_000,
+                );
+                div_round(
+                    frame * target.denominator as u128 * 1_000_000_000,
+                    target.numerator as u128,
+                )
+            },
+        };
+
+        let milliseconds = div_round(nanoseconds, 1_000_000);
+        u64::try_from(milliseconds)
+            .map(Duration::from_millis)
+            .or(Err(OutOfRange::Overflow))
+    })
+}
+
+/// Creates the linear mapping that moves `a.0` to `a.1` and `b.0` to `b.1`,
+/// clamping negative results at zero.
+pub(crate) fn resync(
+    a: (Duration, Duration),
+    b: (Duration, Duration),
+) -> Result<impl Fn(Duration) -> Result<Duration, OutOfRange>, TimingError> {
+    if a.0 == b.0 {
+        return Err(TimingError::IdenticalReferences);
+    }
+
+    let (source, target) = (a.0.as_secs_f64(), a.1.as_secs_f64());
+    let slope = (b.1.as_secs_f64() - target) / (b.0.as_secs_f64() - source);
+
+    Ok(move |time: Duration| {
+        let mapped = target + (time.as_secs_f64() - source) * slope;
+        from_seconds(mapped.max(0.0))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(seconds: f64) -> Duration {
+        Duration::from_secs_f64(seconds)
+    }
+
+    #[test]
+    fn map_times() {
+        let times = vec![
+            (seconds(1.0), seconds(2.0)),
+            (seconds(3.0), seconds(4.0)),
+        ];
+
+        assert_eq!(
+            super::map_times(
+                times.clone(),
+                seconds(10.0),
+                shift(TimeOffset::backward(seconds(1.0)))
+            ),
+            Ok(vec![
+                (seconds(0.0), seconds(1.0)),
+                (seconds(2.0), seconds(3.0))
+            ])
+        );
+        assert_eq!(
+            super::map_times(
+                times.clone(),
+                seconds(10.0),
+                shift(TimeOffset::backward(seconds(1.5)))
+            ),
+            Err(TimingError::Underflow {
+                cue: 0
+            })
+        );
+        assert_eq!(
+            super::map_times(
+                times.clone(),
+                seconds(5.0),
+                shift(TimeOffset::forward(seconds(1.5)))
+            ),
+            Err(TimingError::Overflow {
+                cue: 1
+            })
+        );
+        // Beyond the range of duration.
+        assert_eq!(
+            super::map_times(
+                times.clone(),
+                Duration::MAX,
+                shift(TimeOffset::forward(Duration::MAX))
+            ),
+            Err(TimingError::Overflow {
+                cue: 0
+            })
+        );
+        assert_eq!(
+            super::map_times(
+                times,
+                Duration::MAX,
+                super::scale(f64::MAX).unwrap()
+            ),
+            Err(TimingError::Overflow {
+                cue: 0
+            })
+        );
+    }
+
+    #[test]
+    fn scale() {
+        let scale = super::scale(1.5).unwrap();
+        assert_eq!(scale(seconds(2.0)), Ok(seconds(3.0)));
+        assert_eq!(
+            scale(Duration::from_millis(1)),
+            Ok(Duration::from_millis(2))
+        );
+
+        assert!(super::scale(-1.0).is_err());
+        assert!(super::scale(f64::NAN).is_err());
+        assert!(super::scale(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn frame_rate() {
+        let milliseconds = |source, target, snapping, time| {
+            super::frame_rate(source, target, snapping).unwrap()(
+                Duration::from_millis(time),
+            )
+            .unwrap()
+            .as_millis()
+        };
+
+        // PAL speedup from 23.976 fps to 25 fps.
+        assert_eq!(
+            milliseconds(
+                FrameRate::FPS_23_976,
+                FrameRate::FPS_25,
+                FrameSnapping::Off,
+                3_600_000
+            ),
+            3_452_547
+        );
+        assert_eq!(
+            milliseconds(
+                FrameRate::FPS_25,
+                FrameRate::FPS_23_976,
+                FrameSnapping::Off,
+                3_452_547
+            ),
+            3_600_000
+        );
+        assert_eq!(
+            milliseconds(
+                FrameRate::FPS_24,
+                FrameRate::FPS_24,
+                FrameSnapping::Off,
+                1_234
+            ),
+            1_234
+        );
+
+        // The 30th frame at 25 fps is at 1.2 seconds.
+        assert_eq!(
+            milliseconds(
+                FrameRate::FPS_25,
+                FrameRate::FPS_25,
+                FrameSnapping::Nearest,
+                1_219
+            ),
+            1_200
+        );
+        // The 24th frame at 23.976 fps is at 1.001 seconds.
+        assert_eq!(
+            milliseconds(
+                FrameRate::FPS_24,
+                FrameRate::FPS_23_976,
+                FrameSnapping::Nearest,
+                1_000
+            ),
+            1_001
+        );
+
+        assert_eq!(
+            super::frame_rate(
+                FrameRate::new(0, 1),
+                FrameRate::FPS_25,
+                FrameSnapping::Off
+            )
+            .err(),
+            Some(TimingError::InvalidFrameRate(
+                FrameRate::new(0, 1)
+            ))
+        );
+    }
+
+    #[test]
+    fn resync() {
+        let resync = super::resync(
+            (seconds(10.0), seconds(12.0)),
+            (seconds(20.0), seconds(32.0)),
+        )
+        .unwrap();
+        assert_eq!(
+            resync(seconds(10.0)),
+            Ok(seconds(12.0))
+        );
+        assert_eq!(
+            resync(seconds(15.0)),
+            Ok(seconds(22.0))
+        );
+        assert_eq!(
+            resync(seconds(20.0)),
+            Ok(seconds(32.0))
+        );
+        assert_eq!(resync(seconds(0.0)), Ok(seconds(0.0)));
+
+        assert!(super::resync(
+            (seconds(10.0), seconds(12.0)),
+            (seconds(10.0), seconds(32.0)),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,2470 @@
+//! A parser for the Timed Text Markup Language (`.ttml`/`.dfxp`) format provided by [`subtp::ttml::Ttml`](Ttml).
+//!
+//! Supports the TTML1/TTML2 core vocabulary used by DFXP and the IMSC1 text profile:
+//! `<tt>`, the styling and layout in `<head>`, and `<body>`, `<div>`, `<p>`, `<span>` and `<br>`
+//! with `begin`, `end` and `dur` time expressions.
+//!
+//! ## Example
+//! ```
+//! use subtp::ttml::Ttml;
+//! use subtp::ttml::TtmlBlock;
+//! use subtp::ttml::TtmlContent;
+//! use subtp::ttml::TtmlTime;
+//!
+//! let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+//! <tt xmlns="http://www.w3.org/ns/ttml" xmlns:tts="http://www.w3.org/ns/ttml#styling" xml:lang="en">
+//!   <head>
+//!     <styling>
+//!       <style xml:id="s1" tts:color="white"/>
+//!     </styling>
+//!     <layout>
+//!       <region xml:id="bottom" tts:origin="10% 80%" tts:extent="80% 20%"/>
+//!     </layout>
+//!   </head>
+//!   <body style="s1" region="bottom">
+//!     <div>
+//!       <p begin="00:00:01.000" end="00:00:02.000">Hello, world!</p>
+//!       <p begin="00:00:03.000" end="00:00:04.000">This is a <span tts:fontStyle="italic">sample</span>.<br/>Thank you for your reading.</p>
+//!     </div>
+//!   </body>
+//! </tt>
+//! "#;
+//!
+//! let ttml = Ttml::parse(text).unwrap();
+//!
+//! assert_eq!(ttml.lang, Some("en".to_string()));
+//! assert_eq!(ttml.head.styles[0].attributes.get("tts:color"), Some("white"));
+//! assert_eq!(ttml.head.regions[0].id, "bottom");
+//!
+//! let TtmlBlock::Paragraph(paragraph) = &ttml.body.divs[0].blocks[0] else {
+//!     panic!();
+//! };
+//! assert_eq!(
+//!     paragraph.timing.begin,
+//!     Some(TtmlTime::Clock {
+//!         hours: 0,
+//!         minutes: 0,
+//!         seconds: 1,
+//!         milliseconds: 0,
+//!     })
+//! );
+//! assert_eq!(
+//!     paragraph.content,
+//!     vec![TtmlContent::Text("Hello, world!".to_string())]
+//! );
+//!
+//! let rendered = ttml.render();
+//! assert_eq!(rendered, text);
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::srt::{SrtSubtitle, SrtTimestamp, SubRip};
+use crate::ttml_parser;
+use crate::vtt::{VttBlock, VttCue, VttTimestamp, VttTimings, WebVtt};
+use crate::ParseResult;
+
+/// The namespace of TTML.
+const TTML_NAMESPACE: &str = "http://www.w3.org/ns/ttml";
+
+/// The namespace of TTML parameters.
+const PARAMETER_NAMESPACE: &str = "http://www.w3.org/ns/ttml#parameter";
+
+/// The Timed Text Markup Language (`.ttml`/`.dfxp`) format.
+///
+/// Parses from text by [`Ttml::parse`](Ttml::parse)
+/// and renders to text by [`Ttml::render`](Ttml::render).
+///
+/// Namespace prefixes of elements are accepted on parsing and dropped on rendering,
+/// while the namespace declarations are kept in [`Ttml::attributes`](Ttml::attributes).
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::Ttml;
+/// use subtp::ttml::TtmlBody;
+/// use subtp::ttml::TtmlDiv;
+/// use subtp::ttml::TtmlParagraph;
+/// use subtp::ttml::TtmlTiming;
+/// use subtp::ttml::TtmlTime;
+/// use subtp::ttml::TtmlContent;
+///
+/// let ttml = Ttml {
+///     lang: Some("en".to_string()),
+///     body: TtmlBody {
+///         divs: vec![TtmlDiv {
+///             blocks: vec![TtmlParagraph {
+///                 timing: TtmlTiming {
+///                     begin: Some(TtmlTime::Clock {
+///                         hours: 0,
+///                         minutes: 0,
+///                         seconds: 1,
+///                         milliseconds: 0,
+///                     }),
+///                     end: Some(TtmlTime::Clock {
+///                         hours: 0,
+///                         minutes: 0,
+///                         seconds: 2,
+///                         milliseconds: 0,
+///                     }),
+///                     ..Default::default()
+///                 },
+///                 content: vec![TtmlContent::Text("Hello, world!".to_string())],
+///                 ..Default::default()
+///             }
+///             .into()],
+///             ..Default::default()
+///         }],
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     ttml.render(),
+///     r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <tt xmlns="http://www.w3.org/ns/ttml" xml:lang="en">
+///   <body>
+///     <div>
+///       <p begin="00:00:01.000" end="00:00:02.000">Hello, world!</p>
+///     </div>
+///   </body>
+/// </tt>
+/// "#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Ttml {
+    /// The language of `xml:lang`.
+    pub lang: Option<String>,
+    /// The timing parameters of `ttp:*`.
+    pub parameters: TtmlParameters,
+    /// The other attributes of `<tt>` such as the namespace declarations.
+    pub attributes: TtmlAttributes,
+    /// The `<head>` element.
+    pub head: TtmlHead,
+    /// The `<body>` element.
+    pub body: TtmlBody,
+}
+
+impl Ttml {
+    /// Parses the Timed Text Markup Language format from the given text.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::ttml::Ttml;
+    ///
+    /// let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <tt xmlns="http://www.w3.org/ns/ttml">
+    ///   <body>
+    ///     <div>
+    ///       <p begin="1s" end="2s">Hello, world!</p>
+    ///     </div>
+    ///   </body>
+    /// </tt>
+    /// "#;
+    ///
+    /// let ttml = Ttml::parse(text).unwrap();
+    /// ```
+    pub fn parse(text: &str) -> ParseResult<Self> {
+        ttml_parser::ttml(text).map_err(|err| err.into())
+    }
+
+    /// Renders the text from the Timed Text Markup Language format.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::ttml::Ttml;
+    /// use subtp::ttml::TtmlBody;
+    /// use subtp::ttml::TtmlDiv;
+    /// use subtp::ttml::TtmlParagraph;
+    /// use subtp::ttml::TtmlContent;
+    ///
+    /// let ttml = Ttml {
+    ///     body: TtmlBody {
+    ///         divs: vec![TtmlDiv {
+    ///             blocks: vec![TtmlParagraph {
+    ///                 content: vec![TtmlContent::Text("Hello, world!".to_string())],
+    ///                 ..Default::default()
+    ///             }
+    ///             .into()],
+    ///             ..Default::default()
+    ///         }],
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let rendered = ttml.render();
+    /// ```
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    /// Resolves the timing of each paragraph into the flat list of cues ordered by the start time.
+    ///
+    /// Paragraphs whose end time can not be resolved are skipped.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::ttml::Ttml;
+    ///
+    /// let text = r#"<tt xmlns="http://www.w3.org/ns/ttml">
+    ///   <body>
+    ///     <div begin="10s">
+    ///       <p xml:id="c1" begin="1s" dur="2s">Hello,<br/>world!</p>
+    ///     </div>
+    ///   </body>
+    /// </tt>"#;
+    ///
+    /// let cues = Ttml::parse(text).unwrap().cues();
+    ///
+    /// assert_eq!(cues[0].id, Some("c1".to_string()));
+    /// assert_eq!(cues[0].start, Duration::from_secs(11));
+    /// assert_eq!(cues[0].end, Duration::from_secs(13));
+    /// assert_eq!(cues[0].lines, vec!["Hello,", "world!"]);
+    /// ```
+    pub fn cues(&self) -> Vec<TtmlCue> {
+        self.timed_paragraphs()
+            .into_iter()
+            .map(|timed| {
+                TtmlCue {
+                    id: timed.paragraph.id.clone(),
+                    start: timed.start,
+                    end: timed.end,
+                    region: timed
+                        .region
+                        .map(|region| region.to_string()),
+                    lines: self.lines(
+                        timed.paragraph,
+                        timed.emphasis,
+                        false,
+                        false,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Collects the paragraphs with the resolved timing in order of the start time.
+    fn timed_paragraphs(&self) -> Vec<TimedParagraph> {
+        let (begin, end) =
+            self.body
+                .timing
+                .resolve(Duration::ZERO, None, &self.parameters);
+        let emphasis = self.emphasis(
+            Emphasis::default(),
+            &self.body.style,
+            &self.body.attributes,
+        );
+
+        let mut paragraphs = vec![];
+        for div in &self.body.divs {
+            self.collect_paragraphs(
+                div,
+                begin,
+                end,
+                self.body.region.as_deref(),
+                emphasis,
+                &mut paragraphs,
+            );
+        }
+        paragraphs.sort_by_key(|timed| timed.start);
+
+        paragraphs
+    }
+
+    fn collect_paragraphs<'a>(
+        &self,
+        div: &'a TtmlDiv,
+        parent_begin: Duration,
+        parent_end: Option<Duration>,
+        region: Option<&'a str>,
+        emphasis: Emphasis,
+        paragraphs: &mut Vec<TimedParagraph<'a>>,
+    ) {
+        let (begin, end) = div.timing.resolve(
+            parent_begin,
+            parent_end,
+            &self.parameters,
+        );
+        let region = div
+            .region
+            .as_deref()
+            .or(region);
+        let emphasis = self.emphasis(emphasis, &div.style, &div.attributes);
+
+        for block in &div.blocks {
+            match block {
+                | TtmlBlock::Div(div) => {
+                    self.collect_paragraphs(
+                        div, begin, end, region, emphasis, paragraphs,
+                    );
+                },
+                | TtmlBlock::Paragraph(paragraph) => {
+                    let (start, end) =
+                        paragraph
+                            .timing
+                            .resolve(begin, end, &self.parameters);
+                    if let Some(end) = end {
+                        paragraphs.push(TimedParagraph {
+                            start,
+                            end,
+                            region: paragraph
+                                .region
+                                .as_deref()
+                                .or(region),
+                            emphasis: self.emphasis(
+                                emphasis,
+                                &paragraph.style,
+                                &paragraph.attributes,
+                            ),
+                            paragraph,
+                        });
+                    }
+                },
+            }
+        }
+    }
+
+    /// Looks up the styling attribute from the inline attributes and then the referential styles.
+    fn styling<'a>(
+        &'a self,
+        name: &str,
+        style: &[String],
+        attributes: &'a TtmlAttributes,
+        depth: usize,
+    ) -> Option<&'a str> {
+        if let Some(value) = attributes.get(name) {
+            return Some(value);
+        }
+        if depth > 8 {
+            return None;
+        }
+
+        style
+            .iter()
+            .rev()
+            .filter_map(|id| {
+                self.head
+                    .styles
+                    .iter()
+                    .find(|s| &s.id == id)
+            })
+            .find_map(|s| {
+                self.styling(name, &s.style, &s.attributes, depth + 1)
+            })
+    }
+
+    fn emphasis(
+        &self,
+        parent: Emphasis,
+        style: &[String],
+        attributes: &TtmlAttributes,
+    ) -> Emphasis {
+        let styling = |name| self.styling(name, style, attributes, 0);
+
+        Emphasis {
+            italic: styling("tts:fontStyle")
+                .map(|value| value == "italic" || value == "oblique")
+                .unwrap_or(parent.italic),
+            bold: styling("tts:fontWeight")
+                .map(|value| value == "bold")
+                .unwrap_or(parent.bold),
+            underline: styling("tts:textDecoration")
+                .map(|value| {
+                    value
+                        .split_whitespace()
+                        .any(|value| value == "underline")
+                })
+                .unwrap_or(parent.underline),
+        }
+    }
+
+    /// Flattens the content of the paragraph into the lines,
+    /// optionally with `<i>`, `<b>` and `<u>` tags and escaping.
+    fn lines(
+        &self,
+        paragraph: &TtmlParagraph,
+        emphasis: Emphasis,
+        markup: bool,
+        escape: bool,
+    ) -> Vec<String> {
+        let mut lines = vec![String::new()];
+        self.write_lines(
+            &paragraph.content,
+            Emphasis::default(),
+            emphasis,
+            markup,
+            escape,
+            &mut lines,
+        );
+
+        lines
+    }
+
+    fn write_lines(
+        &self,
+        content: &[TtmlContent],
+        outer: Emphasis,
+        inner: Emphasis,
+        markup: bool,
+        escape: bool,
+        lines: &mut Vec<String>,
+    ) {
+        let tags = [
+            ("i", outer.italic, inner.italic),
+            ("b", outer.bold, inner.bold),
+            ("u", outer.underline, inner.underline),
+        ];
+        let opened = tags
+            .iter()
+            .filter(|(_, outer, inner)| markup && *inner && !*outer)
+            .map(|(tag, ..)| *tag)
+            .collect::<Vec<_>>();
+
+        for tag in &opened {
+            lines
+                .last_mut()
+                .unwrap()
+                .push_str(&format!("<{}>", tag));
+        }
+
+        for item in content {
+            match item {
+                | TtmlContent::Text(text) => {
+                    let line = lines.last_mut().unwrap();
+                    if escape {
+                        line.push_str(
+                            &text
+                                .replace('&', "&amp;")
+                                .replace('<', "&lt;")
+                                .replace('>', "&gt;"),
+                        );
+                    } else {
+                        line.push_str(text);
+                    }
+                },
+                | TtmlContent::LineBreak => lines.push(String::new()),
+                | TtmlContent::Span(span) => {
+                    let emphasis =
+                        self.emphasis(inner, &span.style, &span.attributes);
+                    self.write_lines(
+                        &span.content,
+                        inner,
+                        emphasis,
+                        markup,
+                        escape,
+                        lines,
+                    );
+                },
+            }
+        }
+
+        for tag in opened.iter().rev() {
+            lines
+                .last_mut()
+                .unwrap()
+                .push_str(&format!("</{}>", tag));
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Ttml {
+    fn default() -> Self {
+        Self {
+            lang: None,
+            parameters: TtmlParameters::default(),
+            attributes: TtmlAttributes::default(),
+            head: TtmlHead::default(),
+            body: TtmlBody::default(),
+        }
+    }
+}
+
+impl Display for Ttml {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(
+            f,
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#
+        )?;
+
+        write!(f, "<tt")?;
+        if self
+            .attributes
+            .get("xmlns")
+            .is_none()
+        {
+            write!(f, r#" xmlns="{}""#, TTML_NAMESPACE)?;
+        }
+        if self.parameters != TtmlParameters::default()
+            && self
+                .attributes
+                .get("xmlns:ttp")
+                .is_none()
+        {
+            write!(
+                f,
+                r#" xmlns:ttp="{}""#,
+                PARAMETER_NAMESPACE
+            )?;
+        }
+        write!(f, "{}", self.attributes)?;
+        if let Some(lang) = &self.lang {
+            write!(f, r#" xml:lang="{}""#, escape(lang))?;
+        }
+        writeln!(f, "{}>", self.parameters)?;
+
+        if !self.head.is_empty() {
+            indent(f, &self.head)?;
+        }
+        indent(f, &self.body)?;
+
+        writeln!(f, "</tt>")
+    }
+}
+
+/// A paragraph with the resolved timing.
+struct TimedParagraph<'a> {
+    start: Duration,
+    end: Duration,
+    region: Option<&'a str>,
+    emphasis: Emphasis,
+    paragraph: &'a TtmlParagraph,
+}
+
+/// The emphasis styles that can be mapped onto the markup of other formats.
+#[derive(Clone, Copy, Default)]
+struct Emphasis {
+    italic: bool,
+    bold: bool,
+    underline: bool,
+}
+
+/// A cue flattened from a paragraph by [`Ttml::cues`](Ttml::cues).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlCue {
+    /// The `xml:id` of the paragraph.
+    pub id: Option<String>,
+    /// The resolved start time.
+    pub start: Duration,
+    /// The resolved end time.
+    pub end: Duration,
+    /// The region of the paragraph or inherited from its ancestors.
+    pub region: Option<String>,
+    /// The plain text lines separated by `<br/>`.
+    pub lines: Vec<String>,
+}
+
+impl Default for TtmlCue {
+    fn default() -> Self {
+        Self {
+            id: None,
+            start: Duration::ZERO,
+            end: Duration::ZERO,
+            region: None,
+            lines: vec![],
+        }
+    }
+}
+
+impl From<&Ttml> for SubRip {
+    /// Converts the paragraphs into the subtitles,
+    /// mapping italic, bold and underline styles onto `<i>`, `<b>` and `<u>` tags.
+    /// The times beyond [`SrtTimestamp::MAX`] are clamped.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::srt::SubRip;
+    /// use subtp::ttml::Ttml;
+    ///
+    /// let text = r#"<tt xmlns="http://www.w3.org/ns/ttml" xmlns:tts="http://www.w3.org/ns/ttml#styling">
+    ///   <body>
+    ///     <div>
+    ///       <p begin="1s" end="2s">Hello, <span tts:fontStyle="italic">world</span>!</p>
+    ///     </div>
+    ///   </body>
+    /// </tt>"#;
+    ///
+    /// let srt = SubRip::from(&Ttml::parse(text).unwrap());
+    ///
+    /// assert_eq!(
+    ///     srt.render(),
+    ///     "1\n00:00:01,000 --> 00:00:02,000\nHello, <i>world</i>!\n"
+    /// );
+    /// ```
+    fn from(ttml: &Ttml) -> Self {
+        let subtitles = ttml
+            .timed_paragraphs()
+            .into_iter()
+            .enumerate()
+            .map(|(index, timed)| {
+                SrtSubtitle {
+                    sequence: index as u32 + 1,
+                    start: SrtTimestamp::saturating_from(timed.start),
+                    end: SrtTimestamp::saturating_from(timed.end),
+                    text: ttml.lines(
+                        timed.paragraph,
+                        timed.emphasis,
+                        true,
+                        false,
+                    ),
+                    line_position: None,
+                }
+            })
+            .collect();
+
+        SubRip {
+            subtitles,
+        }
+    }
+}
+
+impl From<&Ttml> for WebVtt {
+    /// Converts the paragraphs into the cues,
+    /// mapping italic, bold and underline styles onto `<i>`, `<b>` and `<u>` tags.
+    /// The times beyond [`VttTimestamp::MAX`] are clamped.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::ttml::Ttml;
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = r#"<tt xmlns="http://www.w3.org/ns/ttml">
+    ///   <body>
+    ///     <div>
+    ///       <p xml:id="c1" begin="1s" end="2s">Tom &amp; Jerry</p>
+    ///     </div>
+    ///   </body>
+    /// </tt>"#;
+    ///
+    /// let vtt = WebVtt::from(&Ttml::parse(text).unwrap());
+    ///
+    /// assert_eq!(
+    ///     vtt.render(),
+    ///     "WEBVTT\n\nc1\n00:00:01.000 --> 00:00:02.000\nTom &amp; Jerry\n"
+    /// );
+    /// ```
+    fn from(ttml: &Ttml) -> Self {
+        let blocks = ttml
+            .timed_paragraphs()
+            .into_iter()
+            .map(|timed| {
+                VttBlock::from(VttCue {
+                    identifier: timed.paragraph.id.clone(),
+                    timings: VttTimings {
+                        start: VttTimestamp::saturating_from(timed.start),
+                        end: VttTimestamp::saturating_from(timed.end),
+                    },
+                    settings: None,
+                    payload: ttml.lines(
+                        timed.paragraph,
+                        timed.emphasis,
+                        true,
+                        true,
+                    ),
+                })
+            })
+            .collect();
+
+        WebVtt {
+            blocks,
+            ..Default::default()
+        }
+    }
+}
+
+/// The timing parameters of `ttp:*` attributes on `<tt>`.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use subtp::ttml::TtmlParameters;
+/// use subtp::ttml::TtmlTime;
+/// use subtp::ttml::TtmlTimeMetric;
+///
+/// let parameters = TtmlParameters {
+///     frame_rate: Some(25),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     parameters.to_string(),
+///     r#" ttp:frameRate="25""#.to_string()
+/// );
+///
+/// let time = TtmlTime::Offset {
+///     value: 50.0,
+///     metric: TtmlTimeMetric::Frames,
+/// };
+/// assert_eq!(time.to_duration(&parameters), Duration::from_secs(2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlParameters {
+    /// The frame rate of `ttp:frameRate`, 30 if not specified.
+    pub frame_rate: Option<u32>,
+    /// The numerator and denominator of `ttp:frameRateMultiplier`.
+    pub frame_rate_multiplier: Option<(u32, u32)>,
+    /// The sub-frame rate of `ttp:subFrameRate`, 1 if not specified.
+    pub sub_frame_rate: Option<u32>,
+    /// The tick rate of `ttp:tickRate`.
+    pub tick_rate: Option<u32>,
+    /// The profile designator of `ttp:profile`, e.g. `http://www.w3.org/ns/ttml/profile/imsc1/text`.
+    pub profile: Option<String>,
+}
+
+impl TtmlParameters {
+    /// The effective frame rate with the multiplier applied.
+    pub fn effective_frame_rate(&self) -> f64 {
+        let frame_rate = self.frame_rate.unwrap_or(30) as f64;
+        match self.frame_rate_multiplier {
+            | Some((numerator, denominator)) if denominator != 0 => {
+                frame_rate * numerator as f64 / denominator as f64
+            },
+            | _ => frame_rate,
+        }
+    }
+
+    /// The effective tick rate,
+    /// which defaults to the frame rate times the sub-frame rate if the frame rate is specified, or 1 otherwise.
+    pub fn effective_tick_rate(&self) -> f64 {
+        match (self.tick_rate, self.frame_rate) {
+            | (Some(tick_rate), _) => tick_rate as f64,
+            | (None, Some(frame_rate)) => {
+                frame_rate as f64
+                    * self
+                        .sub_frame_rate
+                        .unwrap_or(1) as f64
+            },
+            | (None, None) => 1.0,
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlParameters {
+    fn default() -> Self {
+        Self {
+            frame_rate: None,
+            frame_rate_multiplier: None,
+            sub_frame_rate: None,
+            tick_rate: None,
+            profile: None,
+        }
+    }
+}
+
+impl Display for TtmlParameters {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        if let Some(frame_rate) = self.frame_rate {
+            write!(f, r#" ttp:frameRate="{}""#, frame_rate)?;
+        }
+        if let Some((numerator, denominator)) = self.frame_rate_multiplier {
+            write!(
+                f,
+                r#" ttp:frameRateMultiplier="{} {}""#,
+                numerator, denominator
+            )?;
+        }
+        if let Some(sub_frame_rate) = self.sub_frame_rate {
+            write!(
+                f,
+                r#" ttp:subFrameRate="{}""#,
+                sub_frame_rate
+            )?;
+        }
+        if let Some(tick_rate) = self.tick_rate {
+            write!(f, r#" ttp:tickRate="{}""#, tick_rate)?;
+        }
+        if let Some(profile) = &self.profile {
+            write!(
+                f,
+                r#" ttp:profile="{}""#,
+                escape(profile)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The attributes of an element in order of appearance.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlAttributes;
+///
+/// let mut attributes = TtmlAttributes::default();
+/// attributes.set("tts:color", "white");
+/// attributes.set("tts:fontStyle", "italic");
+/// attributes.set("tts:color", "yellow");
+///
+/// assert_eq!(attributes.get("tts:color"), Some("yellow"));
+/// assert_eq!(
+///     attributes.to_string(),
+///     r#" tts:color="yellow" tts:fontStyle="italic""#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlAttributes {
+    /// The attributes.
+    pub entries: Vec<TtmlAttribute>,
+}
+
+impl TtmlAttributes {
+    /// Gets the value of the attribute by the qualified name, e.g. `tts:color`.
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Sets the value of the attribute, appending it if not present.
+    pub fn set(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|attribute| attribute.name == name)
+        {
+            | Some(attribute) => attribute.value = value.to_string(),
+            | None => {
+                self.entries
+                    .push(TtmlAttribute {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })
+            },
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlAttributes {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+        }
+    }
+}
+
+impl Display for TtmlAttributes {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        for attribute in &self.entries {
+            write!(f, " {}", attribute)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An attribute of an element.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlAttribute;
+///
+/// let attribute = TtmlAttribute {
+///     name: "tts:fontFamily".to_string(),
+///     value: "\"Noto Sans\"".to_string(),
+/// };
+///
+/// assert_eq!(
+///     attribute.to_string(),
+///     r#"tts:fontFamily="&quot;Noto Sans&quot;""#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlAttribute {
+    /// The qualified name with the namespace prefix.
+    pub name: String,
+    /// The unescaped value.
+    pub value: String,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlAttribute {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            value: String::new(),
+        }
+    }
+}
+
+impl Display for TtmlAttribute {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            r#"{}="{}""#,
+            self.name,
+            escape(&self.value)
+        )
+    }
+}
+
+/// The `<head>` element.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlHead;
+/// use subtp::ttml::TtmlStyle;
+///
+/// let mut head = TtmlHead {
+///     styles: vec![TtmlStyle {
+///         id: "s1".to_string(),
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+/// head.styles[0].attributes.set("tts:color", "white");
+///
+/// assert_eq!(
+///     head.to_string(),
+///     r#"<head>
+///   <styling>
+///     <style xml:id="s1" tts:color="white"/>
+///   </styling>
+/// </head>
+/// "#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlHead {
+    /// The styles in `<styling>`.
+    pub styles: Vec<TtmlStyle>,
+    /// The regions in `<layout>`.
+    pub regions: Vec<TtmlRegion>,
+    /// The other elements such as `<metadata>`, kept as they are.
+    pub elements: Vec<TtmlElement>,
+}
+
+impl TtmlHead {
+    /// Returns `true` if the head has no content.
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+            && self.regions.is_empty()
+            && self.elements.is_empty()
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlHead {
+    fn default() -> Self {
+        Self {
+            styles: vec![],
+            regions: vec![],
+            elements: vec![],
+        }
+    }
+}
+
+impl Display for TtmlHead {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(f, "<head>")?;
+
+        for element in &self.elements {
+            writeln!(f, "  {}", element)?;
+        }
+
+        if !self.styles.is_empty() {
+            writeln!(f, "  <styling>")?;
+            for style in &self.styles {
+                writeln!(f, "    {}", style)?;
+            }
+            writeln!(f, "  </styling>")?;
+        }
+
+        if !self.regions.is_empty() {
+            writeln!(f, "  <layout>")?;
+            for region in &self.regions {
+                for line in region.to_string().lines() {
+                    writeln!(f, "    {}", line)?;
+                }
+            }
+            writeln!(f, "  </layout>")?;
+        }
+
+        writeln!(f, "</head>")
+    }
+}
+
+/// The `<style>` element.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlStyle;
+/// use subtp::ttml::TtmlAttributes;
+/// use subtp::ttml::TtmlAttribute;
+///
+/// let style = TtmlStyle {
+///     id: "s2".to_string(),
+///     style: vec!["s1".to_string()],
+///     attributes: TtmlAttributes {
+///         entries: vec![TtmlAttribute {
+///             name: "tts:fontStyle".to_string(),
+///             value: "italic".to_string(),
+///         }],
+///     },
+/// };
+///
+/// assert_eq!(
+///     style.to_string(),
+///     r#"<style xml:id="s2" style="s1" tts:fontStyle="italic"/>"#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlStyle {
+    /// The `xml:id`, which is empty for an inline style in `<region>`.
+    pub id: String,
+    /// The referential styles.
+    pub style: Vec<String>,
+    /// The styling attributes such as `tts:color`.
+    pub attributes: TtmlAttributes,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlStyle {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            style: vec![],
+            attributes: TtmlAttributes::default(),
+        }
+    }
+}
+
+impl Display for TtmlStyle {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "<style")?;
+        if !self.id.is_empty() {
+            write!(f, r#" xml:id="{}""#, escape(&self.id))?;
+        }
+        write_style(f, &self.style)?;
+        write!(f, "{}/>", self.attributes)
+    }
+}
+
+/// The `<region>` element.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlRegion;
+///
+/// let mut region = TtmlRegion {
+///     id: "bottom".to_string(),
+///     ..Default::default()
+/// };
+/// region.attributes.set("tts:origin", "10% 80%");
+/// region.attributes.set("tts:extent", "80% 20%");
+///
+/// assert_eq!(
+///     region.to_string(),
+///     r#"<region xml:id="bottom" tts:origin="10% 80%" tts:extent="80% 20%"/>"#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlRegion {
+    /// The `xml:id`.
+    pub id: String,
+    /// The referential styles.
+    pub style: Vec<String>,
+    /// The styling attributes such as `tts:origin` and `tts:extent`.
+    pub attributes: TtmlAttributes,
+    /// The inline `<style>` elements.
+    pub styles: Vec<TtmlStyle>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlRegion {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            style: vec![],
+            attributes: TtmlAttributes::default(),
+            styles: vec![],
+        }
+    }
+}
+
+impl Display for TtmlRegion {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            r#"<region xml:id="{}""#,
+            escape(&self.id)
+        )?;
+        write_style(f, &self.style)?;
+        write!(f, "{}", self.attributes)?;
+
+        if self.styles.is_empty() {
+            return write!(f, "/>");
+        }
+
+        writeln!(f, ">")?;
+        for style in &self.styles {
+            writeln!(f, "  {}", style)?;
+        }
+        write!(f, "</region>")
+    }
+}
+
+/// The `<body>` element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlBody {
+    /// The `xml:id`.
+    pub id: Option<String>,
+    /// The referential styles.
+    pub style: Vec<String>,
+    /// The region.
+    pub region: Option<String>,
+    /// The timing.
+    pub timing: TtmlTiming,
+    /// The other attributes such as the inline styles.
+    pub attributes: TtmlAttributes,
+    /// The `<div>` elements.
+    pub divs: Vec<TtmlDiv>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlBody {
+    fn default() -> Self {
+        Self {
+            id: None,
+            style: vec![],
+            region: None,
+            timing: TtmlTiming::default(),
+            attributes: TtmlAttributes::default(),
+            divs: vec![],
+        }
+    }
+}
+
+impl Display for TtmlBody {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "<body")?;
+        write_common(
+            f,
+            &self.id,
+            &self.style,
+            &self.region,
+            &self.timing,
+            &self.attributes,
+        )?;
+
+        if self.divs.is_empty() {
+            return writeln!(f, "/>");
+        }
+
+        writeln!(f, ">")?;
+        for div in &self.divs {
+            indent(f, div)?;
+        }
+        writeln!(f, "</body>")
+    }
+}
+
+/// The `<div>` element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlDiv {
+    /// The `xml:id`.
+    pub id: Option<String>,
+    /// The referential styles.
+    pub style: Vec<String>,
+    /// The region.
+    pub region: Option<String>,
+    /// The timing.
+    pub timing: TtmlTiming,
+    /// The other attributes such as the inline styles.
+    pub attributes: TtmlAttributes,
+    /// The nested `<div>` and `<p>` elements.
+    pub blocks: Vec<TtmlBlock>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlDiv {
+    fn default() -> Self {
+        Self {
+            id: None,
+            style: vec![],
+            region: None,
+            timing: TtmlTiming::default(),
+            attributes: TtmlAttributes::default(),
+            blocks: vec![],
+        }
+    }
+}
+
+impl Display for TtmlDiv {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "<div")?;
+        write_common(
+            f,
+            &self.id,
+            &self.style,
+            &self.region,
+            &self.timing,
+            &self.attributes,
+        )?;
+
+        if self.blocks.is_empty() {
+            return writeln!(f, "/>");
+        }
+
+        writeln!(f, ">")?;
+        for block in &self.blocks {
+            indent(f, block)?;
+        }
+        writeln!(f, "</div>")
+    }
+}
+
+/// A block in `<div>`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TtmlBlock {
+    /// The nested `<div>` element.
+    Div(TtmlDiv),
+    /// The `<p>` element.
+    Paragraph(TtmlParagraph),
+}
+
+impl From<TtmlDiv> for TtmlBlock {
+    fn from(div: TtmlDiv) -> Self {
+        Self::Div(div)
+    }
+}
+
+impl From<TtmlParagraph> for TtmlBlock {
+    fn from(paragraph: TtmlParagraph) -> Self {
+        Self::Paragraph(paragraph)
+    }
+}
+
+impl Display for TtmlBlock {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Div(div) => write!(f, "{}", div),
+            | Self::Paragraph(paragraph) => writeln!(f, "{}", paragraph),
+        }
+    }
+}
+
+/// The `<p>` element.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlParagraph;
+/// use subtp::ttml::TtmlTiming;
+/// use subtp::ttml::TtmlTime;
+/// use subtp::ttml::TtmlTimeMetric;
+/// use subtp::ttml::TtmlContent;
+///
+/// let paragraph = TtmlParagraph {
+///     id: Some("c1".to_string()),
+///     timing: TtmlTiming {
+///         begin: Some(TtmlTime::Offset {
+///             value: 1.5,
+///             metric: TtmlTimeMetric::Seconds,
+///         }),
+///         dur: Some(TtmlTime::Offset {
+///             value: 2.0,
+///             metric: TtmlTimeMetric::Seconds,
+///         }),
+///         ..Default::default()
+///     },
+///     content: vec![
+///         TtmlContent::Text("Hello,".to_string()),
+///         TtmlContent::LineBreak,
+///         TtmlContent::Text("<world>".to_string()),
+///     ],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     paragraph.to_string(),
+///     r#"<p xml:id="c1" begin="1.5s" dur="2s">Hello,<br/>&lt;world&gt;</p>"#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlParagraph {
+    /// The `xml:id`.
+    pub id: Option<String>,
+    /// The referential styles.
+    pub style: Vec<String>,
+    /// The region.
+    pub region: Option<String>,
+    /// The timing.
+    pub timing: TtmlTiming,
+    /// The other attributes such as the inline styles.
+    pub attributes: TtmlAttributes,
+    /// The content with whitespaces collapsed unless `xml:space="preserve"`.
+    pub content: Vec<TtmlContent>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlParagraph {
+    fn default() -> Self {
+        Self {
+            id: None,
+            style: vec![],
+            region: None,
+            timing: TtmlTiming::default(),
+            attributes: TtmlAttributes::default(),
+            content: vec![],
+        }
+    }
+}
+
+impl Display for TtmlParagraph {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "<p")?;
+        write_common(
+            f,
+            &self.id,
+            &self.style,
+            &self.region,
+            &self.timing,
+            &self.attributes,
+        )?;
+
+        if self.content.is_empty() {
+            return write!(f, "/>");
+        }
+
+        write!(f, ">")?;
+        for content in &self.content {
+            write!(f, "{}", content)?;
+        }
+        write!(f, "</p>")
+    }
+}
+
+/// The `<span>` element.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlSpan;
+/// use subtp::ttml::TtmlContent;
+///
+/// let mut span = TtmlSpan {
+///     content: vec![TtmlContent::Text("world".to_string())],
+///     ..Default::default()
+/// };
+/// span.attributes.set("tts:fontWeight", "bold");
+///
+/// assert_eq!(
+///     span.to_string(),
+///     r#"<span tts:fontWeight="bold">world</span>"#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlSpan {
+    /// The `xml:id`.
+    pub id: Option<String>,
+    /// The referential styles.
+    pub style: Vec<String>,
+    /// The region.
+    pub region: Option<String>,
+    /// The timing.
+    pub timing: TtmlTiming,
+    /// The other attributes such as the inline styles.
+    pub attributes: TtmlAttributes,
+    /// The content.
+    pub content: Vec<TtmlContent>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlSpan {
+    fn default() -> Self {
+        Self {
+            id: None,
+            style: vec![],
+            region: None,
+            timing: TtmlTiming::default(),
+            attributes: TtmlAttributes::default(),
+            content: vec![],
+        }
+    }
+}
+
+impl Display for TtmlSpan {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "<span")?;
+        write_common(
+            f,
+            &self.id,
+            &self.style,
+            &self.region,
+            &self.timing,
+            &self.attributes,
+        )?;
+
+        if self.content.is_empty() {
+            return write!(f, "/>");
+        }
+
+        write!(f, ">")?;
+        for content in &self.content {
+            write!(f, "{}", content)?;
+        }
+        write!(f, "</span>")
+    }
+}
+
+/// The content of `<p>` and `<span>`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TtmlContent {
+    /// The text.
+    Text(String),
+    /// The `<span>` element.
+    Span(TtmlSpan),
+    /// The `<br>` element.
+    LineBreak,
+}
+
+impl Display for TtmlContent {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Text(text) => write!(f, "{}", escape(text)),
+            | Self::Span(span) => write!(f, "{}", span),
+            | Self::LineBreak => write!(f, "<br/>"),
+        }
+    }
+}
+
+/// The timing attributes of `begin`, `end` and `dur`.
+///
+/// The times are relative to the begin of the parent element.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlTiming;
+/// use subtp::ttml::TtmlTime;
+///
+/// let timing = TtmlTiming {
+///     begin: Some(TtmlTime::Clock {
+///         hours: 0,
+///         minutes: 0,
+///         seconds: 1,
+///         milliseconds: 0,
+///     }),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     timing.to_string(),
+///     r#" begin="00:00:01.000""#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlTiming {
+    /// The `begin` attribute.
+    pub begin: Option<TtmlTime>,
+    /// The `end` attribute.
+    pub end: Option<TtmlTime>,
+    /// The `dur` attribute.
+    pub dur: Option<TtmlTime>,
+}
+
+impl TtmlTiming {
+    /// Resolves the absolute begin and end in the parent interval.
+    fn resolve(
+        &self,
+        parent_begin: Duration,
+        parent_end: Option<Duration>,
+        parameters: &TtmlParameters,
+    ) -> (Duration, Option<Duration>) {
+        let begin = parent_begin.saturating_add(
+            self.begin
+                .map(|time| time.to_duration(parameters))
+                .unwrap_or_default(),
+        );
+        let end = self.end.map(|time| {
+            parent_begin.saturating_add(time.to_duration(parameters))
+        });
+        let dur = self.dur.map(|time| {
+            begin.saturating_add(time.to_duration(parameters))
+        });
+        let end = match (end, dur) {
+            | (Some(end), Some(dur)) => Some(end.min(dur)),
+            | (Some(end), None) => Some(end),
+            | (None, Some(dur)) => Some(dur),
+            | (None, None) => parent_end,
+        };
+        let end = match (end, parent_end) {
+            | (Some(end), Some(parent_end)) => Some(end.min(parent_end)),
+            | (end, _) => end,
+        };
+
+        (begin, end.map(|end| end.max(begin)))
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlTiming {
+    fn default() -> Self {
+        Self {
+            begin: None,
+            end: None,
+            dur: None,
+        }
+    }
+}
+
+impl Display for TtmlTiming {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        if let Some(begin) = &self.begin {
+            write!(f, r#" begin="{}""#, begin)?;
+        }
+        if let Some(end) = &self.end {
+            write!(f, r#" end="{}""#, end)?;
+        }
+        if let Some(dur) = &self.dur {
+            write!(f, r#" dur="{}""#, dur)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The time expression.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use subtp::ttml::TtmlParameters;
+/// use subtp::ttml::TtmlTime;
+/// use subtp::ttml::TtmlTimeMetric;
+///
+/// let parameters = TtmlParameters {
+///     tick_rate: Some(10_000_000),
+///     ..Default::default()
+/// };
+///
+/// let clock = TtmlTime::Clock {
+///     hours: 0,
+///     minutes: 0,
+///     seconds: 1,
+///     milliseconds: 500,
+/// };
+/// assert_eq!(clock.to_string(), "00:00:01.500");
+/// assert_eq!(clock.to_duration(&parameters), Duration::from_millis(1500));
+///
+/// let ticks = TtmlTime::Offset {
+///     value: 15_000_000.0,
+///     metric: TtmlTimeMetric::Ticks,
+/// };
+/// assert_eq!(ticks.to_string(), "15000000t");
+/// assert_eq!(ticks.to_duration(&parameters), Duration::from_millis(1500));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TtmlTime {
+    /// The clock time with fraction, e.g. `00:00:01.500`.
+    Clock {
+        /// The hours.
+        hours: u32,
+        /// The minutes.
+        minutes: u8,
+        /// The seconds.
+        seconds: u8,
+        /// The milliseconds.
+        milliseconds: u16,
+    },
+    /// The clock time with frames, e.g. `00:00:01:12`.
+    Frames {
+        /// The hours.
+        hours: u32,
+        /// The minutes.
+        minutes: u8,
+        /// The seconds.
+        seconds: u8,
+        /// The frames.
+        frames: u32,
+        /// The sub-frames.
+        sub_frames: Option<u32>,
+    },
+    /// The offset time, e.g. `1.5s` or `100t`.
+    Offset {
+        /// The value.
+        value: f64,
+        /// The metric.
+        metric: TtmlTimeMetric,
+    },
+}
+
+impl TtmlTime {
+    /// Converts the time expression into the duration with the timing parameters.
+    ///
+    /// The duration saturates at [`Duration::MAX`](Duration::MAX).
+    pub fn to_duration(
+        &self,
+        parameters: &TtmlParameters,
+    ) -> Duration {
+        match *self {
+            | Self::Clock {
+                hours,
+                minutes,
+                seconds,
+                milliseconds,
+            } => {
+                Duration::from_secs(
+                    hours as u64 * 3600 + minutes as u64 * 60 + seconds as u64,
+                )
+                .saturating_add(Duration::from_millis(milliseconds as u64))
+            },
+            | Self::Frames {
+                hours,
+                minutes,
+                seconds,
+                frames,
+                sub_frames,
+            } => {
+                let frames = frames as f64
+                    + sub_frames.unwrap_or(0) as f64
+                        / parameters
+                            .sub_frame_rate
+                            .unwrap_or(1) as f64;
+                Duration::from_secs(
+                    hours as u64 * 3600 + minutes as u64 * 60 + seconds as u64,
+                )
+                .saturating_add(seconds_f64(
+                    frames / parameters.effective_frame_rate(),
+                ))
+            },
+            | Self::Offset {
+                value,
+                metric,
+            } => {
+                let seconds = match metric {
+                    | TtmlTimeMetric::Hours => value * 3600.0,
+                    | TtmlTimeMetric::Minutes => value * 60.0,
+                    | TtmlTimeMetric::Seconds => value,
+                    | TtmlTimeMetric::Milliseconds => value / 1000.0,
+                    | TtmlTimeMetric::Frames => {
+                        value / parameters.effective_frame_rate()
+                    },
+                    | TtmlTimeMetric::Ticks => {
+                        value / parameters.effective_tick_rate()
+                    },
+                };
+                seconds_f64(seconds)
+            },
+        }
+    }
+}
+
+impl Default for TtmlTime {
+    fn default() -> Self {
+        Self::Clock {
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+            milliseconds: 0,
+        }
+    }
+}
+
+impl Display for TtmlTime {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Clock {
+                hours,
+                minutes,
+                seconds,
+                milliseconds,
+            } => {
+                write!(
+                    f,
+                    "{:02}:{:02}:{:02}.{:03}",
+                    hours, minutes, seconds, milliseconds
+                )
+            },
+            | Self::Frames {
+                hours,
+                minutes,
+                seconds,
+                frames,
+                sub_frames,
+            } => {
+                write!(
+                    f,
+                    "{:02}:{:02}:{:02}:{:02}",
+                    hours, minutes, seconds, frames
+                )?;
+                if let Some(sub_frames) = sub_frames {
+                    write!(f, ".{}", sub_frames)?;
+                }
+                Ok(())
+            },
+            | Self::Offset {
+                value,
+                metric,
+            } => write!(f, "{}{}", value, metric),
+        }
+    }
+}
+
+impl From<Duration> for TtmlTime {
+    fn from(duration: Duration) -> Self {
+        let seconds = duration.as_secs();
+        let milliseconds = duration.subsec_millis() as u16;
+
+        let hours = (seconds / 3600) as u32;
+        let minutes = ((seconds % 3600) / 60) as u8;
+        let seconds = (seconds % 60) as u8;
+
+        Self::Clock {
+            hours,
+            minutes,
+            seconds,
+            milliseconds,
+        }
+    }
+}
+
+/// The metric of the offset time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TtmlTimeMetric {
+    /// `h`.
+    Hours,
+    /// `m`.
+    Minutes,
+    /// `s`.
+    Seconds,
+    /// `ms`.
+    Milliseconds,
+    /// `f`.
+    Frames,
+    /// `t`.
+    Ticks,
+}
+
+impl Display for TtmlTimeMetric {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Hours => write!(f, "h"),
+            | Self::Minutes => write!(f, "m"),
+            | Self::Seconds => write!(f, "s"),
+            | Self::Milliseconds => write!(f, "ms"),
+            | Self::Frames => write!(f, "f"),
+            | Self::Ticks => write!(f, "t"),
+        }
+    }
+}
+
+/// A generic XML element kept as it is, e.g. `<metadata>` in `<head>`.
+///
+/// ## Example
+/// ```
+/// use subtp::ttml::TtmlElement;
+/// use subtp::ttml::TtmlNode;
+///
+/// let element = TtmlElement {
+///     name: "ttm:title".to_string(),
+///     attributes: vec![],
+///     children: vec![TtmlNode::Text("Example".to_string())],
+/// };
+///
+/// assert_eq!(
+///     element.to_string(),
+///     "<ttm:title>Example</ttm:title>".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TtmlElement {
+    /// The qualified name with the namespace prefix.
+    pub name: String,
+    /// The attributes.
+    pub attributes: Vec<TtmlAttribute>,
+    /// The child nodes.
+    pub children: Vec<TtmlNode>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TtmlElement {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+}
+
+impl Display for TtmlElement {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "<{}", self.name)?;
+        for attribute in &self.attributes {
+            write!(f, " {}", attribute)?;
+        }
+
+        if self.children.is_empty() {
+            return write!(f, "/>");
+        }
+
+        write!(f, ">")?;
+        for child in &self.children {
+            write!(f, "{}", child)?;
+        }
+        write!(f, "</{}>", self.name)
+    }
+}
+
+/// A node of a generic XML element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TtmlNode {
+    /// The child element.
+    Element(TtmlElement),
+    /// The unescaped text.
+    Text(String),
+}
+
+impl Display for TtmlNode {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Element(element) => write!(f, "{}", element),
+            | Self::Text(text) => write!(f, "{}", escape(text)),
+        }
+    }
+}
+
+/// Escapes the text for the content and the attribute values,
+/// including newlines so that an element can be rendered in a line.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            | '&' => escaped.push_str("&amp;"),
+            | '<' => escaped.push_str("&lt;"),
+            | '>' => escaped.push_str("&gt;"),
+            | '"' => escaped.push_str("&quot;"),
+            | '\n' => escaped.push_str("&#10;"),
+            | '\r' => escaped.push_str("&#13;"),
+            | _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Writes the rendered item indented by one level.
+fn indent<T: Display>(
+    f: &mut Formatter<'_>,
+    item: &T,
+) -> std::fmt::Result {
+    for line in item.to_string().lines() {
+        writeln!(f, "  {}", line)?;
+    }
+
+    Ok(())
+}
+
+fn write_style(
+    f: &mut Formatter<'_>,
+    style: &[String],
+) -> std::fmt::Result {
+    if !style.is_empty() {
+        write!(
+            f,
+            r#" style="{}""#,
+            escape(&style.join(" "))
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes the attributes shared by the content elements.
+fn write_common(
+    f: &mut Formatter<'_>,
+    id: &Option<String>,
+    style: &[String],
+    region: &Option<String>,
+    timing: &TtmlTiming,
+    attributes: &TtmlAttributes,
+) -> std::fmt::Result {
+    if let Some(id) = id {
+        write!(f, r#" xml:id="{}""#, escape(id))?;
+    }
+    write_style(f, style)?;
+    if let Some(region) = region {
+        write!(f, r#" region="{}""#, escape(region))?;
+    }
+    write!(f, "{}{}", timing, attributes)
+}
+
+/// Converts the non-negative seconds into the duration,
+/// saturating at [`Duration::MAX`](Duration::MAX) and treating NaN as zero.
+fn seconds_f64(seconds: f64) -> Duration {
+    Duration::try_from_secs_f64(seconds.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srt::SrtTimestamp;
+    use crate::vtt::VttTimestamp;
+
+    const TEXT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xmlns:tts="http://www.w3.org/ns/ttml#styling" xmlns:ttp="http://www.w3.org/ns/ttml#parameter" xml:lang="ja" ttp:frameRate="25" ttp:profile="http://www.w3.org/ns/ttml/profile/imsc1/text">
+  <head>
+    <metadata><ttm:title xmlns:ttm="http://www.w3.org/ns/ttml#metadata">Example</ttm:title></metadata>
+    <styling>
+      <style xml:id="italic" tts:fontStyle="italic"/>
+      <style xml:id="strong" style="italic" tts:fontWeight="bold"/>
+    </styling>
+    <layout>
+      <region xml:id="bottom" tts:origin="10% 80%" tts:extent="80% 20%">
+        <style tts:textAlign="center"/>
+      </region>
+    </layout>
+  </head>
+  <body region="bottom">
+    <div begin="10s">
+      <p xml:id="c2" begin="00:00:05:00" end="00:00:06:00">Second</p>
+      <p xml:id="c1" style="italic" begin="1s" dur="2s">Hello,<br/><span tts:fontStyle="normal">world</span> &amp; <span style="strong">you</span></p>
+      <div region="top">
+        <p begin="500ms" end="10s">Nested</p>
+      </div>
+      <p begin="3s">Indefinite</p>
+    </div>
+  </body>
+</tt>
+"#;
+
+    fn clock(
+        seconds: u8,
+        milliseconds: u16,
+    ) -> TtmlTime {
+        TtmlTime::Clock {
+            hours: 0,
+            minutes: 0,
+            seconds,
+            milliseconds,
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let ttml = Ttml::parse(TEXT).unwrap();
+
+        assert_eq!(ttml.lang, Some("ja".to_string()));
+        assert_eq!(
+            ttml.parameters,
+            TtmlParameters {
+                frame_rate: Some(25),
+                profile: Some(
+                    "http://www.w3.org/ns/ttml/profile/imsc1/text".to_string()
+                ),
+                ..Default::default()
+            }
+        );
+        assert_eq!(ttml.attributes.entries.len(), 3);
+        assert_eq!(
+            ttml.head.elements[0].name,
+            "metadata".to_string()
+        );
+        assert_eq!(ttml.head.styles.len(), 2);
+        assert_eq!(
+            ttml.head.styles[1].style,
+            vec!["italic".to_string()]
+        );
+        assert_eq!(
+            ttml.head.regions[0].styles[0]
+                .attributes
+                .get("tts:textAlign"),
+            Some("center")
+        );
+        assert_eq!(
+            ttml.body.region,
+            Some("bottom".to_string())
+        );
+        assert_eq!(ttml.body.divs[0].blocks.len(), 4);
+
+        let TtmlBlock::Paragraph(paragraph) = &ttml.body.divs[0].blocks[1]
+        else {
+            panic!("paragraph expected");
+        };
+        assert_eq!(
+            paragraph.content,
+            vec![
+                TtmlContent::Text("Hello,".to_string()),
+                TtmlContent::LineBreak,
+                TtmlContent::Span(TtmlSpan {
+                    attributes: TtmlAttributes {
+                        entries: vec![TtmlAttribute {
+                            name: "tts:fontStyle".to_string(),
+                            value: "normal".to_string(),
+                        }],
+                    },
+                    content: vec![TtmlContent::Text(
+                        "world".to_string()
+                    )],
+                    ..Default::default()
+                }),
+                TtmlContent::Text(" & ".to_string()),
+                TtmlContent::Span(TtmlSpan {
+                    style: vec!["strong".to_string()],
+                    content: vec![TtmlContent::Text(
+                        "you".to_string()
+                    )],
+                    ..Default::default()
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn render() {
+        assert_eq!(
+            Ttml::parse(TEXT)
+                .unwrap()
+                .render(),
+            TEXT
+        );
+
+        let ttml = Ttml {
+            parameters: TtmlParameters {
+                tick_rate: Some(10_000_000),
+                ..Default::default()
+            },
+            body: TtmlBody {
+                divs: vec![TtmlDiv {
+                    blocks: vec![
+                        TtmlParagraph {
+                            timing: TtmlTiming {
+                                begin: Some(clock(1, 0)),
+                                end: Some(clock(2, 0)),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        }
+                        .into(),
+                        TtmlDiv::default().into(),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ttml.render(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttp="http://www.w3.org/ns/ttml#parameter" ttp:tickRate="10000000">
+  <body>
+    <div>
+      <p begin="00:00:01.000" end="00:00:02.000"/>
+      <div/>
+    </div>
+  </body>
+</tt>
+"#
+        );
+
+        assert_eq!(
+            Ttml::default().render(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml">
+  <body/>
+</tt>
+"#
+        );
+    }
+
+    #[test]
+    fn cues() {
+        let cues = Ttml::parse(TEXT)
+            .unwrap()
+            .cues();
+
+        assert_eq!(
+            cues,
+            vec![
+                TtmlCue {
+                    id: None,
+                    start: Duration::from_millis(10500),
+                    end: Duration::from_secs(20),
+                    region: Some("top".to_string()),
+                    lines: vec!["Nested".to_string()],
+                },
+                TtmlCue {
+                    id: Some("c1".to_string()),
+                    start: Duration::from_secs(11),
+                    end: Duration::from_secs(13),
+                    region: Some("bottom".to_string()),
+                    lines: vec![
+                        "Hello,".to_string(),
+                        "world & you".to_string()
+                    ],
+                },
+                TtmlCue {
+                    id: Some("c2".to_string()),
+                    start: Duration::from_secs(15),
+                    end: Duration::from_secs(16),
+                    region: Some("bottom".to_string()),
+                    lines: vec!["Second".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn to_sub_rip() {
+        let srt = SubRip::from(&Ttml::parse(TEXT).unwrap());
+
+        assert_eq!(srt.subtitles.len(), 3);
+        assert_eq!(srt.subtitles[1].sequence, 2);
+        assert_eq!(
+            srt.subtitles[1].start,
+            SrtTimestamp {
+                seconds: 11,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            srt.subtitles[1].text,
+            vec![
+                "<i>Hello,".to_string(),
+                "world & <b>you</b></i>".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn to_web_vtt() {
+        let vtt = WebVtt::from(&Ttml::parse(TEXT).unwrap());
+
+        assert_eq!(vtt.blocks.len(), 3);
+        assert_eq!(
+            vtt.blocks[1],
+            VttCue {
+                identifier: Some("c1".to_string()),
+                timings: VttTimings {
+                    start: VttTimestamp {
+                        seconds: 11,
+                        ..Default::default()
+                    },
+                    end: VttTimestamp {
+                        seconds: 13,
+                        ..Default::default()
+                    },
+                },
+                settings: None,
+                payload: vec![
+                    "<i>Hello,".to_string(),
+                    "world &amp; <b>you</b></i>".to_string(),
+                ],
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn time_to_duration() {
+        let parameters = TtmlParameters {
+            frame_rate: Some(30),
+            frame_rate_multiplier: Some((1000, 1001)),
+            sub_frame_rate: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            clock(1, 500).to_duration(&parameters),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(
+            TtmlTime::Frames {
+                hours: 1,
+                minutes: 0,
+                seconds: 0,
+                frames: 0,
+                sub_frames: None,
+            }
+            .to_duration(&parameters),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            TtmlTime::Offset {
+                value: 3000.0,
+                metric: TtmlTimeMetric::Frames,
+            }
+            .to_duration(&parameters)
+            .as_secs_f64(),
+            100.1
+        );
+        assert_eq!(
+            TtmlTime::Offset {
+                value: 60.0,
+                metric: TtmlTimeMetric::Ticks,
+            }
+            .to_duration(&parameters),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            TtmlTime::Offset {
+                value: 1.5,
+                metric: TtmlTimeMetric::Hours,
+            }
+            .to_duration(&TtmlParameters::default()),
+            Duration::from_secs(5400)
+        );
+        assert_eq!(
+            TtmlTime::Offset {
+                value: 3.0,
+                metric: TtmlTimeMetric::Ticks,
+            }
+            .to_duration(&TtmlParameters::default()),
+            Duration::from_secs(3)
+        );
+    }
+
+    #[test]
+    fn from_duration_to_time() {
+        assert_eq!(
+            TtmlTime::from(Duration::from_millis(3_723_004)),
+            TtmlTime::Clock {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                milliseconds: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_timing() {
+        let parameters = TtmlParameters::default();
+        let timing = TtmlTiming {
+            begin: Some(clock(1, 0)),
+            end: Some(clock(5, 0)),
+            dur: Some(clock(2, 0)),
+        };
+
+        assert_eq!(
+            timing.resolve(
+                Duration::from_secs(10),
+                None,
+                &parameters
+            ),
+            (
+                Duration::from_secs(11),
+                Some(Duration::from_secs(13))
+            )
+        );
+        assert_eq!(
+            timing.resolve(
+                Duration::from_secs(10),
+                Some(Duration::from_secs(12)),
+                &parameters
+            ),
+            (
+                Duration::from_secs(11),
+                Some(Duration::from_secs(12))
+            )
+        );
+        assert_eq!(
+            TtmlTiming::default().resolve(
+                Duration::from_secs(10),
+                None,
+                &parameters
+            ),
+            (Duration::from_secs(10), None)
+        );
+    }
+
+    #[test]
+    fn reject_zero_rates() {
+        for parameter in [
+            r#"ttp:frameRate="0""#,
+            r#"ttp:subFrameRate="0""#,
+            r#"ttp:tickRate="0""#,
+            r#"ttp:frameRateMultiplier="1000 0""#,
+        ] {
+            let text = format!(
+                r#"<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttp="http://www.w3.org/ns/ttml#parameter" {}><body><div><p begin="5f" end="10t">A</p></div></body></tt>"#,
+                parameter
+            );
+            assert!(Ttml::parse(&text).is_err(), "{}", parameter);
+        }
+    }
+
+    #[test]
+    fn saturate_degenerate_times() {
+        let text = r#"<tt xmlns="http://www.w3.org/ns/ttml"><body><div><p begin="99999999999999999999999h" dur="99999999999999999999999h">A</p></div></body></tt>"#;
+        let ttml = Ttml::parse(text).unwrap();
+        let cues = ttml.cues();
+        assert_eq!(cues[0].start, Duration::MAX);
+        assert_eq!(cues[0].end, Duration::MAX);
+        assert_eq!(
+            SubRip::from(&ttml).subtitles[0].start,
+            SrtTimestamp::MAX
+        );
+
+        // Zero rates constructed directly do not panic.
+        let parameters = TtmlParameters {
+            frame_rate: Some(0),
+            tick_rate: Some(0),
+            ..Default::default()
+        };
+        let frames = TtmlTime::Offset {
+            value: 5.0,
+            metric: TtmlTimeMetric::Frames,
+        };
+        assert_eq!(frames.to_duration(&parameters), Duration::MAX);
+        let ticks = TtmlTime::Offset {
+            value: 0.0,
+            metric: TtmlTimeMetric::Ticks,
+        };
+        assert_eq!(ticks.to_duration(&parameters), Duration::ZERO);
+        assert_eq!(
+            TtmlTiming {
+                begin: Some(clock(1, 0)),
+                dur: Some(frames),
+                ..Default::default()
+            }
+            .resolve(Duration::MAX, None, &parameters),
+            (Duration::MAX, Some(Duration::MAX))
+        );
+    }
+}
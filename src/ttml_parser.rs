@@ -0,0 +1,896 @@
+//! A parser implementation for the Timed Text Markup Language format.
+
+pub(crate) use ttml_parser::ttml;
+
+use crate::ttml::{
+    Ttml, TtmlAttribute, TtmlAttributes, TtmlBlock, TtmlBody, TtmlContent,
+    TtmlDiv, TtmlElement, TtmlHead, TtmlNode, TtmlParagraph, TtmlParameters,
+    TtmlRegion, TtmlSpan, TtmlStyle, TtmlTiming,
+};
+
+peg::parser! {
+    /// The parser for the Timed Text Markup Language format.
+    grammar ttml_parser() for str {
+        use crate::ttml::Ttml;
+        use crate::ttml::TtmlAttribute;
+        use crate::ttml::TtmlElement;
+        use crate::ttml::TtmlNode;
+        use crate::ttml::TtmlTime;
+        use crate::ttml::TtmlTimeMetric;
+
+        /// Whitespace in XML.
+        rule whitespace() = [' ' | '\t' | '\r' | '\n']
+
+        /// Any-digit number.
+        rule number() -> u32
+            = n:$(['0'..='9']+) {?
+                n.parse().or(Err("number in u32"))
+            }
+
+        /// Two-digit number.
+        rule two_number() -> u8
+            = n:$(['0'..='9']['0'..='9']) {?
+                n.parse().or(Err("two-digit number"))
+            }
+
+        /// Two-or-more digit number.
+        rule two_or_more_number() -> u32
+            = n:$(['0'..='9']['0'..='9']+) {?
+                n.parse().or(Err("two-or-more digit number"))
+            }
+
+        /// Fraction of seconds in milliseconds.
+        rule fraction() -> u16
+            = "." n:$(['0'..='9']+) {?
+                format!("{:0<3}", n)[..3]
+                    .parse()
+                    .or(Err("fraction of seconds"))
+            }
+
+        /// Time metric of offset time.
+        rule time_metric() -> TtmlTimeMetric
+            = "h" { TtmlTimeMetric::Hours }
+            / "ms" { TtmlTimeMetric::Milliseconds }
+            / "m" { TtmlTimeMetric::Minutes }
+            / "s" { TtmlTimeMetric::Seconds }
+            / "f" { TtmlTimeMetric::Frames }
+            / "t" { TtmlTimeMetric::Ticks }
+
+        /// Time expression, e.g. `00:00:01.500`, `00:00:01:12` or `1.5s`.
+        pub(crate) rule time_expression() -> TtmlTime
+            = whitespace()* hours:two_or_more_number() ":" minutes:two_number() ":" seconds:two_number()
+                frames:(":" frames:number() sub_frames:("." n:number() { n })? { (frames, sub_frames) })
+                whitespace()*
+            {
+                TtmlTime::Frames {
+                    hours,
+                    minutes,
+                    seconds,
+                    frames: frames.0,
+                    sub_frames: frames.1,
+                }
+            }
+            / whitespace()* hours:two_or_more_number() ":" minutes:two_number() ":" seconds:two_number()
+                milliseconds:fraction()? whitespace()*
+            {
+                TtmlTime::Clock {
+                    hours,
+                    minutes,
+                    seconds,
+                    milliseconds: milliseconds.unwrap_or(0),
+                }
+            }
+            / whitespace()* value:$(['0'..='9']+ ("." ['0'..='9']+)?) metric:time_metric() whitespace()* {?
+                value
+                    .parse()
+                    .map(|value| TtmlTime::Offset { value, metric })
+                    .or(Err("offset time"))
+            }
+
+        /// Name of an element or an attribute, with an optional namespace prefix.
+        rule name() -> &'input str
+            = $([c if c.is_alphabetic() || c == '_'] [c if c.is_alphanumeric() || matches!(c, '_' | ':' | '.' | '-')]*)
+
+        /// Entity or character reference, e.g. `&amp;` or `&#x3C;`.
+        rule reference() -> String
+            = "&lt;" { "<".to_string() }
+            / "&gt;" { ">".to_string() }
+            / "&amp;" { "&".to_string() }
+            / "&apos;" { "'".to_string() }
+            / "&quot;" { "\"".to_string() }
+            / "&#x" n:$(['0'..='9' | 'a'..='f' | 'A'..='F']+) ";" {?
+                u32::from_str_radix(n, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(String::from)
+                    .ok_or("hexadecimal character reference")
+            }
+            / "&#" n:$(['0'..='9']+) ";" {?
+                n.parse()
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(String::from)
+                    .ok_or("decimal character reference")
+            }
+
+        /// Character data.
+        rule text() -> String
+            = parts:(s:$([^ '<' | '&']+) { s.to_string() } / reference())+
+            {
+                parts.concat()
+            }
+
+        /// CDATA section.
+        rule cdata() -> String
+            = "<![CDATA[" s:$((!"]]>" [_])*) "]]>" { s.to_string() }
+
+        /// Comment.
+        rule comment() = "<!--" (!"-->" [_])* "-->"
+
+        /// Processing instruction including the XML declaration.
+        rule processing_instruction() = "<?" (!"?>" [_])* "?>"
+
+        /// Document type declaration.
+        rule doctype() = "<!DOCTYPE" [^ '>']* ">"
+
+        /// Miscellaneous markups outside the root element.
+        rule misc() = whitespace() / comment() / processing_instruction() / doctype()
+
+        /// Attribute value quoted by `"` or `'`.
+        rule attribute_value() -> String
+            = "\"" parts:(s:$([^ '"' | '<' | '&']+) { s.to_string() } / reference())* "\"" { parts.concat() }
+            / "'" parts:(s:$([^ '\'' | '<' | '&']+) { s.to_string() } / reference())* "'" { parts.concat() }
+
+        /// Attribute, e.g. `begin="00:00:01.000"`.
+        rule attribute() -> TtmlAttribute
+            = whitespace()+ name:name() whitespace()* "=" whitespace()* value:attribute_value()
+            {
+                TtmlAttribute {
+                    name: name.to_string(),
+                    value,
+                }
+            }
+
+        /// Element with its attributes and children.
+        pub(crate) rule element() -> TtmlElement
+            = "<" name:name() attributes:attribute()* whitespace()*
+                children:(
+                    "/>" { vec![] }
+                    / ">" children:node()* "</" end:name() whitespace()* ">" {?
+                        if end == name {
+                            Ok(children.into_iter().flatten().collect())
+                        } else {
+                            Err("matching end tag")
+                        }
+                    }
+                )
+            {
+                TtmlElement {
+                    name: name.to_string(),
+                    attributes,
+                    children,
+                }
+            }
+
+        /// Node in the content of an element.
+        rule node() -> Option<TtmlNode>
+            = e:element() { Some(TtmlNode::Element(e)) }
+            / t:text() { Some(TtmlNode::Text(t)) }
+            / c:cdata() { Some(TtmlNode::Text(c)) }
+            / comment() { None }
+            / processing_instruction() { None }
+
+        /// XML document with the root element.
+        pub(crate) rule document() -> TtmlElement
+            = "\u{FEFF}"? misc()* root:element() misc()* ![_] { root }
+
+        /// The Timed Text Markup Language document.
+        pub(crate) rule ttml() -> Ttml
+            = root:document() {? super::tt(root) }
+    }
+}
+
+/// Returns the local name without the namespace prefix.
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':')
+        .map(|(_, local)| local)
+        .unwrap_or(name)
+}
+
+/// Returns `true` if the name has a namespace prefix and the given local name.
+fn is_prefixed(
+    name: &str,
+    local: &str,
+) -> bool {
+    name.split_once(':')
+        .is_some_and(|(prefix, name)| prefix != "xml" && name == local)
+}
+
+/// Attributes shared by the content elements.
+struct Common {
+    id: Option<String>,
+    style: Vec<String>,
+    region: Option<String>,
+    timing: TtmlTiming,
+    preserve: Option<bool>,
+    attributes: TtmlAttributes,
+}
+
+fn common(attributes: Vec<TtmlAttribute>) -> Result<Common, &'static str> {
+    let mut common = Common {
+        id: None,
+        style: vec![],
+        region: None,
+        timing: TtmlTiming::default(),
+        preserve: None,
+        attributes: TtmlAttributes::default(),
+    };
+
+    for attribute in attributes {
+        match attribute.name.as_str() {
+            | "xml:id" => common.id = Some(attribute.value),
+            | "style" => {
+                common.style = references(&attribute.value);
+            },
+            | "region" => common.region = Some(attribute.value),
+            | "begin" => {
+                common.timing.begin = Some(time(&attribute.value)?);
+            },
+            | "end" => {
+                common.timing.end = Some(time(&attribute.value)?);
+            },
+            | "dur" => {
+                common.timing.dur = Some(time(&attribute.value)?);
+            },
+            | _ => {
+                if attribute.name == "xml:space" {
+                    common.preserve = Some(attribute.value == "preserve");
+                }
+                common
+                    .attributes
+                    .entries
+                    .push(attribute);
+            },
+        }
+    }
+
+    Ok(common)
+}
+
+fn references(value: &str) -> Vec<String> {
+    value
+        .split_whitespace()
+        .map(|id| id.to_string())
+        .collect()
+}
+
+fn time(value: &str) -> Result<crate::ttml::TtmlTime, &'static str> {
+    ttml_parser::time_expression(value).or(Err("time expression"))
+}
+
+/// Parses the positive integer of a rate, rejecting zero.
+fn positive(
+    value: &str,
+    expected: &'static str,
+) -> Result<u32, &'static str> {
+    match value.trim().parse() {
+        | Ok(0) | Err(_) => Err(expected),
+        | Ok(value) => Ok(value),
+    }
+}
+
+/// Converts the root element into the Timed Text Markup Language document.
+fn tt(root: TtmlElement) -> Result<Ttml, &'static str> {
+    if local_name(&root.name) != "tt" {
+        return Err("root element tt");
+    }
+
+    let mut lang = None;
+    let mut parameters = TtmlParameters::default();
+    let mut attributes = TtmlAttributes::default();
+    for attribute in root.attributes {
+        let name = attribute.name.as_str();
+        if name == "xml:lang" {
+            lang = Some(attribute.value);
+        } else if is_prefixed(name, "frameRate") {
+            parameters.frame_rate = Some(positive(&attribute.value, "frame rate")?);
+        } else if is_prefixed(name, "frameRateMultiplier") {
+            let (numerator, denominator) = attribute
+                .value
+                .split_once(' ')
+                .ok_or("frame rate multiplier")?;
+            parameters.frame_rate_multiplier = Some((
+                positive(numerator, "frame rate multiplier")?,
+                positive(denominator, "frame rate multiplier")?,
+            ));
+        } else if is_prefixed(name, "subFrameRate") {
+            parameters.sub_frame_rate = Some(positive(&attribute.value, "sub-frame rate")?);
+        } else if is_prefixed(name, "tickRate") {
+            parameters.tick_rate = Some(positive(&attribute.value, "tick rate")?);
+        } else if is_prefixed(name, "profile") {
+            parameters.profile = Some(attribute.value);
+        } else {
+            attributes
+                .entries
+                .push(attribute);
+        }
+    }
+
+    let preserve = attributes.get("xml:space") == Some("preserve");
+    let mut head = TtmlHead::default();
+    let mut body = TtmlBody::default();
+    for child in root.children {
+        if let TtmlNode::Element(element) = child {
+            match local_name(&element.name) {
+                | "head" => head = self::head(element)?,
+                | "body" => body = self::body(element, preserve)?,
+                | _ => {},
+            }
+        }
+    }
+
+    Ok(Ttml {
+        lang,
+        parameters,
+        attributes,
+        head,
+        body,
+    })
+}
+
+fn head(element: TtmlElement) -> Result<TtmlHead, &'static str> {
+    let mut head = TtmlHead::default();
+    for child in element.children {
+        if let TtmlNode::Element(element) = child {
+            match local_name(&element.name) {
+                | "styling" => {
+                    for style in elements(element.children, "style") {
+                        let style = self::style(style)?;
+                        if style.id.is_empty() {
+                            return Err("xml:id of style");
+                        }
+                        head.styles.push(style);
+                    }
+                },
+                | "layout" => {
+                    for region in elements(element.children, "region") {
+                        head.regions
+                            .push(self::region(region)?);
+                    }
+                },
+                | _ => head.elements.push(element),
+            }
+        }
+    }
+
+    Ok(head)
+}
+
+/// Collects the child elements of the given local name.
+fn elements(
+    children: Vec<TtmlNode>,
+    local: &str,
+) -> impl Iterator<Item = TtmlElement> + '_ {
+    children
+        .into_iter()
+        .filter_map(move |child| {
+            match child {
+                | TtmlNode::Element(element)
+                    if local_name(&element.name) == local =>
+                {
+                    Some(element)
+                },
+                | _ => None,
+            }
+        })
+}
+
+fn style(element: TtmlElement) -> Result<TtmlStyle, &'static str> {
+    let common = common(element.attributes)?;
+
+    Ok(TtmlStyle {
+        id: common.id.unwrap_or_default(),
+        style: common.style,
+        attributes: common.attributes,
+    })
+}
+
+fn region(element: TtmlElement) -> Result<TtmlRegion, &'static str> {
+    let common = common(element.attributes)?;
+    let styles = elements(element.children, "style")
+        .map(style)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TtmlRegion {
+        id: common
+            .id
+            .ok_or("xml:id of region")?,
+        style: common.style,
+        attributes: common.attributes,
+        styles,
+    })
+}
+
+fn body(
+    element: TtmlElement,
+    preserve: bool,
+) -> Result<TtmlBody, &'static str> {
+    let common = common(element.attributes)?;
+    let preserve = common
+        .preserve
+        .unwrap_or(preserve);
+    let divs = elements(element.children, "div")
+        .map(|element| div(element, preserve))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TtmlBody {
+        id: common.id,
+        style: common.style,
+        region: common.region,
+        timing: common.timing,
+        attributes: common.attributes,
+        divs,
+    })
+}
+
+fn div(
+    element: TtmlElement,
+    preserve: bool,
+) -> Result<TtmlDiv, &'static str> {
+    let common = common(element.attributes)?;
+    let preserve = common
+        .preserve
+        .unwrap_or(preserve);
+
+    let mut blocks = vec![];
+    for child in element.children {
+        if let TtmlNode::Element(element) = child {
+            match local_name(&element.name) {
+                | "div" => blocks.push(TtmlBlock::Div(div(element, preserve)?)),
+                | "p" => {
+                    blocks.push(TtmlBlock::Paragraph(paragraph(
+                        element, preserve,
+                    )?))
+                },
+                | _ => {},
+            }
+        }
+    }
+
+    Ok(TtmlDiv {
+        id: common.id,
+        style: common.style,
+        region: common.region,
+        timing: common.timing,
+        attributes: common.attributes,
+        blocks,
+    })
+}
+
+fn paragraph(
+    element: TtmlElement,
+    preserve: bool,
+) -> Result<TtmlParagraph, &'static str> {
+    let common = common(element.attributes)?;
+    let preserve = common
+        .preserve
+        .unwrap_or(preserve);
+    let mut content = content(element.children, preserve)?;
+
+    if !preserve {
+        if let Some(TtmlContent::Text(text)) = content.first_mut() {
+            *text = text.trim_start().to_string();
+        }
+        if let Some(TtmlContent::Text(text)) = content.last_mut() {
+            *text = text.trim_end().to_string();
+        }
+        content.retain(|content| !matches!(content, TtmlContent::Text(text) if text.is_empty()));
+    }
+
+    Ok(TtmlParagraph {
+        id: common.id,
+        style: common.style,
+        region: common.region,
+        timing: common.timing,
+        attributes: common.attributes,
+        content,
+    })
+}
+
+fn span(
+    element: TtmlElement,
+    preserve: bool,
+) -> Result<TtmlSpan, &'static str> {
+    let common = common(element.attributes)?;
+    let preserve = common
+        .preserve
+        .unwrap_or(preserve);
+
+    Ok(TtmlSpan {
+        id: common.id,
+        style: common.style,
+        region: common.region,
+        timing: common.timing,
+        attributes: common.attributes,
+        content: content(element.children, preserve)?,
+    })
+}
+
+/// Converts the children of `p` or `span` into the content,
+/// collapsing whitespaces unless `xml:space="preserve"` is specified.
+fn content(
+    children: Vec<TtmlNode>,
+    preserve: bool,
+) -> Result<Vec<TtmlContent>, &'static str> {
+    let mut content: Vec<TtmlContent> = vec![];
+    for child in children {
+        match child {
+            | TtmlNode::Text(text) => {
+                if let Some(TtmlContent::Text(last)) = content.last_mut() {
+                    last.push_str(&text);
+                } else {
+                    content.push(TtmlContent::Text(text));
+                }
+            },
+            | TtmlNode::Element(element) => {
+                match local_name(&element.name) {
+                    | "span" => {
+                        content.push(TtmlContent::Span(span(
+                            element, preserve,
+                        )?))
+                    },
+                    | "br" => content.push(TtmlContent::LineBreak),
+                    | _ => {},
+                }
+            },
+        }
+    }
+
+    if preserve {
+        return Ok(content);
+    }
+
+    for i in 0..content.len() {
+        let after_break =
+            i > 0 && matches!(content[i - 1], TtmlContent::LineBreak);
+        let before_break = matches!(
+            content.get(i + 1),
+            Some(TtmlContent::LineBreak)
+        );
+        if let TtmlContent::Text(text) = &mut content[i] {
+            let mut collapsed = collapse(text);
+            if after_break {
+                collapsed = collapsed
+                    .trim_start()
+                    .to_string();
+            }
+            if before_break {
+                collapsed = collapsed
+                    .trim_end()
+                    .to_string();
+            }
+            *text = collapsed;
+        }
+    }
+    content.retain(|content| !matches!(content, TtmlContent::Text(text) if text.is_empty()));
+
+    Ok(content)
+}
+
+/// Collapses each run of whitespaces into a single space.
+fn collapse(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut whitespace = false;
+    for c in text.chars() {
+        if matches!(c, ' ' | '\t' | '\r' | '\n') {
+            if !whitespace {
+                collapsed.push(' ');
+            }
+            whitespace = true;
+        } else {
+            collapsed.push(c);
+            whitespace = false;
+        }
+    }
+
+    collapsed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ttml::{TtmlTime, TtmlTimeMetric};
+
+    #[test]
+    fn time_expression() {
+        assert_eq!(
+            ttml_parser::time_expression("00:00:01.500").unwrap(),
+            TtmlTime::Clock {
+                hours: 0,
+                minutes: 0,
+                seconds: 1,
+                milliseconds: 500,
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("01:02:03").unwrap(),
+            TtmlTime::Clock {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                milliseconds: 0,
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("100:00:00.1234").unwrap(),
+            TtmlTime::Clock {
+                hours: 100,
+                minutes: 0,
+                seconds: 0,
+                milliseconds: 123,
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("00:00:01:12").unwrap(),
+            TtmlTime::Frames {
+                hours: 0,
+                minutes: 0,
+                seconds: 1,
+                frames: 12,
+                sub_frames: None,
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("00:00:01:12.1").unwrap(),
+            TtmlTime::Frames {
+                hours: 0,
+                minutes: 0,
+                seconds: 1,
+                frames: 12,
+                sub_frames: Some(1),
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("1.5s").unwrap(),
+            TtmlTime::Offset {
+                value: 1.5,
+                metric: TtmlTimeMetric::Seconds,
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("10000000t").unwrap(),
+            TtmlTime::Offset {
+                value: 10000000.0,
+                metric: TtmlTimeMetric::Ticks,
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("250ms").unwrap(),
+            TtmlTime::Offset {
+                value: 250.0,
+                metric: TtmlTimeMetric::Milliseconds,
+            }
+        );
+        assert_eq!(
+            ttml_parser::time_expression("2m").unwrap(),
+            TtmlTime::Offset {
+                value: 2.0,
+                metric: TtmlTimeMetric::Minutes,
+            }
+        );
+        assert!(ttml_parser::time_expression("0:00:01.000").is_err());
+        assert!(ttml_parser::time_expression("1.5").is_err());
+        assert!(ttml_parser::time_expression("1.5x").is_err());
+    }
+
+    #[test]
+    fn element() {
+        assert_eq!(
+            ttml_parser::element("<br/>").unwrap(),
+            TtmlElement {
+                name: "br".to_string(),
+                attributes: vec![],
+                children: vec![],
+            }
+        );
+        assert_eq!(
+            ttml_parser::element(
+                "<tt:p begin='1s' tts:color=\"red\">A &amp; B<!-- comment --><![CDATA[<C>]]></tt:p>"
+            )
+            .unwrap(),
+            TtmlElement {
+                name: "tt:p".to_string(),
+                attributes: vec![
+                    TtmlAttribute {
+                        name: "begin".to_string(),
+                        value: "1s".to_string(),
+                    },
+                    TtmlAttribute {
+                        name: "tts:color".to_string(),
+                        value: "red".to_string(),
+                    },
+                ],
+                children: vec![
+                    TtmlNode::Text("A & B".to_string()),
+                    TtmlNode::Text("<C>".to_string()),
+                ],
+            }
+        );
+        assert_eq!(
+            ttml_parser::element("<p>&#60;&#x3E;&quot;&apos;&lt;&gt;</p>")
+                .unwrap()
+                .children,
+            vec![TtmlNode::Text(
+                "<>\"'<>".to_string()
+            )]
+        );
+        assert!(ttml_parser::element("<p>text</span>").is_err());
+        assert!(ttml_parser::element("<p>text").is_err());
+        assert!(ttml_parser::element("<p>&unknown;</p>").is_err());
+    }
+
+    #[test]
+    fn document() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE tt>
+<!-- comment -->
+<tt xmlns="http://www.w3.org/ns/ttml"/>
+"#;
+        assert_eq!(
+            ttml_parser::document(text).unwrap(),
+            TtmlElement {
+                name: "tt".to_string(),
+                attributes: vec![TtmlAttribute {
+                    name: "xmlns".to_string(),
+                    value: "http://www.w3.org/ns/ttml".to_string(),
+                }],
+                children: vec![],
+            }
+        );
+        assert!(ttml_parser::document("<tt/><tt/>").is_err());
+        assert!(ttml_parser::document("text<tt/>").is_err());
+    }
+
+    #[test]
+    fn whitespace() {
+        let ttml = ttml_parser::ttml(
+            r#"<tt xmlns="http://www.w3.org/ns/ttml"><body><div>
+<p>
+  Hello,
+  <span>world</span> !
+  <br />
+  Second   line
+</p>
+<p xml:space="preserve"> Keep  this </p>
+</div></body></tt>"#,
+        )
+        .unwrap();
+
+        let TtmlBlock::Paragraph(first) = &ttml.body.divs[0].blocks[0] else {
+            panic!("paragraph expected");
+        };
+        assert_eq!(
+            first.content,
+            vec![
+                TtmlContent::Text("Hello, ".to_string()),
+                TtmlContent::Span(TtmlSpan {
+                    content: vec![TtmlContent::Text(
+                        "world".to_string()
+                    )],
+                    ..Default::default()
+                }),
+                TtmlContent::Text(" !".to_string()),
+                TtmlContent::LineBreak,
+                TtmlContent::Text("Second line".to_string()),
+            ]
+        );
+
+        let TtmlBlock::Paragraph(second) = &ttml.body.divs[0].blocks[1] else {
+            panic!("paragraph expected");
+        };
+        assert_eq!(
+            second.content,
+            vec![TtmlContent::Text(
+                " Keep  this ".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn ttml() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt:tt xmlns:tt="http://www.w3.org/ns/ttml" xmlns:ttp="http://www.w3.org/ns/ttml#parameter" xmlns:tts="http://www.w3.org/ns/ttml#styling" ttp:frameRate="24" ttp:frameRateMultiplier="1000 1001" ttp:tickRate="10000000" ttp:profile="http://www.w3.org/ns/ttml/profile/imsc1/text" xml:lang="en">
+  <tt:head>
+    <tt:metadata><ttm:title xmlns:ttm="http://www.w3.org/ns/ttml#metadata">Example</ttm:title></tt:metadata>
+    <tt:styling>
+      <tt:style xml:id="s1" tts:color="white" tts:fontStyle="italic"/>
+    </tt:styling>
+    <tt:layout>
+      <tt:region xml:id="r1" style="s1" tts:origin="10% 80%" tts:extent="80% 20%"/>
+    </tt:layout>
+  </tt:head>
+  <tt:body region="r1">
+    <tt:div begin="10s">
+      <tt:p xml:id="c1" begin="1s" dur="2s" style="s1 s2">Hello</tt:p>
+    </tt:div>
+  </tt:body>
+</tt:tt>
+"#;
+        let ttml = ttml_parser::ttml(text).unwrap();
+
+        assert_eq!(ttml.lang, Some("en".to_string()));
+        assert_eq!(
+            ttml.parameters,
+            TtmlParameters {
+                frame_rate: Some(24),
+                frame_rate_multiplier: Some((1000, 1001)),
+                sub_frame_rate: None,
+                tick_rate: Some(10000000),
+                profile: Some(
+                    "http://www.w3.org/ns/ttml/profile/imsc1/text".to_string()
+                ),
+            }
+        );
+        assert_eq!(
+            ttml.attributes
+                .get("xmlns:tt"),
+            Some("http://www.w3.org/ns/ttml")
+        );
+        assert_eq!(ttml.head.elements.len(), 1);
+        assert_eq!(ttml.head.styles[0].id, "s1");
+        assert_eq!(
+            ttml.head.styles[0]
+                .attributes
+                .get("tts:fontStyle"),
+            Some("italic")
+        );
+        assert_eq!(ttml.head.regions[0].id, "r1");
+        assert_eq!(
+            ttml.head.regions[0].style,
+            vec!["s1".to_string()]
+        );
+        assert_eq!(ttml.body.region, Some("r1".to_string()));
+
+        let TtmlBlock::Paragraph(p) = &ttml.body.divs[0].blocks[0] else {
+            panic!("paragraph expected");
+        };
+        assert_eq!(p.id, Some("c1".to_string()));
+        assert_eq!(
+            p.style,
+            vec![
+                "s1".to_string(),
+                "s2".to_string()
+            ]
+        );
+        assert_eq!(
+            p.timing,
+            TtmlTiming {
+                begin: Some(TtmlTime::Offset {
+                    value: 1.0,
+                    metric: TtmlTimeMetric::Seconds,
+                }),
+                end: None,
+                dur: Some(TtmlTime::Offset {
+                    value: 2.0,
+                    metric: TtmlTimeMetric::Seconds,
+                }),
+            }
+        );
+        assert_eq!(
+            p.content,
+            vec![TtmlContent::Text(
+                "Hello".to_string()
+            )]
+        );
+
+        assert!(ttml_parser::ttml("<html/>").is_err());
+        assert!(ttml_parser::ttml(
+            "<tt><body><div><p begin=\"soon\"/></div></body></tt>"
+        )
+        .is_err());
+        assert!(ttml_parser::ttml(
+            "<tt><head><styling><style/></styling></head></tt>"
+        )
+        .is_err());
+    }
+}
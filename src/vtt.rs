@@ -67,8 +67,31 @@
 //! ```
 
 use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
 use std::time::Duration;
 
+use crate::block_reader::BlockReader;
+use crate::block_writer;
+#[cfg(feature = "encoding")]
+use crate::encoding::{self, Decoded, TextEncoding};
+use crate::lint::{self, LintConfig, LintCue, LintFinding};
+use crate::reflow::{self, ReflowConfig};
+use crate::repair::{self, RepairChange, RepairConfig};
+use crate::split::{self, MergeConfig, SplitConfig};
+use crate::timing::{
+    self, FrameRate, FrameSnapping, OutOfRange, TimeOffset, TimingError,
+};
+use crate::vtt::cue_text::CueText;
+use crate::vtt_parser;
+use crate::{
+    LineEnding, ParseError, ParseResult, ReadResult, TimestampFieldError,
+    TimestampRangeError,
+};
+
+pub mod cue_text;
+
 /// The WebVTT (`.vtt`) format.
 ///
 /// Parses from text by [`WebVtt::parse`](WebVtt::parse)
@@ -136,6 +159,10 @@ use std::time::Duration;
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct WebVtt {
     /// The header of the WebVTT.
     pub header: VttHeader,
@@ -163,7 +190,123 @@ impl WebVtt {
     /// let vtt = WebVtt::parse(text).unwrap();
     /// ```
     pub fn parse(input: &str) -> Result<Self, crate::error::ParseError> {
-        crate::vtt_parser::vtt(input).map_err(Into::into)
+        WebVttRef::parse(input).map(|vtt| vtt.to_web_vtt())
+    }
+
+    /// Parses the WebVTT format from the given text by the hand-written scanner
+    /// in a single pass without backtracking, producing the same value as [`WebVtt::parse`].
+    ///
+    /// The input that the scanner does not accept, e.g. malformed one,
+    /// falls back to [`WebVtt::parse`] for the same error.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// 00:00:01.000 --> 00:00:04.000 align:end
+    /// - Never drink liquid nitrogen.
+    /// "#;
+    ///
+    /// assert_eq!(WebVtt::parse_fast(text), WebVtt::parse(text));
+    /// ```
+    pub fn parse_fast(input: &str) -> Result<Self, crate::error::ParseError> {
+        WebVttRef::parse_fast(input).map(|vtt| vtt.to_web_vtt())
+    }
+
+    /// Parses the WebVTT format from the given text leniently,
+    /// skipping or repairing malformed blocks instead of failing.
+    /// The valid input gives the same value as [`WebVtt::parse`] without warnings.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::WebVtt;
+    /// use subtp::WarningKind;
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// 00:00:01.000 --> 00:00:04.000 line:bogus
+    /// - Never drink liquid nitrogen.
+    ///
+    /// 00:05 --> 00:09
+    /// - It will perforate your stomach.
+    /// "#;
+    ///
+    /// let lenient = WebVtt::parse_lenient(text);
+    /// assert_eq!(lenient.value.blocks.len(), 2);
+    /// assert_eq!(
+    ///     lenient
+    ///         .warnings
+    ///         .iter()
+    ///         .map(|warning| warning.kind)
+    ///         .collect::<Vec<_>>(),
+    ///     vec![
+    ///         WarningKind::InvalidSettings,
+    ///         WarningKind::RepairedTimestamp,
+    ///         WarningKind::RepairedTimestamp,
+    ///     ]
+    /// );
+    /// ```
+    pub fn parse_lenient(input: &str) -> crate::Lenient<Self> {
+        crate::lenient_parser::vtt(input)
+    }
+
+    /// Parses the WebVTT format from the given text by the file parsing algorithm of
+    /// [the W3C WebVTT specification](https://www.w3.org/TR/webvtt1/#file-parsing),
+    /// failing only if the `WEBVTT` signature is missing.
+    ///
+    /// As a user agent does, it ignores invalid cue and region settings with the last valid one winning,
+    /// drops cues with invalid timings, and ends a cue before a line containing `-->`.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = r#"WEBVTT
+    /// 00:00:01.000 --> 00:00:04.000 align:bogus align:end size:120%
+    /// - Never drink liquid nitrogen.
+    /// 00:00:05.000 --> 00:00:09.000
+    /// - It will perforate your stomach.
+    /// "#;
+    ///
+    /// let vtt = WebVtt::parse_spec(text).unwrap();
+    /// assert_eq!(
+    ///     vtt.render(),
+    ///     "WEBVTT\n\n00:00:01.000 --> 00:00:04.000 align:end\n- Never drink liquid nitrogen.\n\n00:00:05.000 --> 00:00:09.000\n- It will perforate your stomach.\n"
+    /// );
+    /// ```
+    pub fn parse_spec(input: &str) -> Result<Self, crate::error::ParseError> {
+        crate::vtt_spec_parser::vtt(input)
+    }
+
+    /// Parses the bytes of the WebVTT format with the character encoding detected by
+    /// [`TextEncoding::detect`](crate::encoding::TextEncoding::detect) behind `encoding` feature.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// // UTF-16LE with the byte order mark.
+    /// let bytes: Vec<u8> = "\u{FEFF}WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nHello, world!\n"
+    ///     .encode_utf16()
+    ///     .flat_map(|unit| unit.to_le_bytes())
+    ///     .collect();
+    ///
+    /// let decoded = WebVtt::parse_bytes(&bytes).unwrap();
+    /// assert_eq!(decoded.encoding.encoding.name(), "UTF-16LE");
+    /// assert!(decoded.encoding.bom);
+    /// assert_eq!(decoded.value.render(), "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nHello, world!\n");
+    /// ```
+    #[cfg(feature = "encoding")]
+    pub fn parse_bytes(bytes: &[u8]) -> ParseResult<Decoded<Self>> {
+        let decoded = encoding::decode(bytes);
+
+        Ok(Decoded {
+            value: Self::parse(&decoded.value)?,
+            encoding: decoded.encoding,
+            replaced: decoded.replaced,
+        })
     }
 
     /// Renders the text from the WebVTT format.
@@ -219,8 +362,515 @@ impl WebVtt {
     pub fn render(&self) -> String {
         self.to_string()
     }
+
+    /// Renders the bytes of the WebVTT format in the character encoding behind `encoding` feature,
+    /// e.g. the encoding of the input detected by [`parse_bytes`](WebVtt::parse_bytes),
+    /// and returns whether unrepresentable characters are replaced as by
+    /// [`TextEncoding::encode`](crate::encoding::TextEncoding::encode).
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::encoding::TextEncoding;
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let decoded = WebVtt::parse_bytes(b"WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nHello, world!\n").unwrap();
+    /// let (bytes, replaced) = decoded.value.render_bytes(&TextEncoding {
+    ///     bom: true,
+    ///     ..decoded.encoding
+    /// });
+    ///
+    /// assert!(!replaced);
+    /// assert_eq!(&bytes[..9], b"\xEF\xBB\xBFWEBVTT");
+    /// ```
+    #[cfg(feature = "encoding")]
+    pub fn render_bytes(
+        &self,
+        encoding: &TextEncoding,
+    ) -> (Vec<u8>, bool) {
+        encoding.encode(&self.render())
+    }
+
+    /// Shifts all cues by the offset,
+    /// failing without changes if a time would be negative.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::timing::TimeOffset;
+    /// use subtp::timing::TimingError;
+    /// use subtp::vtt::{VttBlock, WebVtt};
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// 00:00:01.000 --> 00:00:04.000
+    /// - Never drink liquid nitrogen.
+    /// "#;
+    ///
+    /// let mut vtt = WebVtt::parse(text).unwrap();
+    ///
+    /// vtt.shift(TimeOffset::forward(Duration::from_millis(1500)))
+    ///     .unwrap();
+    /// let VttBlock::Que(cue) = &vtt.blocks[0] else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(cue.timings.to_string(), "00:00:02.500 --> 00:00:05.500");
+    ///
+    /// assert_eq!(
+    ///     vtt.shift(TimeOffset::backward(Duration::from_secs(3))),
+    ///     Err(TimingError::Underflow { cue: 0 })
+    /// );
+    /// ```
+    pub fn shift(
+        &mut self,
+        offset: TimeOffset,
+    ) -> Result<(), TimingError> {
+        self.map_times(timing::shift(offset))
+    }
+
+    /// Shifts all cues by the offset, clamping negative times at zero.
+    pub fn shift_saturating(
+        &mut self,
+        offset: TimeOffset,
+    ) -> Result<(), TimingError> {
+        self.map_times(|time| Ok(offset.saturating_apply(time)))
+    }
+
+    /// Scales all times of cues by the factor, rounding to milliseconds,
+    /// e.g. `25.0 / 23.976` to convert from 25 fps to 23.976 fps.
+    pub fn scale(
+        &mut self,
+        factor: f64,
+    ) -> Result<(), TimingError> {
+        self.map_times(timing::scale(factor)?)
+    }
+
+    /// Resyncs all cues linearly so that the start of the cue at index `a` is at `a_time`
+    /// and the start of the cue at index `b` is at `b_time`,
+    /// clamping negative times at zero.
+    pub fn resync(
+        &mut self,
+        a: usize,
+        a_time: Duration,
+        b: usize,
+        b_time: Duration,
+    ) -> Result<(), TimingError> {
+        let times = self.times();
+        let start = |cue: usize| {
+            times
+                .get(cue)
+                .map(|(start, _)| *start)
+                .ok_or(TimingError::CueNotFound {
+                    cue,
+                })
+        };
+
+        self.map_times(timing::resync(
+            (start(a)?, a_time),
+            (start(b)?, b_time),
+        )?)
+    }
+
+    /// Returns the cues.
+    fn cues_mut(&mut self) -> impl Iterator<Item = &mut VttCue> {
+        self.blocks
+            .iter_mut()
+            .filter_map(|block| {
+                match block {
+                    | VttBlock::Que(cue) => Some(cue),
+                    | _ => None,
+                }
+            })
+    }
+
+    /// Converts the times of all cues from the source frame rate to the target frame rate,
+    /// keeping the frame numbers, e.g. for the PAL speedup from 23.976 fps to 25 fps.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::timing::{FrameRate, FrameSnapping};
+    /// use subtp::vtt::{VttBlock, WebVtt};
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// 00:00:01.000 --> 00:00:04.000
+    /// - Never drink liquid nitrogen.
+    /// "#;
+    ///
+    /// let mut vtt = WebVtt::parse(text).unwrap();
+    /// vtt.convert_frame_rate(
+    ///     FrameRate::FPS_25,
+    ///     FrameRate::FPS_23_976,
+    ///     FrameSnapping::Nearest,
+    /// )
+    /// .unwrap();
+    ///
+    /// let VttBlock::Que(cue) = &vtt.blocks[0] else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(cue.timings.to_string(), "00:00:01.043 --> 00:00:04.171");
+    /// ```
+    pub fn convert_frame_rate(
+        &mut self,
+        source: FrameRate,
+        target: FrameRate,
+        snapping: FrameSnapping,
+    ) -> Result<(), TimingError> {
+        self.map_times(timing::frame_rate(
+            source, target, snapping,
+        )?)
+    }
+
+    /// Runs the quality checks over the cues,
+    /// measuring the lengths of the lines without markups.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::lint::{LintConfig, LintKind};
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// 00:00:01.000 --> 00:00:02.000
+    /// <v Roger>Hello, world!
+    ///
+    /// 00:00:02.000 --> 00:00:02.000
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let vtt = WebVtt::parse(text).unwrap();
+    /// let findings = vtt.lint(&LintConfig::default());
+    ///
+    /// assert_eq!(findings.len(), 1);
+    /// assert_eq!(findings[0].cue, 1);
+    /// assert_eq!(findings[0].kind, LintKind::ZeroDuration);
+    /// ```
+    pub fn lint(
+        &self,
+        config: &LintConfig,
+    ) -> Vec<LintFinding> {
+        let cues: Vec<_> = self
+            .blocks
+            .iter()
+            .filter_map(|block| {
+                match block {
+                    | VttBlock::Que(cue) => Some(cue),
+                    | _ => None,
+                }
+            })
+            .map(|cue| {
+                // The payload is measured as it is if the cue text is malformed.
+                let text = cue
+                    .cue_text()
+                    .map(|cue_text| cue_text.plain_text())
+                    .unwrap_or_else(|_| cue.payload.join("\n"));
+
+                LintCue {
+                    start: cue.timings.start.into(),
+                    end: cue.timings.end.into(),
+                    lines: text
+                        .split('\n')
+                        .map(|line| line.to_string())
+                        .collect(),
+                    sequence: None,
+                }
+            })
+            .collect();
+
+        lint::lint(&cues, config)
+    }
+
+    /// Repairs the timing defects of the cues, keeping the positions of the other blocks,
+    /// and returns the changes made.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::repair::{OverlapFix, RepairConfig};
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// 00:00:01.000 --> 00:00:03.000
+    /// Hello, world!
+    ///
+    /// NOTE A comment
+    ///
+    /// 00:00:02.000 --> 00:00:04.000
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let mut vtt = WebVtt::parse(text).unwrap();
+    /// let changes = vtt.repair(&RepairConfig {
+    ///     overlaps: Some(OverlapFix::PushLater),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(
+    ///     vtt.render(),
+    ///     "WEBVTT\n\n00:00:01.000 --> 00:00:03.000\nHello, world!\n\nNOTE A comment\n\n00:00:03.000 --> 00:00:05.000\nThis is a sample.\n"
+    /// );
+    /// assert_eq!(
+    ///     changes[0].to_string(),
+    ///     "cue 1: start delayed from 2000 ms to 3000 ms to resolve overlap"
+    /// );
+    /// ```
+    pub fn repair(
+        &mut self,
+        config: &RepairConfig,
+    ) -> Vec<RepairChange> {
+        let repaired = repair::repair(self.times(), config);
+
+        // Reorders the cues among the positions of cue blocks.
+        let positions: Vec<usize> = self
+            .blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| matches!(block, VttBlock::Que(_)))
+            .map(|(position, _)| position)
+            .collect();
+        let mut order: Vec<usize> = (0..self.blocks.len()).collect();
+        for (position, &cue) in positions
+            .iter()
+            .zip(&repaired.order)
+        {
+            order[*position] = positions[cue];
+        }
+        self.blocks = repair::reorder(std::mem::take(&mut self.blocks), &order);
+
+        for (cue, (start, end)) in self
+            .cues_mut()
+            .zip(repaired.times)
+        {
+            cue.timings.start = VttTimestamp::saturating_from(start);
+            cue.timings.end = VttTimestamp::saturating_from(end);
+        }
+
+        repaired.changes
+    }
+
+    /// Rewraps the payloads of all cues by [`reflow`](crate::reflow::reflow).
+    pub fn reflow(
+        &mut self,
+        config: &ReflowConfig,
+    ) {
+        for cue in self.cues_mut() {
+            cue.reflow(config);
+        }
+    }
+
+    /// Splits the cues exceeding the limits into consecutive cues with the same settings,
+    /// where only the first one keeps the identifier to keep identifiers unique.
+    ///
+    /// The tags open at a cut, e.g. `<i>`, are closed there and reopened in the next part.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::split::SplitConfig;
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// greeting
+    /// 00:00:01.000 --> 00:00:09.000 align:start
+    /// Hello, world! This is a sample.
+    /// "#;
+    ///
+    /// let mut vtt = WebVtt::parse(text).unwrap();
+    /// vtt.split(&SplitConfig {
+    ///     max_width: Some(20),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(
+    ///     vtt.render(),
+    ///     "WEBVTT\n\ngreeting\n00:00:01.000 --> 00:00:04.466 align:start\nHello, world!\n\n00:00:04.466 --> 00:00:09.000 align:start\nThis is a sample.\n"
+    /// );
+    /// ```
+    pub fn split(
+        &mut self,
+        config: &SplitConfig,
+    ) {
+        let mut blocks = Vec::with_capacity(self.blocks.len());
+        for block in std::mem::take(&mut self.blocks) {
+            let cue = match block {
+                | VttBlock::Que(cue) => cue,
+                | block => {
+                    blocks.push(block);
+                    continue;
+                },
+            };
+
+            let parts = split::split(
+                cue.timings.start.into(),
+                cue.timings.end.into(),
+                &cue.payload,
+                config,
+            );
+            match parts {
+                | Some(parts) => {
+                    blocks.extend(
+                        parts
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, part)| {
+                                VttBlock::Que(VttCue {
+                                    identifier: if i == 0 {
+                                        cue.identifier.clone()
+                                    } else {
+                                        None
+                                    },
+                                    timings: VttTimings {
+                                        start: VttTimestamp::saturating_from(
+                                            part.start,
+                                        ),
+                                        end: VttTimestamp::saturating_from(
+                                            part.end,
+                                        ),
+                                    },
+                                    settings: cue.settings.clone(),
+                                    payload: part.lines,
+                                })
+                            }),
+                    );
+                },
+                | None => blocks.push(VttBlock::Que(cue)),
+            }
+        }
+        self.blocks = blocks;
+    }
+
+    /// Merges the short cues with the consecutive cues of the same settings,
+    /// keeping the identifier of the first cue and the other blocks between them.
+    ///
+    /// The merged cue has the payloads of all cues.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::split::MergeConfig;
+    /// use subtp::vtt::WebVtt;
+    ///
+    /// let text = r#"WEBVTT
+    ///
+    /// first
+    /// 00:00:01.000 --> 00:00:01.500
+    /// Hello,
+    ///
+    /// second
+    /// 00:00:01.500 --> 00:00:03.000
+    /// world!
+    ///
+    /// 00:00:03.000 --> 00:00:03.500 line:0
+    /// This is a sample.
+    /// "#;
+    ///
+    /// let mut vtt = WebVtt::parse(text).unwrap();
+    /// vtt.merge(&MergeConfig::default());
+    ///
+    /// assert_eq!(
+    ///     vtt.render(),
+    ///     "WEBVTT\n\nfirst\n00:00:01.000 --> 00:00:03.000\nHello,\nworld!\n\n00:00:03.000 --> 00:00:03.500 line:0\nThis is a sample.\n"
+    /// );
+    /// ```
+    pub fn merge(
+        &mut self,
+        config: &MergeConfig,
+    ) {
+        let cues: Vec<&VttCue> = self
+            .blocks
+            .iter()
+            .filter_map(|block| {
+                match block {
+                    | VttBlock::Que(cue) => Some(cue),
+                    | _ => None,
+                }
+            })
+            .collect();
+        let groups = split::merge(
+            &cues
+                .iter()
+                .map(|cue| {
+                    (
+                        cue.timings.start.into(),
+                        cue.timings.end.into(),
+                        split::text_width(&cue.payload),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            |cue| cues[cue].settings == cues[cue - 1].settings,
+            config,
+        );
+        if groups.len() == cues.len() {
+            return;
+        }
+
+        // Whether each cue starts a merged cue.
+        let mut starts = vec![false; cues.len()];
+        for group in groups {
+            starts[group.start] = true;
+        }
+
+        let mut blocks: Vec<VttBlock> = Vec::with_capacity(self.blocks.len());
+        // The position of the merged cue in the blocks.
+        let mut merged = 0;
+        let mut cue_index = 0;
+        for block in std::mem::take(&mut self.blocks) {
+            match block {
+                | VttBlock::Que(cue) => {
+                    if starts[cue_index] {
+                        merged = blocks.len();
+                        blocks.push(VttBlock::Que(cue));
+                    } else if let VttBlock::Que(merged) = &mut blocks[merged] {
+                        merged.timings.end = merged
+                            .timings
+                            .end
+                            .max(cue.timings.end);
+                        merged
+                            .payload
+                            .extend(cue.payload);
+                    }
+                    cue_index += 1;
+                },
+                | block => blocks.push(block),
+            }
+        }
+        self.blocks = blocks;
+    }
+
+    /// Returns the start and end times of the cues.
+    fn times(&self) -> Vec<(Duration, Duration)> {
+        self.blocks
+            .iter()
+            .filter_map(|block| {
+                match block {
+                    | VttBlock::Que(cue) => {
+                        Some((
+                            cue.timings.start.into(),
+                            cue.timings.end.into(),
+                        ))
+                    },
+                    | _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Maps the times of the cues, failing without changes.
+    fn map_times(
+        &mut self,
+        map: impl Fn(Duration) -> Result<Duration, OutOfRange>,
+    ) -> Result<(), TimingError> {
+        let max = Duration::from(VttTimestamp::MAX);
+        let times = timing::map_times(self.times(), max, map)?;
+        for (cue, (start, end)) in self.cues_mut().zip(times) {
+            // The times are bounded by the maximum timestamp.
+            cue.timings.start = VttTimestamp::saturating_from(start);
+            cue.timings.end = VttTimestamp::saturating_from(end);
+        }
+
+        Ok(())
+    }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for WebVtt {
     fn default() -> Self {
         Self {
@@ -235,12 +885,12 @@ impl Display for WebVtt {
         &self,
         f: &mut std::fmt::Formatter<'_>,
     ) -> std::fmt::Result {
-        write!(f, "{}\n", self.header)?;
+        writeln!(f, "{}", self.header)?;
 
         let length = self.blocks.len();
         for (i, block) in self.blocks.iter().enumerate() {
             if i + 1 < length {
-                write!(f, "{}\n", block)?;
+                writeln!(f, "{}", block)?;
             } else {
                 write!(f, "{}", block)?;
             }
@@ -262,6 +912,311 @@ impl Iterator for WebVtt {
     }
 }
 
+/// The WebVTT format borrowing the identifiers and the payloads of cues from the input,
+/// parsed by the same grammar as [`WebVtt::parse`] without allocating each line.
+///
+/// The header, comments, styles and regions are owned as in [`WebVtt`].
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::{VttBlockRef, WebVttRef};
+///
+/// let text = r#"WEBVTT
+///
+/// intro
+/// 00:00:01.000 --> 00:00:04.000
+/// - Never drink liquid nitrogen.
+/// "#;
+///
+/// let vtt = WebVttRef::parse(text).unwrap();
+/// let VttBlockRef::Que(cue) = &vtt.blocks[0] else {
+///     panic!("not a cue");
+/// };
+/// assert_eq!(cue.identifier, Some("intro"));
+/// assert_eq!(cue.payload, vec!["- Never drink liquid nitrogen."]);
+///
+/// let owned = vtt.to_web_vtt();
+/// assert_eq!(owned.render(), text);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize)
+)]
+pub struct WebVttRef<'a> {
+    /// The header of the WebVTT.
+    pub header: VttHeader,
+    /// The blocks of the WebVTT.
+    pub blocks: Vec<VttBlockRef<'a>>,
+}
+
+impl<'a> WebVttRef<'a> {
+    /// Parses the WebVTT format from the given text, borrowing the texts of cues.
+    pub fn parse(input: &'a str) -> Result<Self, crate::error::ParseError> {
+        crate::vtt_parser::vtt(input).map_err(|err| {
+            let err = crate::error::ParseError::from(err);
+            match crate::error::block_at(input, err.offset) {
+                | (0, _) => err.with_context("header", None),
+                | (block, first) => {
+                    err.with_context(block_rule(first), Some(block - 1))
+                },
+            }
+        })
+    }
+
+    /// Parses the WebVTT format from the given text by the hand-written scanner,
+    /// borrowing the texts of cues as [`WebVtt::parse_fast`].
+    pub fn parse_fast(
+        input: &'a str
+    ) -> Result<Self, crate::error::ParseError> {
+        match crate::scanner::vtt(input) {
+            | Some(vtt) => Ok(vtt),
+            | None => Self::parse(input),
+        }
+    }
+
+    /// Converts into [`WebVtt`] by allocating the texts of cues.
+    pub fn to_web_vtt(&self) -> WebVtt {
+        WebVtt {
+            header: self.header.clone(),
+            blocks: self
+                .blocks
+                .iter()
+                .map(VttBlockRef::to_vtt_block)
+                .collect(),
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for WebVttRef<'_> {
+    fn default() -> Self {
+        Self {
+            header: VttHeader::default(),
+            blocks: vec![],
+        }
+    }
+}
+
+/// Returns the name of the block rule from the first line of the block.
+fn block_rule(first_line: &str) -> &'static str {
+    if first_line.starts_with("NOTE") {
+        "comment"
+    } else if first_line.starts_with("STYLE") {
+        "style"
+    } else if first_line.starts_with("REGION") {
+        "region"
+    } else {
+        "cue"
+    }
+}
+
+/// The streaming reader of the WebVTT format
+/// that reads the header first and then yields blocks one at a time from [`BufRead`].
+///
+/// Only the current block is kept in memory,
+/// so that long or incrementally piped inputs can be processed.
+/// A malformed block is reported as an error and the reader continues with the next one.
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::VttBlock;
+/// use subtp::vtt::VttReader;
+///
+/// let text = r#"WEBVTT
+///
+/// 00:00:01.000 --> 00:00:04.000
+/// - Never drink liquid nitrogen.
+///
+/// NOTE This is a comment.
+/// "#;
+///
+/// let reader = VttReader::new(text.as_bytes()).unwrap();
+/// assert_eq!(reader.header().description, None);
+///
+/// let blocks = reader
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(blocks.len(), 2);
+/// assert!(matches!(blocks[0], VttBlock::Que(_)));
+/// assert!(matches!(blocks[1], VttBlock::Comment(_)));
+/// ```
+pub struct VttReader<R> {
+    header: VttHeader,
+    blocks: BlockReader<R>,
+    /// Whether the underlying reader has failed.
+    failed: bool,
+}
+
+impl<R: BufRead> VttReader<R> {
+    /// Creates a reader from the buffered reader, reading the header.
+    pub fn new(reader: R) -> ReadResult<Self> {
+        let mut blocks = BlockReader::new(reader);
+        let header = match blocks.next_block()? {
+            | Some(block) => {
+                vtt_parser::header(&block.text).map_err(|err| {
+                    block
+                        .relocate(err.into())
+                        .with_context("header", None)
+                })?
+            },
+            | None => {
+                vtt_parser::header("").map_err(|err| {
+                    ParseError::from(err).with_context("header", None)
+                })?
+            },
+        };
+
+        Ok(Self {
+            header,
+            blocks,
+            failed: false,
+        })
+    }
+
+    /// Returns the header of the WebVTT.
+    pub fn header(&self) -> &VttHeader {
+        &self.header
+    }
+
+    /// Unwraps the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.blocks.into_inner()
+    }
+}
+
+impl<R: BufRead> Iterator for VttReader<R> {
+    type Item = ReadResult<VttBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let block = match self.blocks.next_block() {
+            | Ok(block) => block?,
+            | Err(err) => {
+                self.failed = true;
+                return Some(Err(err.into()));
+            },
+        };
+
+        Some(
+            vtt_parser::block(&block.text).map_err(|err| {
+                block
+                    .relocate(err.into())
+                    .with_context(
+                        block_rule(block.first_line()),
+                        Some(block.index - 1),
+                    )
+                    .into()
+            }),
+        )
+    }
+}
+
+/// The streaming renderer of the WebVTT format
+/// that writes the header first and then blocks one at a time to [`Write`].
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::VttCue;
+/// use subtp::vtt::VttHeader;
+/// use subtp::vtt::VttTimestamp;
+/// use subtp::vtt::VttTimings;
+/// use subtp::vtt::VttWriter;
+///
+/// let mut writer = VttWriter::new(vec![], &VttHeader::default()).unwrap();
+/// writer
+///     .write_block(
+///         &VttCue {
+///             timings: VttTimings {
+///                 start: VttTimestamp {
+///                     seconds: 1,
+///                     ..Default::default()
+///                 },
+///                 end: VttTimestamp {
+///                     seconds: 4,
+///                     ..Default::default()
+///                 },
+///             },
+///             payload: vec!["- Never drink liquid nitrogen.".to_string()],
+///             ..Default::default()
+///         }
+///         .into(),
+///     )
+///     .unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(writer.into_inner()).unwrap(),
+///     "WEBVTT\n\n00:00:01.000 --> 00:00:04.000\n- Never drink liquid nitrogen.\n".to_string()
+/// );
+/// ```
+pub struct VttWriter<W> {
+    inner: W,
+    line_ending: LineEnding,
+    /// The number of blocks written.
+    written: usize,
+}
+
+impl<W: Write> VttWriter<W> {
+    /// Creates a writer with `\n` line endings, writing the header.
+    pub fn new(
+        writer: W,
+        header: &VttHeader,
+    ) -> std::io::Result<Self> {
+        Self::with_line_ending(writer, header, LineEnding::default())
+    }
+
+    /// Creates a writer with the line ending, writing the header.
+    pub fn with_line_ending(
+        mut writer: W,
+        header: &VttHeader,
+        line_ending: LineEnding,
+    ) -> std::io::Result<Self> {
+        block_writer::write_text(
+            &mut writer,
+            &format!("{}\n", header),
+            line_ending,
+        )?;
+
+        Ok(Self {
+            inner: writer,
+            line_ending,
+            written: 0,
+        })
+    }
+
+    /// Writes the block, preceded by a blank line unless it is the first one.
+    pub fn write_block(
+        &mut self,
+        block: &VttBlock,
+    ) -> std::io::Result<()> {
+        if self.written > 0 {
+            block_writer::write_text(&mut self.inner, "\n", self.line_ending)?;
+        }
+        block_writer::write_text(
+            &mut self.inner,
+            &block.to_string(),
+            self.line_ending,
+        )?;
+        self.written += 1;
+
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
 /// The header block.
 ///
 /// ## Example
@@ -297,11 +1252,16 @@ impl Iterator for WebVtt {
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct VttHeader {
     /// The description of this file.
     pub description: Option<VttDescription>,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for VttHeader {
     fn default() -> Self {
         Self {
@@ -316,9 +1276,9 @@ impl Display for VttHeader {
         f: &mut std::fmt::Formatter<'_>,
     ) -> std::fmt::Result {
         if let Some(description) = &self.description {
-            write!(f, "WEBVTT{}\n", description)
+            writeln!(f, "WEBVTT{}", description)
         } else {
-            write!(f, "WEBVTT\n")
+            writeln!(f, "WEBVTT")
         }
     }
 }
@@ -343,6 +1303,10 @@ impl Display for VttHeader {
 ///     "\nThis is a description.".to_string()
 /// );
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum VttDescription {
     /// From side with "WEBVTT".
     Side(String),
@@ -457,6 +1421,10 @@ impl Display for VttDescription {
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum VttBlock {
     /// The cue block.
     Que(VttCue),
@@ -514,6 +1482,35 @@ impl Display for VttBlock {
     }
 }
 
+/// The block of [`WebVttRef`] borrowing the texts of cues from the input.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize)
+)]
+pub enum VttBlockRef<'a> {
+    /// The cue block.
+    Que(VttCueRef<'a>),
+    /// The comment block.
+    Comment(VttComment),
+    /// The style block.
+    Style(VttStyle),
+    /// The region block.
+    Region(VttRegion),
+}
+
+impl VttBlockRef<'_> {
+    /// Converts into [`VttBlock`] by allocating the texts of cues.
+    pub fn to_vtt_block(&self) -> VttBlock {
+        match self {
+            | Self::Que(que) => VttBlock::Que(que.to_vtt_cue()),
+            | Self::Comment(comment) => VttBlock::Comment(comment.clone()),
+            | Self::Style(style) => VttBlock::Style(style.clone()),
+            | Self::Region(region) => VttBlock::Region(region.clone()),
+        }
+    }
+}
+
 /// The region block.
 ///
 /// ## Example
@@ -554,6 +1551,10 @@ impl Display for VttBlock {
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct VttRegion {
     /// The identifier.
     pub id: Option<RegionId>,
@@ -572,6 +1573,7 @@ pub struct VttRegion {
 /// The region identifier.
 pub type RegionId = String;
 
+#[allow(clippy::derivable_impls)]
 impl Default for VttRegion {
     fn default() -> Self {
         Self {
@@ -590,34 +1592,30 @@ impl Display for VttRegion {
         &self,
         f: &mut std::fmt::Formatter<'_>,
     ) -> std::fmt::Result {
-        write!(f, "REGION\n")?;
+        writeln!(f, "REGION")?;
 
         if let Some(id) = &self.id {
-            write!(f, "id:{}\n", id)?;
+            writeln!(f, "id:{}", id)?;
         }
 
         if let Some(width) = self.width {
-            write!(f, "width:{}\n", width)?;
+            writeln!(f, "width:{}", width)?;
         }
 
         if let Some(lines) = self.lines {
-            write!(f, "lines:{}\n", lines)?;
+            writeln!(f, "lines:{}", lines)?;
         }
 
         if let Some(region_anchor) = self.region_anchor {
-            write!(f, "regionanchor:{}\n", region_anchor)?;
+            writeln!(f, "regionanchor:{}", region_anchor)?;
         }
 
         if let Some(viewport_anchor) = self.viewport_anchor {
-            write!(
-                f,
-                "viewportanchor:{}\n",
-                viewport_anchor
-            )?;
+            writeln!(f, "viewportanchor:{}", viewport_anchor)?;
         }
 
         if let Some(scroll) = self.scroll {
-            write!(f, "scroll:{}\n", scroll)?;
+            writeln!(f, "scroll:{}", scroll)?;
         }
 
         Ok(())
@@ -643,6 +1641,10 @@ impl Display for VttRegion {
 /// );
 /// ```
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum VttComment {
     /// Side with "NOTE".
     Side(String),
@@ -663,10 +1665,10 @@ impl Display for VttComment {
     ) -> std::fmt::Result {
         match self {
             | Self::Side(comment) => {
-                write!(f, "NOTE {}\n", comment)
+                writeln!(f, "NOTE {}", comment)
             },
             | Self::Below(comment) => {
-                write!(f, "NOTE\n{}\n", comment)
+                writeln!(f, "NOTE\n{}", comment)
             },
         }
     }
@@ -692,10 +1694,15 @@ impl Display for VttComment {
 /// );
 /// ```
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct VttStyle {
     pub style: String,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for VttStyle {
     fn default() -> Self {
         Self {
@@ -709,7 +1716,7 @@ impl Display for VttStyle {
         &self,
         f: &mut std::fmt::Formatter<'_>,
     ) -> std::fmt::Result {
-        write!(f, "STYLE\n{}\n", self.style)
+        writeln!(f, "STYLE\n{}", self.style)
     }
 }
 
@@ -767,7 +1774,7 @@ impl Display for VttStyle {
 ///     },
 ///     settings: Some(CueSettings {
 ///         vertical: Some(Vertical::Lr),
-///         line: Some(Line::LineNumber(3, Some(LineAlignment::Center))),
+///         line: Some(Line::LineNumber(3.0, Some(LineAlignment::Center))),
 ///         position: Some(Position {
 ///             value: Percentage {
 ///                 value: 50.0,
@@ -789,6 +1796,10 @@ impl Display for VttStyle {
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct VttCue {
     /// The identifier.
     pub identifier: Option<String>,
@@ -800,6 +1811,61 @@ pub struct VttCue {
     pub payload: Vec<String>,
 }
 
+impl VttCue {
+    /// Parses the payload as the cue text with markup.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::VttCue;
+    /// use subtp::vtt::cue_text::CueNode;
+    /// use subtp::vtt::cue_text::CueSpan;
+    ///
+    /// let cue = VttCue {
+    ///     payload: vec!["<i>Hello</i>".to_string(), "world!".to_string()],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let cue_text = cue.cue_text().unwrap();
+    ///
+    /// assert_eq!(
+    ///     cue_text.nodes,
+    ///     vec![
+    ///         CueNode::Italic(CueSpan {
+    ///             children: vec![CueNode::Text("Hello".to_string())],
+    ///             ..Default::default()
+    ///         }),
+    ///         CueNode::Text("\nworld!".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn cue_text(&self) -> ParseResult<CueText> {
+        CueText::parse(&self.payload.join("\n"))
+    }
+
+    /// Rewraps the payload by [`reflow`](crate::reflow::reflow).
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::reflow::ReflowConfig;
+    /// use subtp::vtt::VttCue;
+    ///
+    /// let mut cue = VttCue {
+    ///     payload: vec!["<v Roger>Hello,</v>".to_string(), "world!".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// cue.reflow(&ReflowConfig::default());
+    ///
+    /// assert_eq!(cue.payload, vec!["<v Roger>Hello,</v> world!".to_string()]);
+    /// ```
+    pub fn reflow(
+        &mut self,
+        config: &ReflowConfig,
+    ) {
+        self.payload = reflow::reflow(&self.payload, config);
+    }
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for VttCue {
     fn default() -> Self {
         Self {
@@ -817,7 +1883,7 @@ impl Display for VttCue {
         f: &mut std::fmt::Formatter<'_>,
     ) -> std::fmt::Result {
         if let Some(identifier) = &self.identifier {
-            write!(f, "{}\n", identifier)?;
+            writeln!(f, "{}", identifier)?;
         }
 
         write!(f, "{}", self.timings)?;
@@ -826,7 +1892,54 @@ impl Display for VttCue {
             write!(f, " {}", settings)?;
         }
 
-        write!(f, "\n{}\n", self.payload.join("\n"))
+        writeln!(f, "\n{}", self.payload.join("\n"))
+    }
+}
+
+/// The cue block of [`WebVttRef`] borrowing the identifier and the payload from the input.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize)
+)]
+pub struct VttCueRef<'a> {
+    /// The identifier.
+    pub identifier: Option<&'a str>,
+    /// The timings.
+    pub timings: VttTimings,
+    /// The settings.
+    pub settings: Option<CueSettings>,
+    /// The payload of subtitle text.
+    pub payload: Vec<&'a str>,
+}
+
+impl VttCueRef<'_> {
+    /// Converts into [`VttCue`] by allocating the identifier and the payload.
+    pub fn to_vtt_cue(&self) -> VttCue {
+        VttCue {
+            identifier: self
+                .identifier
+                .map(String::from),
+            timings: self.timings,
+            settings: self.settings.clone(),
+            payload: self
+                .payload
+                .iter()
+                .map(|line| line.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for VttCueRef<'_> {
+    fn default() -> Self {
+        Self {
+            identifier: None,
+            timings: VttTimings::default(),
+            settings: None,
+            payload: vec![],
+        }
     }
 }
 
@@ -854,6 +1967,10 @@ impl Display for VttCue {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct VttTimings {
     /// The start timestamp.
     pub start: VttTimestamp,
@@ -861,6 +1978,7 @@ pub struct VttTimings {
     pub end: VttTimestamp,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for VttTimings {
     fn default() -> Self {
         Self {
@@ -920,6 +2038,127 @@ pub struct VttTimestamp {
     pub milliseconds: u16,
 }
 
+impl VttTimestamp {
+    /// The maximum timestamp `4294967295:59:59.999`.
+    pub const MAX: Self = Self {
+        hours: u32::MAX,
+        minutes: 59,
+        seconds: 59,
+        milliseconds: 999,
+    };
+
+    /// Creates the timestamp, validating that the minutes and the seconds are less than 60
+    /// and the milliseconds are less than 1000.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::VttTimestamp;
+    ///
+    /// let timestamp = VttTimestamp::new(1, 2, 3, 4).unwrap();
+    /// assert_eq!(timestamp.to_string(), "01:02:03.004");
+    ///
+    /// assert!(VttTimestamp::new(0, 0, 60, 0).is_err());
+    /// ```
+    pub fn new(
+        hours: u32,
+        minutes: u8,
+        seconds: u8,
+        milliseconds: u16,
+    ) -> Result<Self, TimestampFieldError> {
+        TimestampFieldError::validate(minutes, seconds, milliseconds)?;
+
+        Ok(Self {
+            hours,
+            minutes,
+            seconds,
+            milliseconds,
+        })
+    }
+
+    /// Converts the duration truncated to milliseconds, clamping at [`VttTimestamp::MAX`].
+    pub fn saturating_from(duration: Duration) -> Self {
+        Self::try_from(duration).unwrap_or(Self::MAX)
+    }
+
+    /// Adds the duration, returning `None` if the result exceeds [`VttTimestamp::MAX`].
+    ///
+    /// The result is truncated to milliseconds.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    /// use subtp::vtt::VttTimestamp;
+    ///
+    /// let timestamp = VttTimestamp {
+    ///     seconds: 1,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     timestamp
+    ///         .checked_add(Duration::from_millis(1500))
+    ///         .map(|timestamp| timestamp.to_string()),
+    ///     Some("00:00:02.500".to_string())
+    /// );
+    /// assert_eq!(VttTimestamp::MAX.checked_add(Duration::from_millis(1)), None);
+    /// ```
+    pub fn checked_add(
+        self,
+        duration: Duration,
+    ) -> Option<Self> {
+        Duration::from(self)
+            .checked_add(duration)
+            .and_then(|duration| Self::try_from(duration).ok())
+    }
+
+    /// Subtracts the duration, returning `None` if the result is negative.
+    ///
+    /// The result is truncated to milliseconds.
+    pub fn checked_sub(
+        self,
+        duration: Duration,
+    ) -> Option<Self> {
+        Duration::from(self)
+            .checked_sub(duration)
+            .and_then(|duration| Self::try_from(duration).ok())
+    }
+
+    /// Adds the duration, clamping at [`VttTimestamp::MAX`].
+    pub fn saturating_add(
+        self,
+        duration: Duration,
+    ) -> Self {
+        self.checked_add(duration)
+            .unwrap_or(Self::MAX)
+    }
+
+    /// Subtracts the duration, clamping at zero.
+    pub fn saturating_sub(
+        self,
+        duration: Duration,
+    ) -> Self {
+        self.checked_sub(duration)
+            .unwrap_or_default()
+    }
+
+    /// Returns the duration from the earlier timestamp, or `None` if it is later than this one.
+    pub fn checked_duration_since(
+        self,
+        earlier: Self,
+    ) -> Option<Duration> {
+        Duration::from(self).checked_sub(earlier.into())
+    }
+
+    /// Returns the duration from the earlier timestamp, or zero if it is later than this one.
+    pub fn saturating_duration_since(
+        self,
+        earlier: Self,
+    ) -> Duration {
+        Duration::from(self).saturating_sub(earlier.into())
+    }
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for VttTimestamp {
     fn default() -> Self {
         Self {
@@ -944,35 +2183,151 @@ impl Display for VttTimestamp {
     }
 }
 
-impl From<Duration> for VttTimestamp {
-    fn from(duration: Duration) -> Self {
+impl FromStr for VttTimestamp {
+    type Err = ParseError;
+
+    /// Parses the timestamp such as `00:01:02.345`.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::VttTimestamp;
+    ///
+    /// let timestamp: VttTimestamp = "00:01:02.345".parse().unwrap();
+    /// assert_eq!(timestamp.minutes, 1);
+    ///
+    /// assert!("00:61:02.345".parse::<VttTimestamp>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        vtt_parser::timestamp(s).map_err(ParseError::from)
+    }
+}
+
+impl TryFrom<Duration> for VttTimestamp {
+    type Error = TimestampRangeError;
+
+    /// Converts the duration truncated to milliseconds,
+    /// failing if it exceeds [`VttTimestamp::MAX`].
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
         let seconds = duration.as_secs();
         let milliseconds = duration.subsec_millis() as u16;
 
-        let hours = (seconds / 3600) as u32;
+        let hours = u32::try_from(seconds / 3600).map_err(|_| {
+            TimestampRangeError {
+                duration,
+            }
+        })?;
         let minutes = ((seconds % 3600) / 60) as u8;
         let seconds = (seconds % 60) as u8;
 
-        Self {
+        Ok(Self {
             hours,
             minutes,
             seconds,
             milliseconds,
-        }
+        })
+    }
+}
+
+impl Add<Duration> for VttTimestamp {
+    type Output = Self;
+
+    /// Adds the duration.
+    ///
+    /// ## Panics
+    /// Panics if the result exceeds [`VttTimestamp::MAX`], see [`VttTimestamp::checked_add`].
+    fn add(
+        self,
+        duration: Duration,
+    ) -> Self::Output {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to timestamp")
+    }
+}
+
+impl AddAssign<Duration> for VttTimestamp {
+    fn add_assign(
+        &mut self,
+        duration: Duration,
+    ) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for VttTimestamp {
+    type Output = Self;
+
+    /// Subtracts the duration.
+    ///
+    /// ## Panics
+    /// Panics if the result is negative, see [`VttTimestamp::checked_sub`].
+    fn sub(
+        self,
+        duration: Duration,
+    ) -> Self::Output {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from timestamp")
+    }
+}
+
+impl SubAssign<Duration> for VttTimestamp {
+    fn sub_assign(
+        &mut self,
+        duration: Duration,
+    ) {
+        *self = *self - duration;
+    }
+}
+
+impl Sub for VttTimestamp {
+    type Output = Duration;
+
+    /// Returns the duration between the timestamps.
+    ///
+    /// ## Panics
+    /// Panics if `rhs` is later than `self`, see [`VttTimestamp::checked_duration_since`].
+    fn sub(
+        self,
+        rhs: Self,
+    ) -> Self::Output {
+        self.checked_duration_since(rhs)
+            .expect("overflow when subtracting timestamps")
     }
 }
 
-impl Into<Duration> for VttTimestamp {
-    fn into(self) -> Duration {
+impl From<VttTimestamp> for Duration {
+    fn from(timestamp: VttTimestamp) -> Self {
         Duration::new(
-            self.hours as u64 * 3600
-                + self.minutes as u64 * 60
-                + self.seconds as u64,
-            self.milliseconds as u32 * 1_000_000,
+            timestamp.hours as u64 * 3600
+                + timestamp.minutes as u64 * 60
+                + timestamp.seconds as u64,
+            timestamp.milliseconds as u32 * 1_000_000,
         )
     }
 }
 
+/// Serializes the timestamp as a string such as `00:00:01.000`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for VttTimestamp {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes the timestamp from a string such as `00:00:01.000`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VttTimestamp {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 /// The settings of cue.
 ///
 /// ## Example
@@ -988,7 +2343,7 @@ impl Into<Duration> for VttTimestamp {
 ///
 /// let settings = CueSettings {
 ///     vertical: Some(Vertical::Lr),
-///     line: Some(Line::LineNumber(3, Some(LineAlignment::Center))),
+///     line: Some(Line::LineNumber(3.0, Some(LineAlignment::Center))),
 ///     position: Some(Position {
 ///         value: Percentage {
 ///             value: 50.0,
@@ -1008,6 +2363,10 @@ impl Into<Duration> for VttTimestamp {
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct CueSettings {
     /// The vertical setting.
     pub vertical: Option<Vertical>,
@@ -1023,6 +2382,7 @@ pub struct CueSettings {
     pub region: Option<RegionId>,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for CueSettings {
     fn default() -> Self {
         Self {
@@ -1087,6 +2447,10 @@ impl Display for CueSettings {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct Percentage {
     pub value: f32,
 }
@@ -1134,6 +2498,10 @@ impl Display for Percentage {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct Anchor {
     /// The horizontal setting.
     pub x: Percentage,
@@ -1176,6 +2544,10 @@ impl Display for Anchor {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Scroll {
     /// The scroll up.
     Up,
@@ -1214,6 +2586,10 @@ impl Display for Scroll {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Vertical {
     /// From right to left.
     Rl,
@@ -1251,7 +2627,7 @@ impl Display for Vertical {
 /// use subtp::vtt::LineAlignment;
 ///
 /// // Without alignment.
-/// let line = Line::LineNumber(3, None);
+/// let line = Line::LineNumber(3.0, None);
 ///
 /// assert_eq!(
 ///     line.to_string(),
@@ -1259,7 +2635,7 @@ impl Display for Vertical {
 /// );
 ///
 /// // With alignment.
-/// let line = Line::LineNumber(3, Some(LineAlignment::Center));
+/// let line = Line::LineNumber(3.0, Some(LineAlignment::Center));
 ///
 /// assert_eq!(
 ///     line.to_string(),
@@ -1267,11 +2643,16 @@ impl Display for Vertical {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Line {
     /// The percentage.
     Percentage(Percentage, Option<LineAlignment>),
-    /// The line number.
-    LineNumber(i32, Option<LineAlignment>),
+    /// The line number, with a fraction only by [`WebVtt::parse_spec`]
+    /// as the syntax allows integers.
+    LineNumber(f32, Option<LineAlignment>),
 }
 
 impl Default for Line {
@@ -1318,6 +2699,10 @@ impl Display for Line {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum LineAlignment {
     /// The start alignment.
     Start,
@@ -1387,6 +2772,10 @@ impl Display for LineAlignment {
 /// );
 /// ```
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct Position {
     /// The position value.
     pub value: Percentage,
@@ -1394,6 +2783,7 @@ pub struct Position {
     pub alignment: Option<PositionAlignment>,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Position {
     fn default() -> Self {
         Self {
@@ -1430,6 +2820,10 @@ impl Display for Position {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum PositionAlignment {
     /// The line left alignment.
     LineLeft,
@@ -1478,6 +2872,10 @@ impl Display for PositionAlignment {
 /// );
 /// ```
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Alignment {
     /// The start alignment.
     Start,
@@ -2002,7 +3400,7 @@ video::cue {
     #[test]
     fn from_duration_to_timestamp() {
         let duration = Duration::new(1, 0);
-        let timestamp: VttTimestamp = duration.into();
+        let timestamp: VttTimestamp = duration.try_into().unwrap();
         assert_eq!(
             timestamp,
             VttTimestamp {
@@ -2012,7 +3410,7 @@ video::cue {
         );
 
         let duration = Duration::new(1, 500_000_000);
-        let timestamp: VttTimestamp = duration.into();
+        let timestamp: VttTimestamp = duration.try_into().unwrap();
         assert_eq!(
             timestamp,
             VttTimestamp {
@@ -2021,6 +3419,98 @@ video::cue {
                 ..Default::default()
             }
         );
+
+        let duration =
+            Duration::from(VttTimestamp::MAX) + Duration::from_secs(1);
+        assert!(VttTimestamp::try_from(duration).is_err());
+        assert_eq!(
+            VttTimestamp::saturating_from(duration),
+            VttTimestamp::MAX
+        );
+    }
+
+    #[test]
+    fn repair_sorts_cues_around_other_blocks() {
+        let mut vtt = WebVtt::parse(
+            r#"WEBVTT
+
+b
+00:00:03.000 --> 00:00:04.000
+Second
+
+NOTE A comment
+
+a
+00:00:01.000 --> 00:00:02.000
+First
+"#,
+        )
+        .unwrap();
+
+        let changes = vtt.repair(&RepairConfig::default());
+        assert_eq!(
+            vtt.render(),
+            "WEBVTT\n\na\n00:00:01.000 --> 00:00:02.000\nFirst\n\nNOTE A comment\n\nb\n00:00:03.000 --> 00:00:04.000\nSecond\n"
+        );
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn merge_keeps_other_blocks() {
+        let mut vtt = WebVtt::parse(
+            r#"WEBVTT
+
+a
+00:00:01.000 --> 00:00:01.500
+First
+
+NOTE A comment
+
+b
+00:00:01.500 --> 00:00:02.000
+Second
+
+00:00:02.000 --> 00:00:04.000
+Third
+"#,
+        )
+        .unwrap();
+
+        vtt.merge(&MergeConfig::default());
+        assert_eq!(
+            vtt.render(),
+            "WEBVTT\n\na\n00:00:01.000 --> 00:00:02.000\nFirst\nSecond\n\nNOTE A comment\n\n00:00:02.000 --> 00:00:04.000\nThird\n"
+        );
+    }
+
+    #[test]
+    fn timestamp_arithmetic() {
+        let timestamp = VttTimestamp {
+            seconds: 1,
+            ..Default::default()
+        };
+
+        let mut later = timestamp + Duration::from_secs(300 * 3600);
+        assert_eq!(later.to_string(), "300:00:01.000");
+        later -= Duration::from_millis(1);
+        assert_eq!(later.to_string(), "300:00:00.999");
+        assert_eq!(
+            later - timestamp,
+            Duration::from_millis(300 * 3600 * 1000 - 1)
+        );
+
+        assert_eq!(
+            timestamp.checked_sub(Duration::from_secs(2)),
+            None
+        );
+        assert_eq!(
+            VttTimestamp::MAX.saturating_add(Duration::from_secs(1)),
+            VttTimestamp::MAX
+        );
+        assert_eq!(
+            timestamp.checked_duration_since(later),
+            None
+        );
     }
 
     #[test]
@@ -2058,7 +3548,9 @@ video::cue {
         let duration = end - start;
         assert_eq!(duration, Duration::new(3, 0));
 
-        let timestamp: VttTimestamp = (start + duration).into();
+        let timestamp: VttTimestamp = (start + duration)
+            .try_into()
+            .unwrap();
         assert_eq!(
             timestamp,
             VttTimestamp {
@@ -2082,4 +3574,259 @@ video::cue {
 
         assert!(start < end);
     }
+
+    #[test]
+    fn reader() {
+        let text = "WEBVTT - Example\n\n\
+            00:00:01.000 --> 00:00:02.000\nHello, world!\n\n\
+            intro\n00:00:03 --> 00:00:04.000\nBroken\n\n\
+            NOTE A comment\n";
+
+        let mut reader = VttReader::new(text.as_bytes()).unwrap();
+        assert_eq!(
+            reader.header().description,
+            Some(VttDescription::Side(
+                "- Example\n".to_string()
+            ))
+        );
+
+        assert!(matches!(
+            reader.next(),
+            Some(Ok(VttBlock::Que(_)))
+        ));
+
+        let err = match reader.next().unwrap() {
+            | Err(crate::ReadError::Parse(err)) => err,
+            | other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(err.line, 7);
+        assert_eq!(err.column, 9);
+        assert_eq!(err.rule.as_deref(), Some("cue"));
+        assert_eq!(err.block, Some(1));
+
+        assert!(matches!(
+            reader.next(),
+            Some(Ok(VttBlock::Comment(_)))
+        ));
+        assert!(reader.next().is_none());
+
+        let err = match VttReader::new("".as_bytes()) {
+            | Err(crate::ReadError::Parse(err)) => err,
+            | _ => panic!("expected a parse error"),
+        };
+        assert_eq!(err.rule.as_deref(), Some("header"));
+    }
+
+    #[test]
+    fn reader_matches_parse() {
+        let text = r#"WEBVTT
+Description below
+
+STYLE
+::cue {
+  color: yellow;
+}
+
+REGION
+id:bottom
+
+NOTE A comment
+
+intro
+00:00:01.000 --> 00:00:02.000 line:0 region:bottom
+Hello, world!
+
+00:00:03.000 --> 00:00:04.000
+This is a test.
+"#;
+
+        let vtt = WebVtt::parse(text).unwrap();
+        let reader = VttReader::new(text.as_bytes()).unwrap();
+
+        assert_eq!(reader.header(), &vtt.header);
+        assert_eq!(
+            reader
+                .collect::<ReadResult<Vec<_>>>()
+                .unwrap(),
+            vtt.blocks
+        );
+    }
+
+    #[test]
+    fn reader_whitespace_lines() {
+        for text in [
+            "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nHello\n \nWorld\n",
+            "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n\
+                Hello\n\u{A0}\n\u{3000}\nWorld\n",
+            "WEBVTT\n\n \n00:00:01.000 --> 00:00:02.000\nHello\n\t\n",
+        ] {
+            let vtt = WebVtt::parse(text).unwrap();
+            let reader = VttReader::new(text.as_bytes()).unwrap();
+
+            assert_eq!(reader.header(), &vtt.header);
+            assert_eq!(
+                reader
+                    .collect::<ReadResult<Vec<_>>>()
+                    .unwrap(),
+                vtt.blocks,
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn writer_matches_render() {
+        let text = r#"WEBVTT - Example
+
+STYLE
+::cue {
+  color: yellow;
+}
+
+NOTE A comment
+
+intro
+00:00:01.000 --> 00:00:02.000 line:0
+Hello, world!
+
+00:00:03.000 --> 00:00:04.000
+This is a test.
+"#;
+        let vtt = WebVtt::parse(text).unwrap();
+
+        let mut writer = VttWriter::new(vec![], &vtt.header).unwrap();
+        for block in &vtt.blocks {
+            writer
+                .write_block(block)
+                .unwrap();
+        }
+        writer.flush().unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            vtt.render()
+        );
+
+        let mut writer = VttWriter::with_line_ending(
+            vec![],
+            &VttHeader::default(),
+            LineEnding::CrLf,
+        )
+        .unwrap();
+        writer
+            .write_block(&vtt.blocks[1])
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            "WEBVTT\r\n\r\nNOTE A comment\r\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let text = r#"WEBVTT
+
+intro
+00:00:01.000 --> 00:00:02.000 line:0 align:start
+Hello, world!
+
+NOTE A comment
+"#;
+        let vtt = WebVtt::parse(text).unwrap();
+
+        let json = serde_json::to_string(&vtt).unwrap();
+        assert!(json.contains(r#""start":"00:00:01.000""#));
+        assert_eq!(
+            serde_json::from_str::<WebVtt>(&json).unwrap(),
+            vtt
+        );
+
+        assert_eq!(
+            serde_json::from_str::<VttTimestamp>(r#""01:02.003""#).unwrap(),
+            VttTimestamp {
+                minutes: 1,
+                seconds: 2,
+                milliseconds: 3,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn timing() {
+        let text = r#"WEBVTT
+
+NOTE The cues are 10 seconds late.
+
+00:00:11.000 --> 00:00:12.000
+Hello, world!
+
+00:00:31.000 --> 00:00:34.000
+This is a test.
+"#;
+        let mut vtt = WebVtt::parse(text).unwrap();
+
+        vtt.resync(
+            0,
+            Duration::from_secs(1),
+            1,
+            Duration::from_secs(21),
+        )
+        .unwrap();
+
+        let timings = vtt
+            .blocks
+            .iter()
+            .filter_map(|block| {
+                match block {
+                    | VttBlock::Que(cue) => Some(cue.timings.to_string()),
+                    | _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            timings,
+            vec![
+                "00:00:01.000 --> 00:00:02.000".to_string(),
+                "00:00:21.000 --> 00:00:24.000".to_string(),
+            ]
+        );
+        assert!(matches!(
+            vtt.blocks[0],
+            VttBlock::Comment(_)
+        ));
+
+        assert_eq!(
+            vtt.scale(-1.0),
+            Err(TimingError::InvalidFactor(-1.0))
+        );
+    }
+
+    #[test]
+    fn parse_ref() {
+        let text = "WEBVTT\n\nNOTE comment\n\nid\n00:01.000 --> 00:02.000 align:end\n  Hello  \nworld\n";
+        let vtt = WebVttRef::parse(text).unwrap();
+
+        assert_eq!(vtt.blocks.len(), 2);
+        assert_eq!(
+            vtt.blocks[0],
+            VttBlockRef::Comment(VttComment::Side("comment".to_string()))
+        );
+        let VttBlockRef::Que(cue) = &vtt.blocks[1] else {
+            panic!("not a cue: {:?}", vtt.blocks[1]);
+        };
+        assert_eq!(cue.identifier, Some("id"));
+        assert_eq!(cue.payload, vec!["Hello", "world"]);
+        // The lines are slices of the input.
+        let range = text.as_bytes().as_ptr_range();
+        assert!(range.contains(&cue.payload[0].as_ptr()));
+
+        assert_eq!(
+            vtt.to_web_vtt(),
+            WebVtt::parse(text).unwrap()
+        );
+        assert!(WebVttRef::parse("WEBVTT\n\n00:01.000 --> bogus\n").is_err());
+    }
 }
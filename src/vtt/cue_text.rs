@@ -0,0 +1,775 @@
+//! The cue text markup of WebVTT provided by [`subtp::vtt::cue_text::CueText`](CueText).
+//!
+//! ## Example
+//! ```
+//! use subtp::vtt::cue_text::CueText;
+//! use subtp::vtt::cue_text::CueNode;
+//! use subtp::vtt::cue_text::CueSpan;
+//! use subtp::vtt::cue_text::CueAnnotatedSpan;
+//!
+//! let text = "<v Roger Bingham>We are in New York City</v>\n<i.loud>Yes!</i>";
+//!
+//! let cue_text = CueText::parse(text).unwrap();
+//!
+//! assert_eq!(
+//!     cue_text,
+//!     CueText {
+//!         nodes: vec![
+//!             CueNode::Voice(CueAnnotatedSpan {
+//!                 annotation: "Roger Bingham".to_string(),
+//!                 children: vec![CueNode::Text(
+//!                     "We are in New York City".to_string()
+//!                 )],
+//!                 ..Default::default()
+//!             }),
+//!             CueNode::Text("\n".to_string()),
+//!             CueNode::Italic(CueSpan {
+//!                 classes: vec!["loud".to_string()],
+//!                 children: vec![CueNode::Text("Yes!".to_string())],
+//!             }),
+//!         ],
+//!     }
+//! );
+//!
+//! assert_eq!(cue_text.render(), text);
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+use crate::vtt::VttTimestamp;
+use crate::vtt_parser;
+use crate::ParseResult;
+
+/// The cue text of a [`VttCue`](crate::vtt::VttCue) payload as a node tree.
+///
+/// Parses from text by [`CueText::parse`](CueText::parse)
+/// and renders to text by [`CueText::render`](CueText::render).
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::cue_text::CueText;
+/// use subtp::vtt::cue_text::CueNode;
+/// use subtp::vtt::cue_text::CueSpan;
+///
+/// let cue_text = CueText {
+///     nodes: vec![
+///         CueNode::Text("Hello, ".to_string()),
+///         CueNode::Bold(CueSpan {
+///             children: vec![CueNode::Text("world".to_string())],
+///             ..Default::default()
+///         }),
+///         CueNode::Text("!".to_string()),
+///     ],
+/// };
+///
+/// assert_eq!(
+///     cue_text.render(),
+///     "Hello, <b>world</b>!".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CueText {
+    /// The top-level nodes.
+    pub nodes: Vec<CueNode>,
+}
+
+impl CueText {
+    /// Parses the cue text from the given text.
+    ///
+    /// The text follows the WebVTT cue text parsing rules:
+    /// unknown tags and unmatched end tags are ignored,
+    /// and tags left open at the end of the text are closed implicitly.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::cue_text::CueText;
+    ///
+    /// let cue_text = CueText::parse("<c.yellow>Hello</c>, <00:00:01.500>world!").unwrap();
+    /// ```
+    pub fn parse(text: &str) -> ParseResult<Self> {
+        let tokens = vtt_parser::cue_text(text)?;
+        Ok(build_tree(tokens))
+    }
+
+    /// Renders the text from the cue text.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::cue_text::CueText;
+    /// use subtp::vtt::cue_text::CueNode;
+    ///
+    /// let cue_text = CueText {
+    ///     nodes: vec![CueNode::Text("Fish & Chips".to_string())],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     cue_text.render(),
+    ///     "Fish &amp; Chips".to_string()
+    /// );
+    /// ```
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    /// Collects the text without any markup.
+    ///
+    /// ## Example
+    /// ```
+    /// use subtp::vtt::cue_text::CueText;
+    ///
+    /// let cue_text = CueText::parse("<v Bob><b>Hello</b>, world!</v>").unwrap();
+    ///
+    /// assert_eq!(
+    ///     cue_text.plain_text(),
+    ///     "Hello, world!".to_string()
+    /// );
+    /// ```
+    pub fn plain_text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.nodes {
+            node.collect_text(&mut text);
+        }
+        text
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for CueText {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+        }
+    }
+}
+
+impl Display for CueText {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        for node in &self.nodes {
+            write!(f, "{}", node)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The node of cue text.
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::cue_text::CueNode;
+/// use subtp::vtt::cue_text::CueSpan;
+/// use subtp::vtt::cue_text::CueAnnotatedSpan;
+/// use subtp::vtt::VttTimestamp;
+///
+/// let node = CueNode::Language(CueAnnotatedSpan {
+///     annotation: "en".to_string(),
+///     classes: vec![],
+///     children: vec![
+///         CueNode::Text("Hello, ".to_string()),
+///         CueNode::Timestamp(VttTimestamp {
+///             seconds: 1,
+///             milliseconds: 500,
+///             ..Default::default()
+///         }),
+///         CueNode::Class(CueSpan {
+///             classes: vec!["yellow".to_string()],
+///             children: vec![CueNode::Text("world!".to_string())],
+///         }),
+///     ],
+/// });
+///
+/// assert_eq!(
+///     node.to_string(),
+///     "<lang en>Hello, <00:00:01.500><c.yellow>world!</c></lang>".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum CueNode {
+    /// The text.
+    Text(String),
+    /// The inline timestamp, `<00:00:00.000>`.
+    Timestamp(VttTimestamp),
+    /// The class span, `<c>`.
+    Class(CueSpan),
+    /// The italics span, `<i>`.
+    Italic(CueSpan),
+    /// The bold span, `<b>`.
+    Bold(CueSpan),
+    /// The underline span, `<u>`.
+    Underline(CueSpan),
+    /// The ruby span, `<ruby>`.
+    Ruby(CueSpan),
+    /// The ruby text span, `<rt>`, only inside of a ruby span.
+    RubyText(CueSpan),
+    /// The voice span, `<v>`, annotated with the name of the speaker.
+    Voice(CueAnnotatedSpan),
+    /// The language span, `<lang>`, annotated with the language tag.
+    Language(CueAnnotatedSpan),
+}
+
+impl CueNode {
+    /// Returns the tag name of this node if it is a span.
+    fn tag_name(&self) -> Option<&'static str> {
+        match self {
+            | Self::Text(_) | Self::Timestamp(_) => None,
+            | Self::Class(_) => Some("c"),
+            | Self::Italic(_) => Some("i"),
+            | Self::Bold(_) => Some("b"),
+            | Self::Underline(_) => Some("u"),
+            | Self::Ruby(_) => Some("ruby"),
+            | Self::RubyText(_) => Some("rt"),
+            | Self::Voice(_) => Some("v"),
+            | Self::Language(_) => Some("lang"),
+        }
+    }
+
+    /// Returns the child nodes of this node.
+    fn children(&self) -> &[CueNode] {
+        match self {
+            | Self::Text(_) | Self::Timestamp(_) => &[],
+            | Self::Class(span)
+            | Self::Italic(span)
+            | Self::Bold(span)
+            | Self::Underline(span)
+            | Self::Ruby(span)
+            | Self::RubyText(span) => &span.children,
+            | Self::Voice(span) | Self::Language(span) => &span.children,
+        }
+    }
+
+    /// Returns the mutable child nodes of this node.
+    fn children_mut(&mut self) -> Option<&mut Vec<CueNode>> {
+        match self {
+            | Self::Text(_) | Self::Timestamp(_) => None,
+            | Self::Class(span)
+            | Self::Italic(span)
+            | Self::Bold(span)
+            | Self::Underline(span)
+            | Self::Ruby(span)
+            | Self::RubyText(span) => Some(&mut span.children),
+            | Self::Voice(span) | Self::Language(span) => {
+                Some(&mut span.children)
+            },
+        }
+    }
+
+    fn collect_text(
+        &self,
+        text: &mut String,
+    ) {
+        if let Self::Text(value) = self {
+            text.push_str(value);
+        }
+
+        for child in self.children() {
+            child.collect_text(text);
+        }
+    }
+}
+
+impl Default for CueNode {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl Display for CueNode {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::Text(text) => {
+                write!(f, "{}", escape(text, &['&', '<', '>']))
+            },
+            | Self::Timestamp(timestamp) => {
+                write!(f, "<{}>", timestamp)
+            },
+            | Self::Class(span)
+            | Self::Italic(span)
+            | Self::Bold(span)
+            | Self::Underline(span)
+            | Self::Ruby(span)
+            | Self::RubyText(span) => {
+                let tag = self
+                    .tag_name()
+                    .unwrap_or_default();
+                write!(f, "<{}", tag)?;
+                for class in &span.classes {
+                    write!(f, ".{}", class)?;
+                }
+                write!(f, ">")?;
+                for child in &span.children {
+                    write!(f, "{}", child)?;
+                }
+                write!(f, "</{}>", tag)
+            },
+            | Self::Voice(span) | Self::Language(span) => {
+                let tag = self
+                    .tag_name()
+                    .unwrap_or_default();
+                write!(f, "<{}", tag)?;
+                for class in &span.classes {
+                    write!(f, ".{}", class)?;
+                }
+                if !span.annotation.is_empty() {
+                    write!(
+                        f,
+                        " {}",
+                        escape(&span.annotation, &['&', '>'])
+                    )?;
+                }
+                write!(f, ">")?;
+                for child in &span.children {
+                    write!(f, "{}", child)?;
+                }
+                write!(f, "</{}>", tag)
+            },
+        }
+    }
+}
+
+/// The span of cue text with classes.
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::cue_text::CueNode;
+/// use subtp::vtt::cue_text::CueSpan;
+///
+/// let node = CueNode::Underline(CueSpan {
+///     classes: vec!["a".to_string(), "b".to_string()],
+///     children: vec![CueNode::Text("Underlined".to_string())],
+/// });
+///
+/// assert_eq!(
+///     node.to_string(),
+///     "<u.a.b>Underlined</u>".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CueSpan {
+    /// The class names.
+    pub classes: Vec<String>,
+    /// The child nodes.
+    pub children: Vec<CueNode>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for CueSpan {
+    fn default() -> Self {
+        Self {
+            classes: vec![],
+            children: vec![],
+        }
+    }
+}
+
+/// The span of cue text with classes and an annotation.
+///
+/// ## Example
+/// ```
+/// use subtp::vtt::cue_text::CueNode;
+/// use subtp::vtt::cue_text::CueAnnotatedSpan;
+///
+/// let node = CueNode::Voice(CueAnnotatedSpan {
+///     annotation: "Esme".to_string(),
+///     classes: vec!["first".to_string()],
+///     children: vec![CueNode::Text("It's a blue apple tree!".to_string())],
+/// });
+///
+/// assert_eq!(
+///     node.to_string(),
+///     "<v.first Esme>It's a blue apple tree!</v>".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CueAnnotatedSpan {
+    /// The annotation, e.g. the name of speaker or the language tag.
+    pub annotation: String,
+    /// The class names.
+    pub classes: Vec<String>,
+    /// The child nodes.
+    pub children: Vec<CueNode>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for CueAnnotatedSpan {
+    fn default() -> Self {
+        Self {
+            annotation: String::new(),
+            classes: vec![],
+            children: vec![],
+        }
+    }
+}
+
+/// The token of cue text produced by the tokenizer.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CueTextToken {
+    /// The text with resolved character references.
+    Text(String),
+    /// The start tag.
+    StartTag {
+        name: String,
+        classes: Vec<String>,
+        annotation: Option<String>,
+    },
+    /// The end tag.
+    EndTag(String),
+    /// The timestamp tag.
+    Timestamp(VttTimestamp),
+}
+
+/// Builds the node tree from tokens by the WebVTT cue text parsing rules.
+fn build_tree(tokens: Vec<CueTextToken>) -> CueText {
+    // The stack of open spans, excluding the root.
+    let mut stack: Vec<CueNode> = vec![];
+    let mut root: Vec<CueNode> = vec![];
+
+    fn current<'a>(
+        root: &'a mut Vec<CueNode>,
+        stack: &'a mut [CueNode],
+    ) -> &'a mut Vec<CueNode> {
+        match stack.last_mut() {
+            | Some(node) => {
+                node.children_mut()
+                    .expect("open node must be a span")
+            },
+            | None => root,
+        }
+    }
+
+    fn close(
+        root: &mut Vec<CueNode>,
+        stack: &mut Vec<CueNode>,
+    ) {
+        if let Some(node) = stack.pop() {
+            current(root, stack).push(node);
+        }
+    }
+
+    for token in tokens {
+        match token {
+            | CueTextToken::Text(text) => {
+                let children = current(&mut root, &mut stack);
+                if let Some(CueNode::Text(last)) = children.last_mut() {
+                    last.push_str(&text);
+                } else {
+                    children.push(CueNode::Text(text));
+                }
+            },
+            | CueTextToken::Timestamp(timestamp) => {
+                current(&mut root, &mut stack)
+                    .push(CueNode::Timestamp(timestamp));
+            },
+            | CueTextToken::StartTag {
+                name,
+                classes,
+                annotation,
+            } => {
+                let span = CueSpan {
+                    classes,
+                    children: vec![],
+                };
+                let node = match name.as_str() {
+                    | "c" => CueNode::Class(span),
+                    | "i" => CueNode::Italic(span),
+                    | "b" => CueNode::Bold(span),
+                    | "u" => CueNode::Underline(span),
+                    | "ruby" => CueNode::Ruby(span),
+                    | "rt" => {
+                        match stack.last() {
+                            | Some(CueNode::Ruby(_)) => CueNode::RubyText(span),
+                            | _ => continue,
+                        }
+                    },
+                    | "v" => {
+                        CueNode::Voice(CueAnnotatedSpan {
+                            annotation: annotation.unwrap_or_default(),
+                            classes: span.classes,
+                            children: vec![],
+                        })
+                    },
+                    | "lang" => {
+                        CueNode::Language(CueAnnotatedSpan {
+                            annotation: annotation.unwrap_or_default(),
+                            classes: span.classes,
+                            children: vec![],
+                        })
+                    },
+                    | _ => continue,
+                };
+                stack.push(node);
+            },
+            | CueTextToken::EndTag(name) => {
+                let current_tag = stack
+                    .last()
+                    .and_then(CueNode::tag_name);
+                if current_tag == Some(name.as_str()) {
+                    close(&mut root, &mut stack);
+                } else if current_tag == Some("rt") && name == "ruby" {
+                    close(&mut root, &mut stack);
+                    close(&mut root, &mut stack);
+                }
+            },
+        }
+    }
+
+    while !stack.is_empty() {
+        close(&mut root, &mut stack);
+    }
+
+    CueText {
+        nodes: root,
+    }
+}
+
+/// Escapes the given characters by character references.
+fn escape(
+    text: &str,
+    characters: &[char],
+) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !characters.contains(&c) {
+            escaped.push(c);
+            continue;
+        }
+
+        match c {
+            | '&' => escaped.push_str("&amp;"),
+            | '<' => escaped.push_str("&lt;"),
+            | '>' => escaped.push_str("&gt;"),
+            | _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            CueText::parse("Hello, world!").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Text(
+                    "Hello, world!".to_string()
+                )],
+            }
+        );
+
+        assert_eq!(
+            CueText::parse("<b>Bold <i>and italic</i></b>").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Bold(
+                    CueSpan {
+                        classes: vec![],
+                        children: vec![
+                            CueNode::Text("Bold ".to_string()),
+                            CueNode::Italic(CueSpan {
+                                classes: vec![],
+                                children: vec![CueNode::Text(
+                                    "and italic".to_string()
+                                )],
+                            }),
+                        ],
+                    }
+                )],
+            }
+        );
+
+        assert_eq!(
+            CueText::parse("<c.yellow.bg_blue>Colored</c>").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Class(
+                    CueSpan {
+                        classes: vec![
+                            "yellow".to_string(),
+                            "bg_blue".to_string(),
+                        ],
+                        children: vec![CueNode::Text(
+                            "Colored".to_string()
+                        )],
+                    }
+                )],
+            }
+        );
+
+        assert_eq!(
+            CueText::parse("<v.loud   Mary   Jane >Hi").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Voice(
+                    CueAnnotatedSpan {
+                        annotation: "Mary Jane".to_string(),
+                        classes: vec!["loud".to_string()],
+                        children: vec![CueNode::Text(
+                            "Hi".to_string()
+                        )],
+                    }
+                )],
+            }
+        );
+
+        assert_eq!(
+            CueText::parse("<lang en-US>Color</lang>").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Language(
+                    CueAnnotatedSpan {
+                        annotation: "en-US".to_string(),
+                        classes: vec![],
+                        children: vec![CueNode::Text(
+                            "Color".to_string()
+                        )],
+                    }
+                )],
+            }
+        );
+
+        assert_eq!(
+            CueText::parse("<ruby>漢<rt>kan</rt>字<rt>ji</ruby>").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Ruby(
+                    CueSpan {
+                        classes: vec![],
+                        children: vec![
+                            CueNode::Text("漢".to_string()),
+                            CueNode::RubyText(CueSpan {
+                                classes: vec![],
+                                children: vec![CueNode::Text(
+                                    "kan".to_string()
+                                )],
+                            }),
+                            CueNode::Text("字".to_string()),
+                            CueNode::RubyText(CueSpan {
+                                classes: vec![],
+                                children: vec![CueNode::Text(
+                                    "ji".to_string()
+                                )],
+                            }),
+                        ],
+                    }
+                )],
+            }
+        );
+
+        assert_eq!(
+            CueText::parse("Hello <00:00:01.500>world").unwrap(),
+            CueText {
+                nodes: vec![
+                    CueNode::Text("Hello ".to_string()),
+                    CueNode::Timestamp(VttTimestamp {
+                        seconds: 1,
+                        milliseconds: 500,
+                        ..Default::default()
+                    }),
+                    CueNode::Text("world".to_string()),
+                ],
+            }
+        );
+
+        // Character references.
+        assert_eq!(
+            CueText::parse("&lt;a&gt; &amp; &#65;&#x42; &foo;").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Text(
+                    "<a> & AB &foo;".to_string()
+                )],
+            }
+        );
+
+        // Ignore unknown tags, invalid timestamps and unmatched end tags.
+        assert_eq!(
+            CueText::parse("<font>A</font><00:01>B</i><rt>C</rt>").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Text(
+                    "ABC".to_string()
+                )],
+            }
+        );
+
+        // Close unclosed tags at the end.
+        assert_eq!(
+            CueText::parse("<i>Unclosed <b>tags").unwrap(),
+            CueText {
+                nodes: vec![CueNode::Italic(
+                    CueSpan {
+                        classes: vec![],
+                        children: vec![
+                            CueNode::Text("Unclosed ".to_string()),
+                            CueNode::Bold(CueSpan {
+                                classes: vec![],
+                                children: vec![CueNode::Text(
+                                    "tags".to_string()
+                                )],
+                            }),
+                        ],
+                    }
+                )],
+            }
+        );
+
+        // Unterminated tag at the end of text.
+        assert_eq!(
+            CueText::parse("A<b").unwrap(),
+            CueText {
+                nodes: vec![
+                    CueNode::Text("A".to_string()),
+                    CueNode::Bold(CueSpan::default()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn render() {
+        let texts = [
+            "Hello, world!",
+            "<b>Bold <i>and italic</i></b>",
+            "<c.yellow.bg_blue>Colored</c>",
+            "<v.loud Mary Jane>Hi</v>",
+            "<lang en-US>Color</lang>",
+            "<ruby>漢<rt>kan</rt>字<rt>ji</rt></ruby>",
+            "Hello <00:00:01.500>world",
+            "&lt;a&gt; &amp; b",
+            "<v Bob>Line 1\nLine 2</v>",
+        ];
+
+        for text in texts {
+            assert_eq!(
+                CueText::parse(text)
+                    .unwrap()
+                    .render(),
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn plain_text() {
+        let cue_text =
+            CueText::parse("<v Bob><b>Hi</b> <00:00:01.000>there</v>").unwrap();
+        assert_eq!(cue_text.plain_text(), "Hi there");
+    }
+}
@@ -1,15 +1,23 @@
 //! A parser implementation for the WebVTT format.
 
+pub(crate) use vtt_parser::block;
+pub(crate) use vtt_parser::cue_settings;
+pub(crate) use vtt_parser::cue_text;
+pub(crate) use vtt_parser::header;
+pub(crate) use vtt_parser::region;
+pub(crate) use vtt_parser::timestamp;
 pub(crate) use vtt_parser::vtt;
 
 peg::parser! {
     /// The parser for the WebVTT format.
     grammar vtt_parser() for str {
-        use crate::vtt::WebVtt;
+        use crate::vtt::WebVttRef;
         use crate::vtt::VttHeader;
         use crate::vtt::VttRegion;
         use crate::vtt::VttBlock;
+        use crate::vtt::VttBlockRef;
         use crate::vtt::VttCue;
+        use crate::vtt::VttCueRef;
         use crate::vtt::VttComment;
         use crate::vtt::VttStyle;
         use crate::vtt::VttTimings;
@@ -25,6 +33,7 @@ peg::parser! {
         use crate::vtt::PositionAlignment;
         use crate::vtt::Position;
         use crate::vtt::VttDescription;
+        use crate::vtt::cue_text::CueTextToken;
 
         /// Whitespace.
         rule whitespace() = [' ' | '\t']
@@ -50,6 +59,12 @@ peg::parser! {
                 n.parse().or(Err("two-digit number"))
             }
 
+        /// Two-digit number of minutes or seconds less than 60.
+        rule sexagesimal_number() -> u8
+            = n:two_number() {?
+                if n < 60 { Ok(n) } else { Err("two-digit number less than 60") }
+            }
+
         /// Two-or-more digit number
         rule two_or_more_number() -> u32
             = n:$(['0'..='9']['0'..='9']+) {?
@@ -81,7 +96,7 @@ peg::parser! {
         /// Percentage of floating number.
         rule percentage_float() -> f32
             = f:float() "%" {?
-                if f >= 0.0 && f <= 100.0 {
+                if (0.0..=100.0).contains(&f) {
                     Ok(f)
                 } else {
                     Err("Number out of range")
@@ -118,19 +133,19 @@ peg::parser! {
             }
 
         /// Single text with newline.
-        rule line() -> String
+        rule line() -> &'input str
             = !(whitespace() / newline()) t:$((!newline() [_])+) newline()
             {
-                t.to_string().trim().to_string()
+                t.trim()
             }
 
         /// Multiple lines.
-        rule multiline() -> Vec<String>
+        rule multiline() -> Vec<&'input str>
             = lines:$((!newline() [_])+ newline()) ** ()
             {
                 lines
                     .iter()
-                    .map(|l| l.to_string().trim().to_string())
+                    .map(|l| l.trim())
                     .collect()
             }
 
@@ -141,7 +156,7 @@ peg::parser! {
 
         /// Timestamp with hours.
         rule timestamp_with_hours() -> VttTimestamp
-            = hours:two_or_more_number() ":" minutes:two_number() ":" seconds:two_number() "." milliseconds:three_number()
+            = hours:two_or_more_number() ":" minutes:sexagesimal_number() ":" seconds:sexagesimal_number() "." milliseconds:three_number()
             {
                 VttTimestamp {
                     hours,
@@ -153,7 +168,7 @@ peg::parser! {
 
         /// timestamp without hours.
         rule timestamp_without_hours() -> VttTimestamp
-            = minutes:two_number() ":" seconds:two_number() "." milliseconds:three_number()
+            = minutes:sexagesimal_number() ":" seconds:sexagesimal_number() "." milliseconds:three_number()
             {
                 VttTimestamp {
                     hours: 0,
@@ -208,12 +223,12 @@ peg::parser! {
 
         rule cue_line_number() -> Line
             = "line:" i:int() {
-                Line::LineNumber(i, None)
+                Line::LineNumber(i as f32, None)
             }
 
         rule cue_line_number_with_alignment() -> Line
             = "line:" i:int() "," align:cue_line_alignment() {
-                Line::LineNumber(i, Some(align))
+                Line::LineNumber(i as f32, Some(align))
             }
 
         rule cue_line_percentage() -> Line
@@ -292,17 +307,21 @@ peg::parser! {
 
         /// Cue block
         pub(crate) rule cue() -> VttCue
+            = cue:cue_ref() { cue.to_vtt_cue() }
+
+        /// Cue block borrowing the identifier and the payload.
+        rule cue_ref() -> VttCueRef<'input>
             = cue_with_identifier_and_settings()
                 / cue_with_identifier()
                 / cue_with_settings()
                 / cue_minimal()
 
         /// Minimal cue block
-        rule cue_minimal() -> VttCue
+        rule cue_minimal() -> VttCueRef<'input>
             = whitespace()* timings:timings() whitespace()* newline()
                 payload:multiline()
             {
-                VttCue {
+                VttCueRef {
                     identifier: None,
                     timings,
                     settings: None,
@@ -311,12 +330,12 @@ peg::parser! {
             }
 
         /// Cue block with an identifier.
-        rule cue_with_identifier() -> VttCue
+        rule cue_with_identifier() -> VttCueRef<'input>
             = whitespace()* identifier:line()
                 whitespace()* timings:timings() whitespace()* newline()
                 payload:multiline()
             {
-                VttCue {
+                VttCueRef {
                     identifier: Some(identifier),
                     timings,
                     settings: None,
@@ -325,11 +344,11 @@ peg::parser! {
             }
 
         /// Cue block with settings.
-        rule cue_with_settings() -> VttCue
+        rule cue_with_settings() -> VttCueRef<'input>
             = whitespace()* timings:timings() whitespace()+ settings:cue_settings() whitespace()* newline()
                 payload:multiline()
             {
-                VttCue {
+                VttCueRef {
                     identifier: None,
                     timings,
                     settings: Some(settings),
@@ -338,12 +357,12 @@ peg::parser! {
             }
 
         /// Cue block with an identifier and settings.
-        rule cue_with_identifier_and_settings() -> VttCue
+        rule cue_with_identifier_and_settings() -> VttCueRef<'input>
             = whitespace()* identifier:line()
                 whitespace()* timings:timings() whitespace()+ settings:cue_settings() whitespace()* newline()
                 payload:multiline()
             {
-                VttCue {
+                VttCueRef {
                     identifier: Some(identifier),
                     timings,
                     settings: Some(settings),
@@ -359,7 +378,7 @@ peg::parser! {
         rule comment_side() -> VttComment
             = "NOTE" whitespace()+ comment:line()
             {
-                VttComment::Side(comment)
+                VttComment::Side(comment.to_string())
             }
 
         /// Multiple lines comment block.
@@ -429,20 +448,24 @@ peg::parser! {
         pub(crate) rule region_scroll() -> Scroll
             = "scroll:up" { Scroll::Up }
 
-        rule cue_block() -> VttBlock
-            = cue:cue() { cue.into() }
+        rule cue_block() -> VttBlockRef<'input>
+            = cue:cue_ref() { VttBlockRef::Que(cue) }
 
-        rule comment_block() -> VttBlock
-            = comment:comment() { comment.into() }
+        rule comment_block() -> VttBlockRef<'input>
+            = comment:comment() { VttBlockRef::Comment(comment) }
 
-        rule style_block() -> VttBlock
-            = style:style() { style.into() }
+        rule style_block() -> VttBlockRef<'input>
+            = style:style() { VttBlockRef::Style(style) }
 
-        rule region_block() -> VttBlock
-            = region:region() { region.into() }
+        rule region_block() -> VttBlockRef<'input>
+            = region:region() { VttBlockRef::Region(region) }
 
         /// Any block (cue, comment, or style)
         pub(crate) rule block() -> VttBlock
+            = block:block_ref() { block.to_vtt_block() }
+
+        /// Any block borrowing the texts of cues.
+        rule block_ref() -> VttBlockRef<'input>
             = cue_block()
                 / comment_block()
                 / style_block()
@@ -474,23 +497,139 @@ peg::parser! {
             }
 
         /// The entire WebVTT file.
-        pub(crate) rule vtt() -> WebVtt
+        pub(crate) rule vtt() -> WebVttRef<'input>
             = header:header() newline()
                 (whitespace() / newline())*
-                blocks:block() ** (newline()+)
+                blocks:block_ref() ** (newline()+)
                 (whitespace() / newline())*
             {
-                WebVtt {
+                WebVttRef {
                     header,
                     blocks,
                 }
             }
+
+        /// Whitespace in a cue text tag.
+        rule cue_text_whitespace() = [' ' | '\t' | '\n' | '\r' | '\x0c']
+
+        /// End of a cue text tag, which may be omitted at the end of input.
+        rule cue_text_tag_end() = ">" / ![_]
+
+        /// Character reference in cue text.
+        rule cue_text_character_reference() -> String
+            = "&#" ['x' | 'X'] n:$(['0'..='9' | 'a'..='f' | 'A'..='F']+) ";" {?
+                u32::from_str_radix(n, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(String::from)
+                    .ok_or("hexadecimal character reference")
+            }
+            / "&#" n:$(['0'..='9']+) ";" {?
+                n.parse::<u32>()
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(String::from)
+                    .ok_or("decimal character reference")
+            }
+            / "&" name:$(['a'..='z' | 'A'..='Z' | '0'..='9']+) ";" {?
+                match name {
+                    "amp" => Ok("&".to_string()),
+                    "lt" => Ok("<".to_string()),
+                    "gt" => Ok(">".to_string()),
+                    "quot" => Ok("\"".to_string()),
+                    "apos" => Ok("'".to_string()),
+                    "lrm" => Ok("\u{200E}".to_string()),
+                    "rlm" => Ok("\u{200F}".to_string()),
+                    "nbsp" => Ok("\u{00A0}".to_string()),
+                    _ => Err("named character reference"),
+                }
+            }
+
+        /// Text between tags in cue text.
+        rule cue_text_string() -> Option<CueTextToken>
+            = text:(cue_text_character_reference() / c:$([^ '<']) { c.to_string() })+
+            {
+                Some(CueTextToken::Text(text.concat()))
+            }
+
+        /// Name or class of a cue text tag.
+        rule cue_text_tag_name() -> String
+            = n:$([^ ' ' | '\t' | '\n' | '\r' | '\x0c' | '.' | '>']*)
+            {
+                n.to_string()
+            }
+
+        /// Annotation of a cue text start tag.
+        rule cue_text_annotation() -> String
+            = annotation:(cue_text_character_reference() / c:$([^ '>']) { c.to_string() })*
+            {
+                annotation
+                    .concat()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+
+        /// Start tag of cue text, e.g. `<c.class>` or `<v Speaker>`.
+        rule cue_text_start_tag() -> Option<CueTextToken>
+            = "<" name:cue_text_tag_name()
+                classes:("." c:cue_text_tag_name() { c })*
+                annotation:(cue_text_whitespace() a:cue_text_annotation() { a })?
+                cue_text_tag_end()
+            {
+                Some(CueTextToken::StartTag {
+                    name,
+                    classes: classes
+                        .into_iter()
+                        .filter(|c| !c.is_empty())
+                        .collect(),
+                    annotation,
+                })
+            }
+
+        /// End tag of cue text, e.g. `</c>`.
+        rule cue_text_end_tag() -> Option<CueTextToken>
+            = "</" name:$([^ '>']*) cue_text_tag_end()
+            {
+                Some(CueTextToken::EndTag(name.to_string()))
+            }
+
+        /// Timestamp tag of cue text, e.g. `<00:00:01.500>`.
+        ///
+        /// A tag that starts with a digit but is not a valid timestamp is ignored.
+        rule cue_text_timestamp_tag() -> Option<CueTextToken>
+            = "<" t:timestamp() cue_text_tag_end()
+            {
+                Some(CueTextToken::Timestamp(t))
+            }
+            / "<" ['0'..='9'] [^ '>']* cue_text_tag_end()
+            {
+                None
+            }
+
+        /// Single token of cue text, or `None` if it should be ignored.
+        rule cue_text_token() -> Option<CueTextToken>
+            = cue_text_timestamp_tag()
+                / cue_text_end_tag()
+                / cue_text_start_tag()
+                / cue_text_string()
+
+        /// The entire cue text as a sequence of tokens.
+        pub(crate) rule cue_text() -> Vec<CueTextToken>
+            = tokens:cue_text_token()*
+            {
+                tokens
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::vtt_parser;
+    use crate::vtt::cue_text::CueTextToken;
     use crate::vtt::*;
 
     #[test]
@@ -553,6 +692,10 @@ mod test {
         assert!(vtt_parser::timestamp("00:00:00").is_err());
         // Invalid separators. (like SubRip Subtitle)
         assert!(vtt_parser::timestamp("00:00:00,000").is_err());
+        // Out of range.
+        assert!(vtt_parser::timestamp("00:60:00.000").is_err());
+        assert!(vtt_parser::timestamp("00:00:60.000").is_err());
+        assert!(vtt_parser::timestamp("61:00.000").is_err());
     }
 
     #[test]
@@ -665,19 +808,19 @@ mod test {
     fn cue_line() {
         assert_eq!(
             vtt_parser::cue_line("line:1").unwrap(),
-            Line::LineNumber(1, None)
+            Line::LineNumber(1.0, None)
         );
         assert_eq!(
             vtt_parser::cue_line("line:1,center").unwrap(),
-            Line::LineNumber(1, Some(LineAlignment::Center))
+            Line::LineNumber(1.0, Some(LineAlignment::Center))
         );
         assert_eq!(
             vtt_parser::cue_line("line:-1").unwrap(),
-            Line::LineNumber(-1, None)
+            Line::LineNumber(-1.0, None)
         );
         assert_eq!(
             vtt_parser::cue_line("line:-1,start").unwrap(),
-            Line::LineNumber(-1, Some(LineAlignment::Start))
+            Line::LineNumber(-1.0, Some(LineAlignment::Start))
         );
         assert_eq!(
             vtt_parser::cue_line("line:10%").unwrap(),
@@ -807,7 +950,7 @@ mod test {
             CueSettings {
                 region: None,
                 vertical: None,
-                line: Some(Line::LineNumber(1, None)),
+                line: Some(Line::LineNumber(1.0, None)),
                 position: None,
                 size: None,
                 align: None,
@@ -860,7 +1003,7 @@ mod test {
         let settings = CueSettings {
             region: Some("id".to_string()),
             vertical: Some(Vertical::Rl),
-            line: Some(Line::LineNumber(1, None)),
+            line: Some(Line::LineNumber(1.0, None)),
             position: Some(Position {
                 value: Percentage {
                     value: 10.0,
@@ -1039,7 +1182,7 @@ mod test {
                     },
                 },
                 settings: Some(CueSettings {
-                    line: Some(Line::LineNumber(1, None)),
+                    line: Some(Line::LineNumber(1.0, None)),
                     position: Some(Position {
                         value: Percentage { value: 50.0 },
                         alignment: None,
@@ -1073,7 +1216,7 @@ mod test {
                     },
                 },
                 settings: Some(CueSettings {
-                    line: Some(Line::LineNumber(1, None)),
+                    line: Some(Line::LineNumber(1.0, None)),
                     position: Some(Position {
                         value: Percentage { value: 50.0 },
                         alignment: None,
@@ -1107,7 +1250,7 @@ mod test {
                     },
                 },
                 settings: Some(CueSettings {
-                    line: Some(Line::LineNumber(1, None)),
+                    line: Some(Line::LineNumber(1.0, None)),
                     position: Some(Position {
                         value: Percentage { value: 50.0 },
                         alignment: None,
@@ -1522,7 +1665,12 @@ mod test {
             ],
         };
 
-        assert_eq!(vtt_parser::vtt(text).unwrap(), expected);
+        assert_eq!(
+            vtt_parser::vtt(text)
+                .unwrap()
+                .to_web_vtt(),
+            expected
+        );
 
         let text = r#"WEBVTT - This file has cues.
 
@@ -1614,7 +1762,12 @@ mod test {
             ],
         };
 
-        assert_eq!(vtt_parser::vtt(text).unwrap(), expected);
+        assert_eq!(
+            vtt_parser::vtt(text)
+                .unwrap()
+                .to_web_vtt(),
+            expected
+        );
 
         let text = r#"WEBVTT - Translation of that film I like
 
@@ -1712,7 +1865,12 @@ NOTE This last line may not translate well.
             ],
         };
 
-        assert_eq!(vtt_parser::vtt(text).unwrap(), expected);
+        assert_eq!(
+            vtt_parser::vtt(text)
+                .unwrap()
+                .to_web_vtt(),
+            expected
+        );
 
         let text = r#"WEBVTT
 
@@ -1771,7 +1929,12 @@ NOTE style blocks cannot appear after the first cue.
             ],
         };
 
-        assert_eq!(vtt_parser::vtt(text).unwrap(), expected);
+        assert_eq!(
+            vtt_parser::vtt(text)
+                .unwrap()
+                .to_web_vtt(),
+            expected
+        );
 
         let text = r#"WEBVTT
 
@@ -1891,6 +2054,66 @@ What are you waiting for?
             ],
         };
 
-        assert_eq!(vtt_parser::vtt(text).unwrap(), expected);
+        assert_eq!(
+            vtt_parser::vtt(text)
+                .unwrap()
+                .to_web_vtt(),
+            expected
+        );
+    }
+
+    #[test]
+    fn cue_text() {
+        assert_eq!(
+            vtt_parser::cue_text("Hello, world!").unwrap(),
+            vec![CueTextToken::Text("Hello, world!".to_string())]
+        );
+
+        assert_eq!(
+            vtt_parser::cue_text("<c.a.b>A</c>").unwrap(),
+            vec![
+                CueTextToken::StartTag {
+                    name: "c".to_string(),
+                    classes: vec!["a".to_string(), "b".to_string()],
+                    annotation: None,
+                },
+                CueTextToken::Text("A".to_string()),
+                CueTextToken::EndTag("c".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            vtt_parser::cue_text("<v.loud \t Mary &amp; Jane >").unwrap(),
+            vec![CueTextToken::StartTag {
+                name: "v".to_string(),
+                classes: vec!["loud".to_string()],
+                annotation: Some("Mary & Jane".to_string()),
+            }]
+        );
+
+        assert_eq!(
+            vtt_parser::cue_text("A<00:00:01.000>B<01.000>C").unwrap(),
+            vec![
+                CueTextToken::Text("A".to_string()),
+                CueTextToken::Timestamp(VttTimestamp {
+                    seconds: 1,
+                    ..Default::default()
+                }),
+                CueTextToken::Text("B".to_string()),
+                CueTextToken::Text("C".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            vtt_parser::cue_text("&lt;&#x3C;&#60;&unknown; & <").unwrap(),
+            vec![
+                CueTextToken::Text("<<<&unknown; & ".to_string()),
+                CueTextToken::StartTag {
+                    name: "".to_string(),
+                    classes: vec![],
+                    annotation: None,
+                },
+            ]
+        );
     }
 }
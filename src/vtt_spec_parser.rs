@@ -0,0 +1,704 @@
+//! A WebVTT parser following the file parsing algorithm of the W3C WebVTT specification,
+//! <https://www.w3.org/TR/webvtt1/#file-parsing>.
+//!
+//! Unlike the grammar of [`vtt_parser`](crate::vtt_parser),
+//! it never fails after the signature and drops or ignores whatever the algorithm does:
+//! - blocks that are neither cues, style sheets, regions nor comments,
+//! - cues with invalid timings,
+//! - style sheets and regions after the first cue,
+//! - invalid and unknown cue and region settings, where the last valid one wins,
+//! - region settings of cues referring to no region defined before.
+//!
+//! Comment blocks are kept as [`VttComment`] although the algorithm discards them.
+
+use std::collections::BTreeSet;
+
+use crate::vtt::{
+    Alignment, Anchor, CueSettings, Line, LineAlignment, Percentage, Position,
+    PositionAlignment, Scroll, Vertical, VttBlock, VttComment, VttCue,
+    VttDescription, VttHeader, VttRegion, VttStyle, VttTimestamp, VttTimings,
+    WebVtt,
+};
+use crate::{ParseError, ParseResult};
+
+/// Parses the WebVTT file, failing only if the signature is invalid.
+pub(crate) fn vtt(text: &str) -> ParseResult<WebVtt> {
+    // Preprocesses the input as the decoding of the specification does.
+    let input = text
+        .strip_prefix('\u{FEFF}')
+        .unwrap_or(text)
+        .replace('\0', "\u{FFFD}")
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+
+    let signature = input
+        .strip_prefix("WEBVTT")
+        .filter(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '\n']));
+    let Some(rest) = signature else {
+        return Err(ParseError {
+            line: 1,
+            column: 1,
+            offset: 0,
+            expected: BTreeSet::from(["\"WEBVTT\"".to_string()]),
+            rule: Some("header".to_string()),
+            block: None,
+        });
+    };
+
+    let mut parser = Parser {
+        input: &input,
+        position: 0,
+        seen_cue: false,
+        regions: vec![],
+    };
+    parser.collect_line();
+
+    // The text after the signature and the header lines are kept as the description.
+    let mut description = rest
+        .split('\n')
+        .next()
+        .unwrap_or("")
+        .get(1..)
+        .unwrap_or("")
+        .to_string();
+    let mut side = !description.is_empty();
+    if !parser.is_end() {
+        parser.position += 1;
+        if !parser.input[parser.position..].starts_with('\n') {
+            if let Collected::Other(lines) = parser.collect_block(true) {
+                if !lines.is_empty() {
+                    if side {
+                        description.push('\n');
+                    }
+                    description.push_str(&lines);
+                }
+            }
+        }
+    }
+    if description.is_empty() {
+        side = false;
+    }
+    parser.skip_newlines();
+
+    let mut blocks = vec![];
+    while !parser.is_end() {
+        match parser.collect_block(false) {
+            | Collected::Cue(cue) => blocks.push(VttBlock::Que(cue)),
+            | Collected::Style(style) => {
+                blocks.push(VttBlock::Style(VttStyle {
+                    style,
+                }))
+            },
+            | Collected::Region(region) => {
+                parser
+                    .regions
+                    .push(region.clone());
+                blocks.push(VttBlock::Region(region));
+            },
+            | Collected::Other(buffer) => blocks.extend(comment(&buffer)),
+        }
+        parser.skip_newlines();
+    }
+
+    Ok(WebVtt {
+        header: VttHeader {
+            description: match (description.is_empty(), side) {
+                | (true, _) => None,
+                | (false, true) => Some(VttDescription::Side(description)),
+                | (false, false) => Some(VttDescription::Below(description)),
+            },
+        },
+        blocks,
+    })
+}
+
+/// The state of the file parsing algorithm.
+struct Parser<'a> {
+    /// The preprocessed input.
+    input: &'a str,
+    /// The byte position in the input.
+    position: usize,
+    /// Whether a cue has been parsed, after which style sheets and regions are not.
+    seen_cue: bool,
+    /// The regions parsed so far for the region settings of cues.
+    regions: Vec<VttRegion>,
+}
+
+/// The result of collecting a block.
+enum Collected {
+    Cue(VttCue),
+    Style(String),
+    Region(VttRegion),
+    /// The buffer of the block that is none of the above.
+    Other(String),
+}
+
+impl<'a> Parser<'a> {
+    fn is_end(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    /// Collects a sequence of code points that are not line feeds.
+    fn collect_line(&mut self) -> &'a str {
+        let rest = &self.input[self.position..];
+        let length = rest
+            .find('\n')
+            .unwrap_or(rest.len());
+        self.position += length;
+
+        &rest[..length]
+    }
+
+    /// Collects a sequence of line feeds.
+    fn skip_newlines(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len()
+            - rest
+                .trim_start_matches('\n')
+                .len();
+    }
+
+    /// Collects a WebVTT block.
+    fn collect_block(
+        &mut self,
+        in_header: bool,
+    ) -> Collected {
+        let mut line_count = 0;
+        let mut previous_position = self.position;
+        let mut buffer = String::new();
+        let mut seen_arrow = false;
+        let mut cue: Option<VttCue> = None;
+        let mut stylesheet = false;
+        let mut region: Option<VttRegion> = None;
+
+        loop {
+            let line = self.collect_line();
+            line_count += 1;
+            let seen_eof = self.is_end();
+            if !seen_eof {
+                self.position += 1;
+            }
+
+            if line.contains("-->") {
+                if !in_header
+                    && (line_count == 1 || line_count == 2 && !seen_arrow)
+                {
+                    seen_arrow = true;
+                    previous_position = self.position;
+                    cue = cue_timings_and_settings(line, &self.regions).map(
+                        |(timings, settings)| {
+                            VttCue {
+                                identifier: (!buffer.is_empty())
+                                    .then(|| std::mem::take(&mut buffer)),
+                                timings,
+                                settings,
+                                payload: vec![],
+                            }
+                        },
+                    );
+                    if cue.is_some() {
+                        self.seen_cue = true;
+                    }
+                } else {
+                    self.position = previous_position;
+                    break;
+                }
+            } else if line.is_empty() {
+                break;
+            } else {
+                if !in_header && line_count == 2 && !self.seen_cue {
+                    if is_keyword(&buffer, "STYLE") {
+                        stylesheet = true;
+                        buffer.clear();
+                    } else if is_keyword(&buffer, "REGION") {
+                        region = Some(VttRegion::default());
+                        buffer.clear();
+                    }
+                }
+                if !buffer.is_empty() {
+                    buffer.push('\n');
+                }
+                buffer.push_str(line);
+                previous_position = self.position;
+            }
+
+            if seen_eof {
+                break;
+            }
+        }
+
+        if let Some(mut cue) = cue {
+            if !buffer.is_empty() {
+                cue.payload = buffer
+                    .split('\n')
+                    .map(String::from)
+                    .collect();
+            }
+            Collected::Cue(cue)
+        } else if stylesheet {
+            Collected::Style(buffer)
+        } else if let Some(mut region) = region {
+            region_settings(&buffer, &mut region);
+            Collected::Region(region)
+        } else {
+            Collected::Other(buffer)
+        }
+    }
+}
+
+/// Returns whether the buffer is the keyword followed only by ASCII whitespaces.
+fn is_keyword(
+    buffer: &str,
+    keyword: &str,
+) -> bool {
+    buffer
+        .strip_prefix(keyword)
+        .map_or(false, |rest| {
+            rest.chars()
+                .all(|c| c.is_ascii_whitespace())
+        })
+}
+
+/// Converts the buffer of a `NOTE` block into a comment.
+fn comment(buffer: &str) -> Option<VttBlock> {
+    let rest = buffer.strip_prefix("NOTE")?;
+    let comment = if let Some(below) = rest.strip_prefix('\n') {
+        VttComment::Below(below.to_string())
+    } else if rest.starts_with([' ', '\t']) {
+        VttComment::Side(rest[1..].to_string())
+    } else if rest.is_empty() {
+        VttComment::Below(String::new())
+    } else {
+        return None;
+    };
+
+    Some(VttBlock::Comment(comment))
+}
+
+/// Collects the cue timings and settings from the line.
+fn cue_timings_and_settings(
+    line: &str,
+    regions: &[VttRegion],
+) -> Option<(VttTimings, Option<CueSettings>)> {
+    let mut rest = line.trim_start_matches(is_whitespace);
+    let start = timestamp(&mut rest)?;
+    rest = rest
+        .trim_start_matches(is_whitespace)
+        .strip_prefix("-->")?
+        .trim_start_matches(is_whitespace);
+    let end = timestamp(&mut rest)?;
+
+    let settings = cue_settings(rest, regions);
+    Some((
+        VttTimings {
+            start,
+            end,
+        },
+        (settings != CueSettings::default()).then_some(settings),
+    ))
+}
+
+fn is_whitespace(c: char) -> bool {
+    c.is_ascii_whitespace()
+}
+
+/// Collects a sequence of ASCII digits from the start of the input.
+fn digits<'a>(input: &mut &'a str) -> &'a str {
+    let length = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (digits, rest) = input.split_at(length);
+    *input = rest;
+
+    digits
+}
+
+/// Collects a WebVTT timestamp from the start of the input.
+fn timestamp(input: &mut &str) -> Option<VttTimestamp> {
+    let first = digits(input);
+    if first.is_empty() {
+        return None;
+    }
+    let first_value: u64 = first.parse().ok()?;
+    let hours_first = first.len() != 2 || first_value > 59;
+
+    *input = input.strip_prefix(':')?;
+    let second = digits(input);
+    if second.len() != 2 {
+        return None;
+    }
+
+    let (hours, minutes, seconds) = if hours_first || input.starts_with(':') {
+        *input = input.strip_prefix(':')?;
+        let third = digits(input);
+        if third.len() != 2 {
+            return None;
+        }
+        (first_value, second, third)
+    } else {
+        (0, first, second)
+    };
+
+    *input = input.strip_prefix('.')?;
+    let fraction = digits(input);
+    if fraction.len() != 3 {
+        return None;
+    }
+
+    let minutes: u8 = minutes.parse().ok()?;
+    let seconds: u8 = seconds.parse().ok()?;
+    if minutes > 59 || seconds > 59 {
+        return None;
+    }
+
+    Some(VttTimestamp {
+        // The hours beyond the model are not representable.
+        hours: u32::try_from(hours).ok()?,
+        minutes,
+        seconds,
+        milliseconds: fraction.parse().ok()?,
+    })
+}
+
+/// Splits the setting into the name and the value,
+/// skipping a setting without `:` or with `:` at either end.
+fn setting(setting: &str) -> Option<(&str, &str)> {
+    let (name, value) = setting.split_once(':')?;
+    if name.is_empty() || value.is_empty() {
+        None
+    } else {
+        Some((name, value))
+    }
+}
+
+/// Parses the cue settings, ignoring the invalid ones.
+fn cue_settings(
+    input: &str,
+    regions: &[VttRegion],
+) -> CueSettings {
+    let mut settings = CueSettings::default();
+    for (name, value) in input
+        .split_ascii_whitespace()
+        .filter_map(setting)
+    {
+        match name {
+            | "region" => {
+                settings.region = regions
+                    .iter()
+                    .rev()
+                    .find(|region| region.id.as_deref() == Some(value))
+                    .and(Some(value.to_string()));
+            },
+            | "vertical" => {
+                match value {
+                    | "rl" => settings.vertical = Some(Vertical::Rl),
+                    | "lr" => settings.vertical = Some(Vertical::Lr),
+                    | _ => {},
+                }
+            },
+            | "line" => {
+                if let Some(line) = line_setting(value) {
+                    settings.line = Some(line);
+                }
+            },
+            | "position" => {
+                if let Some(position) = position_setting(value) {
+                    settings.position = Some(position);
+                }
+            },
+            | "size" => {
+                if let Some(size) = percentage(value) {
+                    settings.size = Some(size);
+                }
+            },
+            | "align" => {
+                settings.align = match value {
+                    | "start" => Some(Alignment::Start),
+                    | "center" => Some(Alignment::Center),
+                    | "end" => Some(Alignment::End),
+                    | "left" => Some(Alignment::Left),
+                    | "right" => Some(Alignment::Right),
+                    | _ => settings.align,
+                };
+            },
+            | _ => {},
+        }
+    }
+
+    // A cue positioned by the line, the vertical or the size setting is not in a region.
+    if settings.line.is_some()
+        || settings.vertical.is_some()
+        || settings
+            .size
+            .map_or(false, |size| size.value != 100.0)
+    {
+        settings.region = None;
+    }
+
+    settings
+}
+
+/// Parses the value of the line setting.
+fn line_setting(value: &str) -> Option<Line> {
+    let (position, alignment) = match value.split_once(',') {
+        | Some((position, alignment)) => (position, Some(alignment)),
+        | None => (value, None),
+    };
+    if !position
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '-' | '%' | '.'))
+        || !position
+            .chars()
+            .any(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let alignment = match alignment {
+        | None => None,
+        | Some("start") => Some(LineAlignment::Start),
+        | Some("center") => Some(LineAlignment::Center),
+        | Some("end") => Some(LineAlignment::End),
+        | Some(_) => return None,
+    };
+
+    if position.ends_with('%') {
+        return Some(Line::Percentage(
+            percentage(position)?,
+            alignment,
+        ));
+    }
+
+    let (sign, number) = match position.strip_prefix('-') {
+        | Some(number) => (-1.0, number),
+        | None => (1.0, position),
+    };
+    if number.contains(['-', '%']) {
+        return None;
+    }
+    let (integer, fraction) = match number.split_once('.') {
+        | Some((integer, fraction)) => (integer, Some(fraction)),
+        | None => (number, None),
+    };
+    if integer.is_empty()
+        || fraction.map_or(false, |fraction| {
+            fraction.is_empty() || fraction.contains('.')
+        })
+    {
+        return None;
+    }
+
+    let number: f32 = number.parse().ok()?;
+    if !number.is_finite() {
+        return None;
+    }
+    Some(Line::LineNumber(
+        sign * number,
+        alignment,
+    ))
+}
+
+/// Parses the value of the position setting.
+fn position_setting(value: &str) -> Option<Position> {
+    let (position, alignment) = match value.split_once(',') {
+        | Some((position, alignment)) => (position, Some(alignment)),
+        | None => (value, None),
+    };
+    let value = percentage(position)?;
+    let alignment = match alignment {
+        | None => None,
+        | Some("line-left") => Some(PositionAlignment::LineLeft),
+        | Some("center") => Some(PositionAlignment::Center),
+        | Some("line-right") => Some(PositionAlignment::LineRight),
+        | Some(_) => return None,
+    };
+
+    Some(Position {
+        value,
+        alignment,
+    })
+}
+
+/// Parses a percentage string matching `^\d+(\.\d+)?%$` within 0% to 100%.
+fn percentage(value: &str) -> Option<Percentage> {
+    let number = value.strip_suffix('%')?;
+    let (integer, fraction) = match number.split_once('.') {
+        | Some((integer, fraction)) => (integer, Some(fraction)),
+        | None => (number, None),
+    };
+    let is_digits = |text: &str| {
+        !text.is_empty()
+            && text
+                .chars()
+                .all(|c| c.is_ascii_digit())
+    };
+    if !is_digits(integer) || !fraction.map_or(true, is_digits) {
+        return None;
+    }
+
+    let value: f32 = number.parse().ok()?;
+    (0.0..=100.0)
+        .contains(&value)
+        .then_some(Percentage {
+            value,
+        })
+}
+
+/// Parses an anchor of two percentages separated by `,`.
+fn anchor(value: &str) -> Option<Anchor> {
+    let (x, y) = value.split_once(',')?;
+
+    Some(Anchor {
+        x: percentage(x)?,
+        y: percentage(y)?,
+    })
+}
+
+/// Collects the region settings, ignoring the invalid ones.
+fn region_settings(
+    input: &str,
+    region: &mut VttRegion,
+) {
+    for (name, value) in input
+        .split_ascii_whitespace()
+        .filter_map(setting)
+    {
+        match name {
+            | "id" => region.id = Some(value.to_string()),
+            | "width" => {
+                if let Some(width) = percentage(value) {
+                    region.width = Some(width);
+                }
+            },
+            | "lines" => {
+                if value
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+                {
+                    if let Ok(lines) = value.parse() {
+                        region.lines = Some(lines);
+                    }
+                }
+            },
+            | "regionanchor" => {
+                if let Some(anchor) = anchor(value) {
+                    region.region_anchor = Some(anchor);
+                }
+            },
+            | "viewportanchor" => {
+                if let Some(anchor) = anchor(value) {
+                    region.viewport_anchor = Some(anchor);
+                }
+            },
+            | "scroll" => {
+                if value == "up" {
+                    region.scroll = Some(Scroll::Up);
+                }
+            },
+            | _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn timestamp(text: &str) -> Option<(u32, u8, u8, u16)> {
+        let mut input = text;
+        super::timestamp(&mut input).map(|timestamp| {
+            (
+                timestamp.hours,
+                timestamp.minutes,
+                timestamp.seconds,
+                timestamp.milliseconds,
+            )
+        })
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(
+            timestamp("00:01.500"),
+            Some((0, 0, 1, 500))
+        );
+        assert_eq!(
+            timestamp("01:02:03.004"),
+            Some((1, 2, 3, 4))
+        );
+        assert_eq!(timestamp("100:00.000"), None);
+        assert_eq!(
+            timestamp("100:00:00.000"),
+            Some((100, 0, 0, 0))
+        );
+        assert_eq!(timestamp("60:00.000"), None);
+        assert_eq!(
+            timestamp("60:00:00.000"),
+            Some((60, 0, 0, 0))
+        );
+        assert_eq!(timestamp("00:60.000"), None);
+        assert_eq!(timestamp("0:00.000"), None);
+        assert_eq!(timestamp("00:00.0000"), None);
+        assert_eq!(timestamp("00:00,000"), None);
+    }
+
+    #[test]
+    fn settings() {
+        let regions = [VttRegion {
+            id: Some("fred".to_string()),
+            ..Default::default()
+        }];
+        let settings = cue_settings(
+            "line:10% line:bogus line:-2,end position:50%,center position:101% size:0% \
+             align:left align:middle vertical:lr vertical:up region:fred :x x: unknown:1",
+            &regions,
+        );
+        assert_eq!(
+            settings,
+            CueSettings {
+                vertical: Some(Vertical::Lr),
+                line: Some(Line::LineNumber(
+                    -2.0,
+                    Some(LineAlignment::End)
+                )),
+                position: Some(Position {
+                    value: Percentage {
+                        value: 50.0,
+                    },
+                    alignment: Some(PositionAlignment::Center),
+                }),
+                size: Some(Percentage {
+                    value: 0.0,
+                }),
+                align: Some(Alignment::Left),
+                // Dropped for the line setting.
+                region: None,
+            }
+        );
+
+        assert_eq!(
+            cue_settings("region:fred position:10%", &regions).region,
+            Some("fred".to_string())
+        );
+        assert_eq!(
+            cue_settings("region:fred region:wilma", &regions).region,
+            None
+        );
+        assert_eq!(
+            line_setting("1.0"),
+            Some(Line::LineNumber(1.0, None))
+        );
+        assert_eq!(
+            line_setting("-1.5,center"),
+            Some(Line::LineNumber(
+                -1.5,
+                Some(LineAlignment::Center)
+            ))
+        );
+        assert_eq!(line_setting("1."), None);
+        assert_eq!(line_setting("1-"), None);
+        assert_eq!(line_setting(".5"), None);
+        assert_eq!(line_setting("-5%"), None);
+        assert_eq!(line_setting("5,middle"), None);
+    }
+}
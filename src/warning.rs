@@ -0,0 +1,102 @@
+//! Warning types for lenient parsing.
+
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// The result of lenient parsing with the warnings about skipped or repaired input.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Lenient<T> {
+    /// The parsed value.
+    pub value: T,
+    /// The warnings in order of appearance.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// The warning of lenient parsing.
+///
+/// ## Example
+/// ```
+/// use subtp::ParseWarning;
+/// use subtp::WarningKind;
+///
+/// let warning = ParseWarning {
+///     kind: WarningKind::InvalidBlock,
+///     line: 5,
+///     column: 1,
+///     span: 40..72,
+/// };
+///
+/// assert_eq!(
+///     warning.to_string(),
+///     "Parse warning at 5:1: skipped invalid block".to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct ParseWarning {
+    /// The kind of the warning.
+    pub kind: WarningKind,
+    /// The line number starting from 1.
+    pub line: usize,
+    /// The column number in characters starting from 1.
+    pub column: usize,
+    /// The byte range in the input.
+    pub span: Range<usize>,
+}
+
+impl Display for ParseWarning {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "Parse warning at {}:{}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+/// The kind of the warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum WarningKind {
+    /// The `WEBVTT` header is missing or malformed.
+    MissingHeader,
+    /// The sequence number is missing or malformed and is numbered automatically.
+    MissingSequence,
+    /// The blank line between blocks is missing and the block is split.
+    MissingBlankLine,
+    /// The malformed timestamp is repaired.
+    RepairedTimestamp,
+    /// The malformed settings are dropped.
+    InvalidSettings,
+    /// The malformed block or lines are skipped.
+    InvalidBlock,
+}
+
+impl Display for WarningKind {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | Self::MissingHeader => write!(f, "missing header"),
+            | Self::MissingSequence => write!(f, "missing sequence number"),
+            | Self::MissingBlankLine => write!(f, "missing blank line"),
+            | Self::RepairedTimestamp => write!(f, "repaired timestamp"),
+            | Self::InvalidSettings => write!(f, "dropped invalid settings"),
+            | Self::InvalidBlock => write!(f, "skipped invalid block"),
+        }
+    }
+}
@@ -49,7 +49,7 @@ peg::parser! {
         /// Percentage of floating number.
         pub(crate) rule percentage_float() -> f32
             = f:float() "%" {?
-                if f >= 0.0 && f <= 100.0 {
+                if (0.0..=100.0).contains(&f) {
                     Ok(f)
                 } else {
                     Err("Number out of range")
@@ -0,0 +1,697 @@
+//! Conformance of `WebVtt::parse_spec` against the web-platform-tests
+//! WebVTT file parsing suite in `tests/fixtures/webvtt/file-parsing`.
+//!
+//! Each test is named after the upstream test and checks the attributes of
+//! `VTTCue` and `VTTRegion` that its assertions check.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use subtp::vtt::{
+    Alignment, Line, LineAlignment, PositionAlignment, Scroll, Vertical,
+    VttBlock, VttCue, VttRegion, VttTimestamp, WebVtt,
+};
+
+fn path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/webvtt/file-parsing")
+        .join(name)
+}
+
+fn read(name: &str) -> String {
+    String::from_utf8(fs::read(path(name)).unwrap()).unwrap()
+}
+
+fn parse(name: &str) -> WebVtt {
+    WebVtt::parse_spec(&read(name))
+        .unwrap_or_else(|err| panic!("{}: {}", name, err))
+}
+
+/// The attributes of a `VTTCue`.
+#[derive(Debug, Clone, PartialEq)]
+struct Cue {
+    id: String,
+    start_time: f64,
+    end_time: f64,
+    text: String,
+    vertical: &'static str,
+    snap_to_lines: bool,
+    /// `None` for `"auto"`.
+    line: Option<f64>,
+    line_align: &'static str,
+    /// `None` for `"auto"`.
+    position: Option<f64>,
+    position_align: &'static str,
+    size: f64,
+    align: &'static str,
+    region: Option<Region>,
+}
+
+/// The attributes of a `VTTRegion`.
+#[derive(Debug, Clone, PartialEq)]
+struct Region {
+    id: String,
+    width: f64,
+    lines: u32,
+    region_anchor: (f64, f64),
+    viewport_anchor: (f64, f64),
+    scroll: &'static str,
+}
+
+fn seconds(timestamp: &VttTimestamp) -> f64 {
+    let milliseconds = timestamp.hours as u64 * 3_600_000
+        + timestamp.minutes as u64 * 60_000
+        + timestamp.seconds as u64 * 1_000
+        + timestamp.milliseconds as u64;
+
+    milliseconds as f64 / 1000.0
+}
+
+fn region(region: &VttRegion) -> Region {
+    let anchor = |anchor: Option<subtp::vtt::Anchor>| {
+        anchor.map_or((0.0, 100.0), |anchor| {
+            (anchor.x.value as f64, anchor.y.value as f64)
+        })
+    };
+
+    Region {
+        id: region
+            .id
+            .clone()
+            .unwrap_or_default(),
+        width: region
+            .width
+            .map_or(100.0, |width| width.value as f64),
+        lines: region.lines.unwrap_or(3),
+        region_anchor: anchor(region.region_anchor),
+        viewport_anchor: anchor(region.viewport_anchor),
+        scroll: match region.scroll {
+            | Some(Scroll::Up) => "up",
+            | None => "",
+        },
+    }
+}
+
+fn regions(vtt: &WebVtt) -> Vec<Region> {
+    vtt.blocks
+        .iter()
+        .filter_map(|block| match block {
+            | VttBlock::Region(found) => Some(region(found)),
+            | _ => None,
+        })
+        .collect()
+}
+
+fn cue(
+    vtt: &WebVtt,
+    cue: &VttCue,
+) -> Cue {
+    let settings = cue
+        .settings
+        .clone()
+        .unwrap_or_default();
+    let (snap_to_lines, line, line_align) = match settings.line {
+        | None => (true, None, None),
+        | Some(Line::LineNumber(number, alignment)) => {
+            (true, Some(number as f64), alignment)
+        },
+        | Some(Line::Percentage(percentage, alignment)) => {
+            (false, Some(percentage.value as f64), alignment)
+        },
+    };
+
+    Cue {
+        id: cue
+            .identifier
+            .clone()
+            .unwrap_or_default(),
+        start_time: seconds(&cue.timings.start),
+        end_time: seconds(&cue.timings.end),
+        text: cue.payload.join("\n"),
+        vertical: match settings.vertical {
+            | None => "",
+            | Some(Vertical::Rl) => "rl",
+            | Some(Vertical::Lr) => "lr",
+        },
+        snap_to_lines,
+        line,
+        line_align: match line_align {
+            | None | Some(LineAlignment::Start) => "start",
+            | Some(LineAlignment::Center) => "center",
+            | Some(LineAlignment::End) => "end",
+        },
+        position: settings
+            .position
+            .map(|position| position.value.value as f64),
+        position_align: match settings
+            .position
+            .and_then(|position| position.alignment)
+        {
+            | None => "auto",
+            | Some(PositionAlignment::LineLeft) => "line-left",
+            | Some(PositionAlignment::Center) => "center",
+            | Some(PositionAlignment::LineRight) => "line-right",
+        },
+        size: settings
+            .size
+            .map_or(100.0, |size| size.value as f64),
+        align: match settings.align {
+            | None | Some(Alignment::Center) => "center",
+            | Some(Alignment::Start) => "start",
+            | Some(Alignment::End) => "end",
+            | Some(Alignment::Left) => "left",
+            | Some(Alignment::Right) => "right",
+        },
+        // The last region with the identifier wins.
+        region: settings.region.map(|id| {
+            regions(vtt)
+                .into_iter()
+                .rev()
+                .find(|region| region.id == id)
+                .expect("region is defined")
+        }),
+    }
+}
+
+fn cues(name: &str) -> Vec<Cue> {
+    let vtt = parse(name);
+    vtt.blocks
+        .iter()
+        .filter_map(|block| match block {
+            | VttBlock::Que(found) => Some(cue(&vtt, found)),
+            | _ => None,
+        })
+        .collect()
+}
+
+fn texts(cues: &[Cue]) -> Vec<&str> {
+    cues.iter()
+        .map(|cue| cue.text.as_str())
+        .collect()
+}
+
+fn identifiers(cues: &[Cue]) -> Vec<&str> {
+    cues.iter()
+        .map(|cue| cue.id.as_str())
+        .collect()
+}
+
+fn times(cues: &[Cue]) -> Vec<(f64, f64)> {
+    cues.iter()
+        .map(|cue| (cue.start_time, cue.end_time))
+        .collect()
+}
+
+fn region_of(cue: &Cue) -> &Region {
+    cue.region
+        .as_ref()
+        .expect("cue has a region")
+}
+
+#[test]
+fn signature() {
+    for (name, count) in [
+        ("signature-bom.vtt", 1),
+        ("signature-no-newline.vtt", 0),
+        ("signature-space.vtt", 1),
+        ("signature-space-no-newline.vtt", 0),
+        ("signature-tab.vtt", 1),
+        ("signature-tab-no-newline.vtt", 0),
+        ("signature-timings.vtt", 0),
+    ] {
+        let cues = cues(name);
+        assert_eq!(cues.len(), count, "{}", name);
+        for cue in cues {
+            assert_eq!((cue.start_time, cue.end_time), (0.0, 1.0));
+            assert_eq!(cue.text, "text");
+        }
+    }
+}
+
+#[test]
+fn signature_invalid() {
+    let mut names: Vec<String> = fs::read_dir(path(""))
+        .unwrap()
+        .map(|entry| {
+            entry
+                .unwrap()
+                .file_name()
+                .into_string()
+                .unwrap()
+        })
+        .filter(|name| name.starts_with("signature-invalid-"))
+        .collect();
+    names.sort();
+    assert_eq!(names.len(), 11);
+
+    for name in names {
+        assert!(
+            WebVtt::parse_spec(&read(&name)).is_err(),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn header() {
+    for name in [
+        "header-garbage.vtt",
+        "header-timings.vtt",
+        "header-space.vtt",
+    ] {
+        let cues = cues(name);
+        assert_eq!(texts(&cues), ["text"], "{}", name);
+        assert_eq!(identifiers(&cues), [""], "{}", name);
+    }
+
+    // The regions of the old header syntax are not regions.
+    let cues = cues("header-regions.vtt");
+    assert_eq!(cues.len(), 1);
+    assert_eq!(cues[0].region, None);
+}
+
+#[test]
+fn ids() {
+    let cues = cues("ids.vtt");
+    assert_eq!(
+        identifiers(&cues),
+        ["foo", " bar ", "1", "日本", "-- >", "NOTE", "STYLE", "REGION"]
+    );
+    assert!(cues
+        .iter()
+        .all(|cue| cue.text == "text"));
+}
+
+#[test]
+fn newlines() {
+    let cues = cues("newlines.vtt");
+    assert_eq!(
+        texts(&cues),
+        ["text0", "text1", "line1\nline2\nline3", "text3"]
+    );
+    assert_eq!(identifiers(&cues), ["", "", "foo", ""]);
+}
+
+#[test]
+fn nulls() {
+    let cues = cues("nulls.vtt");
+    assert_eq!(identifiers(&cues), ["foo\u{FFFD}", ""]);
+    assert_eq!(
+        texts(&cues),
+        ["\u{FFFD}text\u{FFFD}", "\u{FFFD}"]
+    );
+}
+
+#[test]
+fn arrows() {
+    let cues = cues("arrows.vtt");
+    assert_eq!(
+        identifiers(&cues),
+        ["", "", "-- >", "- ->", "", "", "id", ""]
+    );
+    assert_eq!(
+        texts(&cues),
+        ["text0", "text1", "text2", "text3", "text4", "", "", "text10"]
+    );
+}
+
+#[test]
+fn comments() {
+    let cues = cues("comments.vtt");
+    assert_eq!(identifiers(&cues), ["NOTEcomment", ""]);
+    assert_eq!(texts(&cues), ["text0", "NOTE"]);
+}
+
+#[test]
+fn settings_align() {
+    let cues = cues("settings-align.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| cue.align)
+            .collect::<Vec<_>>(),
+        [
+            "start", "center", "end", "left", "right", "center", "center",
+            "center", "end", "end",
+        ]
+    );
+}
+
+#[test]
+fn settings_line() {
+    let cues = cues("settings-line.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| (cue.snap_to_lines, cue.line, cue.line_align))
+            .collect::<Vec<_>>(),
+        [
+            (true, Some(0.0), "start"),
+            (true, Some(-1.0), "start"),
+            (true, Some(100.0), "start"),
+            (true, Some(1.0), "start"),
+            (false, Some(0.0), "start"),
+            (false, Some(50.0), "start"),
+            (false, Some(0.5), "start"),
+            (false, Some(100.0), "start"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, Some(0.0), "start"),
+            (true, Some(0.0), "center"),
+            (true, Some(0.0), "end"),
+            (false, Some(50.0), "end"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, None, "start"),
+            (true, Some(1.5), "start"),
+            (true, Some(-1.5), "start"),
+        ]
+    );
+}
+
+#[test]
+fn settings_position() {
+    let cues = cues("settings-position.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| (cue.position, cue.position_align))
+            .collect::<Vec<_>>(),
+        [
+            (Some(0.0), "auto"),
+            (Some(50.0), "auto"),
+            (Some(100.0), "auto"),
+            (Some(50.5), "auto"),
+            (None, "auto"),
+            (None, "auto"),
+            (None, "auto"),
+            (None, "auto"),
+            (Some(50.0), "line-left"),
+            (Some(50.0), "center"),
+            (Some(50.0), "line-right"),
+            (None, "auto"),
+            (None, "auto"),
+            (Some(10.0), "auto"),
+        ]
+    );
+}
+
+#[test]
+fn settings_size() {
+    let cues = cues("settings-size.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| cue.size)
+            .collect::<Vec<_>>(),
+        [0.0, 50.0, 100.0, 50.5, 100.0, 100.0, 100.0, 100.0, 10.0]
+    );
+}
+
+#[test]
+fn settings_vertical() {
+    let cues = cues("settings-vertical.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| cue.vertical)
+            .collect::<Vec<_>>(),
+        ["rl", "lr", "", "", "", "rl", "lr"]
+    );
+}
+
+#[test]
+fn settings_multiple() {
+    let cues = cues("settings-multiple.vtt");
+    assert_eq!(cues.len(), 5);
+
+    assert_eq!(cues[0].align, "start");
+    assert_eq!(cues[0].line, Some(1.0));
+    assert!(cues[0].snap_to_lines);
+    assert_eq!(cues[0].position, Some(25.0));
+    assert_eq!(cues[0].size, 50.0);
+    assert_eq!(cues[0].vertical, "lr");
+
+    assert_eq!((cues[1].align, cues[1].size), ("end", 10.0));
+    assert_eq!((cues[2].align, cues[2].line), ("end", Some(1.0)));
+
+    assert_eq!(cues[3].align, "center");
+    assert_eq!(cues[3].line, None);
+
+    assert_eq!((cues[4].align, cues[4].line), ("end", Some(-1.0)));
+}
+
+#[test]
+fn settings_region() {
+    let cues = cues("settings-region.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| {
+                cue.region
+                    .as_ref()
+                    .map(|region| region.id.as_str())
+            })
+            .collect::<Vec<_>>(),
+        [
+            Some("fred"),
+            None,
+            None,
+            None,
+            None,
+            Some("fred"),
+            Some("bill"),
+            None,
+            None,
+            Some("fred"),
+        ]
+    );
+}
+
+#[test]
+fn regions_id() {
+    let cues = cues("regions-id.vtt");
+    assert_eq!(cues.len(), 4);
+    assert_eq!(region_of(&cues[0]).id, "foo");
+    assert_eq!(cues[1].region, None);
+    assert_eq!(region_of(&cues[2]).id, "baz");
+    // The last region with the identifier.
+    assert_eq!(region_of(&cues[3]).width, 50.0);
+}
+
+#[test]
+fn regions_width() {
+    let cues = cues("regions-width.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| region_of(cue).width)
+            .collect::<Vec<_>>(),
+        [0.0, 50.0, 100.0, 50.5, 100.0, 100.0, 100.0, 100.0]
+    );
+}
+
+#[test]
+fn regions_lines() {
+    let cues = cues("regions-lines.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| region_of(cue).lines)
+            .collect::<Vec<_>>(),
+        [0, 1, 100, 3, 3, 3, 3, 5]
+    );
+}
+
+#[test]
+fn regions_regionanchor() {
+    let cues = cues("regions-regionanchor.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| region_of(cue).region_anchor)
+            .collect::<Vec<_>>(),
+        [
+            (0.0, 0.0),
+            (100.0, 100.0),
+            (50.0, 25.5),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+        ]
+    );
+}
+
+#[test]
+fn regions_viewportanchor() {
+    let cues = cues("regions-viewportanchor.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| region_of(cue).viewport_anchor)
+            .collect::<Vec<_>>(),
+        [
+            (0.0, 0.0),
+            (100.0, 100.0),
+            (50.0, 25.5),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 100.0),
+        ]
+    );
+}
+
+#[test]
+fn regions_scroll() {
+    let cues = cues("regions-scroll.vtt");
+    assert_eq!(
+        cues.iter()
+            .map(|cue| region_of(cue).scroll)
+            .collect::<Vec<_>>(),
+        ["up", "", "", "", "up"]
+    );
+}
+
+#[test]
+fn regions_old() {
+    let cues = cues("regions-old.vtt");
+    assert_eq!(cues.len(), 2);
+    assert!(cues
+        .iter()
+        .all(|cue| cue.region.is_none()));
+}
+
+#[test]
+fn regions_edge_case() {
+    let vtt = parse("regions-edge-case.vtt");
+    assert_eq!(
+        regions(&vtt)
+            .iter()
+            .map(|region| region.id.as_str())
+            .collect::<Vec<_>>(),
+        ["foo", "arrow"]
+    );
+
+    let cues = cues("regions-edge-case.vtt");
+    assert_eq!(cues.len(), 3);
+    assert_eq!(cues[0].region, None);
+    assert_eq!(region_of(&cues[1]).width, 40.0);
+    // The region after a cue is not a region.
+    assert_eq!(cues[2].region, None);
+}
+
+#[test]
+fn stylesheets() {
+    let vtt = parse("stylesheets.vtt");
+    assert_eq!(
+        vtt.blocks
+            .iter()
+            .filter_map(|block| match block {
+                | VttBlock::Style(style) => Some(style.style.as_str()),
+                | _ => None,
+            })
+            .collect::<Vec<_>>(),
+        [
+            "::cue { color: red }",
+            "::cue(b) {\n  color: blue\n}",
+            "::cue { color: lime }",
+        ]
+    );
+    assert_eq!(texts(&cues("stylesheets.vtt")), ["text"]);
+}
+
+#[test]
+fn timings() {
+    for (name, expected) in [
+        (
+            "timings-60.vtt",
+            vec![
+                ("text3", (216000.0, 216001.0)),
+                ("text5", (3599.999, 3600.0)),
+            ],
+        ),
+        ("timings-eof.vtt", vec![("", (0.0, 1.0))]),
+        (
+            "timings-garbage.vtt",
+            vec![
+                ("text0", (0.0, 1.0)),
+                ("text3", (0.0, 1.0)),
+                ("text5", (0.0, 1.0)),
+                ("text6", (0.0, 1.0)),
+            ],
+        ),
+        (
+            "timings-negative.vtt",
+            vec![
+                ("text0", (1.0, 0.0)),
+                ("text3", (1.0, 1.0)),
+            ],
+        ),
+        (
+            "timings-omitted-hours.vtt",
+            vec![
+                ("text0", (0.0, 1.0)),
+                ("text1", (0.0, 1.0)),
+                ("text2", (0.0, 1.0)),
+                ("text4", (3599.0, 3600.0)),
+            ],
+        ),
+        (
+            "timings-too-long.vtt",
+            vec![("text0", (0.0, 1.0))],
+        ),
+        ("timings-too-short.vtt", vec![]),
+        (
+            "timings-whitespace.vtt",
+            vec![
+                ("text0", (0.0, 1.0)),
+                ("text1", (0.0, 1.0)),
+                ("text2", (0.0, 1.0)),
+                ("text4", (0.0, 1.0)),
+            ],
+        ),
+    ] {
+        let cues = cues(name);
+        assert_eq!(
+            texts(&cues)
+                .into_iter()
+                .zip(times(&cues))
+                .collect::<Vec<_>>(),
+            expected,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn render_round_trip() {
+    for entry in fs::read_dir(path("")).unwrap() {
+        let name = entry
+            .unwrap()
+            .file_name()
+            .into_string()
+            .unwrap();
+        if name.starts_with("signature-invalid-") {
+            continue;
+        }
+
+        // The rendered file is conforming and parsed into the same file,
+        // except the regions without settings that have no syntax.
+        let mut vtt = parse(&name);
+        vtt.blocks.retain(|block| {
+            !matches!(
+                block,
+                VttBlock::Region(region) if *region == VttRegion::default()
+            )
+        });
+        assert_eq!(
+            WebVtt::parse_spec(&vtt.render()).unwrap(),
+            vtt,
+            "{}",
+            name
+        );
+    }
+}